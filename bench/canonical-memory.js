@@ -0,0 +1,81 @@
+#!/usr/bin/env node
+/**
+ * Canonical hashing memory benchmark.
+ *
+ * Hashes a 50k-entity transformed context two ways, each in its own
+ * process: by building the canonical string (SHA-256 of canonicalize()) and
+ * by streaming it into the digest (hash()). Reports peak RSS growth over
+ * the baseline after the context is built, and wall time.
+ * Run with: npm run bench:canonical
+ */
+
+import { spawnSync } from "child_process";
+import { createHash } from "crypto";
+import { fileURLToPath } from "url";
+import { canonicalize, hash } from "../src/core/canonical.ts";
+
+const ENTITIES = 50_000;
+
+function buildContext(count) {
+  const entities = [];
+  const relations = [];
+  for (let i = 0; i < count; i++) {
+    const id = `ENTITY_${String(count - i).padStart(6, "0")}`;
+    entities.push({
+      syntheticId: id,
+      role: i % 3 === 0 ? "Actor" : "Value",
+      attributes: {
+        type: i % 3 === 0 ? "name" : "amount",
+        confidence: 0.5 + (i % 50) / 100,
+        occurrences: 1 + (i % 7),
+        sourceDoc: `doc_${i % 20}`,
+        magnitude: "1k-10k",
+      },
+    });
+    if (i > 0) {
+      relations.push({ from: id, to: entities[i - 1].syntheticId, type: "related" });
+    }
+  }
+  return { entities, relations, task: "benchmark" };
+}
+
+const run = {
+  string: (context) => createHash("sha256").update(canonicalize(context), "utf8").digest("hex"),
+  stream: (context) => hash(context),
+};
+
+const mode = process.argv[2];
+if (mode in run) {
+  // Child: measure one path
+  const context = buildContext(ENTITIES);
+  globalThis.gc?.();
+  const baseline = process.resourceUsage().maxRSS;
+  const start = performance.now();
+  const digest = run[mode](context);
+  const elapsed = performance.now() - start;
+  const peak = process.resourceUsage().maxRSS;
+  console.log(JSON.stringify({ digest, growthKiB: peak - baseline, elapsedMs: elapsed }));
+} else {
+  const results = {};
+  for (const path of Object.keys(run)) {
+    const child = spawnSync(
+      process.execPath,
+      [...process.execArgv, "--expose-gc", fileURLToPath(import.meta.url), path],
+      { encoding: "utf8" }
+    );
+    if (child.status !== 0) {
+      throw new Error(child.stderr);
+    }
+    results[path] = JSON.parse(child.stdout.trim().split("\n").pop());
+  }
+
+  if (results.string.digest !== results.stream.digest) {
+    throw new Error("Streamed digest differs from the string path");
+  }
+  console.log(`Canonical SHA-256 of a ${ENTITIES}-entity context (${results.stream.digest.slice(0, 16)}…)`);
+  for (const [path, result] of Object.entries(results)) {
+    console.log(
+      `  ${path.padEnd(6)}  peak RSS +${(result.growthKiB / 1024).toFixed(1)} MiB  ${result.elapsedMs.toFixed(0)} ms`
+    );
+  }
+}
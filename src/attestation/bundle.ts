@@ -0,0 +1,313 @@
+/**
+ * Verification bundles: one self-describing JSON artifact holding
+ * everything needed to re-verify a response's attestation offline — the
+ * evidence envelope with its certificates, the measurement, the binding
+ * inputs (hashes only), and the versions that produced them. Verification
+ * uses no network access, so bundles can be checked on air-gapped
+ * machines long after the fact.
+ */
+import { readFileSync } from "fs";
+import { HASH_ALGORITHMS, HASH_SCOPES } from "../core/canonical.ts";
+import type { BindingVersion, HashAlgorithm, HashScope } from "../core/canonical.ts";
+import { AttestationError } from "../core/errors.ts";
+import { OUTPUT_ENCODINGS } from "../core/serialization.ts";
+import type { OutputEncoding } from "../core/serialization.ts";
+import { openEvidence } from "./evidence.ts";
+import type {
+  EnclaveResponse,
+  EvidenceEnvelope,
+  ExpectedBinding,
+  ReportVerificationResult,
+} from "./types.ts";
+import { verifyAttestationReport } from "./verifier.ts";
+
+/**
+ * Bundle format version this module reads and writes.
+ */
+export const VERIFICATION_BUNDLE_VERSION = 1;
+
+/**
+ * Version of this SDK, recorded in the bundles it writes.
+ */
+export const SDK_VERSION: string = (
+  JSON.parse(readFileSync(new URL("../../package.json", import.meta.url), "utf8")) as {
+    version: string;
+  }
+).version;
+
+/**
+ * A verification bundle. Byte fields are lowercase hex; evidence is an
+ * evidence envelope (see evidenceToJson), certificates included.
+ */
+export interface VerificationBundle {
+  bundleVersion: number;
+
+  /**
+   * SDK version that wrote the bundle.
+   */
+  sdkVersion: string;
+
+  /**
+   * Runner version, when the response metadata carried one.
+   */
+  runnerVersion?: string;
+
+  evidence: EvidenceEnvelope;
+
+  /**
+   * Enclave measurement the response reported.
+   */
+  measurement: string;
+
+  /**
+   * Binding inputs report_data was computed from, with the hash
+   * parameters needed to recompute outputHash from the output.
+   */
+  binding: {
+    sessionId: string;
+    configHash: string;
+    policyHash?: string;
+    outputHash: string;
+    timestamp: number;
+    inputHash?: string;
+    chainedFrom?: string;
+    hashAlgorithm: HashAlgorithm;
+    outputEncoding: OutputEncoding;
+    hashScope: HashScope;
+  };
+
+  /**
+   * Session key proof, for a session-cached attestation.
+   */
+  sessionAttestation?: {
+    publicKey: string;
+    windowStart: number;
+    expiresAt: number;
+    signature: string;
+  };
+
+  /**
+   * PEM public key simulator evidence is signed with, for reference.
+   * Verification never trusts it. Never present for hardware evidence.
+   */
+  simulatorKey?: string;
+}
+
+/**
+ * Request fields a bundle records that the response does not carry.
+ */
+export interface BundleRequestFields {
+  sessionId: Uint8Array;
+  configHash: string;
+  timestamp: number;
+
+  /**
+   * Set when the request asked for legacy report_data (no policy hash).
+   */
+  legacyReportData?: boolean;
+}
+
+/**
+ * Options for verifyVerificationBundle.
+ */
+export interface BundleVerificationOptions {
+  /**
+   * AMD ARK to anchor the certificate chain (PEM or DER). Required for
+   * hardware evidence to verify.
+   */
+  trustedArk?: string | Uint8Array;
+
+  /**
+   * Measurement the auditor expects. Default: the bundle's own, which
+   * only shows the report agrees with its bundle.
+   */
+  measurement?: string;
+
+  /**
+   * Accept simulator evidence. Default: false.
+   */
+  allowSimulator?: boolean;
+
+  /**
+   * Simulator verification key, obtained from the runner rather than the
+   * bundle. Required for simulator evidence to verify; the key a bundle
+   * embeds is never used.
+   */
+  simulatorKey?: string | Uint8Array;
+
+  /**
+   * Time of verification (milliseconds since epoch); a session-cached
+   * bundle fails once its window has ended. Default: now.
+   */
+  verifiedAt?: number;
+}
+
+const hex = (bytes: Uint8Array) => Buffer.from(bytes).toString("hex");
+
+/**
+ * Build a verification bundle for a response. The response must carry an
+ * evidence envelope (EnclaveBridge.execute attaches one).
+ * @param response - Enclave response
+ * @param request - Session ID, config hash, and timestamp of its request
+ * @param simulatorKey - PEM key simulator evidence is signed with
+ * @returns Bundle JSON
+ * @throws AttestationError if the response has no usable evidence
+ */
+export function createVerificationBundle(
+  response: EnclaveResponse,
+  request: BundleRequestFields,
+  simulatorKey?: string
+): string {
+  if (response.evidence === undefined) {
+    throw new AttestationError("Response carries no evidence envelope to bundle");
+  }
+  const opened = openEvidence(response.evidence);
+  const runnerVersion = response.metadata?.runner_version;
+  const session = response.sessionAttestation;
+  const bundle: VerificationBundle = {
+    bundleVersion: VERIFICATION_BUNDLE_VERSION,
+    sdkVersion: SDK_VERSION,
+    ...(typeof runnerVersion === "string" ? { runnerVersion } : {}),
+    evidence: response.evidence,
+    measurement: response.measurement,
+    binding: {
+      sessionId: hex(request.sessionId),
+      configHash: request.configHash,
+      ...(request.legacyReportData ? {} : { policyHash: hex(response.policyHash) }),
+      outputHash: hex(response.outputHash),
+      timestamp: request.timestamp,
+      ...(response.inputHash !== undefined ? { inputHash: hex(response.inputHash) } : {}),
+      ...(response.chainedFrom !== undefined ? { chainedFrom: response.chainedFrom } : {}),
+      hashAlgorithm: response.hashAlgorithm,
+      outputEncoding: response.outputEncoding,
+      hashScope: response.hashScope,
+    },
+    ...(session !== undefined
+      ? {
+          sessionAttestation: {
+            publicKey: hex(session.publicKey),
+            windowStart: session.windowStart,
+            expiresAt: session.expiresAt,
+            signature: hex(session.signature),
+          },
+        }
+      : {}),
+    ...(opened.simulator && simulatorKey !== undefined ? { simulatorKey } : {}),
+  };
+  return JSON.stringify(bundle);
+}
+
+/**
+ * Parse a verification bundle and check its shape.
+ * @param json - Bundle JSON from createVerificationBundle
+ * @throws AttestationError if the JSON is not a valid bundle
+ */
+export function verificationBundleFromJson(json: string): VerificationBundle {
+  let value: unknown;
+  try {
+    value = JSON.parse(json);
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    throw new AttestationError(`Verification bundle is not valid JSON: ${reason}`);
+  }
+  if (value === null || typeof value !== "object") {
+    throw new AttestationError("Verification bundle is not a JSON object");
+  }
+  const bundle = value as VerificationBundle;
+  if (bundle.bundleVersion !== VERIFICATION_BUNDLE_VERSION) {
+    throw new AttestationError(
+      `Unsupported verification bundle version ${bundle.bundleVersion}`
+    );
+  }
+  if (bundle.evidence === null || typeof bundle.evidence !== "object") {
+    throw new AttestationError("Verification bundle carries no evidence");
+  }
+  if (typeof bundle.measurement !== "string") {
+    throw new AttestationError("Verification bundle carries no measurement");
+  }
+  const binding = bundle.binding;
+  if (
+    binding === null ||
+    typeof binding !== "object" ||
+    typeof binding.sessionId !== "string" ||
+    typeof binding.configHash !== "string" ||
+    typeof binding.outputHash !== "string" ||
+    typeof binding.timestamp !== "number"
+  ) {
+    throw new AttestationError("Verification bundle binding is incomplete");
+  }
+  if (!HASH_ALGORITHMS.includes(binding.hashAlgorithm)) {
+    throw new AttestationError(`Unknown bundle hash algorithm: ${binding.hashAlgorithm}`);
+  }
+  if (!OUTPUT_ENCODINGS.includes(binding.outputEncoding)) {
+    throw new AttestationError(`Unknown bundle output encoding: ${binding.outputEncoding}`);
+  }
+  if (!HASH_SCOPES.includes(binding.hashScope)) {
+    throw new AttestationError(`Unknown bundle hash scope: ${binding.hashScope}`);
+  }
+  return bundle;
+}
+
+/**
+ * Verify a bundle's evidence against an output hash the auditor computed
+ * themselves, without network access: every check of
+ * verifyAttestationReport runs, with the bundle's binding inputs and
+ * certificates. The bundle's own outputHash is not trusted; a different
+ * expectedOutputHash fails report_data. A malformed bundle fails
+ * structure.
+ * @param bundle - Bundle JSON from createVerificationBundle
+ * @param expectedOutputHash - Hex output hash to verify against
+ * @param options - Trust anchors and simulator handling
+ * @returns Named pass/fail checks; valid only if all passed
+ */
+export function verifyVerificationBundle(
+  bundle: string,
+  expectedOutputHash: string,
+  options: BundleVerificationOptions = {}
+): ReportVerificationResult {
+  let parsed: VerificationBundle;
+  let bindingVersion: BindingVersion;
+  try {
+    parsed = verificationBundleFromJson(bundle);
+    bindingVersion = openEvidence(parsed.evidence).bindingVersion;
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    return {
+      valid: false,
+      simulator: false,
+      checks: [{ name: "structure", passed: false, reason }],
+    };
+  }
+
+  const { binding, sessionAttestation } = parsed;
+  // Whoever forged a simulator report would embed their own key, so only
+  // a key the caller supplies counts
+  const simulatorKey = options.simulatorKey;
+  const expected: ExpectedBinding = {
+    sessionId: binding.sessionId,
+    configHash: binding.configHash,
+    ...(binding.policyHash !== undefined ? { policyHash: binding.policyHash } : {}),
+    outputHash: expectedOutputHash.toLowerCase(),
+    timestamp: binding.timestamp,
+    ...(binding.inputHash !== undefined ? { inputHash: binding.inputHash } : {}),
+    ...(binding.chainedFrom !== undefined ? { chainedFrom: binding.chainedFrom } : {}),
+    hashAlgorithm: binding.hashAlgorithm,
+    acceptShortReportData: bindingVersion !== 3,
+    measurement: options.measurement ?? parsed.measurement,
+    allowSimulator: options.allowSimulator === true,
+    ...(simulatorKey !== undefined ? { simulatorKey } : {}),
+    ...(options.trustedArk !== undefined ? { trustedArk: options.trustedArk } : {}),
+    ...(options.verifiedAt !== undefined ? { verifiedAt: options.verifiedAt } : {}),
+    ...(sessionAttestation !== undefined
+      ? {
+          sessionAttestation: {
+            publicKey: new Uint8Array(Buffer.from(sessionAttestation.publicKey, "hex")),
+            windowStart: sessionAttestation.windowStart,
+            expiresAt: sessionAttestation.expiresAt,
+            signature: new Uint8Array(Buffer.from(sessionAttestation.signature, "hex")),
+          },
+        }
+      : {}),
+  };
+  return verifyAttestationReport(parsed.evidence, expected);
+}
@@ -0,0 +1,391 @@
+/**
+ * Experimental VCEK certificate retrieval and certificate table parsing.
+ * This is an opt-in preview path and does not provide v1.0 guarantees.
+ */
+import { X509Certificate } from "crypto";
+import { mkdir, readFile, rename, writeFile } from "fs/promises";
+import { homedir } from "os";
+import { join } from "path";
+import { DEFAULT_CRL_MAX_AGE_MS, parseCrl } from "./crl.ts";
+import { isSimulatorReport } from "./parser.ts";
+import { parseReport } from "./report.ts";
+import type { AttestationReport, TcbVersion } from "./report.ts";
+
+/**
+ * AMD Key Distribution Service base URL.
+ */
+export const AMD_KDS_URL = "https://kdsintf.amd.com";
+
+/**
+ * Processor families the KDS serves VCEKs for.
+ */
+export const VCEK_PRODUCTS = ["Milan", "Genoa"] as const;
+
+export type VcekProduct = (typeof VCEK_PRODUCTS)[number];
+
+/**
+ * Options for fetching and caching VCEK certificates.
+ */
+export interface VcekOptions {
+  /**
+   * KDS base URL. Default: AMD_KDS_URL.
+   */
+  kdsUrl?: string;
+
+  /**
+   * Processor family that produced the report. Default: "Milan".
+   */
+  product?: VcekProduct;
+
+  /**
+   * Directory for cached certificates, one PEM file per chip and TCB.
+   * Default: ~/.cache/axiom-core/vcek
+   */
+  cacheDir?: string;
+
+  /**
+   * Timeout per request in milliseconds. Default: 10000.
+   */
+  timeoutMs?: number;
+
+  /**
+   * Retries after a network error, timeout, 429, or 5xx. Default: 2.
+   */
+  retries?: number;
+
+  /**
+   * Delay before the first retry in milliseconds, doubled for each
+   * further retry. Default: 500.
+   */
+  retryDelayMs?: number;
+
+  /**
+   * How long a fetched CRL is reused when the KDS response sets no
+   * max-age. Default: DEFAULT_CRL_MAX_AGE_MS.
+   */
+  crlMaxAgeMs?: number;
+}
+
+/**
+ * TCB security version numbers the report was signed under.
+ */
+export type ReportedTcb = TcbVersion;
+
+/**
+ * AMD certificates from an extended report's certificate table, DER-encoded.
+ * A certificate the host did not supply is absent.
+ */
+export interface CertChain {
+  /**
+   * Versioned Chip Endorsement Key certificate (signs the report).
+   */
+  vcek?: Uint8Array;
+
+  /**
+   * AMD SEV Key certificate (signs the VCEK).
+   */
+  ask?: Uint8Array;
+
+  /**
+   * AMD Root Key certificate (signs the ASK).
+   */
+  ark?: Uint8Array;
+}
+
+/**
+ * Certificate table GUIDs (GHCB specification) for the CertChain entries.
+ */
+export const CERT_TABLE_GUIDS = {
+  vcek: "63da758d-e664-4564-adc5-f4b93be8accd",
+  ask: "4ab7b379-bbac-4fe4-a02f-05aef327c782",
+  ark: "c0b406a4-a803-4952-9743-3fb6014cd0ae",
+} as const;
+
+const CERT_TABLE_ENTRY_BYTES = 24;
+const CERT_TABLE_KINDS = ["vcek", "ask", "ark"] as const;
+function parseHardwareReport(report: Uint8Array): AttestationReport {
+  if (isSimulatorReport(report)) {
+    throw new Error("VCEK lookup requires a SEV-SNP hardware report");
+  }
+  return parseReport(report);
+}
+
+/**
+ * Extract the chip identifier from a SEV-SNP report.
+ * @param report - Raw attestation report
+ * @returns Hex-encoded 64-byte chip_id
+ * @throws Error for simulator or malformed reports
+ */
+export function extractChipId(report: Uint8Array): string {
+  return Buffer.from(parseHardwareReport(report).chipId).toString("hex");
+}
+
+/**
+ * Extract the reported TCB from a SEV-SNP report.
+ * @param report - Raw attestation report
+ * @returns Security version numbers the VCEK was derived for
+ * @throws Error for simulator or malformed reports
+ */
+export function extractReportedTcb(report: Uint8Array): ReportedTcb {
+  return parseHardwareReport(report).reportedTcb;
+}
+
+// Offset of the 8-byte reported TCB in a SEV-SNP report
+const REPORTED_TCB_OFFSET = 0x180;
+
+/**
+ * Cache key for a report's VCEK: chip_id and the raw reported TCB.
+ * @param report - Raw attestation report
+ */
+export function vcekCacheKey(report: Uint8Array): string {
+  const chipId = extractChipId(report);
+  const tcb = Buffer.from(
+    report.subarray(REPORTED_TCB_OFFSET, REPORTED_TCB_OFFSET + 8)
+  ).toString("hex");
+  return `${chipId}-${tcb}`;
+}
+
+// The KDS base URL without trailing slashes, so "https://kds/" builds the
+// same URLs (and cache keys) as "https://kds"
+function kdsBase(options: VcekOptions): string {
+  return (options.kdsUrl ?? AMD_KDS_URL).replace(/\/+$/, "");
+}
+
+/**
+ * KDS URL of the VCEK for a report's chip and reported TCB.
+ * @param report - Raw attestation report
+ * @param options - KDS base URL and product
+ */
+export function vcekUrl(report: Uint8Array, options: VcekOptions = {}): string {
+  const tcb = extractReportedTcb(report);
+  const spl = (value: number) => value.toString().padStart(2, "0");
+  return (
+    `${kdsBase(options)}/vcek/v1/${options.product ?? "Milan"}/${extractChipId(report)}` +
+    `?blSPL=${spl(tcb.bootLoader)}&teeSPL=${spl(tcb.tee)}` +
+    `&snpSPL=${spl(tcb.snp)}&ucodeSPL=${spl(tcb.microcode)}`
+  );
+}
+
+function guidBytes(guid: string): Buffer {
+  return Buffer.from(guid.replace(/-/g, ""), "hex");
+}
+
+/**
+ * Parse the certificate table returned with an extended report
+ * (SNP_GET_EXT_REPORT). The table is a list of 24-byte entries (16-byte
+ * GUID, then offset and length as little-endian u32, relative to the
+ * table start) ending at an all-zero GUID. Unknown GUIDs are skipped, as
+ * are repeats of a GUID already seen and entries whose data lies outside
+ * the table; a table cut off before its terminator yields the entries
+ * that are complete.
+ * @param table - Raw certificate table
+ * @returns Certificates found, DER-encoded
+ */
+export function parseCertTable(table: Uint8Array): CertChain {
+  const chain: CertChain = {};
+  const view = new DataView(table.buffer, table.byteOffset, table.byteLength);
+
+  for (let at = 0; at + CERT_TABLE_ENTRY_BYTES <= table.length; at += CERT_TABLE_ENTRY_BYTES) {
+    const guid = table.subarray(at, at + 16);
+    if (guid.every((byte) => byte === 0)) {
+      break;
+    }
+
+    const kind = CERT_TABLE_KINDS.find((name) =>
+      guidBytes(CERT_TABLE_GUIDS[name]).equals(guid)
+    );
+    const offset = view.getUint32(at + 16, true);
+    const length = view.getUint32(at + 20, true);
+    if (kind === undefined || chain[kind] !== undefined) {
+      continue;
+    }
+    if (length === 0 || offset + length > table.length) {
+      continue;
+    }
+    chain[kind] = new Uint8Array(table.subarray(offset, offset + length));
+  }
+
+  return chain;
+}
+
+/**
+ * Build a certificate table in the layout parseCertTable reads: entries
+ * in VCEK, ASK, ARK order, the terminator, then the certificates.
+ * @param chain - Certificates to include
+ * @returns Raw certificate table
+ */
+export function createCertTable(chain: CertChain): Uint8Array {
+  const kinds = CERT_TABLE_KINDS.filter((kind) => chain[kind] !== undefined);
+  const headerBytes = (kinds.length + 1) * CERT_TABLE_ENTRY_BYTES;
+  const total = kinds.reduce((sum, kind) => sum + chain[kind]!.length, headerBytes);
+  const table = new Uint8Array(total);
+  const view = new DataView(table.buffer);
+
+  let offset = headerBytes;
+  kinds.forEach((kind, index) => {
+    const at = index * CERT_TABLE_ENTRY_BYTES;
+    const der = chain[kind]!;
+    table.set(guidBytes(CERT_TABLE_GUIDS[kind]), at);
+    view.setUint32(at + 16, offset, true);
+    view.setUint32(at + 20, der.length, true);
+    table.set(der, offset);
+    offset += der.length;
+  });
+
+  return table;
+}
+
+/**
+ * PEM-encode a DER certificate.
+ * @param der - DER-encoded certificate
+ * @param label - PEM label. Default: "CERTIFICATE" ("X509 CRL" for a CRL).
+ */
+export function derToPem(der: Uint8Array, label: string = "CERTIFICATE"): string {
+  const lines = Buffer.from(der).toString("base64").match(/.{1,64}/g) ?? [];
+  return `-----BEGIN ${label}-----\n${lines.join("\n")}\n-----END ${label}-----\n`;
+}
+
+/**
+ * Whether data parses as an X.509 certificate (PEM or DER).
+ */
+export function isCertificate(data: string | Uint8Array): boolean {
+  try {
+    new X509Certificate(data);
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+async function readCached(path: string): Promise<string | undefined> {
+  try {
+    const pem = await readFile(path, "utf8");
+    // A truncated or corrupted entry is refetched and overwritten.
+    return isCertificate(pem) ? pem : undefined;
+  } catch {
+    return undefined;
+  }
+}
+
+async function writeCached(path: string, dir: string, pem: string): Promise<void> {
+  try {
+    await mkdir(dir, { recursive: true });
+    const temporary = `${path}.${process.pid}.tmp`;
+    await writeFile(temporary, pem, "utf8");
+    await rename(temporary, path);
+  } catch {
+    // The cache is an optimization; an unwritable directory is not an error.
+  }
+}
+
+async function download(
+  url: string,
+  options: VcekOptions,
+  check: (body: Uint8Array) => string | undefined
+): Promise<{ body: Uint8Array; cacheControl: string | null }> {
+  const retries = options.retries ?? 2;
+  const timeoutMs = options.timeoutMs ?? 10000;
+  let delay = options.retryDelayMs ?? 500;
+  let lastError = "";
+
+  for (let attempt = 0; attempt <= retries; attempt++) {
+    if (attempt > 0) {
+      await new Promise((resolve) => setTimeout(resolve, delay));
+      delay *= 2;
+    }
+
+    let response: Response;
+    try {
+      response = await fetch(url, { signal: AbortSignal.timeout(timeoutMs) });
+    } catch (error) {
+      lastError = error instanceof Error ? error.message : String(error);
+      continue;
+    }
+
+    if (response.ok) {
+      const body = new Uint8Array(await response.arrayBuffer());
+      const problem = check(body);
+      if (problem !== undefined) {
+        throw new Error(`KDS returned a body that is not ${problem}`);
+      }
+      return { body, cacheControl: response.headers.get("cache-control") };
+    }
+
+    lastError = `KDS returned HTTP ${response.status}`;
+    if (response.status !== 429 && response.status < 500) {
+      break;
+    }
+  }
+
+  throw new Error(lastError);
+}
+
+/**
+ * Fetch the VCEK certificate for a SEV-SNP report from AMD KDS, using the
+ * on-disk cache when it already holds the certificate for the report's
+ * chip_id and reported TCB.
+ * @param report - Raw attestation report (hardware, not simulator)
+ * @param options - KDS, cache, timeout, and retry options
+ * @returns PEM-encoded VCEK certificate
+ * @throws Error if the report is not a hardware report or the fetch fails
+ */
+export async function fetchVcek(
+  report: Uint8Array,
+  options: VcekOptions = {}
+): Promise<string> {
+  const url = vcekUrl(report, options);
+  const dir = options.cacheDir ?? join(homedir(), ".cache", "axiom-core", "vcek");
+  const path = join(dir, `${options.product ?? "Milan"}-${vcekCacheKey(report)}.pem`);
+
+  const cached = await readCached(path);
+  if (cached !== undefined) {
+    return cached;
+  }
+
+  const { body } = await download(url, options, (der) =>
+    isCertificate(der) ? undefined : "an X.509 certificate"
+  );
+  const pem = derToPem(body);
+  await writeCached(path, dir, pem);
+  return pem;
+}
+
+/**
+ * URL of the AMD CRL for a processor family: revoked ASKs (and ARKs),
+ * signed by the family's ARK.
+ */
+export function crlUrl(options: VcekOptions = {}): string {
+  return `${kdsBase(options)}/vcek/v1/${options.product ?? "Milan"}/crl`;
+}
+
+const crlCache = new Map<string, { crl: Uint8Array; expiresAt: number }>();
+
+/**
+ * Fetch the AMD CRL for a processor family from the KDS. A fetched CRL is
+ * kept in memory for the response's Cache-Control max-age (crlMaxAgeMs if
+ * it sets none) and reused until then.
+ * @param options - KDS, timeout, retry, and cache lifetime options
+ * @returns DER-encoded CRL (check it with crlStatus)
+ * @throws Error if the fetch fails or the body is not a CRL
+ */
+export async function fetchCrl(options: VcekOptions = {}): Promise<Uint8Array> {
+  const url = crlUrl(options);
+  const cached = crlCache.get(url);
+  if (cached !== undefined && Date.now() < cached.expiresAt) {
+    return cached.crl;
+  }
+
+  const { body, cacheControl } = await download(url, options, (der) => {
+    try {
+      parseCrl(der);
+      return undefined;
+    } catch {
+      return "an X.509 CRL";
+    }
+  });
+  const maxAge = /(?:^|[,\s])max-age=(\d+)/i.exec(cacheControl ?? "");
+  const maxAgeMs =
+    maxAge !== null ? Number(maxAge[1]) * 1000 : options.crlMaxAgeMs ?? DEFAULT_CRL_MAX_AGE_MS;
+  crlCache.set(url, { crl: body, expiresAt: Date.now() + maxAgeMs });
+  return body;
+}
@@ -0,0 +1,515 @@
+/**
+ * COSE evidence: the attestation payload as a COSE_Sign1 structure
+ * (RFC 9052). Simulator evidence is signed with the simulator Ed25519 key;
+ * hardware evidence is unsigned, with the SEV-SNP report as its authority.
+ * Only the CBOR subset COSE_Sign1 needs is supported: integers, byte and
+ * text strings, arrays, maps, and tag 18, all with definite lengths.
+ */
+import { createPublicKey, sign, verify as verifySignature } from "crypto";
+import type { KeyObject } from "crypto";
+import { BINDING_VERSIONS, HASH_ALGORITHMS, HASH_DIGEST_BYTES } from "../core/canonical.ts";
+import type { BindingVersion, HashAlgorithm } from "../core/canonical.ts";
+import { AttestationError } from "../core/errors.ts";
+import { isSimulatorVersion, parseReport } from "./report.ts";
+import { EVIDENCE_PLATFORMS } from "./types.ts";
+import type {
+  CoseEvidencePayload,
+  CoseVerificationResult,
+  EvidencePlatform,
+  ExpectedBinding,
+} from "./types.ts";
+
+/**
+ * CBOR tag of a COSE_Sign1 structure.
+ */
+export const COSE_SIGN1_TAG = 18;
+
+/**
+ * COSE algorithm identifier for EdDSA (Ed25519 here).
+ */
+export const COSE_ALG_EDDSA = -8;
+
+// Protected header label for the algorithm
+const HEADER_ALG = 1;
+const ED25519_SIGNATURE_BYTES = 64;
+const SESSION_ID_BYTES = 16;
+const MAX_DEPTH = 8;
+
+/**
+ * CBOR values this module encodes and decodes.
+ */
+export type CborValue =
+  | number
+  | string
+  | Uint8Array
+  | CborValue[]
+  | Map<number | string, CborValue>;
+
+/**
+ * Parts of a COSE_Sign1 structure, checked for shape but not signature.
+ */
+export interface Sign1 {
+  protectedHeader: Uint8Array;
+  body: Uint8Array;
+  signature: Uint8Array;
+  signed: boolean;
+}
+
+/**
+ * Build COSE evidence for a payload. With a signing key the structure is
+ * signed (EdDSA over the Sig_structure); without one it is left unsigned.
+ * @param payload - Report, measurement, and binding fields
+ * @param signingKey - Ed25519 private key (simulator evidence only)
+ * @returns CBOR-encoded, tagged COSE_Sign1
+ */
+export function createCoseEvidence(
+  payload: CoseEvidencePayload,
+  signingKey?: KeyObject
+): Uint8Array {
+  return createSign1(encodeCbor(payloadToCbor(payload)), signingKey);
+}
+
+/**
+ * Wrap a payload in a tagged COSE_Sign1, signed with EdDSA over the
+ * Sig_structure when a signing key is given and unsigned otherwise.
+ * @param body - Payload bytes
+ * @param signingKey - Ed25519 private key
+ */
+export function createSign1(body: Uint8Array, signingKey?: KeyObject): Uint8Array {
+  const protectedHeader =
+    signingKey !== undefined
+      ? encodeCbor(new Map<number | string, CborValue>([[HEADER_ALG, COSE_ALG_EDDSA]]))
+      : new Uint8Array(0);
+  const signature =
+    signingKey !== undefined
+      ? new Uint8Array(sign(null, sigStructure(protectedHeader, body), signingKey))
+      : new Uint8Array(0);
+
+  return encodeTagged(COSE_SIGN1_TAG, [protectedHeader, new Map(), body, signature]);
+}
+
+/**
+ * Decode COSE evidence without checking its signature. Signed evidence
+ * must carry a simulator report and unsigned evidence a hardware report.
+ * @param cose - CBOR-encoded COSE_Sign1
+ * @returns Decoded payload, and whether the structure is signed
+ * @throws AttestationError if the structure or payload is malformed
+ */
+export function decodeCoseEvidence(cose: Uint8Array): {
+  payload: CoseEvidencePayload;
+  signed: boolean;
+} {
+  return openSign1(cose).decoded;
+}
+
+/**
+ * Verify COSE evidence: decode it (see decodeCoseEvidence) and, for signed
+ * simulator evidence, check the signature against the simulator key. Pass
+ * the payload to verifyAttestationReport, with coseExpectedBinding, to
+ * check the report binds it.
+ * @param cose - CBOR-encoded COSE_Sign1
+ * @param simulatorKey - Simulator verification key (Ed25519, PEM or SPKI DER)
+ * @returns Validity, signed flag, and the payload when valid
+ */
+export function verifyCoseEvidence(
+  cose: Uint8Array,
+  simulatorKey?: string | Uint8Array
+): CoseVerificationResult {
+  let opened: ReturnType<typeof openSign1>;
+  try {
+    opened = openSign1(cose);
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    return { valid: false, signed: false, reason };
+  }
+  const { payload, signed } = opened.decoded;
+  const reason = checkSign1Signature(opened, simulatorKey);
+  return reason === undefined ? { valid: true, signed, payload } : { valid: false, signed, reason };
+}
+
+/**
+ * Check a COSE_Sign1 signature against the simulator key. Unsigned
+ * structures pass; whether they may be unsigned is the caller's check.
+ * @param sign1 - Structure from readSign1
+ * @param simulatorKey - Ed25519 public key, PEM or SPKI DER
+ * @returns Why the signature fails, or undefined
+ */
+export function checkSign1Signature(
+  sign1: Sign1,
+  simulatorKey: string | Uint8Array | undefined
+): string | undefined {
+  if (!sign1.signed) {
+    return undefined;
+  }
+  if (simulatorKey === undefined) {
+    return "No simulator verification key given";
+  }
+  const key = createPublicKey(
+    typeof simulatorKey === "string"
+      ? simulatorKey
+      : { key: Buffer.from(simulatorKey), format: "der", type: "spki" }
+  );
+  if (key.asymmetricKeyType !== "ed25519") {
+    return "Simulator verification key is not an Ed25519 key";
+  }
+  const toBeSigned = sigStructure(sign1.protectedHeader, sign1.body);
+  return verifySignature(null, toBeSigned, key, sign1.signature)
+    ? undefined
+    : "COSE signature does not verify against the simulator key";
+}
+
+/**
+ * Expected binding for a COSE payload's report, for a second check with
+ * verifyAttestationReport. Simulator payloads allow simulator reports.
+ * @param payload - Payload from verifyCoseEvidence
+ */
+export function coseExpectedBinding(payload: CoseEvidencePayload): ExpectedBinding {
+  return {
+    sessionId: payload.sessionId,
+    configHash: payload.configHash,
+    policyHash: payload.policyHash,
+    outputHash: payload.outputHash,
+    timestamp: payload.timestamp,
+    ...(payload.inputHash !== undefined ? { inputHash: payload.inputHash } : {}),
+    ...(payload.chainedFrom !== undefined ? { chainedFrom: payload.chainedFrom } : {}),
+    hashAlgorithm: payload.hashAlgorithm,
+    bindingVersion: payload.bindingVersion,
+    ...(payload.platform === "simulator" ? { allowSimulator: true } : {}),
+  };
+}
+
+function openSign1(cose: Uint8Array): Sign1 & {
+  decoded: { payload: CoseEvidencePayload; signed: boolean };
+} {
+  const sign1 = readSign1(cose);
+  const payload = payloadFromCbor(decodeCbor(sign1.body));
+  if (sign1.signed !== (payload.platform === "simulator")) {
+    throw new AttestationError(
+      sign1.signed
+        ? "Hardware COSE evidence must be unsigned; its report is the authority"
+        : "Simulator COSE evidence must be signed"
+    );
+  }
+  return { ...sign1, decoded: { payload, signed: sign1.signed } };
+}
+
+/**
+ * Read a tagged COSE_Sign1 and check its shape: four items, an EdDSA
+ * protected header with a 64-byte signature, or an empty protected header
+ * with an empty signature.
+ * @param cose - CBOR-encoded COSE_Sign1
+ * @throws AttestationError if the structure is malformed
+ */
+export function readSign1(cose: Uint8Array): Sign1 {
+  const { tag, value: structure } = decodeTagged(cose);
+  if (tag !== COSE_SIGN1_TAG) {
+    throw new AttestationError("COSE evidence is not a tagged COSE_Sign1 structure");
+  }
+  if (!Array.isArray(structure) || structure.length !== 4) {
+    throw new AttestationError("COSE_Sign1 must be an array of four items");
+  }
+  const [protectedHeader, unprotected, body, signature] = structure;
+  if (
+    !(protectedHeader instanceof Uint8Array) ||
+    !(unprotected instanceof Map) ||
+    !(body instanceof Uint8Array) ||
+    !(signature instanceof Uint8Array)
+  ) {
+    throw new AttestationError("COSE_Sign1 items have the wrong types");
+  }
+
+  const signed = protectedHeader.length > 0;
+  if (signed) {
+    const header = decodeCbor(protectedHeader);
+    if (!(header instanceof Map) || header.get(HEADER_ALG) !== COSE_ALG_EDDSA) {
+      throw new AttestationError("COSE protected header must name EdDSA (-8)");
+    }
+    if (signature.length !== ED25519_SIGNATURE_BYTES) {
+      throw new AttestationError("COSE signature is not 64 bytes");
+    }
+  } else if (signature.length !== 0) {
+    throw new AttestationError("Unsigned COSE_Sign1 carries a signature");
+  }
+  return { protectedHeader, body, signature, signed };
+}
+
+function sigStructure(protectedHeader: Uint8Array, body: Uint8Array): Uint8Array {
+  return encodeCbor(["Signature1", protectedHeader, new Uint8Array(0), body]);
+}
+
+function payloadToCbor(payload: CoseEvidencePayload): Map<number | string, CborValue> {
+  const hex = (value: string) => Uint8Array.from(Buffer.from(value, "hex"));
+  const map = new Map<number | string, CborValue>([
+    ["platform", payload.platform],
+    ["report", payload.report],
+    ["measurement", payload.measurement],
+    ["session_id", hex(payload.sessionId)],
+    ["config_hash", hex(payload.configHash)],
+    ["policy_hash", hex(payload.policyHash)],
+    ["output_hash", hex(payload.outputHash)],
+    ["timestamp", payload.timestamp],
+    ["hash_algorithm", payload.hashAlgorithm],
+    ["binding_version", payload.bindingVersion],
+  ]);
+  if (payload.inputHash !== undefined) {
+    map.set("input_hash", hex(payload.inputHash));
+  }
+  if (payload.chainedFrom !== undefined) {
+    map.set("chained_from", hex(payload.chainedFrom));
+  }
+  return map;
+}
+
+function payloadFromCbor(value: CborValue): CoseEvidencePayload {
+  if (!(value instanceof Map)) {
+    throw new AttestationError("COSE payload must be a map");
+  }
+  const text = (key: string): string => {
+    const field = value.get(key);
+    if (typeof field !== "string") {
+      throw new AttestationError(`COSE payload ${key} must be a text string`);
+    }
+    return field;
+  };
+  const bytes = (key: string, lengths: number[]): string => {
+    const field = value.get(key);
+    if (!(field instanceof Uint8Array) || !lengths.includes(field.length)) {
+      throw new AttestationError(`COSE payload ${key} must be a ${lengths.join(" or ")}-byte string`);
+    }
+    return Buffer.from(field).toString("hex");
+  };
+  const optionalBytes = (key: string, lengths: number[]) =>
+    value.has(key) ? bytes(key, lengths) : undefined;
+
+  const platform = text("platform");
+  if (!EVIDENCE_PLATFORMS.includes(platform as EvidencePlatform)) {
+    throw new AttestationError(`Unknown COSE payload platform: ${platform}`);
+  }
+  const report = value.get("report");
+  if (!(report instanceof Uint8Array)) {
+    throw new AttestationError("COSE payload report must be a byte string");
+  }
+  let simulator: boolean;
+  try {
+    simulator = isSimulatorVersion(parseReport(report).version);
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    throw new AttestationError(`COSE payload report does not parse: ${reason}`);
+  }
+  if ((platform === "simulator") !== simulator) {
+    const kind = simulator ? "simulator" : "hardware";
+    throw new AttestationError(`COSE payload platform ${platform} does not match a ${kind} report`);
+  }
+  const hashAlgorithm = text("hash_algorithm");
+  if (!HASH_ALGORITHMS.includes(hashAlgorithm as HashAlgorithm)) {
+    throw new AttestationError(`Unknown COSE payload hash_algorithm: ${hashAlgorithm}`);
+  }
+  const bindingVersion = value.get("binding_version");
+  if (!BINDING_VERSIONS.includes(bindingVersion as BindingVersion)) {
+    throw new AttestationError(`Unknown COSE payload binding_version: ${String(bindingVersion)}`);
+  }
+  const timestamp = value.get("timestamp");
+  if (typeof timestamp !== "number" || timestamp < 0) {
+    throw new AttestationError("COSE payload timestamp must be an unsigned integer");
+  }
+
+  const digests = [...new Set(Object.values(HASH_DIGEST_BYTES))];
+  const inputHash = optionalBytes("input_hash", [32]);
+  const chainedFrom = optionalBytes("chained_from", digests);
+  return {
+    platform: platform as EvidencePlatform,
+    report,
+    measurement: text("measurement"),
+    sessionId: bytes("session_id", [SESSION_ID_BYTES]),
+    configHash: bytes("config_hash", [32]),
+    policyHash: bytes("policy_hash", [32]),
+    outputHash: bytes("output_hash", [HASH_DIGEST_BYTES[hashAlgorithm as HashAlgorithm]]),
+    timestamp,
+    ...(inputHash !== undefined ? { inputHash } : {}),
+    ...(chainedFrom !== undefined ? { chainedFrom } : {}),
+    hashAlgorithm: hashAlgorithm as HashAlgorithm,
+    bindingVersion: bindingVersion as BindingVersion,
+  };
+}
+
+/**
+ * Encode a value as deterministic CBOR (RFC 8949 §4.2.1).
+ */
+export function encodeCbor(value: CborValue): Uint8Array {
+  const out: number[] = [];
+  encodeInto(value, out, 0);
+  return Uint8Array.from(out);
+}
+
+function encodeInto(value: CborValue, out: number[], depth: number): void {
+  if (depth > MAX_DEPTH) {
+    throw new AttestationError("CBOR value is nested too deeply");
+  }
+  if (typeof value === "number") {
+    if (!Number.isSafeInteger(value)) {
+      throw new AttestationError(`Cannot encode ${value} as a CBOR integer`);
+    }
+    if (value >= 0) {
+      encodeHead(0, value, out);
+    } else {
+      encodeHead(1, -1 - value, out);
+    }
+  } else if (typeof value === "string") {
+    const bytes = new TextEncoder().encode(value);
+    encodeHead(3, bytes.length, out);
+    out.push(...bytes);
+  } else if (value instanceof Uint8Array) {
+    encodeHead(2, value.length, out);
+    for (const byte of value) {
+      out.push(byte);
+    }
+  } else if (Array.isArray(value)) {
+    encodeHead(4, value.length, out);
+    for (const item of value) {
+      encodeInto(item, out, depth + 1);
+    }
+  } else {
+    // Deterministic encoding: keys in bytewise order of their encoding
+    const entries = [...value.entries()]
+      .map(([key, item]) => ({ key: [...encodeCbor(key)], item }))
+      .sort((a, b) => Buffer.compare(Buffer.from(a.key), Buffer.from(b.key)));
+    encodeHead(5, entries.length, out);
+    for (const entry of entries) {
+      out.push(...entry.key);
+      encodeInto(entry.item, out, depth + 1);
+    }
+  }
+}
+
+/**
+ * Encode a value under a CBOR tag.
+ */
+export function encodeTagged(tag: number, value: CborValue): Uint8Array {
+  const out: number[] = [];
+  encodeHead(6, tag, out);
+  encodeInto(value, out, 0);
+  return Uint8Array.from(out);
+}
+
+/**
+ * Decode one tagged CBOR value that fills bytes exactly.
+ * @throws AttestationError if bytes are not a tag and its value
+ */
+export function decodeTagged(bytes: Uint8Array): { tag: number; value: CborValue } {
+  const reader = { bytes, at: 0 };
+  const head = readHead(reader);
+  if (head.major !== 6) {
+    throw new AttestationError("CBOR value is not tagged");
+  }
+  const value = readValue(reader, 0);
+  if (reader.at !== bytes.length) {
+    throw new AttestationError("CBOR value has trailing bytes");
+  }
+  return { tag: head.argument, value };
+}
+
+/**
+ * Write a head in its shortest form (RFC 8949 §4.2.1).
+ */
+function encodeHead(major: number, argument: number, out: number[]): void {
+  const type = major << 5;
+  if (argument < 24) {
+    out.push(type | argument);
+    return;
+  }
+  const width = argument < 0x100 ? 1 : argument < 0x10000 ? 2 : argument < 0x100000000 ? 4 : 8;
+  out.push(type | { 1: 24, 2: 25, 4: 26, 8: 27 }[width]!);
+  const big = BigInt(argument);
+  for (let shift = BigInt((width - 1) * 8); shift >= 0n; shift -= 8n) {
+    out.push(Number((big >> shift) & 0xffn));
+  }
+}
+
+/**
+ * Decode one CBOR value that fills bytes exactly.
+ * @throws AttestationError outside the supported subset
+ */
+export function decodeCbor(bytes: Uint8Array): CborValue {
+  const reader = { bytes, at: 0 };
+  const value = readValue(reader, 0);
+  if (reader.at !== bytes.length) {
+    throw new AttestationError("CBOR value has trailing bytes");
+  }
+  return value;
+}
+
+type Reader = { bytes: Uint8Array; at: number };
+
+function readHead(reader: Reader): { major: number; argument: number } {
+  if (reader.at >= reader.bytes.length) {
+    throw new AttestationError("CBOR value is truncated");
+  }
+  const initial = reader.bytes[reader.at++];
+  const major = initial >> 5;
+  const info = initial & 0x1f;
+  if (info < 24) {
+    return { major, argument: info };
+  }
+  const width = { 24: 1, 25: 2, 26: 4, 27: 8 }[info];
+  if (width === undefined) {
+    throw new AttestationError("CBOR indefinite lengths and reserved values are not supported");
+  }
+  if (reader.at + width > reader.bytes.length) {
+    throw new AttestationError("CBOR value is truncated");
+  }
+  let argument = 0n;
+  for (let i = 0; i < width; i++) {
+    argument = (argument << 8n) | BigInt(reader.bytes[reader.at++]);
+  }
+  if (argument > BigInt(Number.MAX_SAFE_INTEGER)) {
+    throw new AttestationError("CBOR integer exceeds the safe integer range");
+  }
+  return { major, argument: Number(argument) };
+}
+
+function readValue(reader: Reader, depth: number): CborValue {
+  if (depth > MAX_DEPTH) {
+    throw new AttestationError("CBOR value is nested too deeply");
+  }
+  const { major, argument } = readHead(reader);
+  switch (major) {
+    case 0:
+      return argument;
+    case 1:
+      return -1 - argument;
+    case 2:
+    case 3: {
+      if (reader.at + argument > reader.bytes.length) {
+        throw new AttestationError("CBOR string is truncated");
+      }
+      const bytes = reader.bytes.slice(reader.at, reader.at + argument);
+      reader.at += argument;
+      if (major === 2) {
+        return bytes;
+      }
+      try {
+        return new TextDecoder("utf-8", { fatal: true }).decode(bytes);
+      } catch {
+        throw new AttestationError("CBOR text string is not valid UTF-8");
+      }
+    }
+    case 4:
+      return Array.from({ length: argument }, () => readValue(reader, depth + 1));
+    case 5: {
+      const map = new Map<number | string, CborValue>();
+      for (let i = 0; i < argument; i++) {
+        const key = readValue(reader, depth + 1);
+        if (typeof key !== "number" && typeof key !== "string") {
+          throw new AttestationError("CBOR map keys must be integers or text strings");
+        }
+        if (map.has(key)) {
+          throw new AttestationError(`CBOR map repeats key ${key}`);
+        }
+        map.set(key, readValue(reader, depth + 1));
+      }
+      return map;
+    }
+    default:
+      throw new AttestationError(`Unsupported CBOR major type ${major}`);
+  }
+}
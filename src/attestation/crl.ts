@@ -0,0 +1,341 @@
+/**
+ * Certificate revocation lists for the AMD certificate chain: a DER
+ * parser for the X.509 CRLs the KDS serves, and the revocation status of
+ * the ASK and ARK under one. Fetching lives with the VCEK in certs.ts.
+ */
+import { X509Certificate, constants, verify as verifySignature } from "crypto";
+import { AttestationError } from "../core/errors.ts";
+
+/**
+ * Revocation status of the ASK and ARK: "good" when a current CRL signed
+ * by the ARK lists neither, "revoked" when it lists one, "unavailable"
+ * when no such CRL could be obtained.
+ */
+export const CRL_STATUSES = ["good", "revoked", "unavailable"] as const;
+
+export type CrlStatus = (typeof CRL_STATUSES)[number];
+
+/**
+ * How long a fetched CRL is reused when the KDS sets no max-age.
+ */
+export const DEFAULT_CRL_MAX_AGE_MS = 60 * 60 * 1000;
+
+/**
+ * A parsed CRL.
+ */
+export interface CertificateRevocationList {
+  /**
+   * DER tbsCertList, the bytes the signature covers.
+   */
+  tbs: Uint8Array;
+
+  /**
+   * Signature algorithm OID, dotted.
+   */
+  signatureAlgorithm: string;
+
+  /**
+   * DER signature algorithm parameters, if any.
+   */
+  signatureParameters?: Uint8Array;
+
+  signature: Uint8Array;
+
+  /**
+   * Issue time, milliseconds since the epoch.
+   */
+  thisUpdate: number;
+
+  /**
+   * Time by which a newer CRL is issued, if stated.
+   */
+  nextUpdate?: number;
+
+  /**
+   * Serial numbers of revoked certificates, lowercase hex without
+   * leading zeros.
+   */
+  revokedSerials: string[];
+}
+
+interface Tlv {
+  tag: number;
+  offset: number;
+  start: number;
+  end: number;
+}
+
+function readTlv(der: Uint8Array, offset: number, limit: number = der.length): Tlv {
+  if (offset + 2 > limit) {
+    throw new AttestationError("CRL is truncated");
+  }
+  const tag = der[offset];
+  let length = der[offset + 1];
+  let start = offset + 2;
+  if (length & 0x80) {
+    const bytes = length & 0x7f;
+    if (bytes === 0 || bytes > 4 || start + bytes > limit) {
+      throw new AttestationError("CRL has an invalid length");
+    }
+    length = 0;
+    for (let index = 0; index < bytes; index++) {
+      length = length * 256 + der[start + index];
+    }
+    start += bytes;
+  }
+  const end = start + length;
+  if (end > limit) {
+    throw new AttestationError("CRL is truncated");
+  }
+  return { tag, offset, start, end };
+}
+
+function children(der: Uint8Array, parent: Tlv): Tlv[] {
+  const result: Tlv[] = [];
+  for (let offset = parent.start; offset < parent.end; ) {
+    const child = readTlv(der, offset, parent.end);
+    result.push(child);
+    offset = child.end;
+  }
+  return result;
+}
+
+function expectTag(tlv: Tlv | undefined, tag: number, what: string): Tlv {
+  if (tlv === undefined || tlv.tag !== tag) {
+    throw new AttestationError(`CRL has no ${what}`);
+  }
+  return tlv;
+}
+
+function decodeOid(bytes: Uint8Array): string {
+  const arcs: number[] = [];
+  let value = 0;
+  for (const byte of bytes) {
+    value = value * 128 + (byte & 0x7f);
+    if ((byte & 0x80) === 0) {
+      if (arcs.length === 0) {
+        const first = Math.min(Math.floor(value / 40), 2);
+        arcs.push(first, value - first * 40);
+      } else {
+        arcs.push(value);
+      }
+      value = 0;
+    }
+  }
+  return arcs.join(".");
+}
+
+const UTC_TIME = 0x17;
+const GENERALIZED_TIME = 0x18;
+
+function decodeTime(der: Uint8Array, tlv: Tlv): number {
+  const text = Buffer.from(der.subarray(tlv.start, tlv.end)).toString("latin1");
+  const match =
+    tlv.tag === UTC_TIME
+      ? /^(\d{2})(\d{2})(\d{2})(\d{2})(\d{2})(\d{2})Z$/.exec(text)
+      : /^(\d{4})(\d{2})(\d{2})(\d{2})(\d{2})(\d{2})Z$/.exec(text);
+  if (match === null) {
+    throw new AttestationError(`CRL has an invalid time: ${text}`);
+  }
+  const fields = match.slice(1).map(Number);
+  // UTCTime years 50-99 are 19xx (RFC 5280)
+  const year =
+    tlv.tag === UTC_TIME ? (fields[0] < 50 ? 2000 : 1900) + fields[0] : fields[0];
+  return Date.UTC(year, fields[1] - 1, fields[2], fields[3], fields[4], fields[5]);
+}
+
+function isTime(tlv: Tlv | undefined): tlv is Tlv {
+  return tlv !== undefined && (tlv.tag === UTC_TIME || tlv.tag === GENERALIZED_TIME);
+}
+
+/**
+ * Certificate serial number as CRLs are compared: lowercase hex without
+ * leading zeros.
+ */
+export function normalizeSerial(serial: string): string {
+  return serial.toLowerCase().replace(/^0+(?=.)/, "");
+}
+
+/**
+ * Parse an X.509 CRL (RFC 5280).
+ * @param crl - PEM or DER
+ * @returns Signed bytes, signature, validity, and revoked serial numbers
+ * @throws AttestationError if the CRL does not parse
+ */
+export function parseCrl(crl: string | Uint8Array): CertificateRevocationList {
+  let der: Uint8Array;
+  if (typeof crl === "string") {
+    const match = /-----BEGIN X509 CRL-----([\s\S]*?)-----END X509 CRL-----/.exec(crl);
+    if (match === null) {
+      throw new AttestationError("CRL is not PEM-encoded");
+    }
+    der = new Uint8Array(Buffer.from(match[1].replace(/\s+/g, ""), "base64"));
+  } else {
+    der = crl;
+  }
+
+  const outer = expectTag(readTlv(der, 0), 0x30, "CertificateList");
+  if (outer.end !== der.length) {
+    throw new AttestationError("CRL has trailing bytes");
+  }
+  const [tbs, algorithm, signature] = children(der, outer);
+  expectTag(tbs, 0x30, "tbsCertList");
+  expectTag(algorithm, 0x30, "signature algorithm");
+  expectTag(signature, 0x03, "signature");
+
+  const [oid, parameters] = children(der, algorithm);
+  expectTag(oid, 0x06, "signature algorithm OID");
+  // BIT STRING: one byte of unused bits, then the signature
+  if (signature.end <= signature.start || der[signature.start] !== 0) {
+    throw new AttestationError("CRL signature is not a whole number of bytes");
+  }
+
+  const fields = children(der, tbs);
+  let index = fields[0]?.tag === 0x02 ? 1 : 0;
+  expectTag(fields[index++], 0x30, "inner signature algorithm");
+  expectTag(fields[index++], 0x30, "issuer");
+  const thisUpdate = fields[index++];
+  if (!isTime(thisUpdate)) {
+    throw new AttestationError("CRL has no thisUpdate");
+  }
+  const nextUpdate = isTime(fields[index]) ? fields[index++] : undefined;
+
+  const revokedSerials: string[] = [];
+  if (fields[index]?.tag === 0x30) {
+    for (const entry of children(der, fields[index])) {
+      const serial = expectTag(children(der, entry)[0], 0x02, "revoked serial number");
+      revokedSerials.push(
+        normalizeSerial(Buffer.from(der.subarray(serial.start, serial.end)).toString("hex"))
+      );
+    }
+  }
+
+  return {
+    tbs: der.subarray(tbs.offset, tbs.end),
+    signatureAlgorithm: decodeOid(der.subarray(oid.start, oid.end)),
+    ...(parameters !== undefined && parameters.tag !== 0x05
+      ? { signatureParameters: der.subarray(parameters.offset, parameters.end) }
+      : {}),
+    signature: der.subarray(signature.start + 1, signature.end),
+    thisUpdate: decodeTime(der, thisUpdate),
+    ...(nextUpdate !== undefined ? { nextUpdate: decodeTime(der, nextUpdate) } : {}),
+    revokedSerials,
+  };
+}
+
+const DIGESTS: Record<string, string> = {
+  "2.16.840.1.101.3.4.2.1": "sha256",
+  "2.16.840.1.101.3.4.2.2": "sha384",
+  "2.16.840.1.101.3.4.2.3": "sha512",
+};
+
+const SIGNATURE_DIGESTS: Record<string, string> = {
+  "1.2.840.10045.4.3.2": "sha256",
+  "1.2.840.10045.4.3.3": "sha384",
+  "1.2.840.113549.1.1.11": "sha256",
+  "1.2.840.113549.1.1.12": "sha384",
+};
+
+const RSASSA_PSS = "1.2.840.113549.1.1.10";
+
+// RSASSA-PSS-params (RFC 4055): the digest and salt length; MGF1 uses the
+// same digest
+function pssParameters(parameters: Uint8Array | undefined): {
+  digest: string;
+  saltLength: number;
+} {
+  let digest = "sha1";
+  let saltLength = 20;
+  if (parameters === undefined) {
+    return { digest, saltLength };
+  }
+  for (const field of children(parameters, readTlv(parameters, 0))) {
+    const [inner] = children(parameters, field);
+    if (field.tag === 0xa0) {
+      const [oid] = children(parameters, expectTag(inner, 0x30, "PSS hash algorithm"));
+      const name = DIGESTS[decodeOid(parameters.subarray(oid.start, oid.end))];
+      if (name === undefined) {
+        throw new AttestationError("CRL uses an unsupported PSS hash algorithm");
+      }
+      digest = name;
+    } else if (field.tag === 0xa2) {
+      saltLength = Number(
+        BigInt(`0x${Buffer.from(parameters.subarray(inner.start, inner.end)).toString("hex")}`)
+      );
+    }
+  }
+  return { digest, saltLength };
+}
+
+/**
+ * Check a CRL's signature against the certificate that issued it.
+ * @param crl - Parsed CRL
+ * @param issuer - Issuing certificate (the ARK for AMD CRLs), PEM or DER
+ * @returns True if the signature verifies
+ * @throws AttestationError for an unsupported signature algorithm
+ */
+export function verifyCrlSignature(
+  crl: CertificateRevocationList,
+  issuer: string | Uint8Array
+): boolean {
+  const key = new X509Certificate(issuer).publicKey;
+  if (crl.signatureAlgorithm === RSASSA_PSS) {
+    const { digest, saltLength } = pssParameters(crl.signatureParameters);
+    return verifySignature(
+      digest,
+      crl.tbs,
+      { key, padding: constants.RSA_PKCS1_PSS_PADDING, saltLength },
+      crl.signature
+    );
+  }
+  const digest = SIGNATURE_DIGESTS[crl.signatureAlgorithm];
+  if (digest === undefined) {
+    throw new AttestationError(`Unsupported CRL signature algorithm: ${crl.signatureAlgorithm}`);
+  }
+  return verifySignature(digest, crl.tbs, key, crl.signature);
+}
+
+/**
+ * Revocation status of the ASK and ARK under a CRL. The CRL counts only
+ * if it is signed by the ARK and current; otherwise the status is
+ * "unavailable", with the reason.
+ * @param crl - AMD CRL, PEM or DER
+ * @param certificates - ASK and ARK, PEM or DER
+ * @param now - Time to check validity at. Default: now.
+ */
+export function crlStatus(
+  crl: string | Uint8Array,
+  certificates: { ask: string | Uint8Array; ark: string | Uint8Array },
+  now: number = Date.now()
+): { status: CrlStatus; reason?: string } {
+  let parsed: CertificateRevocationList;
+  try {
+    parsed = parseCrl(crl);
+    if (!verifyCrlSignature(parsed, certificates.ark)) {
+      return { status: "unavailable", reason: "CRL signature does not verify under the ARK" };
+    }
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    return { status: "unavailable", reason };
+  }
+  if (now < parsed.thisUpdate) {
+    return { status: "unavailable", reason: "CRL is not yet valid" };
+  }
+  if (parsed.nextUpdate !== undefined && now > parsed.nextUpdate) {
+    return {
+      status: "unavailable",
+      reason: `CRL expired at ${new Date(parsed.nextUpdate).toISOString()}`,
+    };
+  }
+
+  const revoked = new Set(parsed.revokedSerials);
+  for (const [name, pem] of [
+    ["ASK", certificates.ask],
+    ["ARK", certificates.ark],
+  ] as const) {
+    if (revoked.has(normalizeSerial(new X509Certificate(pem).serialNumber))) {
+      return { status: "revoked", reason: `${name} certificate is revoked` };
+    }
+  }
+  return { status: "good" };
+}
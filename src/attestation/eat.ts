@@ -0,0 +1,278 @@
+/**
+ * Entity Attestation Tokens (RFC 9711): the evidence as an EAT claim set,
+ * CBOR-encoded. Simulator tokens are CWTs signed with the simulator
+ * Ed25519 key (COSE_Sign1); hardware tokens are unsigned claim sets (UCCS,
+ * tag 601) carrying the SEV-SNP report, which is their authority.
+ */
+import type { KeyObject } from "crypto";
+import { BINDING_VERSIONS, HASH_ALGORITHMS, HASH_DIGEST_BYTES } from "../core/canonical.ts";
+import type { BindingVersion, HashAlgorithm } from "../core/canonical.ts";
+import { AttestationError, ConfigurationError } from "../core/errors.ts";
+import {
+  COSE_SIGN1_TAG,
+  checkSign1Signature,
+  createSign1,
+  decodeCbor,
+  decodeTagged,
+  encodeCbor,
+  encodeTagged,
+  readSign1,
+} from "./cose.ts";
+import type { CborValue, Sign1 } from "./cose.ts";
+import { isSimulatorVersion, parseReport } from "./report.ts";
+import type { EatClaims, EatVerificationResult, EnclaveResponse } from "./types.ts";
+
+/**
+ * CBOR keys of the claims in an EAT: registered CWT and EAT claims, and
+ * one private-use claim holding what the report binds.
+ */
+export const EAT_CLAIM_KEYS = {
+  iat: 6,
+  eatNonce: 10,
+  oemid: 258,
+  hwmodel: 259,
+  swname: 270,
+  measurements: 273,
+  axiom: -65537,
+} as const;
+
+/**
+ * CBOR tag of an unprotected CWT claim set (UCCS).
+ */
+export const UCCS_TAG = 601;
+
+/**
+ * oemid of SEV-SNP evidence: AMD's IANA Private Enterprise Number.
+ */
+export const AMD_OEMID = 3704;
+
+/**
+ * swname of tokens from the Axiom runner (and its simulator).
+ */
+export const EAT_SWNAME = "axiom-enclave-runner";
+
+// CoAP content format of a measurements entry: application/octet-stream
+const OCTET_STREAM_CONTENT_FORMAT = 42;
+
+/**
+ * Claims for a report and what it binds. oemid, hwmodel, and the
+ * measurement are read from the report.
+ * @param report - Raw attestation report
+ * @param fields - Request nonce and timestamp (ms), and the output hash
+ * @throws AttestationError if the report does not parse
+ */
+export function eatClaimsFor(
+  report: Uint8Array,
+  fields: {
+    nonce: Uint8Array;
+    timestamp: number;
+    outputHash: string;
+    hashAlgorithm: HashAlgorithm;
+    bindingVersion: BindingVersion;
+  }
+): EatClaims {
+  const parsed = parseReport(report);
+  const { cpuidFamId, cpuidModId, cpuidStep } = parsed;
+  return {
+    nonce: new Uint8Array(fields.nonce),
+    issuedAt: Math.floor(fields.timestamp / 1000),
+    oemid: AMD_OEMID,
+    ...(cpuidFamId !== undefined && cpuidModId !== undefined && cpuidStep !== undefined
+      ? { hwmodel: Uint8Array.of(cpuidFamId, cpuidModId, cpuidStep) }
+      : {}),
+    swname: EAT_SWNAME,
+    measurement: new Uint8Array(parsed.measurement),
+    outputHash: fields.outputHash.toLowerCase(),
+    hashAlgorithm: fields.hashAlgorithm,
+    bindingVersion: fields.bindingVersion,
+    report,
+  };
+}
+
+/**
+ * Serialize claims as an EAT: a signed CWT with a signing key, an
+ * unsigned UCCS without one.
+ * @param claims - Token claims
+ * @param signingKey - Ed25519 private key (simulator tokens only)
+ * @returns CBOR-encoded token
+ */
+export function createEatToken(claims: EatClaims, signingKey?: KeyObject): Uint8Array {
+  const claimSet = claimsToCbor(claims);
+  return signingKey !== undefined
+    ? createSign1(encodeCbor(claimSet), signingKey)
+    : encodeTagged(UCCS_TAG, claimSet);
+}
+
+/**
+ * Decode an EAT without checking its signature. Signed tokens must carry
+ * a simulator report and unsigned tokens a hardware report.
+ * @param token - CBOR-encoded token
+ * @returns Claims, and whether the token is signed
+ * @throws AttestationError if the token or its claims are malformed
+ */
+export function decodeEatToken(token: Uint8Array): { claims: EatClaims; signed: boolean } {
+  return openEatToken(token).decoded;
+}
+
+/**
+ * Verify an EAT: decode it (see decodeEatToken) and, for a signed
+ * simulator token, check the signature against the simulator key.
+ * @param token - CBOR-encoded token
+ * @param simulatorKey - Simulator verification key (Ed25519, PEM or SPKI DER)
+ * @returns Validity, signed flag, and the claims when valid
+ */
+export function verifyEatToken(
+  token: Uint8Array,
+  simulatorKey?: string | Uint8Array
+): EatVerificationResult {
+  let opened: ReturnType<typeof openEatToken>;
+  try {
+    opened = openEatToken(token);
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    return { valid: false, signed: false, reason };
+  }
+  const { claims, signed } = opened.decoded;
+  const reason =
+    opened.sign1 !== undefined ? checkSign1Signature(opened.sign1, simulatorKey) : undefined;
+  return reason === undefined ? { valid: true, signed, claims } : { valid: false, signed, reason };
+}
+
+/**
+ * The EAT of a response made with evidenceFormat "eat".
+ * @param response - Enclave response
+ * @throws ConfigurationError if the response carries no token
+ */
+export function getEatToken(response: EnclaveResponse): Uint8Array {
+  if (response.eatToken === undefined) {
+    throw new ConfigurationError('Response carries no EAT token; request evidenceFormat "eat"');
+  }
+  return response.eatToken;
+}
+
+function openEatToken(token: Uint8Array): {
+  sign1?: Sign1;
+  decoded: { claims: EatClaims; signed: boolean };
+} {
+  const { tag, value } = decodeTagged(token);
+  let sign1: Sign1 | undefined;
+  let claimSet: CborValue;
+  if (tag === COSE_SIGN1_TAG) {
+    sign1 = readSign1(token);
+    if (!sign1.signed) {
+      throw new AttestationError("An unsigned EAT must be a UCCS, not a COSE_Sign1");
+    }
+    claimSet = decodeCbor(sign1.body);
+  } else if (tag === UCCS_TAG) {
+    claimSet = value;
+  } else {
+    throw new AttestationError(`EAT has unexpected CBOR tag ${tag}`);
+  }
+
+  const claims = claimsFromCbor(claimSet);
+  const signed = sign1 !== undefined;
+  let simulator: boolean;
+  try {
+    simulator = isSimulatorVersion(parseReport(claims.report).version);
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    throw new AttestationError(`EAT report does not parse: ${reason}`);
+  }
+  if (signed !== simulator) {
+    throw new AttestationError(
+      signed
+        ? "A hardware EAT must be unsigned; its report is the authority"
+        : "A simulator EAT must be signed"
+    );
+  }
+  return { ...(sign1 !== undefined ? { sign1 } : {}), decoded: { claims, signed } };
+}
+
+function claimsToCbor(claims: EatClaims): Map<number | string, CborValue> {
+  const axiom = new Map<number | string, CborValue>([
+    ["output_hash", Uint8Array.from(Buffer.from(claims.outputHash, "hex"))],
+    ["hash_algorithm", claims.hashAlgorithm],
+    ["binding_version", claims.bindingVersion],
+    ["report", claims.report],
+  ]);
+  const map = new Map<number | string, CborValue>([
+    [EAT_CLAIM_KEYS.iat, claims.issuedAt],
+    [EAT_CLAIM_KEYS.eatNonce, claims.nonce],
+    [EAT_CLAIM_KEYS.oemid, claims.oemid],
+    [EAT_CLAIM_KEYS.swname, claims.swname],
+    [EAT_CLAIM_KEYS.measurements, [[OCTET_STREAM_CONTENT_FORMAT, claims.measurement]]],
+    [EAT_CLAIM_KEYS.axiom, axiom],
+  ]);
+  if (claims.hwmodel !== undefined) {
+    map.set(EAT_CLAIM_KEYS.hwmodel, claims.hwmodel);
+  }
+  return map;
+}
+
+function claimsFromCbor(value: CborValue): EatClaims {
+  if (!(value instanceof Map)) {
+    throw new AttestationError("EAT claim set must be a map");
+  }
+  const axiom = value.get(EAT_CLAIM_KEYS.axiom);
+  if (!(axiom instanceof Map)) {
+    throw new AttestationError("EAT is missing the Axiom claim");
+  }
+  const bytes = (map: Map<number | string, CborValue>, key: number | string, name: string) => {
+    const field = map.get(key);
+    if (!(field instanceof Uint8Array)) {
+      throw new AttestationError(`EAT ${name} must be a byte string`);
+    }
+    return field;
+  };
+  const uint = (key: number, name: string) => {
+    const field = value.get(key);
+    if (typeof field !== "number" || field < 0) {
+      throw new AttestationError(`EAT ${name} must be an unsigned integer`);
+    }
+    return field;
+  };
+
+  const swname = value.get(EAT_CLAIM_KEYS.swname);
+  if (typeof swname !== "string") {
+    throw new AttestationError("EAT swname must be a text string");
+  }
+  const measurements = value.get(EAT_CLAIM_KEYS.measurements);
+  const entry = Array.isArray(measurements) && measurements.length === 1 ? measurements[0] : [];
+  const measurement = Array.isArray(entry) && entry.length === 2 ? entry[1] : undefined;
+  if (
+    !Array.isArray(entry) ||
+    entry[0] !== OCTET_STREAM_CONTENT_FORMAT ||
+    !(measurement instanceof Uint8Array)
+  ) {
+    throw new AttestationError("EAT measurements must hold one octet-stream measurement");
+  }
+
+  const hashAlgorithm = axiom.get("hash_algorithm");
+  if (!HASH_ALGORITHMS.includes(hashAlgorithm as HashAlgorithm)) {
+    throw new AttestationError(`Unknown EAT hash_algorithm: ${String(hashAlgorithm)}`);
+  }
+  const bindingVersion = axiom.get("binding_version");
+  if (!BINDING_VERSIONS.includes(bindingVersion as BindingVersion)) {
+    throw new AttestationError(`Unknown EAT binding_version: ${String(bindingVersion)}`);
+  }
+  const outputHash = bytes(axiom, "output_hash", "output_hash");
+  if (outputHash.length !== HASH_DIGEST_BYTES[hashAlgorithm as HashAlgorithm]) {
+    throw new AttestationError(`EAT output_hash is not a ${hashAlgorithm} digest`);
+  }
+  const hwmodel = value.has(EAT_CLAIM_KEYS.hwmodel)
+    ? bytes(value, EAT_CLAIM_KEYS.hwmodel, "hwmodel")
+    : undefined;
+
+  return {
+    nonce: bytes(value, EAT_CLAIM_KEYS.eatNonce, "eat_nonce"),
+    issuedAt: uint(EAT_CLAIM_KEYS.iat, "iat"),
+    oemid: uint(EAT_CLAIM_KEYS.oemid, "oemid"),
+    ...(hwmodel !== undefined ? { hwmodel } : {}),
+    swname,
+    measurement,
+    outputHash: Buffer.from(outputHash).toString("hex"),
+    hashAlgorithm: hashAlgorithm as HashAlgorithm,
+    bindingVersion: bindingVersion as BindingVersion,
+    report: bytes(axiom, "report", "report"),
+  };
+}
@@ -0,0 +1,208 @@
+/**
+ * Evidence envelopes: an attestation report (or TDX quote) together with
+ * the platform, format, certificates, and binding formula it was produced
+ * under.
+ */
+import { BINDING_VERSIONS } from "../core/canonical.ts";
+import type { BindingVersion } from "../core/canonical.ts";
+import { AttestationError } from "../core/errors.ts";
+import type { CertChain } from "./certs.ts";
+import { isSimulatorVersion, parseReport, reportLayout } from "./report.ts";
+import { isSimulatorQuote, isTdxQuote, parseTdxQuote } from "./tdx.ts";
+import { BINDING_FORMULAS, EVIDENCE_FORMATS, EVIDENCE_PLATFORMS } from "./types.ts";
+import type {
+  BindingFormula,
+  EvidenceEnvelope,
+  EvidenceFormat,
+  EvidencePlatform,
+  ExpectedBinding,
+} from "./types.ts";
+
+const CERTIFICATE_KINDS = ["vcek", "ask", "ark"] as const;
+
+/**
+ * Platform and format of raw evidence: a TDX quote (simulator quotes are
+ * marked by their qe_vendor_id) or a SEV-SNP report (simulator reports by
+ * their version).
+ * @param report - Raw attestation report or TDX quote
+ * @throws AttestationError if the bytes do not parse
+ */
+export function evidenceKind(report: Uint8Array): {
+  platform: EvidencePlatform;
+  format: EvidenceFormat;
+} {
+  if (isTdxQuote(report)) {
+    return {
+      platform: isSimulatorQuote(parseTdxQuote(report)) ? "simulator" : "tdx",
+      format: "tdx-quote-v4",
+    };
+  }
+  const version = parseReport(report).version;
+  return {
+    platform: isSimulatorVersion(version) ? "simulator" : "sev-snp",
+    format: `snp-report-v${reportLayout(version)}`,
+  };
+}
+
+/**
+ * Wrap a raw attestation report in an evidence envelope. Platform and
+ * format are read from the report (see evidenceKind).
+ * @param report - Raw attestation report or TDX quote
+ * @param bindingVersion - Binding formula report_data was computed with
+ * @param certificates - DER certificates that came with the report
+ * @returns Evidence envelope
+ * @throws AttestationError if the report does not parse
+ */
+export function createEvidenceEnvelope(
+  report: Uint8Array,
+  bindingVersion: BindingVersion,
+  certificates: CertChain = {}
+): EvidenceEnvelope {
+  const kind = evidenceKind(report);
+  const encoded: Record<string, string> = {};
+  for (const kind of CERTIFICATE_KINDS) {
+    const der = certificates[kind];
+    if (der !== undefined) {
+      encoded[kind] = Buffer.from(der).toString("base64");
+    }
+  }
+  const bindingFormula: BindingFormula = `v${bindingVersion}`;
+  return {
+    ...kind,
+    reportB64: Buffer.from(report).toString("base64"),
+    ...(Object.keys(encoded).length > 0 ? { certificates: encoded } : {}),
+    bindingFormula,
+  };
+}
+
+/**
+ * Contents of an evidence envelope, checked against each other.
+ */
+export interface OpenedEvidence {
+  report: Uint8Array;
+  certificates: CertChain;
+  bindingVersion: BindingVersion;
+  simulator: boolean;
+}
+
+/**
+ * Decode an evidence envelope and check that its platform and format
+ * match the report it carries.
+ * @param envelope - Evidence envelope (for example from evidenceFromJson)
+ * @returns Report bytes, DER certificates, and binding version
+ * @throws AttestationError for an unknown platform, format, or binding
+ *         formula, a report that does not parse, or a mismatch
+ */
+export function openEvidence(envelope: EvidenceEnvelope): OpenedEvidence {
+  if (!EVIDENCE_PLATFORMS.includes(envelope.platform)) {
+    throw new AttestationError(`Unknown evidence platform: ${envelope.platform}`);
+  }
+  if (!EVIDENCE_FORMATS.includes(envelope.format)) {
+    throw new AttestationError(`Unknown evidence format: ${envelope.format}`);
+  }
+  if (!BINDING_FORMULAS.includes(envelope.bindingFormula)) {
+    throw new AttestationError(`Unknown binding formula: ${envelope.bindingFormula}`);
+  }
+  if (typeof envelope.reportB64 !== "string") {
+    throw new AttestationError("Evidence carries no report");
+  }
+
+  const report = new Uint8Array(Buffer.from(envelope.reportB64, "base64"));
+  const { platform, format } = evidenceKind(report);
+  const simulator = platform === "simulator";
+  if (envelope.platform !== platform) {
+    const kind = simulator
+      ? "simulator report"
+      : platform === "tdx"
+        ? "TDX quote"
+        : "hardware report";
+    throw new AttestationError(`Evidence platform ${envelope.platform} does not match a ${kind}`);
+  }
+  if (envelope.format !== format) {
+    const kind = format === "tdx-quote-v4" ? "TDX quote" : `version ${format.slice(-1)} report`;
+    throw new AttestationError(`Evidence format ${envelope.format} does not match a ${kind}`);
+  }
+
+  const certificates: CertChain = {};
+  for (const kind of CERTIFICATE_KINDS) {
+    const encoded = envelope.certificates?.[kind];
+    if (encoded !== undefined) {
+      certificates[kind] = new Uint8Array(Buffer.from(encoded, "base64"));
+    }
+  }
+  const bindingVersion = BINDING_VERSIONS[BINDING_FORMULAS.indexOf(envelope.bindingFormula)];
+  return { report, certificates, bindingVersion, simulator };
+}
+
+/**
+ * Merge an opened envelope into an expected binding: its certificates
+ * and binding version fill in what the caller left out.
+ * @param evidence - Opened evidence envelope
+ * @param expected - Expected binding
+ * @returns Expected binding for the envelope's report
+ * @throws AttestationError if expected names a different binding version
+ */
+export function expectedForEvidence(
+  evidence: OpenedEvidence,
+  expected: ExpectedBinding
+): ExpectedBinding {
+  const claimed = expected.bindingVersion;
+  if (claimed !== undefined && claimed !== evidence.bindingVersion) {
+    throw new AttestationError(
+      `Evidence binding formula v${evidence.bindingVersion} does not match bindingVersion ${claimed}`
+    );
+  }
+  const certificates = expected.certificates ?? evidence.certificates;
+  return {
+    ...expected,
+    ...(Object.keys(certificates).length > 0 ? { certificates } : {}),
+    bindingVersion: evidence.bindingVersion,
+  };
+}
+
+/**
+ * Report and expected binding to verify, from a raw report or an
+ * envelope (see openEvidence and expectedForEvidence).
+ * @param evidence - Raw attestation report or evidence envelope
+ * @param expected - Expected binding
+ * @throws AttestationError if the envelope is invalid or inconsistent
+ */
+export function resolveEvidence(
+  evidence: Uint8Array | EvidenceEnvelope,
+  expected: ExpectedBinding
+): { report: Uint8Array; expected: ExpectedBinding } {
+  if (evidence instanceof Uint8Array) {
+    return { report: evidence, expected };
+  }
+  const opened = openEvidence(evidence);
+  return { report: opened.report, expected: expectedForEvidence(opened, expected) };
+}
+
+/**
+ * Serialize an evidence envelope as JSON (camelCase keys).
+ */
+export function evidenceToJson(envelope: EvidenceEnvelope): string {
+  return JSON.stringify(envelope);
+}
+
+/**
+ * Parse an evidence envelope from JSON and check it (see openEvidence).
+ * @param json - JSON from evidenceToJson or a runner
+ * @returns Evidence envelope
+ * @throws AttestationError if the JSON is not a valid envelope
+ */
+export function evidenceFromJson(json: string): EvidenceEnvelope {
+  let value: unknown;
+  try {
+    value = JSON.parse(json);
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    throw new AttestationError(`Evidence is not valid JSON: ${reason}`);
+  }
+  if (typeof value !== "object" || value === null || Array.isArray(value)) {
+    throw new AttestationError("Evidence must be a JSON object");
+  }
+  const envelope = value as EvidenceEnvelope;
+  openEvidence(envelope);
+  return envelope;
+}
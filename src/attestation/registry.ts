@@ -0,0 +1,128 @@
+/**
+ * Measurement registries: signed lists of the enclave measurements
+ * operations has approved (typically the current release and the one
+ * before it). A runner given one refuses to attest unless its own
+ * measurement is listed.
+ */
+import { createPublicKey, sign, verify as verifySignature } from "crypto";
+import type { KeyObject } from "crypto";
+import { readFileSync } from "fs";
+import { canonicalJson } from "../core/canonical.ts";
+import { ConfigurationError } from "../core/errors.ts";
+
+/**
+ * Registry format version this module reads and writes.
+ */
+export const MEASUREMENT_REGISTRY_VERSION = 1;
+
+/**
+ * A registry file: the approved measurements (96 lowercase hex characters
+ * each) and an Ed25519 signature, base64-encoded, over the JCS
+ * serialization of the other two fields.
+ */
+export interface MeasurementRegistry {
+  version: number;
+  measurements: string[];
+  signature: string;
+}
+
+/**
+ * Load the public key registries are verified with.
+ * @param path - PEM (SPKI) Ed25519 public key
+ * @throws ConfigurationError if the key does not load or is not Ed25519
+ */
+export function loadRegistryKey(path: string): KeyObject {
+  let key: KeyObject;
+  try {
+    key = createPublicKey(readFileSync(path, "utf8"));
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    throw new ConfigurationError(`Cannot load measurement registry key from ${path}: ${reason}`);
+  }
+  if (key.asymmetricKeyType !== "ed25519") {
+    throw new ConfigurationError(`Measurement registry key at ${path} is not an Ed25519 key`);
+  }
+  return key;
+}
+
+function signedBytes(measurements: string[]): Buffer {
+  return Buffer.from(
+    canonicalJson({ version: MEASUREMENT_REGISTRY_VERSION, measurements }),
+    "utf8"
+  );
+}
+
+/**
+ * Build a registry file for a set of measurements.
+ * @param measurements - Approved measurements, 48 bytes of hex each
+ * @param privateKey - Ed25519 key the registry is signed with
+ * @returns Registry, ready for JSON.stringify
+ * @throws ConfigurationError if a measurement is not 48 bytes of hex
+ */
+export function signMeasurementRegistry(
+  measurements: string[],
+  privateKey: KeyObject
+): MeasurementRegistry {
+  const normalized = normalizeMeasurements(measurements);
+  return {
+    version: MEASUREMENT_REGISTRY_VERSION,
+    measurements: normalized,
+    signature: sign(null, signedBytes(normalized), privateKey).toString("base64"),
+  };
+}
+
+/**
+ * Read and verify a registry file.
+ * @param path - Registry JSON file
+ * @param publicKey - Ed25519 key the registry must be signed with
+ * @returns The approved measurements, lowercase
+ * @throws ConfigurationError if the file does not load, is malformed,
+ *         is unsigned, or its signature does not verify
+ */
+export function loadMeasurementRegistry(path: string, publicKey: KeyObject): Set<string> {
+  let registry: Partial<MeasurementRegistry>;
+  try {
+    registry = JSON.parse(readFileSync(path, "utf8")) as Partial<MeasurementRegistry>;
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    throw new ConfigurationError(`Cannot load measurement registry from ${path}: ${reason}`);
+  }
+  if (registry === null || typeof registry !== "object") {
+    throw new ConfigurationError(`Measurement registry at ${path} is not a JSON object`);
+  }
+  if (registry.version !== MEASUREMENT_REGISTRY_VERSION) {
+    throw new ConfigurationError(
+      `Unsupported measurement registry version ${registry.version} at ${path}`
+    );
+  }
+  if (!Array.isArray(registry.measurements)) {
+    throw new ConfigurationError(`Measurement registry at ${path} has no measurements list`);
+  }
+  if (typeof registry.signature !== "string" || registry.signature === "") {
+    throw new ConfigurationError(`Measurement registry at ${path} is not signed`);
+  }
+  // The signature covers the measurements as written
+  const valid = verifySignature(
+    null,
+    signedBytes(registry.measurements),
+    publicKey,
+    Buffer.from(registry.signature, "base64")
+  );
+  if (!valid) {
+    throw new ConfigurationError(
+      `Measurement registry at ${path} does not verify under the registry key`
+    );
+  }
+  return new Set(normalizeMeasurements(registry.measurements));
+}
+
+function normalizeMeasurements(measurements: unknown[]): string[] {
+  return measurements.map((measurement) => {
+    if (typeof measurement !== "string" || !/^[0-9a-f]{96}$/i.test(measurement)) {
+      throw new ConfigurationError(
+        `Invalid registry measurement ${JSON.stringify(measurement)}: must be 48 bytes of hex`
+      );
+    }
+    return measurement.toLowerCase();
+  });
+}
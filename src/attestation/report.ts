@@ -0,0 +1,603 @@
+/**
+ * Typed AMD SEV-SNP attestation report. This module is the one place that
+ * knows the report layouts (versions 2 and 3, 1184 bytes each); the
+ * parser, the VCEK lookup, and the simulator all go through it.
+ */
+import { createHash } from "crypto";
+import { AttestationError } from "../core/errors.ts";
+
+/**
+ * Size of a SEV-SNP attestation report in bytes.
+ */
+export const REPORT_BYTES = 1184;
+
+/**
+ * Default report version, and the one emptyReport() builds.
+ */
+export const REPORT_VERSION = 2;
+
+/**
+ * Report layouts this module reads and writes. Version 3 adds the CPUID
+ * family, model, and stepping of the chip.
+ */
+export const REPORT_VERSIONS = [2, 3] as const;
+
+export type ReportVersion = (typeof REPORT_VERSIONS)[number];
+
+/**
+ * Report version simulator reports with the version 2 layout carry. Its
+ * little-endian bytes read "FAKE", which is how isSimulatorReport
+ * recognizes them.
+ */
+export const SIMULATOR_REPORT_VERSION = 0x454b4146;
+
+/**
+ * Report version simulator reports with the version 3 layout carry
+ * ("FAK3").
+ */
+export const SIMULATOR_REPORT_V3_VERSION = 0x334b4146;
+
+/**
+ * Security version numbers of the firmware components.
+ */
+export interface TcbVersion {
+  bootLoader: number;
+  tee: number;
+  snp: number;
+  microcode: number;
+}
+
+/**
+ * SEV firmware version.
+ */
+export interface FirmwareVersion {
+  major: number;
+  minor: number;
+  build: number;
+}
+
+/**
+ * ECDSA P-384 signature over the first 0x2A0 bytes of the report. Each
+ * component is 72 bytes, little-endian, zero-padded.
+ */
+export interface ReportSignature {
+  r: Uint8Array;
+  s: Uint8Array;
+}
+
+/**
+ * AMD SEV-SNP attestation report.
+ */
+export interface AttestationReport {
+  /** Report format version (2 or 3, or a simulator version). */
+  version: number;
+  /** Guest SVN. */
+  guestSvn: number;
+  /** Guest policy the VM was launched with. */
+  guestPolicy: bigint;
+  /** Family ID from the ID block (16 bytes). */
+  familyId: Uint8Array;
+  /** Image ID from the ID block (16 bytes). */
+  imageId: Uint8Array;
+  /** VMPL the report was requested from. */
+  vmpl: number;
+  /** Signature algorithm (1 = ECDSA P-384 with SHA-384). */
+  signatureAlgo: number;
+  /** TCB the platform is currently running. */
+  currentTcb: TcbVersion;
+  /** Platform information flags (SMT, TSME, ...). */
+  platformInfo: bigint;
+  /** Author key, chip key masking, and signing key selection bits. */
+  keyInfo: number;
+  /**
+   * Guest-supplied data (64 bytes): Axiom's report_data, in all 64 under
+   * binding version 3, or in the first 32 with zero padding under 1 and 2.
+   */
+  reportData: Uint8Array;
+  /** Launch measurement (48 bytes, SHA-384). */
+  measurement: Uint8Array;
+  /** Host-supplied data (32 bytes). */
+  hostData: Uint8Array;
+  /** SHA-384 of the ID block signing key (48 bytes). */
+  idKeyDigest: Uint8Array;
+  /** SHA-384 of the ID block author key (48 bytes). */
+  authorKeyDigest: Uint8Array;
+  /** Report ID of this guest (32 bytes). */
+  reportId: Uint8Array;
+  /** Report ID of the migration agent (32 bytes). */
+  reportIdMa: Uint8Array;
+  /** TCB the VCEK that signed the report was derived from. */
+  reportedTcb: TcbVersion;
+  /** Chip identifier (64 bytes; zero when masked). */
+  chipId: Uint8Array;
+  /** TCB committed as the rollback floor. */
+  committedTcb: TcbVersion;
+  /** Firmware version currently running. */
+  currentVersion: FirmwareVersion;
+  /** Firmware version committed as the rollback floor. */
+  committedVersion: FirmwareVersion;
+  /** TCB at launch. */
+  launchTcb: TcbVersion;
+  /** CPUID family of the chip (version 3 only). */
+  cpuidFamId?: number;
+  /** CPUID model of the chip (version 3 only). */
+  cpuidModId?: number;
+  /** CPUID stepping of the chip (version 3 only). */
+  cpuidStep?: number;
+  /** Report signature. */
+  signature: ReportSignature;
+}
+
+type FieldKind = "u8" | "u32" | "u64" | "tcb" | "firmware" | number;
+
+// Field name, wire (snake_case) name, offset, and kind: an integer type,
+// a composite, or a byte length. Everything not listed is reserved and
+// must be zero.
+const FIELDS_V2: Array<[string, string, number, FieldKind]> = [
+  ["version", "version", 0x00, "u32"],
+  ["guestSvn", "guest_svn", 0x04, "u32"],
+  ["guestPolicy", "guest_policy", 0x08, "u64"],
+  ["familyId", "family_id", 0x10, 16],
+  ["imageId", "image_id", 0x20, 16],
+  ["vmpl", "vmpl", 0x30, "u32"],
+  ["signatureAlgo", "signature_algo", 0x34, "u32"],
+  ["currentTcb", "current_tcb", 0x38, "tcb"],
+  ["platformInfo", "platform_info", 0x40, "u64"],
+  ["keyInfo", "key_info", 0x48, "u32"],
+  ["reportData", "report_data", 0x50, 64],
+  ["measurement", "measurement", 0x90, 48],
+  ["hostData", "host_data", 0xc0, 32],
+  ["idKeyDigest", "id_key_digest", 0xe0, 48],
+  ["authorKeyDigest", "author_key_digest", 0x110, 48],
+  ["reportId", "report_id", 0x140, 32],
+  ["reportIdMa", "report_id_ma", 0x160, 32],
+  ["reportedTcb", "reported_tcb", 0x180, "tcb"],
+  ["chipId", "chip_id", 0x1a0, 64],
+  ["committedTcb", "committed_tcb", 0x1e0, "tcb"],
+  ["currentVersion", "current_version", 0x1e8, "firmware"],
+  ["committedVersion", "committed_version", 0x1ec, "firmware"],
+  ["launchTcb", "launch_tcb", 0x1f0, "tcb"],
+];
+
+const FIELDS_V3: Array<[string, string, number, FieldKind]> = [
+  ...FIELDS_V2,
+  ["cpuidFamId", "cpuid_fam_id", 0x188, "u8"],
+  ["cpuidModId", "cpuid_mod_id", 0x189, "u8"],
+  ["cpuidStep", "cpuid_step", 0x18a, "u8"],
+];
+
+/**
+ * Layout a report version uses. Simulator versions use the layout of the
+ * hardware version they stand in for.
+ * @param version - Value of the report's version field
+ * @throws AttestationError for any other version
+ */
+export function reportLayout(version: number): ReportVersion {
+  switch (version) {
+    case 2:
+    case SIMULATOR_REPORT_VERSION:
+      return 2;
+    case 3:
+    case SIMULATOR_REPORT_V3_VERSION:
+      return 3;
+    default:
+      throw new AttestationError(`unsupported report version ${version}`);
+  }
+}
+
+/**
+ * Whether a report version marks a simulator report.
+ */
+export function isSimulatorVersion(version: number): boolean {
+  return version === SIMULATOR_REPORT_VERSION || version === SIMULATOR_REPORT_V3_VERSION;
+}
+
+function fieldsFor(version: number): Array<[string, string, number, FieldKind]> {
+  return reportLayout(version) === 3 ? FIELDS_V3 : FIELDS_V2;
+}
+
+/**
+ * Length of the report prefix the signature covers.
+ */
+export const SIGNED_REPORT_BYTES = 0x2a0;
+
+/**
+ * Signature algorithm value for ECDSA P-384 with SHA-384.
+ */
+export const SIGNATURE_ALGO_ECDSA_P384_SHA384 = 1;
+
+/**
+ * Signature algorithm value simulator reports carry: Ed25519 under the
+ * simulator's non-production key. Not an AMD value.
+ */
+export const SIGNATURE_ALGO_SIMULATOR_ED25519 = 0x0ed25519;
+
+const SIGNATURE_OFFSET = SIGNED_REPORT_BYTES;
+const SIGNATURE_COMPONENT_BYTES = 72;
+const P384_COMPONENT_BYTES = 48;
+
+function readField(bytes: Uint8Array, view: DataView, offset: number, kind: FieldKind): unknown {
+  switch (kind) {
+    case "u8":
+      return bytes[offset];
+    case "u32":
+      return view.getUint32(offset, true);
+    case "u64":
+      return view.getBigUint64(offset, true);
+    case "tcb":
+      return {
+        bootLoader: bytes[offset],
+        tee: bytes[offset + 1],
+        snp: bytes[offset + 6],
+        microcode: bytes[offset + 7],
+      };
+    case "firmware":
+      return { build: bytes[offset], minor: bytes[offset + 1], major: bytes[offset + 2] };
+    default:
+      return new Uint8Array(bytes.subarray(offset, offset + kind));
+  }
+}
+
+function writeField(
+  bytes: Uint8Array,
+  view: DataView,
+  name: string,
+  offset: number,
+  kind: FieldKind,
+  value: unknown
+): void {
+  if (value === undefined) {
+    throw new Error(`Invalid attestation report: ${name} is missing`);
+  }
+  switch (kind) {
+    case "u8":
+      bytes[offset] = value as number;
+      return;
+    case "u32":
+      view.setUint32(offset, value as number, true);
+      return;
+    case "u64":
+      view.setBigUint64(offset, value as bigint, true);
+      return;
+    case "tcb": {
+      const tcb = value as TcbVersion;
+      bytes.set([tcb.bootLoader, tcb.tee], offset);
+      bytes.set([tcb.snp, tcb.microcode], offset + 6);
+      return;
+    }
+    case "firmware": {
+      const firmware = value as FirmwareVersion;
+      bytes.set([firmware.build, firmware.minor, firmware.major], offset);
+      return;
+    }
+    default: {
+      const field = value as Uint8Array;
+      if (!(field instanceof Uint8Array) || field.length !== kind) {
+        throw new Error(`Invalid attestation report: ${name} must be ${kind} bytes`);
+      }
+      bytes.set(field, offset);
+    }
+  }
+}
+
+/**
+ * Parse a SEV-SNP attestation report, in the layout its version field
+ * selects.
+ * @param bytes - Raw report, exactly REPORT_BYTES long
+ * @returns Typed report; reportToBytes() reproduces bytes exactly
+ * @throws AttestationError on a wrong length, an unsupported version, or a
+ *         non-zero reserved byte
+ */
+export function parseReport(bytes: Uint8Array): AttestationReport {
+  if (bytes.length !== REPORT_BYTES) {
+    throw new AttestationError(
+      `Invalid attestation report: expected ${REPORT_BYTES} bytes, got ${bytes.length}`
+    );
+  }
+
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  const layout = fieldsFor(view.getUint32(0, true));
+  const fields: Record<string, unknown> = {};
+  for (const [name, , offset, kind] of layout) {
+    fields[name] = readField(bytes, view, offset, kind);
+  }
+  fields.signature = {
+    r: readField(bytes, view, SIGNATURE_OFFSET, SIGNATURE_COMPONENT_BYTES),
+    s: readField(bytes, view, SIGNATURE_OFFSET + SIGNATURE_COMPONENT_BYTES, SIGNATURE_COMPONENT_BYTES),
+  };
+  const report = fields as unknown as AttestationReport;
+
+  // Whatever the typed fields do not cover is reserved
+  const rebuilt = reportToBytes(report);
+  const reserved = bytes.findIndex((byte, index) => byte !== rebuilt[index]);
+  if (reserved !== -1) {
+    throw new AttestationError(
+      `Invalid attestation report: reserved byte at offset 0x${reserved.toString(16)} is not zero`
+    );
+  }
+
+  return report;
+}
+
+/**
+ * Serialize a report to the 1184-byte layout of its version. Reserved
+ * bytes are zero.
+ * @param report - Typed report
+ * @returns Raw report
+ * @throws AttestationError for an unsupported version
+ * @throws Error if a field is missing, a byte field has the wrong length,
+ *         or a version 2 report sets version 3 fields
+ */
+export function reportToBytes(report: AttestationReport): Uint8Array {
+  const bytes = new Uint8Array(REPORT_BYTES);
+  const view = new DataView(bytes.buffer);
+  const fields = report as unknown as Record<string, unknown>;
+  const layout = fieldsFor(report.version);
+  for (const [name] of FIELDS_V3.slice(FIELDS_V2.length)) {
+    if (layout === FIELDS_V2 && fields[name] !== undefined) {
+      throw new Error(`Invalid attestation report: ${name} is only in version 3 reports`);
+    }
+  }
+  for (const [name, , offset, kind] of layout) {
+    writeField(bytes, view, name, offset, kind, fields[name]);
+  }
+  writeField(bytes, view, "signature.r", SIGNATURE_OFFSET, SIGNATURE_COMPONENT_BYTES, report.signature.r);
+  writeField(
+    bytes,
+    view,
+    "signature.s",
+    SIGNATURE_OFFSET + SIGNATURE_COMPONENT_BYTES,
+    SIGNATURE_COMPONENT_BYTES,
+    report.signature.s
+  );
+  return bytes;
+}
+
+/**
+ * Lowest acceptable security version numbers. A component left out is
+ * not checked.
+ */
+export type MinimumTcb = Partial<TcbVersion>;
+
+const TCB_COMPONENTS = ["bootLoader", "tee", "snp", "microcode"] as const;
+
+/**
+ * Compare a TCB against a floor.
+ * @param tcb - TCB from a report (usually reportedTcb)
+ * @param minimum - Floor per component
+ * @returns One message per component below its floor; empty if none is
+ * @throws Error if a floor is not an integer from 0 to 255
+ */
+export function tcbBelowMinimum(tcb: TcbVersion, minimum: MinimumTcb): string[] {
+  const below: string[] = [];
+  for (const component of TCB_COMPONENTS) {
+    const floor = minimum[component];
+    if (floor === undefined) {
+      continue;
+    }
+    if (!Number.isInteger(floor) || floor < 0 || floor > 0xff) {
+      throw new Error(`Invalid minimum TCB: ${component} must be an integer from 0 to 255`);
+    }
+    if (tcb[component] < floor) {
+      below.push(`${component} ${tcb[component]} < ${floor}`);
+    }
+  }
+  return below;
+}
+
+/**
+ * Platform fields of a report, as EnclaveResponse.platform carries them.
+ */
+export interface PlatformEvidence {
+  /** TCB the VCEK that signed the report was derived from. */
+  reportedTcb: TcbVersion;
+  /** TCB committed as the rollback floor. */
+  committedTcb: TcbVersion;
+  /** Platform information flags (SMT, TSME, ...). */
+  platformInfo: bigint;
+  /** SHA-256 of chip_id (hex), enough to tell chips apart without the identifier. */
+  chipIdHash: string;
+  /** Raw chip_id (hex). Present only when requested. */
+  chipId?: string;
+}
+
+/**
+ * Extract the platform fields of a report.
+ * @param report - Parsed attestation report
+ * @param includeChipId - Also return the raw chip_id. Default: false.
+ */
+export function extractPlatformEvidence(
+  report: AttestationReport,
+  includeChipId: boolean = false
+): PlatformEvidence {
+  const evidence: PlatformEvidence = {
+    reportedTcb: { ...report.reportedTcb },
+    committedTcb: { ...report.committedTcb },
+    platformInfo: report.platformInfo,
+    chipIdHash: createHash("sha256").update(report.chipId).digest("hex"),
+  };
+  if (includeChipId) {
+    evidence.chipId = Buffer.from(report.chipId).toString("hex");
+  }
+  return evidence;
+}
+
+/**
+ * Convert a report signature to IEEE P1363 form (r || s, 48 bytes each,
+ * big-endian), as crypto.verify takes it with dsaEncoding "ieee-p1363".
+ * @param signature - Report signature
+ * @returns P1363 signature, or undefined if a component does not fit in
+ *          48 bytes
+ */
+export function signatureToP1363(signature: ReportSignature): Uint8Array | undefined {
+  const components = [signature.r, signature.s];
+  const fits = components.every((component) =>
+    component.subarray(P384_COMPONENT_BYTES).every((byte) => byte === 0)
+  );
+  if (!fits) {
+    return undefined;
+  }
+  return Buffer.concat(
+    components.map((component) =>
+      Buffer.from(component.subarray(0, P384_COMPONENT_BYTES)).reverse()
+    )
+  );
+}
+
+/**
+ * Convert an IEEE P1363 P-384 signature (96 bytes) to a report signature.
+ * @param p1363 - r || s, 48 bytes each, big-endian
+ * @throws Error if p1363 is not 96 bytes
+ */
+export function signatureFromP1363(p1363: Uint8Array): ReportSignature {
+  if (p1363.length !== 2 * P384_COMPONENT_BYTES) {
+    throw new Error(`Invalid P-384 signature: expected 96 bytes, got ${p1363.length}`);
+  }
+  const component = (start: number) => {
+    const bytes = new Uint8Array(SIGNATURE_COMPONENT_BYTES);
+    bytes.set(Buffer.from(p1363.subarray(start, start + P384_COMPONENT_BYTES)).reverse());
+    return bytes;
+  };
+  return { r: component(0), s: component(P384_COMPONENT_BYTES) };
+}
+
+/**
+ * Extract an Ed25519 signature (64 bytes) from a simulator report: the
+ * first 32 bytes of r and of s, as written.
+ * @param signature - Report signature
+ * @returns Ed25519 signature, or undefined if r or s has bytes past 32
+ */
+export function signatureToEd25519(signature: ReportSignature): Uint8Array | undefined {
+  const components = [signature.r, signature.s];
+  if (!components.every((component) => component.subarray(32).every((byte) => byte === 0))) {
+    return undefined;
+  }
+  return Buffer.concat(components.map((component) => component.subarray(0, 32)));
+}
+
+/**
+ * Store an Ed25519 signature in the report signature fields (see
+ * signatureToEd25519).
+ * @param ed25519 - 64-byte Ed25519 signature
+ * @throws Error if ed25519 is not 64 bytes
+ */
+export function signatureFromEd25519(ed25519: Uint8Array): ReportSignature {
+  if (ed25519.length !== 64) {
+    throw new Error(`Invalid Ed25519 signature: expected 64 bytes, got ${ed25519.length}`);
+  }
+  const component = (start: number) => {
+    const bytes = new Uint8Array(SIGNATURE_COMPONENT_BYTES);
+    bytes.set(ed25519.subarray(start, start + 32));
+    return bytes;
+  };
+  return { r: component(0), s: component(32) };
+}
+
+/**
+ * A report with every field but the version zero.
+ * @param version - A hardware or simulator report version. Default:
+ *                  REPORT_VERSION.
+ * @throws AttestationError for an unsupported version
+ */
+export function emptyReport(version: number = REPORT_VERSION): AttestationReport {
+  const bytes = new Uint8Array(REPORT_BYTES);
+  new DataView(bytes.buffer).setUint32(0, version, true);
+  return parseReport(bytes);
+}
+
+/**
+ * JSON form of a report, as the runner's parse_report returns it:
+ * snake_case names, byte fields as hex, 64-bit integers as decimal strings.
+ * @param report - Typed report
+ */
+export function reportToJson(report: AttestationReport): Record<string, unknown> {
+  const fields = report as unknown as Record<string, unknown>;
+  const json: Record<string, unknown> = {};
+  for (const [name, wire, , kind] of fieldsFor(report.version)) {
+    const value = fields[name];
+    if (kind === "u64") {
+      json[wire] = (value as bigint).toString();
+    } else if (kind === "tcb") {
+      const tcb = value as TcbVersion;
+      json[wire] = {
+        boot_loader: tcb.bootLoader,
+        tee: tcb.tee,
+        snp: tcb.snp,
+        microcode: tcb.microcode,
+      };
+    } else if (typeof kind === "number") {
+      json[wire] = Buffer.from(value as Uint8Array).toString("hex");
+    } else {
+      json[wire] = value;
+    }
+  }
+  json.signature = {
+    r: Buffer.from(report.signature.r).toString("hex"),
+    s: Buffer.from(report.signature.s).toString("hex"),
+  };
+  return json;
+}
+
+/**
+ * Read a report from its JSON form (see reportToJson).
+ * @param json - Parsed JSON object
+ * @throws AttestationError for an unsupported version
+ * @throws Error if a field is missing or malformed
+ */
+export function reportFromJson(json: Record<string, unknown>): AttestationReport {
+  const fields: Record<string, unknown> = {};
+  const hexBytes = (wire: string, value: unknown, length: number) => {
+    if (typeof value !== "string" || !/^(?:[0-9a-f]{2})*$/i.test(value)) {
+      throw new Error(`Invalid attestation report JSON: ${wire} must be hex`);
+    }
+    const bytes = new Uint8Array(Buffer.from(value, "hex"));
+    if (bytes.length !== length) {
+      throw new Error(`Invalid attestation report JSON: ${wire} must be ${length} bytes`);
+    }
+    return bytes;
+  };
+  const integer = (wire: string, value: unknown, max: number) => {
+    if (typeof value !== "number" || !Number.isInteger(value) || value < 0 || value > max) {
+      throw new Error(`Invalid attestation report JSON: ${wire} must be an integer up to ${max}`);
+    }
+    return value;
+  };
+
+  const layout = fieldsFor(integer("version", json.version, 0xffffffff));
+  for (const [name, wire, , kind] of layout) {
+    const value = json[wire];
+    if (kind === "u8") {
+      fields[name] = integer(wire, value, 0xff);
+    } else if (kind === "u32") {
+      fields[name] = integer(wire, value, 0xffffffff);
+    } else if (kind === "u64") {
+      if (typeof value !== "string" || !/^\d+$/.test(value) || BigInt(value) >= 2n ** 64n) {
+        throw new Error(`Invalid attestation report JSON: ${wire} must be a decimal string`);
+      }
+      fields[name] = BigInt(value);
+    } else if (kind === "tcb") {
+      const tcb = (value ?? {}) as Record<string, unknown>;
+      fields[name] = {
+        bootLoader: integer(`${wire}.boot_loader`, tcb.boot_loader, 0xff),
+        tee: integer(`${wire}.tee`, tcb.tee, 0xff),
+        snp: integer(`${wire}.snp`, tcb.snp, 0xff),
+        microcode: integer(`${wire}.microcode`, tcb.microcode, 0xff),
+      };
+    } else if (kind === "firmware") {
+      const firmware = (value ?? {}) as Record<string, unknown>;
+      fields[name] = {
+        major: integer(`${wire}.major`, firmware.major, 0xff),
+        minor: integer(`${wire}.minor`, firmware.minor, 0xff),
+        build: integer(`${wire}.build`, firmware.build, 0xff),
+      };
+    } else {
+      fields[name] = hexBytes(wire, value, kind);
+    }
+  }
+  const signature = (json.signature ?? {}) as Record<string, unknown>;
+  fields.signature = {
+    r: hexBytes("signature.r", signature.r, SIGNATURE_COMPONENT_BYTES),
+    s: hexBytes("signature.s", signature.s, SIGNATURE_COMPONENT_BYTES),
+  };
+  return fields as unknown as AttestationReport;
+}
@@ -0,0 +1,223 @@
+/**
+ * Intel TDX quotes (version 4, as produced through configfs-tsm or the DCAP
+ * quote generation library): a 48-byte header, the 584-byte TD report
+ * body, and the signature data. report_data is computed with the same
+ * formula as a SEV-SNP report's (see createReportData).
+ */
+import { AttestationError } from "../core/errors.ts";
+
+/**
+ * Quote version this module reads.
+ */
+export const TDX_QUOTE_VERSION = 4;
+
+/**
+ * tee_type of a TDX quote (SGX quotes carry 0).
+ */
+export const TDX_TEE_TYPE = 0x81;
+
+/**
+ * att_key_type of an ECDSA-256 (P-256) attestation key.
+ */
+export const TDX_ATTESTATION_KEY_ECDSA_P256 = 2;
+
+/**
+ * Length of the quote header.
+ */
+export const TDX_QUOTE_HEADER_BYTES = 48;
+
+/**
+ * Length of the TD report body that follows the header.
+ */
+export const TD_REPORT_BODY_BYTES = 584;
+
+/**
+ * Length of header and body together: the part the quote signature covers.
+ */
+export const TDX_SIGNED_QUOTE_BYTES = TDX_QUOTE_HEADER_BYTES + TD_REPORT_BODY_BYTES;
+
+/**
+ * qe_vendor_id of simulator quotes, "AXIOM SIMULATOR" zero-padded. Not an
+ * Intel value; Intel's quoting enclave uses 939a7233f79c4ca9940a0db3957f0607.
+ */
+export const SIMULATOR_QE_VENDOR_ID = Uint8Array.from(
+  Buffer.concat([Buffer.from("AXIOM SIMULATOR", "ascii"), Buffer.alloc(1)])
+);
+
+/**
+ * Parsed TDX quote. Byte fields keep the quote's byte order.
+ */
+export interface TdxQuote {
+  version: number;
+  attestationKeyType: number;
+  teeType: number;
+  qeVendorId: Uint8Array;
+  userData: Uint8Array;
+  teeTcbSvn: Uint8Array;
+  mrSeam: Uint8Array;
+  mrSignerSeam: Uint8Array;
+  seamAttributes: Uint8Array;
+  tdAttributes: Uint8Array;
+  xfam: Uint8Array;
+
+  /**
+   * Measurement of the initial TD contents (the TDX counterpart of the
+   * SEV-SNP launch measurement).
+   */
+  mrTd: Uint8Array;
+
+  mrConfigId: Uint8Array;
+  mrOwner: Uint8Array;
+  mrOwnerConfig: Uint8Array;
+
+  /**
+   * Runtime measurement registers RTMR0-3.
+   */
+  rtmrs: [Uint8Array, Uint8Array, Uint8Array, Uint8Array];
+
+  reportData: Uint8Array;
+
+  /**
+   * Signature data (ECDSA quote signature and certification data; for
+   * simulator quotes an Ed25519 signature over the signed bytes).
+   */
+  signatureData: Uint8Array;
+}
+
+// [name, absolute offset, length] of the byte fields after the fixed
+// integers; the body starts at TDX_QUOTE_HEADER_BYTES
+const BYTE_FIELDS: Array<[string, number, number]> = [
+  ["qeVendorId", 12, 16],
+  ["userData", 28, 20],
+  ["teeTcbSvn", 48, 16],
+  ["mrSeam", 64, 48],
+  ["mrSignerSeam", 112, 48],
+  ["seamAttributes", 160, 8],
+  ["tdAttributes", 168, 8],
+  ["xfam", 176, 8],
+  ["mrTd", 184, 48],
+  ["mrConfigId", 232, 48],
+  ["mrOwner", 280, 48],
+  ["mrOwnerConfig", 328, 48],
+];
+const RTMR_OFFSET = 376;
+const RTMR_BYTES = 48;
+const REPORT_DATA_OFFSET = 568;
+const REPORT_DATA_BYTES = 64;
+
+/**
+ * Whether bytes start like a TDX quote: version 4 with the TDX tee_type.
+ * SEV-SNP reports never do (their first four bytes are the report version).
+ */
+export function isTdxQuote(bytes: Uint8Array): boolean {
+  if (bytes.length < TDX_QUOTE_HEADER_BYTES) return false;
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  return view.getUint16(0, true) === TDX_QUOTE_VERSION && view.getUint32(4, true) === TDX_TEE_TYPE;
+}
+
+/**
+ * Whether a quote carries the simulator's qe_vendor_id.
+ */
+export function isSimulatorQuote(quote: TdxQuote): boolean {
+  return Buffer.from(quote.qeVendorId).equals(SIMULATOR_QE_VENDOR_ID);
+}
+
+/**
+ * Parse a version 4 TDX quote.
+ * @param bytes - Raw quote
+ * @returns Typed quote; tdxQuoteToBytes() reproduces bytes exactly
+ * @throws AttestationError on a short quote, another version or tee_type,
+ *         non-zero reserved bytes, or a signature length that does not
+ *         match the quote's length
+ */
+export function parseTdxQuote(bytes: Uint8Array): TdxQuote {
+  if (bytes.length < TDX_SIGNED_QUOTE_BYTES + 4) {
+    throw new AttestationError(
+      `Invalid TDX quote: expected at least ${TDX_SIGNED_QUOTE_BYTES + 4} bytes, got ${bytes.length}`
+    );
+  }
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  const version = view.getUint16(0, true);
+  if (version !== TDX_QUOTE_VERSION) {
+    throw new AttestationError(`unsupported TDX quote version ${version}`);
+  }
+  const teeType = view.getUint32(4, true);
+  if (teeType !== TDX_TEE_TYPE) {
+    throw new AttestationError(`Invalid TDX quote: tee_type 0x${teeType.toString(16)} is not TDX`);
+  }
+  if (view.getUint32(8, true) !== 0) {
+    throw new AttestationError("Invalid TDX quote: reserved header bytes are not zero");
+  }
+  const signatureLength = view.getUint32(TDX_SIGNED_QUOTE_BYTES, true);
+  if (bytes.length !== TDX_SIGNED_QUOTE_BYTES + 4 + signatureLength) {
+    throw new AttestationError(
+      `Invalid TDX quote: signature data length ${signatureLength} does not match the quote length ${bytes.length}`
+    );
+  }
+
+  const slice = (offset: number, length: number) =>
+    new Uint8Array(bytes.subarray(offset, offset + length));
+  const fields: Record<string, unknown> = {
+    version,
+    attestationKeyType: view.getUint16(2, true),
+    teeType,
+  };
+  for (const [name, offset, length] of BYTE_FIELDS) {
+    fields[name] = slice(offset, length);
+  }
+  fields.rtmrs = [0, 1, 2, 3].map((index) => slice(RTMR_OFFSET + index * RTMR_BYTES, RTMR_BYTES));
+  fields.reportData = slice(REPORT_DATA_OFFSET, REPORT_DATA_BYTES);
+  fields.signatureData = slice(TDX_SIGNED_QUOTE_BYTES + 4, signatureLength);
+  return fields as unknown as TdxQuote;
+}
+
+/**
+ * Serialize a quote. Reserved header bytes are zero.
+ * @param quote - Typed quote
+ * @returns Raw quote
+ * @throws Error if a byte field has the wrong length
+ */
+export function tdxQuoteToBytes(quote: TdxQuote): Uint8Array {
+  const bytes = new Uint8Array(TDX_SIGNED_QUOTE_BYTES + 4 + quote.signatureData.length);
+  const view = new DataView(bytes.buffer);
+  view.setUint16(0, quote.version, true);
+  view.setUint16(2, quote.attestationKeyType, true);
+  view.setUint32(4, quote.teeType, true);
+  const fields = quote as unknown as Record<string, Uint8Array>;
+  const write = (name: string, value: Uint8Array | undefined, offset: number, length: number) => {
+    if (!(value instanceof Uint8Array) || value.length !== length) {
+      throw new Error(`Invalid TDX quote: ${name} must be ${length} bytes`);
+    }
+    bytes.set(value, offset);
+  };
+  for (const [name, offset, length] of BYTE_FIELDS) {
+    write(name, fields[name], offset, length);
+  }
+  quote.rtmrs.forEach((rtmr, index) =>
+    write(`rtmr${index}`, rtmr, RTMR_OFFSET + index * RTMR_BYTES, RTMR_BYTES)
+  );
+  write("reportData", quote.reportData, REPORT_DATA_OFFSET, REPORT_DATA_BYTES);
+  view.setUint32(TDX_SIGNED_QUOTE_BYTES, quote.signatureData.length, true);
+  bytes.set(quote.signatureData, TDX_SIGNED_QUOTE_BYTES + 4);
+  return bytes;
+}
+
+/**
+ * A quote with every field zero except version, key type, and tee_type,
+ * to fill in before serializing.
+ */
+export function emptyTdxQuote(): TdxQuote {
+  const zeros = (length: number) => new Uint8Array(length);
+  const fields: Record<string, unknown> = {
+    version: TDX_QUOTE_VERSION,
+    attestationKeyType: TDX_ATTESTATION_KEY_ECDSA_P256,
+    teeType: TDX_TEE_TYPE,
+  };
+  for (const [name, , length] of BYTE_FIELDS) {
+    fields[name] = zeros(length);
+  }
+  fields.rtmrs = [zeros(RTMR_BYTES), zeros(RTMR_BYTES), zeros(RTMR_BYTES), zeros(RTMR_BYTES)];
+  fields.reportData = zeros(REPORT_DATA_BYTES);
+  fields.signatureData = zeros(0);
+  return fields as unknown as TdxQuote;
+}
@@ -0,0 +1,145 @@
+/**
+ * Attestation tokens: a JWT (ES256) stating that an attestation verified,
+ * for backends that check JWTs with standard libraries instead of parsing
+ * SEV-SNP reports.
+ */
+import { createPrivateKey, createPublicKey, sign, verify as verifySignature } from "crypto";
+import type { KeyObject } from "crypto";
+import { readFileSync } from "fs";
+import { AttestationError, TokenError } from "../core/errors.ts";
+import { EVIDENCE_PLATFORMS } from "./types.ts";
+import type { AttestationTokenClaims } from "./types.ts";
+
+/**
+ * Causes a TokenError names: no signing key configured, a key that does
+ * not load or is not P-256, and a clock that cannot be trusted.
+ */
+export const TOKEN_ERROR_CODES = ["TOKEN_KEY_MISSING", "TOKEN_KEY_INVALID", "TOKEN_CLOCK"] as const;
+
+export type TokenErrorCode = (typeof TOKEN_ERROR_CODES)[number];
+
+/**
+ * iss of attestation tokens.
+ */
+export const TOKEN_ISSUER = "axiom-enclave-runner";
+
+/**
+ * How far an attestation timestamp may lie ahead of the clock that
+ * verifies it, in milliseconds.
+ */
+export const TOKEN_CLOCK_SKEW_MS = 5 * 60 * 1000;
+
+// A clock before this (2024-01-01) has not been set
+const EARLIEST_CLOCK_MS = Date.UTC(2024, 0, 1);
+
+/**
+ * Load the ES256 signing key for attestation tokens.
+ * @param path - PEM (PKCS#8 or SEC1) P-256 private key
+ * @throws TokenError TOKEN_KEY_INVALID if the key does not load or is not P-256
+ */
+export function loadTokenKey(path: string): KeyObject {
+  let key: KeyObject;
+  try {
+    key = createPrivateKey(readFileSync(path, "utf8"));
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    throw new TokenError(
+      "TOKEN_KEY_INVALID",
+      `Cannot load token signing key from ${path}: ${reason}`
+    );
+  }
+  if (key.asymmetricKeyType !== "ec" || key.asymmetricKeyDetails?.namedCurve !== "prime256v1") {
+    throw new TokenError("TOKEN_KEY_INVALID", `Token signing key at ${path} is not a P-256 key`);
+  }
+  return key;
+}
+
+/**
+ * Time to state as a token's verification time, after checking the clock
+ * is set and not behind the attestation it vouches for.
+ * @param attestedAt - Attestation timestamp (milliseconds since epoch)
+ * @param now - Clock reading (milliseconds since epoch)
+ * @returns now
+ * @throws TokenError TOKEN_CLOCK if the clock is unset or behind attestedAt
+ *         by more than TOKEN_CLOCK_SKEW_MS
+ */
+export function verificationTime(attestedAt: number, now: number = Date.now()): number {
+  if (!Number.isFinite(now) || now < EARLIEST_CLOCK_MS) {
+    throw new TokenError("TOKEN_CLOCK", `Clock reads ${now}, before 2024; it is not set`);
+  }
+  if (attestedAt > now + TOKEN_CLOCK_SKEW_MS) {
+    throw new TokenError(
+      "TOKEN_CLOCK",
+      `Attestation timestamp ${attestedAt} is ahead of the clock (${now}) by more than ${TOKEN_CLOCK_SKEW_MS} ms`
+    );
+  }
+  return now;
+}
+
+/**
+ * Sign claims as a compact JWT with ES256.
+ * @param claims - Token claims
+ * @param key - P-256 private key (see loadTokenKey)
+ * @returns header.payload.signature, base64url-encoded
+ */
+export function mintAttestationToken(claims: AttestationTokenClaims, key: KeyObject): string {
+  const encode = (value: unknown) => Buffer.from(JSON.stringify(value)).toString("base64url");
+  const signingInput = `${encode({ alg: "ES256", typ: "JWT" })}.${encode(claims)}`;
+  const signature = sign("sha256", Buffer.from(signingInput), { key, dsaEncoding: "ieee-p1363" });
+  return `${signingInput}.${signature.toString("base64url")}`;
+}
+
+/**
+ * Read a token's claims without checking its signature.
+ * @param token - Compact JWT
+ * @throws AttestationError if the token is malformed or not ES256
+ */
+export function decodeAttestationToken(token: string): AttestationTokenClaims {
+  const parts = token.split(".");
+  if (parts.length !== 3) {
+    throw new AttestationError("Attestation token is not a compact JWT");
+  }
+  let header: { alg?: unknown };
+  let claims: AttestationTokenClaims;
+  try {
+    header = JSON.parse(Buffer.from(parts[0], "base64url").toString("utf8"));
+    claims = JSON.parse(Buffer.from(parts[1], "base64url").toString("utf8"));
+  } catch {
+    throw new AttestationError("Attestation token header or claims are not JSON");
+  }
+  if (header.alg !== "ES256") {
+    throw new AttestationError(`Attestation token algorithm is not ES256: ${String(header.alg)}`);
+  }
+  if (
+    typeof claims !== "object" ||
+    claims === null ||
+    !EVIDENCE_PLATFORMS.includes(claims.platform)
+  ) {
+    throw new AttestationError("Attestation token claims name no known platform");
+  }
+  return claims;
+}
+
+/**
+ * Verify a token's ES256 signature and return its claims.
+ * @param token - Compact JWT
+ * @param publicKey - P-256 public key (PEM or KeyObject)
+ * @throws AttestationError if the token is malformed or the signature fails
+ */
+export function verifyAttestationToken(
+  token: string,
+  publicKey: string | KeyObject
+): AttestationTokenClaims {
+  const claims = decodeAttestationToken(token);
+  const [header, payload, signature] = token.split(".");
+  const valid = verifySignature(
+    "sha256",
+    Buffer.from(`${header}.${payload}`),
+    { key: createPublicKey(publicKey), dsaEncoding: "ieee-p1363" },
+    Buffer.from(signature, "base64url")
+  );
+  if (!valid) {
+    throw new AttestationError("Attestation token signature does not verify");
+  }
+  return claims;
+}
@@ -2,6 +2,25 @@
  * Attestation types for Axiom Core v1.0.
  * Attestation is opt-in and requires the native enclave runner.
  */
+import {
+  BINDING_VERSIONS,
+  HASH_ALGORITHMS,
+  HASH_DIGEST_BYTES,
+  HASH_SCOPES,
+  isHexDigest,
+} from "../core/canonical.ts";
+import type {
+  BindingVersion,
+  CanonicalizationMode,
+  HashAlgorithm,
+  HashScope,
+} from "../core/canonical.ts";
+import type { MaskingPolicy } from "../core/policy.ts";
+import type { CertChain } from "./certs.ts";
+import type { CrlStatus } from "./crl.ts";
+import type { MinimumTcb, PlatformEvidence } from "./report.ts";
+import { OUTPUT_ENCODINGS } from "../core/serialization.ts";
+import type { HashEncoding, OutputEncoding } from "../core/serialization.ts";
 
 /**
  * Attestation evidence from TEE execution.
@@ -31,6 +50,12 @@ export interface AttestationEvidence {
    */
   configHash: string;
 
+  /**
+   * Hash of the effective MaskingPolicy (SHA-256, hex-encoded).
+   * Binds the policy that governed the transform to attestation.
+   */
+  policyHash: string;
+
   /**
    * Unique session identifier (128-bit random, hex-encoded).
    * Prevents replay attacks.
@@ -38,11 +63,58 @@ export interface AttestationEvidence {
   sessionId: string;
 
   /**
-   * Hash of the transformed context output (SHA-256, hex-encoded).
+   * Hash of the transformed context output (hex-encoded, computed with
+   * hashAlgorithm).
    * Binds output to this specific execution.
    */
   outputHash: string;
 
+  /**
+   * Algorithm that produced outputHash. Absent on evidence created before
+   * algorithm tagging, which means "sha256".
+   */
+  hashAlgorithm?: HashAlgorithm;
+
+  /**
+   * Encoding outputHash covers ("json" or "cbor"). Absent on evidence
+   * created before encodings were selectable, which means "json".
+   */
+  outputEncoding?: OutputEncoding;
+
+  /**
+   * Part of the transformed context outputHash covers (see HASH_SCOPES).
+   * Absent on evidence created before scopes were recorded, which means
+   * "context-v1".
+   */
+  hashScope?: HashScope;
+
+  /**
+   * Formula for outputHash and report_data (see BINDING_VERSIONS). Absent
+   * on evidence created before version 2, which means 1.
+   */
+  bindingVersion?: BindingVersion;
+
+  /**
+   * Commitment to the raw input (SHA-256, hex-encoded; see
+   * computeInputHash), bound into report_data. Absent when the runner
+   * predates input commitments.
+   */
+  inputHash?: string;
+
+  /**
+   * Output hash of the previous transform this one is chained to
+   * (hex-encoded), bound into report_data. Absent for an unchained
+   * transform.
+   */
+  chainedFrom?: string;
+
+  /**
+   * Session key proof when the report was cached under attestationMode
+   * "session_cached"; report_data then binds the session key, not this
+   * output.
+   */
+  sessionAttestation?: SessionAttestation;
+
   /**
    * Unix timestamp (milliseconds) when attestation was generated.
    */
@@ -99,8 +171,38 @@ export function validateAttestationEvidence(
     errors.push("Config hash must be a 32-byte hex string");
   }
 
-  if (!isHexString(evidence.outputHash, 64)) {
-    errors.push("Output hash must be a 32-byte hex string");
+  if (!isHexString(evidence.policyHash, 64)) {
+    errors.push("Policy hash must be a 32-byte hex string");
+  }
+
+  const hashAlgorithm = evidence.hashAlgorithm ?? "sha256";
+  if (!HASH_ALGORITHMS.includes(hashAlgorithm)) {
+    errors.push(`Unsupported hash algorithm: ${hashAlgorithm}`);
+  } else if (!isHexString(evidence.outputHash, HASH_DIGEST_BYTES[hashAlgorithm] * 2)) {
+    errors.push(`Output hash must be a ${HASH_DIGEST_BYTES[hashAlgorithm]}-byte hex string`);
+  }
+
+  if (evidence.outputEncoding !== undefined && !OUTPUT_ENCODINGS.includes(evidence.outputEncoding)) {
+    errors.push(`Unsupported output encoding: ${evidence.outputEncoding}`);
+  }
+
+  if (evidence.hashScope !== undefined && !HASH_SCOPES.includes(evidence.hashScope)) {
+    errors.push(`Unsupported hash scope: ${evidence.hashScope}`);
+  }
+
+  if (
+    evidence.bindingVersion !== undefined &&
+    !BINDING_VERSIONS.includes(evidence.bindingVersion)
+  ) {
+    errors.push(`Unsupported binding version: ${evidence.bindingVersion}`);
+  }
+
+  if (evidence.inputHash !== undefined && !isHexString(evidence.inputHash, 64)) {
+    errors.push("Input hash must be a 32-byte hex string");
+  }
+
+  if (evidence.chainedFrom !== undefined && !isHexDigest(evidence.chainedFrom)) {
+    errors.push("Chained-from hash must be a 32- or 48-byte hex string");
   }
 
   const measurementOk =
@@ -181,6 +283,12 @@ export interface VerificationVerdict {
      * True if config hash matches expected.
      */
     configBinding: boolean;
+
+    /**
+     * Policy binding.
+     * True if policy hash matches expected.
+     */
+    policyBinding: boolean;
   };
 
   /**
@@ -217,6 +325,36 @@ export interface VerificationOptions {
    */
   expectedConfigHash?: string;
 
+  /**
+   * Expected policy hash (see hashPolicy).
+   * If provided, policyBinding claim requires exact match.
+   */
+  expectedPolicyHash?: string;
+
+  /**
+   * Recompute report_data with the pre-policy-hash formula
+   * SHA-256(sessionId || configHash || outputHash || timestamp), for
+   * evidence from runners still configured with legacyReportData.
+   * Default: false
+   */
+  legacyReportData?: boolean;
+
+  /**
+   * Canonicalization used to recompute the output hash. Set "legacy" to
+   * verify hashes stored before the switch to RFC 8785; "legacy" will be
+   * removed in the next release.
+   * Default: "jcs"
+   */
+  canonicalization?: CanonicalizationMode;
+
+  /**
+   * Accept evidence of binding versions 1 and 2, whose report_data fills
+   * only the first 32 bytes of REPORT_DATA (the rest zero). Migration only:
+   * without it, only the 64-byte version 3 layout verifies.
+   * Default: false
+   */
+  acceptShortReportData?: boolean;
+
   /**
    * Maximum age of attestation in milliseconds.
    * Default: 300000 (5 minutes)
@@ -229,6 +367,33 @@ export interface VerificationOptions {
    */
   validateSignatureChain?: boolean;
 
+  /**
+   * VCEK, ASK, and ARK certificates (PEM or DER) the signature chain is
+   * validated with. Without them, platformAuth fails for hardware reports.
+   */
+  certificates?: {
+    vcek?: string | Uint8Array;
+    ask?: string | Uint8Array;
+    ark?: string | Uint8Array;
+  };
+
+  /**
+   * AMD root key certificate (PEM or DER) the ARK must match. Required
+   * for platformAuth on hardware reports.
+   */
+  trustedArk?: string | Uint8Array;
+
+  /**
+   * AMD CRL (PEM or DER; see fetchCrl) the ASK and ARK are checked
+   * against, as in ExpectedBinding.
+   */
+  crl?: string | Uint8Array | null;
+
+  /**
+   * Fail platformAuth when the CRL is unavailable. Default: false.
+   */
+  enforceCrl?: boolean;
+
   /**
    * Verification mode.
    * - "strict": All claims must pass
@@ -240,8 +405,502 @@ export interface VerificationOptions {
    * Custom nonce for freshness verification (optional).
    */
   nonce?: string;
+
+  /**
+   * Lowest acceptable SVN per component of the report's reported TCB.
+   * Verification fails when any is below its floor, or when the report
+   * carries no TCB.
+   */
+  minimumTcb?: MinimumTcb;
+}
+
+/**
+ * Platforms an evidence envelope can name.
+ */
+export const EVIDENCE_PLATFORMS = ["simulator", "sev-snp", "tdx"] as const;
+
+export type EvidencePlatform = (typeof EVIDENCE_PLATFORMS)[number];
+
+/**
+ * Report formats an evidence envelope can name: one per SEV-SNP report
+ * version, and the TDX quote (simulator evidence uses the layout it
+ * imitates).
+ */
+export const EVIDENCE_FORMATS = ["snp-report-v2", "snp-report-v3", "tdx-quote-v4"] as const;
+
+export type EvidenceFormat = (typeof EVIDENCE_FORMATS)[number];
+
+/**
+ * Binding formulas an evidence envelope can name, "v" followed by the
+ * binding version.
+ */
+export const BINDING_FORMULAS = ["v1", "v2", "v3"] as const;
+
+export type BindingFormula = (typeof BINDING_FORMULAS)[number];
+
+/**
+ * Attestation report with its platform, format, certificates, and binding
+ * formula stated, so a verifier does not have to infer them from the
+ * bytes. Its JSON form uses these camelCase names.
+ */
+export interface EvidenceEnvelope {
+  platform: EvidencePlatform;
+
+  format: EvidenceFormat;
+
+  /**
+   * Raw attestation report, base64-encoded.
+   */
+  reportB64: string;
+
+  /**
+   * DER certificates, base64-encoded, that came with the report.
+   */
+  certificates?: {
+    vcek?: string;
+    ask?: string;
+    ark?: string;
+  };
+
+  /**
+   * Formula report_data was computed with.
+   */
+  bindingFormula: BindingFormula;
+}
+
+/**
+ * How an enclave packages its evidence: "raw" returns the report and its
+ * envelope only; "cose" adds a COSE_Sign1 structure (coseEvidence);
+ * "eat" adds an Entity Attestation Token (eatToken).
+ */
+export const EVIDENCE_PACKAGINGS = ["raw", "cose", "eat"] as const;
+
+export type EvidencePackaging = (typeof EVIDENCE_PACKAGINGS)[number];
+
+/**
+ * How often an enclave generates platform evidence: "per_request" binds
+ * every response's report to its output; "session_cached" generates one
+ * report per session and freshness window, binding a session key that
+ * signs each response's binding (see SessionAttestation).
+ */
+export const ATTESTATION_MODES = ["per_request", "session_cached"] as const;
+
+export type AttestationMode = (typeof ATTESTATION_MODES)[number];
+
+/**
+ * Per-response proof under attestationMode "session_cached". The cached
+ * report's report_data is createSessionKeyReportData(sessionId, publicKey,
+ * windowStart); signature is the session key's Ed25519 signature over
+ * the report_data a per_request report for this response would carry.
+ */
+export interface SessionAttestation {
+  /**
+   * Raw Ed25519 session public key (32 bytes).
+   */
+  publicKey: Uint8Array;
+
+  /**
+   * Start of the freshness window the report was generated in
+   * (milliseconds since epoch).
+   */
+  windowStart: number;
+
+  /**
+   * When the runner stops using the report (milliseconds since epoch).
+   */
+  expiresAt: number;
+
+  /**
+   * Signature over this response's per-request binding (64 bytes).
+   */
+  signature: Uint8Array;
+}
+
+/**
+ * Payload of COSE evidence: the attestation report, the measurement, and
+ * the binding fields report_data was computed over. Hashes are
+ * hex-encoded, as in ExpectedBinding.
+ */
+export interface CoseEvidencePayload {
+  platform: EvidencePlatform;
+
+  /**
+   * Raw attestation report.
+   */
+  report: Uint8Array;
+
+  measurement: string;
+
+  /**
+   * Session identifier (16 bytes).
+   */
+  sessionId: string;
+
+  configHash: string;
+
+  policyHash: string;
+
+  outputHash: string;
+
+  /**
+   * Attestation timestamp (milliseconds since epoch).
+   */
+  timestamp: number;
+
+  inputHash?: string;
+
+  chainedFrom?: string;
+
+  hashAlgorithm: HashAlgorithm;
+
+  bindingVersion: BindingVersion;
+}
+
+/**
+ * Result of verifyCoseEvidence. valid means the structure holds and, for
+ * simulator evidence, the signature verifies; hardware evidence is
+ * unsigned and rests on its report, which verifyAttestationReport checks.
+ */
+export interface CoseVerificationResult {
+  valid: boolean;
+
+  /**
+   * Whether the structure carries a signature (simulator evidence).
+   */
+  signed: boolean;
+
+  /**
+   * Decoded payload. Present only when valid.
+   */
+  payload?: CoseEvidencePayload;
+
+  /**
+   * Why verification failed. Present only when not valid.
+   */
+  reason?: string;
 }
 
+/**
+ * Claims of an Entity Attestation Token (RFC 9711), with their CBOR keys.
+ */
+export interface EatClaims {
+  /**
+   * eat_nonce (10): the request nonce.
+   */
+  nonce: Uint8Array;
+
+  /**
+   * iat (6): attestation time, seconds since epoch.
+   */
+  issuedAt: number;
+
+  /**
+   * oemid (258): IANA Private Enterprise Number of the chip vendor.
+   */
+  oemid: number;
+
+  /**
+   * hwmodel (259): CPUID family, model, and stepping. Absent for version 2
+   * reports, which do not carry them.
+   */
+  hwmodel?: Uint8Array;
+
+  /**
+   * swname (270): name of the software that produced the evidence.
+   */
+  swname: string;
+
+  /**
+   * measurements (273): the report's launch measurement (48 bytes).
+   */
+  measurement: Uint8Array;
+
+  /**
+   * Output hash (hex), in the private Axiom claim (-65537).
+   */
+  outputHash: string;
+
+  hashAlgorithm: HashAlgorithm;
+
+  bindingVersion: BindingVersion;
+
+  /**
+   * Raw attestation report, the authority for an unsigned token.
+   */
+  report: Uint8Array;
+}
+
+/**
+ * Result of verifyEatToken. valid means the token is well-formed and, for
+ * a simulator token, its signature verifies.
+ */
+export interface EatVerificationResult {
+  valid: boolean;
+
+  /**
+   * Whether the token is a signed CWT (simulator) rather than an unsigned
+   * claim set (hardware).
+   */
+  signed: boolean;
+
+  /**
+   * Decoded claims. Present only when valid.
+   */
+  claims?: EatClaims;
+
+  /**
+   * Why verification failed. Present only when not valid.
+   */
+  reason?: string;
+}
+
+/**
+ * Claims of an attestation token (an ES256 JWT minted after the runner
+ * verified its own report). Hex fields are lowercase hex.
+ */
+export interface AttestationTokenClaims {
+  iss: string;
+
+  /**
+   * Verification time, seconds since epoch.
+   */
+  iat: number;
+
+  session_id: string;
+
+  nonce: string;
+
+  output_hash: string;
+
+  measurement: string;
+
+  platform: EvidencePlatform;
+}
+
+/**
+ * Response of attestAndTokenize: the transform response, and the token
+ * for it with its claims.
+ */
+export interface TokenizedAttestation {
+  response: EnclaveResponse;
+
+  /**
+   * Compact JWT (ES256).
+   */
+  token: string;
+
+  claims: AttestationTokenClaims;
+}
+
+/**
+ * Checks verifyAttestationReport runs, in order. session_signature runs
+ * when a sessionAttestation is given, tcb when a minimumTcb is given;
+ * certificate_chain runs for hardware reports only, and crl_status for
+ * those when a crl is given or enforceCrl is set.
+ */
+export const REPORT_CHECKS = [
+  "structure",
+  "platform",
+  "report_data",
+  "session_signature",
+  "measurement",
+  "tcb",
+  "signature",
+  "certificate_chain",
+  "crl_status",
+] as const;
+
+export type ReportCheckName = (typeof REPORT_CHECKS)[number];
+
+/**
+ * What an attestation report is expected to bind, for
+ * verifyAttestationReport. Hashes are hex-encoded.
+ */
+export interface ExpectedBinding {
+  /**
+   * Session identifier (16 bytes).
+   */
+  sessionId: string;
+
+  /**
+   * Configuration hash (32 bytes).
+   */
+  configHash: string;
+
+  /**
+   * Policy hash (32 bytes). Absent means the legacy formula without it.
+   */
+  policyHash?: string;
+
+  /**
+   * Output hash, computed with hashAlgorithm.
+   */
+  outputHash: string;
+
+  /**
+   * Attestation timestamp (milliseconds since epoch).
+   */
+  timestamp: number;
+
+  /**
+   * Input commitment (see computeInputHash), which binds the nonce.
+   */
+  inputHash?: string;
+
+  /**
+   * Output hash of the previous transform, for a chained transform.
+   */
+  chainedFrom?: string;
+
+  /**
+   * Algorithm of outputHash. Default: "sha256".
+   */
+  hashAlgorithm?: HashAlgorithm;
+
+  /**
+   * report_data formula. Default: 1.
+   */
+  bindingVersion?: BindingVersion;
+
+  /**
+   * Accept the 32-byte report_data of binding versions 1 and 2 (migration
+   * only). Default: false.
+   */
+  acceptShortReportData?: boolean;
+
+  /**
+   * Expected measurement (hex). Default for simulator reports: this
+   * process's simulatorMeasurement(), or the simulator marker for a report
+   * with a zero measurement; hardware reports fail without one.
+   */
+  measurement?: string;
+
+  /**
+   * Accept simulator reports. Default: false.
+   */
+  allowSimulator?: boolean;
+
+  /**
+   * Simulator verification key (Ed25519 public key, PEM or SPKI DER; see
+   * getSimulatorVerificationKey) that simulator reports must be signed
+   * with.
+   */
+  simulatorKey?: string | Uint8Array;
+
+  /**
+   * VCEK, ASK, and ARK certificates (PEM or DER) for a hardware report.
+   */
+  certificates?: {
+    vcek?: string | Uint8Array;
+    ask?: string | Uint8Array;
+    ark?: string | Uint8Array;
+  };
+
+  /**
+   * AMD root key certificate (PEM or DER) the ARK must match.
+   */
+  trustedArk?: string | Uint8Array;
+
+  /**
+   * AMD CRL (PEM or DER; see fetchCrl) the ASK and ARK are checked
+   * against. null records that none could be obtained.
+   */
+  crl?: string | Uint8Array | null;
+
+  /**
+   * Fail crl_status when the CRL is unavailable (missing, stale, or not
+   * signed by the ARK). Default: false.
+   */
+  enforceCrl?: boolean;
+
+  /**
+   * Lowest acceptable SVN per component of the reported TCB.
+   */
+  minimumTcb?: MinimumTcb;
+
+  /**
+   * Session key proof of a "session_cached" response. report_data must
+   * then bind the session key, and the key's signature must cover the
+   * binding the other fields describe.
+   */
+  sessionAttestation?: SessionAttestation;
+
+  /**
+   * Time of verification (milliseconds since epoch), against which a
+   * session attestation must not have expired. Default: now.
+   */
+  verifiedAt?: number;
+}
+
+/**
+ * Outcome of one verifyAttestationReport check.
+ */
+export interface ReportCheck {
+  name: ReportCheckName;
+  passed: boolean;
+
+  /**
+   * Why the check failed. Absent when it passed.
+   */
+  reason?: string;
+}
+
+/**
+ * Result of verifyAttestationReport.
+ */
+export interface ReportVerificationResult {
+  /**
+   * True when every check passed.
+   */
+  valid: boolean;
+
+  /**
+   * Whether the report came from the simulator.
+   */
+  simulator: boolean;
+
+  /**
+   * Checks in REPORT_CHECKS order.
+   */
+  checks: ReportCheck[];
+
+  /**
+   * Revocation status of the ASK and ARK, when crl_status ran.
+   */
+  crlStatus?: CrlStatus;
+}
+
+/**
+ * Public key the simulator signs its reports with. It is generated per
+ * runner (or loaded from a file) and proves nothing about the platform.
+ */
+export interface SimulatorVerificationKey {
+  /**
+   * Signature algorithm.
+   */
+  algorithm: "ed25519";
+
+  /**
+   * Ed25519 public key, PEM-encoded SPKI.
+   */
+  publicKey: string;
+
+  /**
+   * Always false: the key must never be trusted for production evidence.
+   */
+  production: false;
+
+  /**
+   * Human-readable label saying so (SIMULATOR_KEY_LABEL).
+   */
+  label: string;
+}
+
+/**
+ * Label every simulator verification key carries.
+ */
+export const SIMULATOR_KEY_LABEL = "Axiom simulator key - NOT FOR PRODUCTION";
+
 /**
  * Session metadata tracked during execution.
  * Used internally by the SDK to bind attestation to execution.
@@ -284,9 +943,10 @@ export interface SessionMetadata {
  */
 export interface EnclaveRequest {
   /**
-   * Raw context to transform (UTF-8 encoded).
+   * Raw context to transform, one UTF-8 encoded entry per document.
+   * Documents are extracted independently and never concatenated.
    */
-  rawContext: Uint8Array;
+  rawContext: Uint8Array[];
 
   /**
    * Task hint (optional, should be non-sensitive).
@@ -296,22 +956,7 @@ export interface EnclaveRequest {
   /**
    * Masking policy parameters.
    */
-  policy: {
-    /**
-     * Policy version.
-     */
-    version: "v1";
-
-    /**
-     * Whether to allow common words in output.
-     */
-    allowCommonWords: boolean;
-
-    /**
-     * Maximum input size in bytes.
-     */
-    maxInputSize: number;
-  };
+  policy: MaskingPolicy;
 
   /**
    * Session ID for this request (128-bit).
@@ -332,6 +977,85 @@ export interface EnclaveRequest {
    * Attestation timestamp for binding (milliseconds since epoch).
    */
   timestamp: number;
+
+  /**
+   * Bind report_data with the pre-policy-hash formula (migration only).
+   */
+  legacyReportData?: boolean;
+
+  /**
+   * Algorithm for the output hash. Default: "sha256".
+   */
+  hashAlgorithm?: HashAlgorithm;
+
+  /**
+   * Encoding the output hash covers. Default: "json".
+   */
+  outputEncoding?: OutputEncoding;
+
+  /**
+   * Binding formula for the output hash and report_data. Default: 1.
+   * Versions 2 and 3 cannot be combined with legacyReportData.
+   */
+  bindingVersion?: BindingVersion;
+
+  /**
+   * How the runner renders output_hash, input_hash, and policy_hash on the
+   * wire (see HASH_ENCODINGS). The SDK decodes them, so the response and
+   * report_data hold the same digest bytes whatever the choice.
+   * Default: "hex".
+   */
+  hashEncoding?: HashEncoding;
+
+  /**
+   * Output hash (hex) of the previous transform, to chain this one to it.
+   * Bound into report_data and echoed as chainedFrom. Cannot be combined
+   * with legacyReportData.
+   */
+  previousOutputHash?: string;
+
+  /**
+   * Secret salt for session-stable synthetic IDs (optional, 16+ bytes).
+   * When set, entity IDs are a truncated HMAC-SHA256 of the normalized
+   * surface form ("ENTITY_a1b2c3d4") instead of sequential counters.
+   * Never returned in the response.
+   */
+  idSalt?: Uint8Array;
+
+  /**
+   * Fail the request when the VCEK certificate for the attestation report
+   * cannot be obtained, instead of returning the response with
+   * vcekUnavailable set. Default: false.
+   */
+  requireVcek?: boolean;
+
+  /**
+   * Return the raw chip_id in platform, not only its hash. chip_id
+   * identifies the physical machine. Default: false.
+   */
+  rawChipId?: boolean;
+
+  /**
+   * How evidence is packaged (wire name evidence_format, see
+   * EVIDENCE_PACKAGINGS). "cose" also returns coseEvidence, "eat"
+   * eatToken. Default: "raw".
+   */
+  evidenceFormat?: EvidencePackaging;
+
+  /**
+   * Measurement the enclave must have (wire name expected_measurement):
+   * 48 bytes of hex in either case, or the simulator marker. The runner
+   * compares it before reading rawContext and fails with
+   * MeasurementMismatchError if it differs.
+   */
+  expectedMeasurement?: string;
+
+  /**
+   * How often platform evidence is generated (wire name attestation_mode,
+   * see ATTESTATION_MODES). Only "raw" evidenceFormat can be cached.
+   * Default: "per_request".
+   */
+  attestationMode?: AttestationMode;
 }
 
 /**
@@ -345,33 +1069,175 @@ export interface EnclaveResponse {
   transformedContext: Uint8Array;
 
   /**
-   * SHA-256 hash of the transformed context.
+   * Hash of the transformed context, computed with hashAlgorithm.
    */
   outputHash: Uint8Array;
 
+  /**
+   * Algorithm that produced outputHash.
+   */
+  hashAlgorithm: HashAlgorithm;
+
+  /**
+   * Encoding outputHash covers. transformedContext is canonical JSON
+   * either way.
+   */
+  outputEncoding: OutputEncoding;
+
+  /**
+   * Part of the transformed context outputHash covers.
+   */
+  hashScope: HashScope;
+
+  /**
+   * Formula that produced outputHash and report_data.
+   */
+  bindingVersion: BindingVersion;
+
+  /**
+   * Runner metadata (outside the hash scope, so not in
+   * transformedContext's canonical bytes).
+   */
+  metadata?: Record<string, string | number | boolean>;
+
+  /**
+   * SHA-256 commitment to the raw input, session ID, and nonce (see
+   * computeInputHash). Absent when the runner predates input commitments.
+   */
+  inputHash?: Uint8Array;
+
+  /**
+   * The request's previousOutputHash (hex), echoed when chained.
+   */
+  chainedFrom?: string;
+
+  /**
+   * SHA-256 hash of the masking policy the enclave applied.
+   */
+  policyHash: Uint8Array;
+
   /**
    * Raw attestation report from platform.
+   * @deprecated Read evidence instead; this field will be removed in the
+   * next release. Until then it is byte-identical to evidence.reportB64.
    */
   attestationReport: Uint8Array;
 
+  /**
+   * The attestation report with its platform, format, certificates, and
+   * binding formula. Absent when the report does not parse.
+   */
+  evidence?: EvidenceEnvelope;
+
+  /**
+   * COSE_Sign1 evidence (CBOR, tag 18) when the request asked for
+   * evidenceFormat "cose". Check it with verifyCoseEvidence.
+   */
+  coseEvidence?: Uint8Array;
+
+  /**
+   * Entity Attestation Token (CBOR) when the request asked for
+   * evidenceFormat "eat". Read it with getEatToken and verifyEatToken.
+   */
+  eatToken?: Uint8Array;
+
+  /**
+   * Session key proof when the request asked for attestationMode
+   * "session_cached"; attestationReport is then the cached session report.
+   * Pass it as ExpectedBinding.sessionAttestation to verify.
+   */
+  sessionAttestation?: SessionAttestation;
+
+  /**
+   * PEM-encoded VCEK certificate for the chip and reported TCB in
+   * attestationReport. Absent for simulator reports and when the
+   * certificate could not be obtained.
+   */
+  vcekPem?: string;
+
+  /**
+   * Set when attestationReport is a hardware report but its VCEK could
+   * not be fetched; warnings says why.
+   */
+  vcekUnavailable?: boolean;
+
+  /**
+   * AMD certificates the host supplied with an extended report. Absent
+   * when the runner fell back to a plain report. Simulator chains hold
+   * placeholder bytes, not certificates.
+   */
+  certificates?: CertChain;
+
+  /**
+   * TCB versions, platform flags, and chip identity from
+   * attestationReport. Simulator reports carry fixed values. Absent for
+   * a TDX quote.
+   */
+  platform?: PlatformEvidence;
+
   /**
    * Redaction statistics (counts only, no content).
    */
   redactionStats: {
     /**
-     * Number of entities extracted and masked.
+     * Number of unique entities extracted and masked (document nodes excluded).
      */
     entityCount: number;
 
+    /**
+     * Number of synthetic document nodes emitted (0 unless the policy's
+     * emitDocumentNodes is set).
+     */
+    documentCount: number;
+
     /**
      * Number of relations built.
      */
     relationCount: number;
 
     /**
-     * Number of identifiers replaced.
+     * Number of identifier occurrences replaced, counting repeats of
+     * the same entity.
      */
     identifiersReplaced: number;
+
+    /**
+     * Number of distinct surface forms mapped to synthetic IDs. The
+     * mapping itself never leaves the transform.
+     */
+    pseudonymCount: number;
+
+    /**
+     * Number of entities masked but omitted from the output because they
+     * scored below the policy's minConfidence.
+     */
+    suppressedLowConfidence: number;
+
+    /**
+     * Number of attribute values replaced with "suppressed" because fewer
+     * than the policy's kThreshold entities shared them.
+     */
+    attributesSuppressed: number;
+
+    /**
+     * Whether entities past the policy's maxEntities were dropped.
+     */
+    entitiesTruncated: boolean;
+
+    /**
+     * Whether relations past the policy's maxRelations were dropped.
+     */
+    relationsTruncated: boolean;
+
+    /**
+     * Number of relations dropped by the policy's allowedRelationTypes.
+     */
+    relationsFiltered: number;
+
+    /**
+     * Number of credentials (API keys, tokens, private keys) found.
+     */
+    credentialsFound: number;
   };
 
   /**
@@ -383,6 +1249,12 @@ export interface EnclaveResponse {
    * Optional signature from enclave.
    */
   signature?: Uint8Array;
+
+  /**
+   * Unknown request or policy fields the enclave ignored, one message per
+   * field naming it. Absent when there are none.
+   */
+  warnings?: string[];
 }
 
 /**
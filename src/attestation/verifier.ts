@@ -4,19 +4,55 @@
  */
 import type {
   AttestationEvidence,
+  EvidenceEnvelope,
+  ExpectedBinding,
+  ReportCheck,
+  ReportCheckName,
+  ReportVerificationResult,
+  SessionAttestation,
   VerificationVerdict,
   VerificationOptions,
 } from "./types.ts";
 import { validateAttestationEvidence } from "./types.ts";
+import { resolveEvidence } from "./evidence.ts";
+import { isTdxQuote } from "./tdx.ts";
 import type { TransformedContext } from "../core/config.ts";
 import {
   parseAttestationReport,
+  extractMeasurement,
   extractReportData,
   isSimulatorReport,
 } from "./parser.ts";
+import {
+  SIGNATURE_ALGO_ECDSA_P384_SHA384,
+  SIGNATURE_ALGO_SIMULATOR_ED25519,
+  SIGNED_REPORT_BYTES,
+  isSimulatorVersion,
+  parseReport,
+  signatureToEd25519,
+  signatureToP1363,
+  tcbBelowMinimum,
+} from "./report.ts";
+import type { AttestationReport, MinimumTcb } from "./report.ts";
 import type { ParsedAttestationReport } from "./types.ts";
-import { hash as hashContext } from "../core/canonical.ts";
-import { createHash } from "crypto";
+import { hashOutput } from "../core/serialization.ts";
+import { HASH_SCOPES } from "../core/canonical.ts";
+import type { CanonicalizationMode } from "../core/canonical.ts";
+import {
+  computeInputHash,
+  createReportData,
+  createSessionKeyReportData,
+} from "../runtime/session.ts";
+import { ed25519PublicKey } from "../runtime/session-attestation.ts";
+import { simulatorMeasurement } from "../runtime/attestation-backend.ts";
+import { fetchCrl } from "./certs.ts";
+import type { VcekOptions } from "./certs.ts";
+import { crlStatus } from "./crl.ts";
+import type { CrlStatus } from "./crl.ts";
+import { X509Certificate, createPublicKey, verify as verifySignature } from "crypto";
+
+const SIMULATOR_MEASUREMENT =
+  "simulator_measurement_0000000000000000000000000000000000000000000000000000000000000000";
 
 /**
  * AttestationVerifier validates attestation evidence and produces verification verdicts.
@@ -48,6 +84,7 @@ export class AttestationVerifier {
       freshness: false,
       reportStructure: false,
       configBinding: false,
+      policyBinding: false,
     };
 
     // 1. Validate report structure
@@ -102,7 +139,7 @@ export class AttestationVerifier {
 
     // 4. Verify platform authentication (signature chain)
     if (options.validateSignatureChain !== false) {
-      const platformAuthResult = await this.verifyPlatformSignature(evidence.report);
+      const platformAuthResult = await this.verifyPlatformSignature(evidence.report, options);
       claims.platformAuth = platformAuthResult.valid;
       if (!platformAuthResult.valid) {
         if (isSimulatorReport(evidence.report)) {
@@ -118,8 +155,34 @@ export class AttestationVerifier {
       claims.platformAuth = true; // Skip if explicitly disabled
     }
 
+    // A TCB below the floor is a platform failure whatever the signature says
+    if (options.minimumTcb) {
+      let report: AttestationReport | undefined;
+      try {
+        report = parseReport(evidence.report);
+      } catch {
+        report = undefined;
+      }
+      let reason: string | undefined;
+      try {
+        reason = checkMinimumTcb(report, options.minimumTcb);
+      } catch (error) {
+        reason = error instanceof Error ? error.message : String(error);
+      }
+      if (reason !== undefined) {
+        claims.platformAuth = false;
+        errors.push(reason);
+      }
+    }
+
     // 5. Verify output binding (session + output hash)
-    const bindingResult = this.verifyOutputBinding(evidence, transformedContext);
+    const bindingResult = this.verifyOutputBinding(
+      evidence,
+      transformedContext,
+      options.legacyReportData === true,
+      options.canonicalization ?? "jcs",
+      options.acceptShortReportData === true
+    );
     claims.sessionBinding = bindingResult.valid;
     if (!bindingResult.valid) {
       errors.push(`Output binding verification failed: ${bindingResult.error}`);
@@ -138,7 +201,23 @@ export class AttestationVerifier {
       claims.configBinding = true; // No expectation, pass by default
     }
 
-    // 7. Verify timestamp freshness
+    // 7. Verify policy binding (if expected policy hash provided)
+    if (options.expectedPolicyHash) {
+      if (evidence.policyHash === options.expectedPolicyHash) {
+        claims.policyBinding = true;
+        if (options.legacyReportData) {
+          warnings.push("Policy hash is not bound into report_data under the legacy formula");
+        }
+      } else {
+        errors.push(
+          `Policy hash mismatch: expected ${options.expectedPolicyHash}, got ${evidence.policyHash}`
+        );
+      }
+    } else {
+      claims.policyBinding = true; // No expectation, pass by default
+    }
+
+    // 8. Verify timestamp freshness
     const maxAge = options.maxAge ?? 5 * 60 * 1000; // Default 5 minutes
     const age = Date.now() - evidence.timestamp;
     if (age <= maxAge && age >= 0) {
@@ -149,7 +228,7 @@ export class AttestationVerifier {
       errors.push(`Attestation too old: ${Math.floor(age / 1000)}s (max: ${Math.floor(maxAge / 1000)}s)`);
     }
 
-    // 8. Verify nonce if provided
+    // 9. Verify nonce if provided
     if (options.nonce) {
       // Nonce verification would check report_data includes the nonce
       // For now, this is a placeholder for future implementation
@@ -172,11 +251,17 @@ export class AttestationVerifier {
   }
 
   /**
-   * Verify platform signature chain (AMD SEV-SNP).
+   * Verify platform signature chain (AMD SEV-SNP): the signature,
+   * certificate_chain, and crl_status checks of verifyAttestationReport.
+   * Fails without the VCEK, ASK, and ARK certificates and a trusted ARK.
    * @param report - Raw attestation report
+   * @param trust - Certificates, trusted ARK, and CRL
    * @returns Validation result
    */
-  async verifyPlatformSignature(report: Uint8Array): Promise<{
+  async verifyPlatformSignature(
+    report: Uint8Array,
+    trust: PlatformTrust = {}
+  ): Promise<{
     valid: boolean;
     error?: string;
   }> {
@@ -189,22 +274,13 @@ export class AttestationVerifier {
     }
 
     try {
-      const parsed = parseAttestationReport(report);
-
-      // In a real implementation, this would:
-      // 1. Extract signature from report
-      // 2. Verify signature against report data using VCEK public key
-      // 3. Verify VCEK certificate chain (VCEK → ASK → ARK)
-      // 4. Verify ARK certificate is AMD root key
-
-      // For now, we validate structure only
-      if (parsed.signature.length === 0) {
-        return { valid: false, error: "Missing signature" };
-      }
-
-      // Placeholder: real signature validation requires AMD key infrastructure
-      // This would integrate with AMD SEV-SNP verification libraries
-      return { valid: true };
+      const error =
+        checkSignature(parseReport(report), report, trust) ??
+        checkCertificateChain(trust) ??
+        (trust.crl !== undefined || trust.enforceCrl === true
+          ? crlFailure(checkCrl(trust), trust.enforceCrl === true)
+          : undefined);
+      return error === undefined ? { valid: true } : { valid: false, error };
     } catch (error) {
       return {
         valid: false,
@@ -224,18 +300,39 @@ export class AttestationVerifier {
   }
 
   /**
-   * Verify output binding (session ID + config hash + output hash).
+   * Verify output binding (session ID + config hash + policy hash + output hash).
+   * Session-cached evidence must bind its session key instead, and the
+   * key's signature must cover the binding before the window ends.
    * @param evidence - Attestation evidence
    * @param transformedContext - The transformed context
+   * @param legacy - Recompute report_data without the policy hash
+   * @param canonicalization - Canonicalization used for the output hash
+   * @param acceptShortReportData - Accept the 32-byte report_data of
+   *        binding versions 1 and 2 (migration only)
    * @returns Validation result
    */
   verifyOutputBinding(
     evidence: AttestationEvidence,
-    transformedContext: TransformedContext
+    transformedContext: TransformedContext,
+    legacy: boolean = false,
+    canonicalization: CanonicalizationMode = "jcs",
+    acceptShortReportData: boolean = false
   ): { valid: boolean; error?: string } {
     try {
-      // 1. Recompute output hash from transformed context
-      const actualOutputHash = hashContext(transformedContext);
+      // 1. Recompute output hash over the recorded scope (metadata excluded)
+      const hashScope = evidence.hashScope ?? "context-v1";
+      if (!HASH_SCOPES.includes(hashScope)) {
+        return { valid: false, error: `Unsupported hash scope: ${hashScope}` };
+      }
+      const hashAlgorithm = evidence.hashAlgorithm ?? "sha256";
+      const bindingVersion = evidence.bindingVersion ?? 1;
+      const actualOutputHash = hashOutput(
+        transformedContext,
+        evidence.outputEncoding ?? "json",
+        hashAlgorithm,
+        canonicalization,
+        bindingVersion
+      );
 
       // 2. Verify evidence.outputHash matches recomputed hash
       if (actualOutputHash !== evidence.outputHash) {
@@ -249,23 +346,45 @@ export class AttestationVerifier {
       const reportData = extractReportData(evidence.report);
 
       // 4. Recompute expected report_data
-      // report_data = SHA-256(sessionId || configHash || outputHash || timestamp)
-      const sessionIdBytes = Buffer.from(evidence.sessionId, "hex");
-      const configHashBytes = Buffer.from(evidence.configHash, "hex");
-      const outputHashBytes = Buffer.from(evidence.outputHash, "hex");
-      const timestampBytes = Buffer.alloc(8);
-      timestampBytes.writeBigUInt64BE(BigInt(evidence.timestamp), 0);
-
-      const expectedReportData = createHash("sha256");
-      expectedReportData.update(sessionIdBytes);
-      expectedReportData.update(configHashBytes);
-      expectedReportData.update(outputHashBytes);
-      expectedReportData.update(timestampBytes);
-      const expectedHash = expectedReportData.digest();
-
-      // 5. Compare first 32 bytes of report_data with expected hash
-      const reportDataHash = reportData.slice(0, 32);
-      if (!expectedHash.equals(Buffer.from(reportDataHash))) {
+      // Version 1: SHA-256(sessionId || configHash || policyHash || [inputHash] || [chainLink] || [algorithmId] || outputHash || timestamp)
+      // Version 2: the same fields, length-prefixed under HASH_DOMAINS.reportData
+      // Version 3: 0x03 || SHA-512 of those fields under HASH_DOMAINS.reportDataV3
+      const expectedHash = createReportData(
+        Buffer.from(evidence.sessionId, "hex"),
+        Buffer.from(evidence.configHash, "hex"),
+        Buffer.from(evidence.policyHash, "hex"),
+        Buffer.from(evidence.outputHash, "hex"),
+        evidence.timestamp,
+        legacy,
+        hashAlgorithm,
+        evidence.inputHash !== undefined ? Buffer.from(evidence.inputHash, "hex") : undefined,
+        evidence.chainedFrom !== undefined ? Buffer.from(evidence.chainedFrom, "hex") : undefined,
+        bindingVersion
+      );
+
+      // A session report binds the session key instead, and the key signs
+      // the expected binding
+      const session = evidence.sessionAttestation;
+      if (session !== undefined) {
+        const sessionData = createSessionKeyReportData(
+          Buffer.from(evidence.sessionId, "hex"),
+          session.publicKey,
+          session.windowStart,
+          session.expiresAt
+        );
+        if (!sessionData.equals(Buffer.from(reportData))) {
+          return { valid: false, error: "Report data does not bind the session key" };
+        }
+        const error = checkSessionSignature(session, evidence.timestamp, expectedHash, Date.now());
+        return error === undefined ? { valid: true } : { valid: false, error };
+      }
+
+      // 5. Compare report_data: all 64 bytes, or the first 32 of a short layout
+      const layoutError = checkReportDataLayout(reportData, expectedHash, acceptShortReportData);
+      if (layoutError !== undefined) {
+        return { valid: false, error: layoutError };
+      }
+      if (!expectedHash.equals(Buffer.from(reportData.subarray(0, expectedHash.length)))) {
         return {
           valid: false,
           error: "Report data does not match expected binding",
@@ -281,6 +400,70 @@ export class AttestationVerifier {
     }
   }
 
+  /**
+   * Walk a chain of sequential transforms. Each link's output binding must
+   * verify (see verifyOutputBinding), and each link after the first must
+   * be chained from the previous link's output hash. Freshness, code
+   * identity, and platform claims still need verify() per link.
+   * @param links - Evidence and transformed context, in chain order
+   * @param acceptShortReportData - Accept the 32-byte report_data of
+   *        binding versions 1 and 2 (migration only)
+   * @returns True if the chain is intact (false for an empty chain)
+   */
+  verifyChain(
+    links: Array<{ evidence: AttestationEvidence; transformedContext: TransformedContext }>,
+    acceptShortReportData: boolean = false
+  ): boolean {
+    if (links.length === 0) {
+      return false;
+    }
+    return links.every(({ evidence, transformedContext }, index) => {
+      const binding = this.verifyOutputBinding(
+        evidence,
+        transformedContext,
+        false,
+        "jcs",
+        acceptShortReportData
+      );
+      if (!binding.valid) {
+        return false;
+      }
+      if (index === 0) {
+        return true;
+      }
+      const previous = links[index - 1].evidence.outputHash.toLowerCase();
+      return evidence.chainedFrom?.toLowerCase() === previous;
+    });
+  }
+
+  /**
+   * Check that evidence commits to the given raw input. Verify the
+   * evidence itself first; this only opens the commitment.
+   * @param evidence - Attestation evidence carrying inputHash
+   * @param rawContext - The raw input that was transformed
+   * @param nonce - Session nonce (hex, ReasonResult.inputNonce)
+   * @returns True if the evidence commits to this input
+   */
+  verifyInputCommitment(
+    evidence: AttestationEvidence,
+    rawContext: string | string[],
+    nonce: string
+  ): boolean {
+    if (evidence.inputHash === undefined) {
+      return false;
+    }
+    const encoder = new TextEncoder();
+    const documents = (Array.isArray(rawContext) ? rawContext : [rawContext]).map((document) =>
+      encoder.encode(document)
+    );
+    const expected = computeInputHash(
+      documents,
+      Buffer.from(evidence.sessionId, "hex"),
+      Buffer.from(nonce, "hex")
+    );
+    return expected.equals(Buffer.from(evidence.inputHash, "hex"));
+  }
+
   /**
    * Quick verification check (no detailed claims).
    * @param evidence - Attestation evidence
@@ -339,3 +522,391 @@ export function createVerifier(): AttestationVerifier {
   return new AttestationVerifier();
 }
 
+function expectedHex(name: string, value: string, bytes?: number): Buffer {
+  const valid =
+    /^(?:[0-9a-fA-F]{2})*$/.test(value) && (bytes === undefined || value.length === bytes * 2);
+  if (!valid) {
+    throw new Error(
+      `Expected ${name} must be ${bytes === undefined ? "a" : `a ${bytes}-byte`} hex string`
+    );
+  }
+  return Buffer.from(value, "hex");
+}
+
+function checkReportData(report: AttestationReport, expected: ExpectedBinding): string | undefined {
+  if (expected.sessionAttestation !== undefined) {
+    const session = expected.sessionAttestation;
+    const sessionData = createSessionKeyReportData(
+      expectedHex("sessionId", expected.sessionId, 16),
+      session.publicKey,
+      session.windowStart,
+      session.expiresAt
+    );
+    return sessionData.equals(report.reportData)
+      ? undefined
+      : "report_data does not bind the session key";
+  }
+  const reportData = expectedReportData(expected);
+  const layoutError = checkReportDataLayout(
+    report.reportData,
+    reportData,
+    expected.acceptShortReportData === true
+  );
+  if (layoutError !== undefined) {
+    return layoutError;
+  }
+  if (!reportData.equals(report.reportData.subarray(0, reportData.length))) {
+    return "report_data does not match the expected binding";
+  }
+  return undefined;
+}
+
+// The session key must have signed the report_data a per_request report
+// would carry for the binding, at a timestamp inside its window, and the
+// window must not have ended before verification
+function checkSessionSignature(
+  session: SessionAttestation,
+  timestamp: number,
+  reportData: Uint8Array,
+  verifiedAt: number
+): string | undefined {
+  if (session.publicKey.length !== 32 || session.signature.length !== 64) {
+    return "Session attestation needs a 32-byte public key and a 64-byte signature";
+  }
+  if (timestamp < session.windowStart || timestamp >= session.expiresAt) {
+    return (
+      `Timestamp ${timestamp} is outside the session window ` +
+      `[${session.windowStart}, ${session.expiresAt})`
+    );
+  }
+  if (session.expiresAt <= verifiedAt) {
+    return `Session attestation expired at ${session.expiresAt}, before ${verifiedAt}`;
+  }
+  const valid = verifySignature(
+    null,
+    reportData,
+    ed25519PublicKey(session.publicKey),
+    session.signature
+  );
+  return valid ? undefined : "Session key signature does not cover the expected binding";
+}
+
+function expectedReportData(expected: ExpectedBinding): Buffer {
+  return createReportData(
+    expectedHex("sessionId", expected.sessionId, 16),
+    expectedHex("configHash", expected.configHash, 32),
+    expected.policyHash !== undefined
+      ? expectedHex("policyHash", expected.policyHash, 32)
+      : new Uint8Array(0),
+    expectedHex("outputHash", expected.outputHash),
+    expected.timestamp,
+    expected.policyHash === undefined,
+    expected.hashAlgorithm ?? "sha256",
+    expected.inputHash !== undefined ? expectedHex("inputHash", expected.inputHash, 32) : undefined,
+    expected.chainedFrom !== undefined ? expectedHex("chainedFrom", expected.chainedFrom) : undefined,
+    expected.bindingVersion ?? 1
+  );
+}
+
+// A 32-byte binding (versions 1 and 2) is only accepted when the caller
+// opts in, and then its padding must be zero
+function checkReportDataLayout(
+  reportData: Uint8Array,
+  expected: Uint8Array,
+  acceptShortReportData: boolean
+): string | undefined {
+  if (expected.length === reportData.length) {
+    return undefined;
+  }
+  if (!acceptShortReportData) {
+    return "The 32-byte report_data layout of binding versions 1 and 2 needs acceptShortReportData";
+  }
+  if (reportData.subarray(expected.length).some((byte) => byte !== 0)) {
+    return "report_data padding is not zero";
+  }
+  return undefined;
+}
+
+function checkMinimumTcb(
+  report: AttestationReport | undefined,
+  minimum: MinimumTcb
+): string | undefined {
+  if (report === undefined) {
+    return "Minimum TCB set, but the report carries no TCB";
+  }
+  const below = tcbBelowMinimum(report.reportedTcb, minimum);
+  return below.length > 0 ? `Reported TCB below minimum: ${below.join(", ")}` : undefined;
+}
+
+function checkSimulatorSignature(
+  report: AttestationReport,
+  bytes: Uint8Array,
+  simulatorKey: string | Uint8Array | undefined
+): string | undefined {
+  if (report.signatureAlgo !== SIGNATURE_ALGO_SIMULATOR_ED25519) {
+    return "Simulator report is not signed with a simulator key";
+  }
+  if (simulatorKey === undefined) {
+    return "No simulator verification key given";
+  }
+  const key = createPublicKey(
+    typeof simulatorKey === "string"
+      ? simulatorKey
+      : { key: Buffer.from(simulatorKey), format: "der", type: "spki" }
+  );
+  if (key.asymmetricKeyType !== "ed25519") {
+    return "Simulator verification key is not an Ed25519 key";
+  }
+  const signature = signatureToEd25519(report.signature);
+  if (signature === undefined) {
+    return "Simulator signature components exceed 32 bytes";
+  }
+  const valid = verifySignature(null, bytes.subarray(0, SIGNED_REPORT_BYTES), key, signature);
+  return valid ? undefined : "Report signature does not verify against the simulator key";
+}
+
+// What the platform checks read from an ExpectedBinding or
+// VerificationOptions
+type PlatformTrust = Pick<
+  ExpectedBinding,
+  "simulatorKey" | "certificates" | "trustedArk" | "crl" | "enforceCrl"
+>;
+
+function checkSignature(
+  report: AttestationReport,
+  bytes: Uint8Array,
+  expected: PlatformTrust
+): string | undefined {
+  if (isSimulatorVersion(report.version)) {
+    return checkSimulatorSignature(report, bytes, expected.simulatorKey);
+  }
+  const vcek = expected.certificates?.vcek;
+
+  if (report.signatureAlgo !== SIGNATURE_ALGO_ECDSA_P384_SHA384) {
+    return `Unsupported signature algorithm: ${report.signatureAlgo}`;
+  }
+  if (vcek === undefined) {
+    return "No VCEK certificate given";
+  }
+  const signature = signatureToP1363(report.signature);
+  if (signature === undefined) {
+    return "Signature components exceed 48 bytes";
+  }
+  const valid = verifySignature(
+    "sha384",
+    bytes.subarray(0, SIGNED_REPORT_BYTES),
+    { key: new X509Certificate(vcek).publicKey, dsaEncoding: "ieee-p1363" },
+    signature
+  );
+  return valid ? undefined : "Report signature does not verify against the VCEK";
+}
+
+function checkCrl(expected: PlatformTrust): { status: CrlStatus; reason?: string } {
+  const ask = expected.certificates?.ask;
+  const ark = expected.trustedArk ?? expected.certificates?.ark;
+  if (ask === undefined || ark === undefined) {
+    return { status: "unavailable", reason: "ASK and ARK certificates are required" };
+  }
+  if (expected.crl === undefined || expected.crl === null) {
+    return { status: "unavailable", reason: "No CRL available" };
+  }
+  try {
+    return crlStatus(expected.crl, { ask, ark });
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    return { status: "unavailable", reason };
+  }
+}
+
+// "revoked" always fails crl_status, "unavailable" only under enforceCrl
+function crlFailure(
+  result: { status: CrlStatus; reason?: string },
+  enforceCrl: boolean
+): string | undefined {
+  return result.status === "revoked" || (result.status !== "good" && enforceCrl)
+    ? result.reason
+    : undefined;
+}
+
+function checkCertificateChain(expected: PlatformTrust): string | undefined {
+  const certificates = expected.certificates;
+  if (!certificates?.vcek || !certificates.ask || !certificates.ark) {
+    return "VCEK, ASK, and ARK certificates are required";
+  }
+  if (expected.trustedArk === undefined) {
+    return "No trusted ARK given";
+  }
+
+  const vcek = new X509Certificate(certificates.vcek);
+  const ask = new X509Certificate(certificates.ask);
+  const ark = new X509Certificate(certificates.ark);
+  if (ark.fingerprint256 !== new X509Certificate(expected.trustedArk).fingerprint256) {
+    return "ARK is not the trusted ARK";
+  }
+  if (!ark.verify(ark.publicKey)) {
+    return "ARK is not self-signed";
+  }
+  if (!ask.checkIssued(ark) || !ask.verify(ark.publicKey)) {
+    return "ASK is not signed by the ARK";
+  }
+  if (!vcek.checkIssued(ask) || !vcek.verify(ask.publicKey)) {
+    return "VCEK is not signed by the ASK";
+  }
+
+  const now = Date.now();
+  const chain: Array<[string, X509Certificate]> = [["VCEK", vcek], ["ASK", ask], ["ARK", ark]];
+  for (const [name, certificate] of chain) {
+    if (now < Date.parse(certificate.validFrom) || now > Date.parse(certificate.validTo)) {
+      return `${name} certificate is not valid now`;
+    }
+  }
+  return undefined;
+}
+
+/**
+ * Verify a raw attestation report on its own: structure, platform,
+ * report_data against the expected binding (for a session report, against
+ * the session key, whose signature over the binding is then checked as
+ * session_signature), measurement, the reported TCB against minimumTcb
+ * (when given), signature, and (hardware reports) the VCEK → ASK → ARK
+ * chain and, when a crl is given or enforceCrl is set, its revocation
+ * (crl_status; "revoked" always fails, "unavailable" only under
+ * enforceCrl). Simulator reports are checked against the
+ * simulator measurement and must be signed with simulatorKey; they fail
+ * the platform check unless allowSimulator is set. An evidence envelope
+ * supplies its certificates and binding version where expected leaves
+ * them out; an envelope that contradicts its report fails structure, as
+ * does a TDX quote.
+ * @param evidence - Raw attestation report or evidence envelope
+ * @param binding - Binding fields, measurement, and certificates
+ * @returns Named pass/fail checks; valid only if all passed
+ */
+export function verifyAttestationReport(
+  evidence: Uint8Array | EvidenceEnvelope,
+  binding: ExpectedBinding
+): ReportVerificationResult {
+  const checks: ReportCheck[] = [];
+  const run = (name: ReportCheckName, check: () => string | undefined) => {
+    let reason: string | undefined;
+    try {
+      reason = check();
+    } catch (error) {
+      reason = error instanceof Error ? error.message : String(error);
+    }
+    checks.push(reason === undefined ? { name, passed: true } : { name, passed: false, reason });
+  };
+
+  let report: Uint8Array;
+  let expected: ExpectedBinding;
+  let parsed: AttestationReport;
+  try {
+    ({ report, expected } = resolveEvidence(evidence, binding));
+    if (isTdxQuote(report)) {
+      throw new Error("TDX quote verification is not supported; only SEV-SNP reports verify");
+    }
+    parsed = parseReport(report);
+    checks.push({ name: "structure", passed: true });
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    checks.push({ name: "structure", passed: false, reason });
+    return { valid: false, simulator: false, checks };
+  }
+
+  const simulator = isSimulatorVersion(parsed.version);
+  run("platform", () =>
+    simulator && expected.allowSimulator !== true
+      ? "Simulator report (set allowSimulator to accept it)"
+      : undefined
+  );
+  run("report_data", () => checkReportData(parsed, expected));
+  if (expected.sessionAttestation !== undefined) {
+    const session = expected.sessionAttestation;
+    run("session_signature", () =>
+      checkSessionSignature(
+        session,
+        expected.timestamp,
+        expectedReportData(expected),
+        expected.verifiedAt ?? Date.now()
+      )
+    );
+  }
+  run("measurement", () => {
+    // A simulator report defaults to this process's simulator measurement,
+    // or the marker if it has a zero measurement
+    const actual = extractMeasurement(report);
+    const measurement =
+      expected.measurement ??
+      (simulator
+        ? actual === SIMULATOR_MEASUREMENT
+          ? SIMULATOR_MEASUREMENT
+          : simulatorMeasurement()
+        : undefined);
+    if (measurement === undefined) {
+      return "No expected measurement given";
+    }
+    return actual === measurement.toLowerCase()
+      ? undefined
+      : `Measurement mismatch: report has ${actual}, expected ${measurement}`;
+  });
+  if (expected.minimumTcb !== undefined) {
+    const minimum = expected.minimumTcb;
+    run("tcb", () => checkMinimumTcb(parsed, minimum));
+  }
+  run("signature", () => checkSignature(parsed, report, expected));
+  let crl: { status: CrlStatus; reason?: string } | undefined;
+  if (!simulator) {
+    run("certificate_chain", () => checkCertificateChain(expected));
+    if (expected.crl !== undefined || expected.enforceCrl === true) {
+      const result = checkCrl(expected);
+      run("crl_status", () => crlFailure(result, expected.enforceCrl === true));
+      crl = result;
+    }
+  }
+
+  return {
+    valid: checks.every((check) => check.passed),
+    simulator,
+    checks,
+    ...(crl !== undefined ? { crlStatus: crl.status } : {}),
+  };
+}
+
+/**
+ * verifyAttestationReport with revocation checking: for a hardware report
+ * whose binding carries no crl, the AMD CRL is fetched first (see
+ * fetchCrl; cached per max-age). A fetch that fails leaves the status
+ * "unavailable", which fails crl_status only under enforceCrl.
+ * @param evidence - Raw attestation report or evidence envelope
+ * @param binding - Binding fields, measurement, and certificates
+ * @param options - KDS, timeout, retry, and cache options for the fetch
+ * @returns Named pass/fail checks, crl_status included for hardware reports
+ */
+export async function verifyAttestationReportWithCrl(
+  evidence: Uint8Array | EvidenceEnvelope,
+  binding: ExpectedBinding,
+  options: VcekOptions = {}
+): Promise<ReportVerificationResult> {
+  let crl = binding.crl;
+  if (crl === undefined && !isSimulatorEvidence(evidence)) {
+    try {
+      crl = await fetchCrl(options);
+    } catch {
+      crl = null;
+    }
+  }
+  return verifyAttestationReport(evidence, crl === undefined ? binding : { ...binding, crl });
+}
+
+// Simulator evidence has no AMD chain to revoke; a report that does not
+// parse fails structure whether or not a CRL is fetched
+function isSimulatorEvidence(evidence: Uint8Array | EvidenceEnvelope): boolean {
+  if (!(evidence instanceof Uint8Array)) {
+    return evidence.platform !== "sev-snp";
+  }
+  try {
+    return isTdxQuote(evidence) || isSimulatorVersion(parseReport(evidence).version);
+  } catch {
+    return true;
+  }
+}
+
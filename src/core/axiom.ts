@@ -3,11 +3,25 @@ import type {
   ReasonInput,
   ReasonResult,
 } from "./config.ts";
+import {
+  BINDING_VERSIONS,
+  HASH_ALGORITHMS,
+  isHashAlgorithmAvailable,
+  isHexDigest,
+} from "./canonical.ts";
 import { ConfigurationError } from "./errors.ts";
+import { OUTPUT_ENCODINGS } from "./serialization.ts";
+import { POLICY_VERSIONS } from "./policy.ts";
+import { ATTESTATION_MODES } from "../attestation/types.ts";
 import { Executor } from "../runtime/executor.ts";
 import { assertNoNetworkAccess } from "../security/guarantees.ts";
 import { Session } from "../runtime/session.ts";
 
+/**
+ * Fields a ReasonInput may carry.
+ */
+const REASON_INPUT_FIELDS = ["context", "task", "model", "previousOutputHash"];
+
 /**
  * Axiom Core main class.
  * Provides semantic transformation of raw input into non-identifying representations.
@@ -55,9 +69,54 @@ export class Axiom {
       );
     }
 
-    if (config.policyVersion !== "v1") {
+    if (!POLICY_VERSIONS.includes(config.policyVersion)) {
+      throw new ConfigurationError(
+        `Invalid policyVersion: ${config.policyVersion}. Must be one of ${POLICY_VERSIONS.join(", ")}.`
+      );
+    }
+
+    if (config.idSalt !== undefined && config.idSalt.length < 16) {
+      throw new ConfigurationError("Invalid idSalt: must be at least 16 bytes");
+    }
+
+    if (config.outputEncoding !== undefined && !OUTPUT_ENCODINGS.includes(config.outputEncoding)) {
+      throw new ConfigurationError(
+        `Invalid outputEncoding: ${config.outputEncoding}. Must be one of ${OUTPUT_ENCODINGS.join(", ")}.`
+      );
+    }
+
+    if (config.hashAlgorithm !== undefined) {
+      if (!HASH_ALGORITHMS.includes(config.hashAlgorithm)) {
+        throw new ConfigurationError(
+          `Invalid hashAlgorithm: ${config.hashAlgorithm}. Must be one of ${HASH_ALGORITHMS.join(", ")}.`
+        );
+      }
+      if (!isHashAlgorithmAvailable(config.hashAlgorithm)) {
+        throw new ConfigurationError(
+          `Invalid hashAlgorithm: ${config.hashAlgorithm} is not available in this runtime`
+        );
+      }
+    }
+
+    if (config.bindingVersion !== undefined) {
+      if (!BINDING_VERSIONS.includes(config.bindingVersion)) {
+        throw new ConfigurationError(
+          `Invalid bindingVersion: ${config.bindingVersion}. Must be one of ${BINDING_VERSIONS.join(", ")}.`
+        );
+      }
+      if (config.bindingVersion !== 1 && config.legacyReportData === true) {
+        throw new ConfigurationError(
+          `Invalid configuration: bindingVersion ${config.bindingVersion} cannot be combined with legacyReportData`
+        );
+      }
+    }
+
+    if (
+      config.attestationMode !== undefined &&
+      !ATTESTATION_MODES.includes(config.attestationMode)
+    ) {
       throw new ConfigurationError(
-        `Invalid policyVersion: ${config.policyVersion}. Must be "v1".`
+        `Invalid attestationMode: ${config.attestationMode}. Must be one of ${ATTESTATION_MODES.join(", ")}.`
       );
     }
 
@@ -86,17 +145,19 @@ export class Axiom {
    * - Attested tier generates cryptographic attestation evidence
    */
   async reason(input: ReasonInput): Promise<ReasonResult> {
-    this.validateReasonInput(input);
+    const inputWarnings = this.validateReasonInput(input);
+    const warnings = [...this.executor.getPolicyWarnings(), ...inputWarnings];
 
     // Create session for this execution
     const session = Session.create(this.config);
 
     // Route execution based on security tier
-    if (this.config.securityTier === "attested") {
-      return await this.executeAttested(input, session);
-    } else {
-      return await this.executeStandard(input, session);
-    }
+    const result =
+      this.config.securityTier === "attested"
+        ? await this.executeAttested(input, session)
+        : await this.executeStandard(input, session);
+    warnings.push(...(result.warnings ?? []));
+    return warnings.length > 0 ? { ...result, warnings } : result;
   }
 
   /**
@@ -108,15 +169,19 @@ export class Axiom {
     session: Session
   ): Promise<ReasonResult> {
     // Execute the transformation pipeline
-    const transformedContext = this.executor.execute(
+    const { transformedContext, entitiesTruncated, relationsTruncated } = this.executor.execute(
       input.context,
       input.task,
       input.model
     );
 
     // Compute output hash and bind to session
-    const { hash } = await import("./canonical.ts");
-    const outputHash = hash(transformedContext);
+    const { hashOutput } = await import("./serialization.ts");
+    const outputHash = hashOutput(
+      transformedContext,
+      this.config.outputEncoding,
+      this.config.hashAlgorithm
+    );
     session.setOutputHash(outputHash);
     session.finalize();
 
@@ -124,6 +189,8 @@ export class Axiom {
     return {
       transformedContext,
       renderedPrompt: undefined, // Could add LLM prompt rendering here
+      ...(entitiesTruncated ? { entitiesTruncated } : {}),
+      ...(relationsTruncated ? { relationsTruncated } : {}),
     };
   }
 
@@ -140,7 +207,8 @@ export class Axiom {
       input.context,
       input.task,
       input.model,
-      session
+      session,
+      input.previousOutputHash
     );
 
     return result;
@@ -148,9 +216,11 @@ export class Axiom {
 
   /**
    * Validate the reason input.
-   * @throws ConfigurationError if input is invalid
+   * @returns Warnings for unknown input fields (non-strict policies only)
+   * @throws ConfigurationError if input is invalid, or names an unknown
+   *         field under the strict preset
    */
-  private validateReasonInput(input: ReasonInput): void {
+  private validateReasonInput(input: ReasonInput): string[] {
     if (!input) {
       throw new ConfigurationError("Reason input is required");
     }
@@ -173,6 +243,24 @@ export class Axiom {
     if (!input.task || input.task.trim().length === 0) {
       throw new ConfigurationError("Task is required");
     }
+
+    if (input.previousOutputHash !== undefined) {
+      if (!isHexDigest(input.previousOutputHash)) {
+        throw new ConfigurationError(
+          "Invalid previousOutputHash: must be a 32- or 48-byte hex string"
+        );
+      }
+      if (this.config.securityTier !== "attested") {
+        throw new ConfigurationError('previousOutputHash requires securityTier "attested"');
+      }
+    }
+
+    // Name only: the value of an unknown field may be raw context
+    const unknown = Object.keys(input).filter((key) => !REASON_INPUT_FIELDS.includes(key));
+    if (unknown.length > 0 && this.executor.isStrict()) {
+      throw new ConfigurationError(`Unknown reason input field: ${unknown[0]}`);
+    }
+    return unknown.map((key) => `Unknown reason input field: ${key}`);
   }
 
   /**
@@ -1,52 +1,476 @@
 import type { TransformedContext } from "./config.ts";
-import { createHash } from "crypto";
+import { ConfigurationError, TransformationError } from "./errors.ts";
+import { createHash, getHashes } from "crypto";
+import type { Hash } from "crypto";
 
 /**
  * Canonical JSON serialization for Axiom transformed context.
  * Ensures deterministic output for hashing and verification.
  *
  * Rules:
- * - Stable key ordering (alphabetical)
- * - Normalized numeric formats (no trailing zeros, consistent precision)
+ * - RFC 8785 (JCS) serialization: object keys sorted by UTF-16 code
+ *   units at every level, ECMAScript number formatting, prescribed
+ *   string escaping, no whitespace
+ * - Normalized numeric values (-0 as 0, ten decimal places)
+ * - Confidence scores fixed at two decimal places
  * - Stable entity/relation ordering by synthetic ID
- * - No whitespace in output
+ * - Constraints included only when present
+ * - Metadata never included (see HASH_SCOPES)
  * - UTF-8 encoding
+ *
+ * Test vectors live in tests/vectors/canonicalization.json.
+ */
+
+/**
+ * Canonicalization modes. "jcs" is RFC 8785; "legacy" is the pre-JCS
+ * serialization (locale-aware ordering of entities and relations,
+ * non-finite numbers written as null), kept for one release so stored
+ * hashes still verify.
+ */
+export const CANONICALIZATION_MODES = ["jcs", "legacy"] as const;
+
+/**
+ * One of CANONICALIZATION_MODES.
+ */
+export type CanonicalizationMode = (typeof CANONICALIZATION_MODES)[number];
+
+/**
+ * Output hash algorithms. "blake3" is only available when the runtime's
+ * crypto provides it (see isHashAlgorithmAvailable).
+ */
+export const HASH_ALGORITHMS = ["sha256", "sha384", "blake3"] as const;
+
+/**
+ * One of HASH_ALGORITHMS. Default: "sha256".
+ */
+export type HashAlgorithm = (typeof HASH_ALGORITHMS)[number];
+
+/**
+ * One-byte algorithm identifiers bound into report_data (see
+ * createReportData).
  */
+export const HASH_ALGORITHM_IDS: Readonly<Record<HashAlgorithm, number>> = {
+  sha256: 0x01,
+  sha384: 0x02,
+  blake3: 0x03,
+};
+
+/**
+ * Digest length in bytes for each algorithm.
+ */
+export const HASH_DIGEST_BYTES: Readonly<Record<HashAlgorithm, number>> = {
+  sha256: 32,
+  sha384: 48,
+  blake3: 32,
+};
+
+/**
+ * Whether a string is a hex digest of one of the supported lengths
+ * (HASH_DIGEST_BYTES).
+ */
+export function isHexDigest(value: unknown): value is string {
+  return (
+    typeof value === "string" &&
+    /^[0-9a-fA-F]+$/.test(value) &&
+    Object.values(HASH_DIGEST_BYTES).some((bytes) => value.length === bytes * 2)
+  );
+}
+
+/**
+ * Whether this runtime can compute the given hash algorithm.
+ */
+export function isHashAlgorithmAvailable(algorithm: HashAlgorithm): boolean {
+  return HASH_ALGORITHMS.includes(algorithm) && getHashes().includes(algorithm);
+}
+
+/**
+ * Binding formulas for attested output. Version 1 hashes the output's
+ * encoded bytes directly and builds report_data from fields told apart by
+ * their lengths. Version 2 length-prefixes every field under a domain tag
+ * (see domainSeparatedHash), so no field can be shifted into another.
+ * Version 3 keeps the version 2 output hash and fills all 64 bytes of
+ * REPORT_DATA: the version byte, then SHA-512 of the same fields under
+ * HASH_DOMAINS.reportDataV3, truncated to 63 bytes. Versions 1 and 2 use
+ * only the first 32 bytes. Evidence without a bindingVersion uses 1.
+ */
+export const BINDING_VERSIONS = [1, 2, 3] as const;
+
+/**
+ * One of BINDING_VERSIONS.
+ */
+export type BindingVersion = (typeof BINDING_VERSIONS)[number];
+
+/**
+ * Domain tags for binding versions 2 and 3, one per kind of digest. A
+ * new kind of digest gets a new tag rather than reusing one.
+ */
+export const HASH_DOMAINS = {
+  reportData: "axiom-report-data-v1",
+  reportDataV3: "axiom-report-data-v3",
+  context: "axiom-context-v1",
+  sessionKey: "axiom-session-key-v1",
+} as const;
+
+/**
+ * Domain-separated digest: domain || (u64BE length || field) for each
+ * field. Every field carries its length, so ("a", "bc") and ("ab", "c")
+ * never collide, and an empty field stands for an absent one.
+ * @param domain - Domain tag (see HASH_DOMAINS)
+ * @param fields - Fields in order
+ * @param algorithm - Hash algorithm (default "sha256"); "sha512" is only
+ *        used for the version 3 report_data
+ * @returns Digest bytes
+ * @throws ConfigurationError if the algorithm is not available
+ */
+export function domainSeparatedHash(
+  domain: string,
+  fields: Uint8Array[],
+  algorithm: HashAlgorithm | "sha512" = "sha256"
+): Buffer {
+  if (algorithm !== "sha512" && !isHashAlgorithmAvailable(algorithm)) {
+    throw new ConfigurationError(`Hash algorithm ${algorithm} is not available in this runtime`);
+  }
+  const digest = createHash(algorithm);
+  digest.update(domain, "utf8");
+  for (const field of fields) {
+    const length = Buffer.alloc(8);
+    length.writeBigUInt64BE(BigInt(field.length), 0);
+    digest.update(length);
+    digest.update(field);
+  }
+  return digest.digest();
+}
 
 /**
  * Canonicalize a transformed context into a deterministic string representation.
  * @param context - The transformed context to canonicalize
+ * @param mode - Canonicalization mode (default "jcs")
  * @returns Canonical JSON string (no whitespace)
+ * @throws TransformationError in "jcs" mode if the context holds a
+ *         non-finite number or a string with a lone surrogate
+ */
+export function canonicalize(
+  context: TransformedContext,
+  mode: CanonicalizationMode = "jcs"
+): string {
+  // Create a normalized copy with stable ordering
+  const normalized = normalizeTransformedContext(context, mode);
+
+  if (mode === "legacy") {
+    return JSON.stringify(normalized, stableStringify);
+  }
+  return canonicalJson(normalized);
+}
+
+/**
+ * Write the canonical form of a transformed context in chunks, without
+ * building the whole string. Joining the chunks gives exactly
+ * canonicalize(); hash() feeds them straight into the digest. No chunk
+ * splits a character.
+ * @param context - The transformed context to serialize
+ * @param write - Receives each chunk in order
+ * @param mode - Canonicalization mode (default "jcs")
+ * @throws TransformationError in "jcs" mode if the context holds a
+ *         non-finite number or a string with a lone surrogate
  */
-export function canonicalize(context: TransformedContext): string {
+export function writeCanonical(
+  context: TransformedContext,
+  write: (chunk: string) => void,
+  mode: CanonicalizationMode = "jcs"
+): void {
   // Create a normalized copy with stable ordering
-  const normalized = normalizeTransformedContext(context);
+  const normalized = normalizeTransformedContext(context, mode);
+
+  if (mode === "legacy") {
+    write(JSON.stringify(normalized, stableStringify));
+    return;
+  }
+  writeCanonicalJson(normalized, write);
+}
 
-  // Serialize with custom replacer for stable key ordering
-  return JSON.stringify(normalized, stableStringify);
+/**
+ * Characters buffered before each digest update when streaming.
+ */
+const DIGEST_BUFFER_CHARS = 64 * 1024;
+
+/**
+ * Feed the canonical form of a transformed context into a digest, in
+ * batches of about DIGEST_BUFFER_CHARS characters.
+ * @returns UTF-8 byte length of the canonical form
+ */
+export function digestCanonical(
+  context: TransformedContext,
+  digest: Hash,
+  mode: CanonicalizationMode = "jcs"
+): number {
+  let pending = "";
+  let byteLength = 0;
+  writeCanonical(
+    context,
+    (chunk) => {
+      pending += chunk;
+      if (pending.length >= DIGEST_BUFFER_CHARS) {
+        digest.update(pending, "utf8");
+        byteLength += Buffer.byteLength(pending, "utf8");
+        pending = "";
+      }
+    },
+    mode
+  );
+  digest.update(pending, "utf8");
+  return byteLength + Buffer.byteLength(pending, "utf8");
+}
+
+/**
+ * UTF-8 byte length of the canonical form, computed without building it.
+ */
+export function canonicalByteLength(
+  context: TransformedContext,
+  mode: CanonicalizationMode = "jcs"
+): number {
+  let byteLength = 0;
+  writeCanonical(context, (chunk) => (byteLength += Buffer.byteLength(chunk, "utf8")), mode);
+  return byteLength;
 }
 
 /**
- * Compute SHA-256 hash of the canonical representation.
+ * Compute the hash of the canonical representation.
  * @param context - The transformed context to hash
- * @returns Hex-encoded SHA-256 hash
+ * @param mode - Canonicalization mode (default "jcs")
+ * @param algorithm - Hash algorithm (default "sha256")
+ * @returns Hex-encoded digest
+ * @throws ConfigurationError if the algorithm is not available
  */
-export function hash(context: TransformedContext): string {
-  const canonical = canonicalize(context);
-  return createHash("sha256").update(canonical, "utf8").digest("hex");
+export function hash(
+  context: TransformedContext,
+  mode: CanonicalizationMode = "jcs",
+  algorithm: HashAlgorithm = "sha256"
+): string {
+  if (!isHashAlgorithmAvailable(algorithm)) {
+    throw new ConfigurationError(`Hash algorithm ${algorithm} is not available in this runtime`);
+  }
+  // Streamed, so the canonical string is never held whole
+  const digest = createHash(algorithm);
+  digestCanonical(context, digest, mode);
+  return digest.digest("hex");
+}
+
+/**
+ * Hash scopes: what part of a transformed context an output hash covers.
+ * "context-v1" covers entities, relations, task, model, and constraints of
+ * a schema version 1 context, every map with sorted keys. `metadata` is
+ * outside the scope, so operational details can change without changing
+ * the hash. The schema version is fixed by the scope identifier rather
+ * than hashed as a field. Responses and evidence record the scope so a
+ * verifier knows what to recompute.
+ */
+export const HASH_SCOPES = ["context-v1"] as const;
+
+/**
+ * One of HASH_SCOPES. Default: "context-v1".
+ */
+export type HashScope = (typeof HASH_SCOPES)[number];
+
+/**
+ * Transformed context schema versions getCanonicalContext accepts. A
+ * context without `schemaVersion` is read as the current version.
+ */
+export const CONTEXT_SCHEMA_VERSIONS = [1] as const;
+
+/**
+ * Canonicalize a transformed context given as JSON, the same way it is
+ * canonicalized before hashing. Lets a verifier obtain the exact bytes
+ * an output hash covers. `schemaVersion`, when present, is checked and
+ * then dropped; it is not part of the canonical form. Neither is
+ * `metadata` (see HASH_SCOPES).
+ * @param transformedContextJson - TransformedContext as JSON
+ * @returns Canonical JSON string
+ * @throws ConfigurationError on invalid JSON or an unknown schemaVersion
+ */
+export function getCanonicalContext(transformedContextJson: string): string {
+  let parsed: unknown;
+  try {
+    parsed = JSON.parse(transformedContextJson);
+  } catch {
+    throw new ConfigurationError("Transformed context is not valid JSON");
+  }
+  if (parsed === null || typeof parsed !== "object" || Array.isArray(parsed)) {
+    throw new ConfigurationError("Transformed context must be a JSON object");
+  }
+
+  const { schemaVersion, ...context } = parsed as Record<string, unknown>;
+  if (
+    schemaVersion !== undefined &&
+    !CONTEXT_SCHEMA_VERSIONS.some((version) => version === schemaVersion)
+  ) {
+    throw new ConfigurationError(
+      `Unsupported transformed context schemaVersion: ${JSON.stringify(schemaVersion)}`
+    );
+  }
+  if (!Array.isArray(context.entities) || !Array.isArray(context.relations)) {
+    throw new ConfigurationError("Transformed context must have entities and relations arrays");
+  }
+
+  return canonicalize(context as unknown as TransformedContext);
+}
+
+/**
+ * Hash a transformed context given as JSON over its canonical form (see
+ * getCanonicalContext). The canonical form is a fixed point, so hashing
+ * the output of getCanonicalContext gives the same digest.
+ * @param transformedContextJson - TransformedContext as JSON
+ * @param algorithm - Hash algorithm (default "sha256")
+ * @returns Hex-encoded digest
+ * @throws ConfigurationError on invalid JSON, an unknown schemaVersion, or
+ *         an unavailable algorithm
+ */
+export function computeContextHash(
+  transformedContextJson: string,
+  algorithm: HashAlgorithm = "sha256"
+): string {
+  if (!isHashAlgorithmAvailable(algorithm)) {
+    throw new ConfigurationError(`Hash algorithm ${algorithm} is not available in this runtime`);
+  }
+  return createHash(algorithm)
+    .update(getCanonicalContext(transformedContextJson), "utf8")
+    .digest("hex");
+}
+
+/**
+ * Serialize any JSON value per RFC 8785 (JCS): object keys sorted by
+ * UTF-16 code units at every level, numbers in ECMAScript shortest
+ * round-trip form, strings with only the escapes JSON requires, and no
+ * whitespace. Array order is kept; undefined object members are omitted.
+ * @param value - JSON-compatible value
+ * @returns Canonical JSON string
+ * @throws TransformationError for a non-finite number, a lone surrogate,
+ *         or a value JSON cannot represent
+ */
+export function canonicalJson(value: unknown): string {
+  if (value !== null && typeof value === "object") {
+    if (Array.isArray(value)) {
+      return `[${value.map((element) => canonicalJson(element ?? null)).join(",")}]`;
+    }
+    const record = value as Record<string, unknown>;
+    const members = canonicalKeys(record).map(
+      (key) => `${canonicalScalar(key)}:${canonicalJson(record[key])}`
+    );
+    return `{${members.join(",")}}`;
+  }
+  return canonicalScalar(value);
+}
+
+/**
+ * Write the RFC 8785 serialization of a JSON value in chunks, without
+ * building the whole string (see canonicalJson, which returns the same
+ * characters in one piece). Each chunk is punctuation, a key, or a scalar.
+ * @param value - JSON-compatible value
+ * @param write - Receives each chunk in order
+ * @throws TransformationError as canonicalJson
+ */
+export function writeCanonicalJson(value: unknown, write: (chunk: string) => void): void {
+  if (value === null || typeof value !== "object") {
+    write(canonicalScalar(value));
+    return;
+  }
+  if (Array.isArray(value)) {
+    write("[");
+    for (let index = 0; index < value.length; index++) {
+      if (index > 0) write(",");
+      writeCanonicalJson(value[index] ?? null, write);
+    }
+    write("]");
+    return;
+  }
+  const record = value as Record<string, unknown>;
+  write("{");
+  canonicalKeys(record).forEach((key, index) => {
+    write(`${index > 0 ? "," : ""}${canonicalScalar(key)}:`);
+    writeCanonicalJson(record[key], write);
+  });
+  write("}");
+}
+
+/**
+ * Members of an object in RFC 8785 order, undefined members omitted.
+ */
+function canonicalKeys(record: Record<string, unknown>): string[] {
+  return Object.keys(record)
+    .filter((key) => record[key] !== undefined)
+    .sort(compareCodeUnits);
+}
+
+/**
+ * RFC 8785 serialization of a scalar JSON value.
+ * @throws TransformationError for a non-finite number, a lone surrogate,
+ *         or a value JSON cannot represent
+ */
+function canonicalScalar(value: unknown): string {
+  if (value === null) {
+    return "null";
+  }
+  switch (typeof value) {
+    case "boolean":
+      return value ? "true" : "false";
+    case "number":
+      if (!Number.isFinite(value)) {
+        throw new TransformationError(`Cannot canonicalize non-finite number: ${value}`);
+      }
+      // ECMAScript Number-to-String is the RFC 8785 number format; -0 is "0"
+      return JSON.stringify(value);
+    case "string":
+      if (!value.isWellFormed()) {
+        throw new TransformationError("Cannot canonicalize a string with a lone surrogate");
+      }
+      // JSON.stringify escapes exactly what RFC 8785 prescribes
+      return JSON.stringify(value);
+    default:
+      throw new TransformationError(`Cannot canonicalize a ${typeof value} value`);
+  }
+}
+
+/**
+ * Order strings by UTF-16 code units, as RFC 8785 sorts keys.
+ */
+function compareCodeUnits(a: string, b: string): number {
+  return a < b ? -1 : a > b ? 1 : 0;
 }
 
 /**
- * Normalize a transformed context for canonical serialization.
+ * Normalize a transformed context for canonical serialization: stable
+ * entity and relation order, rounded numbers. Shared by the JSON and CBOR
+ * encodings (see serialization.ts).
  */
-function normalizeTransformedContext(
-  context: TransformedContext
+export function normalizeTransformedContext(
+  context: TransformedContext,
+  mode: CanonicalizationMode
 ): Record<string, unknown> {
+  // Legacy ordering followed the runtime's locale collation
+  const compare =
+    mode === "legacy" ? (a: string, b: string) => a.localeCompare(b) : compareCodeUnits;
   return {
-    entities: normalizeEntities(context.entities),
+    entities: normalizeEntities(context.entities, compare),
     model: context.model ?? null,
-    relations: normalizeRelations(context.relations),
+    relations: normalizeRelations(context.relations, compare),
     task: context.task,
+    ...(context.constraints ? { constraints: normalizeConstraints(context.constraints) } : {}),
+  };
+}
+
+/**
+ * Normalize constraints: role counts keyed in sorted order.
+ */
+function normalizeConstraints(
+  constraints: NonNullable<TransformedContext["constraints"]>
+): Record<string, unknown> {
+  const roleCounts: Record<string, number> = {};
+  for (const role of Object.keys(constraints.roleCounts).sort()) {
+    roleCounts[role] = constraints.roleCounts[role];
+  }
+  return {
+    maxRelationDegree: constraints.maxRelationDegree,
+    policyVersion: constraints.policyVersion,
+    roleCounts,
   };
 }
 
@@ -54,11 +478,12 @@ function normalizeTransformedContext(
  * Normalize entities array: sort by syntheticId, normalize attributes.
  */
 function normalizeEntities(
-  entities: TransformedContext["entities"]
+  entities: TransformedContext["entities"],
+  compare: (a: string, b: string) => number
 ): Array<Record<string, unknown>> {
   return entities
     .slice() // Create copy to avoid mutation
-    .sort((a, b) => a.syntheticId.localeCompare(b.syntheticId))
+    .sort((a, b) => compare(a.syntheticId, b.syntheticId))
     .map((entity) => ({
       attributes: normalizeAttributes(entity.attributes),
       role: entity.role,
@@ -70,14 +495,16 @@ function normalizeEntities(
  * Normalize attributes: stable key ordering, normalized numbers.
  */
 function normalizeAttributes(
-  attributes: Record<string, string | number>
-): Record<string, string | number> {
-  const normalized: Record<string, string | number> = {};
+  attributes: Record<string, string | number | boolean>
+): Record<string, string | number | boolean> {
+  const normalized: Record<string, string | number | boolean> = {};
   const keys = Object.keys(attributes).sort();
 
   for (const key of keys) {
     const value = attributes[key];
-    if (typeof value === "number") {
+    if (key === "confidence" && typeof value === "number") {
+      normalized[key] = Math.round(value * 100) / 100;
+    } else if (typeof value === "number") {
       // Normalize numbers: remove trailing zeros, consistent precision
       normalized[key] = normalizeNumber(value);
     } else {
@@ -89,23 +516,26 @@ function normalizeAttributes(
 }
 
 /**
- * Normalize relations array: sort by (from, to, type).
+ * Normalize relations array: sort by (from, to, type). `weight` is kept
+ * only when present.
  */
 function normalizeRelations(
-  relations: TransformedContext["relations"]
+  relations: TransformedContext["relations"],
+  compare: (a: string, b: string) => number
 ): Array<Record<string, unknown>> {
   return relations
     .slice()
     .sort((a, b) => {
       // Sort by from, then to, then type
-      if (a.from !== b.from) return a.from.localeCompare(b.from);
-      if (a.to !== b.to) return a.to.localeCompare(b.to);
-      return a.type.localeCompare(b.type);
+      if (a.from !== b.from) return compare(a.from, b.from);
+      if (a.to !== b.to) return compare(a.to, b.to);
+      return compare(a.type, b.type);
     })
     .map((relation) => ({
       from: relation.from,
       to: relation.to,
       type: relation.type,
+      ...(relation.weight !== undefined ? { weight: relation.weight } : {}),
     }));
 }
 
@@ -117,14 +547,19 @@ function normalizeNumber(value: number): number {
   // Handle special values
   if (Object.is(value, -0)) return 0;
   if (!isFinite(value)) return value; // NaN, Infinity, -Infinity
+  // Integers need no rounding, and scaling large ones loses precision
+  if (Number.isInteger(value)) return value;
 
   // Round to avoid floating point precision issues
-  // Use 10 decimal places as reasonable precision for most values
-  return Math.round(value * 10000000000) / 10000000000;
+  // Use 10 decimal places as reasonable precision for most values.
+  // Magnitudes that overflow when scaled have no fraction left to round.
+  const scaled = value * 10000000000;
+  if (!isFinite(scaled)) return value;
+  return Math.round(scaled) / 10000000000;
 }
 
 /**
- * Custom JSON replacer for stable key ordering.
+ * Custom JSON replacer for stable key ordering (legacy mode).
  * Ensures object keys are always sorted alphabetically.
  */
 function stableStringify(_key: string, value: unknown): unknown {
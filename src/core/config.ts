@@ -1,3 +1,7 @@
+import type { BindingVersion, HashAlgorithm, HashScope } from "./canonical.ts";
+import type { OutputEncoding } from "./serialization.ts";
+import type { MaskingPolicy } from "./policy.ts";
+
 /**
  * Configuration for the Axiom Core.
  * All fields are required. No defaults are applied.
@@ -19,10 +23,11 @@ export interface AxiomConfig {
   enclave: "auto" | "required" | "none";
 
   /**
-   * Policy version identifier.
-   * Must be "v1" for current implementation.
+   * Policy version identifier ("v1" or "v2"). Overrides
+   * `maskingPolicy.version`; v2 coarsens count attributes (see
+   * MaskingPolicy.attributeGranularity).
    */
-  policyVersion: "v1";
+  policyVersion: MaskingPolicy["version"];
 
   /**
    * Platform-specific configuration (optional).
@@ -40,6 +45,70 @@ export interface AxiomConfig {
      */
     verificationMode: "strict" | "permissive";
   };
+
+  /**
+   * Masking policy overrides (optional).
+   * Fields not provided use the loaded default policy file (see
+   * loadDefaultPolicy), or DEFAULT_MASKING_POLICY when none is loaded.
+   */
+  maskingPolicy?: Partial<MaskingPolicy>;
+
+  /**
+   * Secret salt for session-stable synthetic IDs (optional, 16+ bytes).
+   * When set, the same surface form gets the same keyed ID
+   * ("ENTITY_a1b2c3d4") on every reason() call made with this salt.
+   * When omitted, IDs are sequential per call ("ENTITY_0000").
+   * The salt is never serialized into output or the config hash.
+   */
+  idSalt?: Uint8Array;
+
+  /**
+   * Bind attestation report_data with the pre-policy-hash formula
+   * SHA-256(sessionId || configHash || outputHash || timestamp) (optional).
+   * For migrating verifiers only; such evidence does not prove which
+   * masking policy was applied. Default: false.
+   */
+  legacyReportData?: boolean;
+
+  /**
+   * Algorithm for the output hash (optional). Attested evidence records
+   * it and report_data binds it, so a digest cannot be checked under the
+   * wrong algorithm. "blake3" needs runtime support. Default: "sha256".
+   */
+  hashAlgorithm?: HashAlgorithm;
+
+  /**
+   * Encoding the output hash is computed over (optional): "json" (RFC 8785)
+   * or "cbor" (RFC 8949 deterministic, see toCanonicalCbor). Attested
+   * evidence records it. Default: "json".
+   */
+  outputEncoding?: OutputEncoding;
+
+  /**
+   * Binding formula for attested output (optional). 2 hashes the output and
+   * report_data as domain-separated, length-prefixed fields; 3 does the
+   * same but fills all 64 bytes of REPORT_DATA with a SHA-512 binding; 1 is
+   * the earlier formula, for runners that predate version 2. Attested
+   * evidence records it. Default: 3, or 1 with legacyReportData.
+   */
+  bindingVersion?: BindingVersion;
+
+  /**
+   * Fail attested calls when the VCEK certificate for the attestation
+   * report cannot be fetched from AMD KDS (optional). Otherwise the result
+   * carries a warning. Simulator reports have no VCEK, so this also fails
+   * simulator runs. Default: false.
+   */
+  requireVcek?: boolean;
+
+  /**
+   * How attested calls are reported (optional): "per_request" generates a
+   * report for every call; "session_cached" reuses one report per session
+   * and freshness window, binding a session key that signs each call's
+   * binding (see AttestationEvidence.sessionAttestation). Default:
+   * "per_request".
+   */
+  attestationMode?: "per_request" | "session_cached";
 }
 
 /**
@@ -62,6 +131,14 @@ export interface ReasonInput {
    * Note: No network calls are made by the SDK.
    */
   model?: string;
+
+  /**
+   * Output hash (hex) of the previous step's attestation evidence, to
+   * chain this transform to it (attested tier only). The new evidence
+   * records it as chainedFrom and binds it into report_data; see
+   * AttestationVerifier.verifyChain.
+   */
+  previousOutputHash?: string;
 }
 
 /**
@@ -75,16 +152,18 @@ export interface TransformedContext {
   entities: Array<{
     syntheticId: string;
     role: string;
-    attributes: Record<string, string | number>;
+    attributes: Record<string, string | number | boolean>;
   }>;
 
   /**
-   * Relations between entities (using synthetic IDs).
+   * Relations between entities (using synthetic IDs). `weight` counts
+   * observations collapsed into one relation and is absent for one.
    */
   relations: Array<{
     type: string;
     from: string;
     to: string;
+    weight?: number;
   }>;
 
   /**
@@ -96,6 +175,38 @@ export interface TransformedContext {
    * Model identifier if provided (passed through).
    */
   model?: string;
+
+  /**
+   * Graph-level invariants the reasoner can check. Aggregates only.
+   */
+  constraints?: ContextConstraints;
+
+  /**
+   * Operational details from the runner (e.g. its version). Outside the
+   * hash scope: never canonicalized or hashed.
+   */
+  metadata?: Record<string, string | number | boolean>;
+}
+
+/**
+ * Aggregate invariants over a transformed context. Never holds surface forms.
+ */
+export interface ContextConstraints {
+  /**
+   * Number of entities per role; roles with no entities are absent.
+   */
+  roleCounts: Record<string, number>;
+
+  /**
+   * Largest number of relations touching a single entity.
+   */
+  maxRelationDegree: number;
+
+  /**
+   * Masking policy version used for the transform. Part of the canonical
+   * form, so v1 and v2 transforms of the same input never hash alike.
+   */
+  policyVersion: string;
 }
 
 /**
@@ -117,6 +228,32 @@ export interface ReasonResult {
    */
   attestationEvidence?: AttestationEvidence;
 
+  /**
+   * Set when extraction stopped at the policy's maxEntities.
+   */
+  entitiesTruncated?: boolean;
+
+  /**
+   * Unknown policy and reason input fields that were ignored, one message
+   * per field naming it. Absent when there are none; under the strict
+   * preset unknown fields fail instead.
+   */
+  warnings?: string[];
+
+  /**
+   * Set when the relation list was truncated at the policy's maxRelations
+   * (MaskingPolicy.truncateRelations).
+   */
+  relationsTruncated?: boolean;
+
+  /**
+   * Session nonce (hex) that opens attestationEvidence.inputHash. Keep it
+   * with the raw input rather than with the evidence: together with the
+   * input it lets an auditor check the commitment (see
+   * AttestationVerifier.verifyInputCommitment).
+   */
+  inputNonce?: string;
+
   /**
    * Verification hint for consumers.
    */
@@ -151,6 +288,11 @@ export interface AttestationEvidence {
    */
   configHash: string;
 
+  /**
+   * Hash of the effective MaskingPolicy used for this execution.
+   */
+  policyHash: string;
+
   /**
    * Unique session identifier (128-bit random).
    */
@@ -161,6 +303,48 @@ export interface AttestationEvidence {
    */
   outputHash: string;
 
+  /**
+   * Algorithm that produced outputHash (absent means "sha256").
+   */
+  hashAlgorithm?: HashAlgorithm;
+
+  /**
+   * Encoding outputHash covers (absent means "json").
+   */
+  outputEncoding?: OutputEncoding;
+
+  /**
+   * Part of the context outputHash covers (absent means "context-v1").
+   */
+  hashScope?: HashScope;
+
+  /**
+   * Formula for outputHash and report_data (absent means 1).
+   */
+  bindingVersion?: BindingVersion;
+
+  /**
+   * Commitment to the raw input (see computeInputHash), when the runner
+   * provides one.
+   */
+  inputHash?: string;
+
+  /**
+   * Output hash of the previous transform in a chain (see
+   * ReasonInput.previousOutputHash).
+   */
+  chainedFrom?: string;
+
+  /**
+   * Session key proof under attestationMode "session_cached".
+   */
+  sessionAttestation?: {
+    publicKey: Uint8Array;
+    windowStart: number;
+    expiresAt: number;
+    signature: Uint8Array;
+  };
+
   /**
    * Unix timestamp (milliseconds).
    */
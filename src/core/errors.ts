@@ -45,3 +45,63 @@ export class SecurityInvariantError extends Error {
   }
 }
 
+
+/**
+ * Thrown when an attestation report is malformed or uses a format this
+ * SDK does not support.
+ */
+export class AttestationError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "AttestationError";
+    Object.setPrototypeOf(this, AttestationError.prototype);
+  }
+}
+
+/**
+ * Thrown when a request field is malformed. code names the rule it broke
+ * (for example NONCE_CONSTANT), so callers can tell violations apart.
+ */
+export class InvalidInputError extends Error {
+  readonly code: string;
+
+  constructor(code: string, message: string) {
+    super(message);
+    this.name = "InvalidInputError";
+    this.code = code;
+    Object.setPrototypeOf(this, InvalidInputError.prototype);
+  }
+}
+
+/**
+ * Thrown when an attestation token cannot be minted: the signing key is
+ * missing or unusable, or the clock cannot be trusted. code names the
+ * cause (see TOKEN_ERROR_CODES).
+ */
+export class TokenError extends Error {
+  readonly code: string;
+
+  constructor(code: string, message: string) {
+    super(message);
+    this.name = "TokenError";
+    this.code = code;
+    Object.setPrototypeOf(this, TokenError.prototype);
+  }
+}
+
+/**
+ * Thrown when a request pins a measurement (expectedMeasurement) and the
+ * enclave measures something else. Nothing was transformed.
+ */
+export class MeasurementMismatchError extends Error {
+  readonly expected: string;
+  readonly actual: string;
+
+  constructor(expected: string, actual: string) {
+    super(`Enclave measurement mismatch: expected ${expected}, enclave measures ${actual}`);
+    this.name = "MeasurementMismatchError";
+    this.expected = expected;
+    this.actual = actual;
+    Object.setPrototypeOf(this, MeasurementMismatchError.prototype);
+  }
+}
@@ -0,0 +1,1327 @@
+/**
+ * Masking policy for the semantic transformation pipeline.
+ * The same policy governs the standard pipeline and is forwarded to the
+ * native enclave runner in the attested tier.
+ */
+
+import { ConfigurationError } from "./errors.ts";
+import { Role } from "../entities/roles.ts";
+import { ENTITY_TYPES } from "../entities/entity.ts";
+import { createHash } from "crypto";
+import { readFileSync } from "fs";
+
+/**
+ * Check-digit algorithms available to custom identifier patterns.
+ */
+export const ID_CHECKSUMS = ["mod11", "verhoeff"] as const;
+
+/**
+ * Jurisdictions with built-in license plate formats, in matching order:
+ * longer, more specific formats first.
+ */
+export const PLATE_LOCALES = ["in", "fr", "de", "gb", "us"] as const;
+
+/**
+ * Supported policy versions. v2 changes canonical output by applying
+ * `attributeGranularity` to count attributes.
+ */
+export const POLICY_VERSIONS = ["v1", "v2"] as const;
+
+/**
+ * How count attributes (`tokenCount`, `digitCount`, `pathDepth`) are
+ * reported under a v2 policy.
+ */
+export const ATTRIBUTE_GRANULARITIES = ["exact", "coarse", "none"] as const;
+
+/**
+ * Named placeholder styles for rendering masked text: "bracketed" gives
+ * "[PERSON_1]", "angle_id" gives "<<ENTITY_0001>>", and "blocks" gives
+ * "█████" for every entity.
+ */
+export const PLACEHOLDER_STYLES = ["bracketed", "angle_id", "blocks"] as const;
+
+/**
+ * A named placeholder style, or a custom template. A template holds
+ * `{id}` (synthetic ID), `{label}` (entity label, "PERSON"), and `{n}`
+ * (1-based index per label) fields between delimiter characters, e.g.
+ * "⟦{label}:{n}⟧".
+ */
+export type PlaceholderStyle = (typeof PLACEHOLDER_STYLES)[number] | { custom: string };
+
+/**
+ * Characters a custom placeholder template may use outside its fields.
+ * None can occur inside a word, so a rendered placeholder cannot run
+ * into or be mistaken for surrounding text.
+ */
+export const PLACEHOLDER_DELIMITERS = "[]<>{}()⟦⟧«»‹›|:#_-█";
+
+/**
+ * Maximum length of a custom placeholder template.
+ */
+export const MAX_PLACEHOLDER_LENGTH = 32;
+
+/**
+ * Strictness presets (see PRESET_EXPANSIONS).
+ */
+export const POLICY_PRESETS = ["strict", "balanced", "permissive"] as const;
+
+/**
+ * One of POLICY_PRESETS.
+ */
+export type Preset = (typeof POLICY_PRESETS)[number];
+
+/**
+ * Industry presets (see INDUSTRY_PRESET_EXPANSIONS).
+ */
+export const INDUSTRY_PRESETS = ["healthcare", "finance"] as const;
+
+/**
+ * One of INDUSTRY_PRESETS.
+ */
+export type IndustryPreset = (typeof INDUSTRY_PRESETS)[number];
+
+/**
+ * Maximum length of a caller-supplied regex source.
+ */
+export const MAX_PATTERN_LENGTH = 256;
+
+/**
+ * Maximum number of caller-supplied patterns of each kind in one policy.
+ */
+export const MAX_PATTERNS = 32;
+
+/**
+ * Smallest accepted maxInputSize, in bytes.
+ */
+export const MIN_INPUT_SIZE = 1024;
+
+/**
+ * Absolute input size ceiling in bytes; larger maxInputSize values are
+ * clamped to it.
+ */
+export const MAX_INPUT_SIZE_CEILING = 100 * 1024 * 1024;
+
+/**
+ * Accepted shape of a locales entry: a 2-3 letter language, then optional
+ * script/region/variant subtags separated by "-" or "_".
+ */
+const LOCALE_TAG = /^[A-Za-z]{2,3}(?:[-_][A-Za-z0-9]{2,8})*$/;
+
+/**
+ * Entity types that may never pass through unmasked: person names,
+ * credentials (API keys, tokens, JWTs), and deny-listed terms.
+ */
+export const PASSTHROUGH_REFUSED_TYPES = ["name", "secret", "jwt", "restricted"] as const;
+
+/**
+ * Per-recognizer switches, keyed by entity type in the masking policy's
+ * entityControls.
+ */
+export interface EntityControl {
+  /**
+   * Whether the recognizer runs. Disabled types are not detected at all.
+   */
+  enabled: boolean;
+
+  /**
+   * Whether matches are kept verbatim: still detected and counted, but
+   * emitted with a `literal` attribute holding the matched text, which the
+   * leak checks exempt. Refused for PASSTHROUGH_REFUSED_TYPES.
+   */
+  passthrough: boolean;
+}
+
+/**
+ * Whether the policy runs the recognizer for an entity type.
+ */
+export function isRecognizerEnabled(
+  policy: Pick<MaskingPolicy, "entityControls">,
+  type: string
+): boolean {
+  const controls: Record<string, EntityControl | undefined> = policy.entityControls;
+  return controls[type]?.enabled !== false;
+}
+
+/**
+ * Whether matches of an entity type are emitted verbatim. Never true for
+ * PASSTHROUGH_REFUSED_TYPES, even if validation was bypassed.
+ */
+export function isPassthrough(policy: Pick<MaskingPolicy, "entityControls">, type: string): boolean {
+  const refused: readonly string[] = PASSTHROUGH_REFUSED_TYPES;
+  const controls: Record<string, EntityControl | undefined> = policy.entityControls;
+  const control = controls[type];
+  return control?.passthrough === true && control.enabled !== false && !refused.includes(type);
+}
+
+/**
+ * Effective input size limit in bytes: the policy's maxInputSize, clamped
+ * to MAX_INPUT_SIZE_CEILING.
+ */
+export function inputSizeLimit(policy: Pick<MaskingPolicy, "maxInputSize">): number {
+  return Math.min(policy.maxInputSize, MAX_INPUT_SIZE_CEILING);
+}
+
+/**
+ * A caller-supplied identifier format (e.g. UK National Insurance number,
+ * Aadhaar, CPF).
+ */
+export interface IdPattern {
+  /**
+   * Pattern name reported on matching entities. Lowercase letters, digits,
+   * and underscores, starting with a letter (e.g. "uk_nino").
+   */
+  name: string;
+
+  /**
+   * Regular expression source. A named group "value" limits the entity to
+   * that part of the match.
+   */
+  pattern: string;
+
+  /**
+   * Optional check-digit validation over the digits of the match.
+   */
+  checksum?: (typeof ID_CHECKSUMS)[number];
+}
+
+/**
+ * A caller-supplied redaction pattern for internal identifiers (ticket
+ * numbers, employee IDs, project codenames).
+ */
+export interface CustomPattern {
+  /**
+   * Pattern name reported on matching entities, in the same form as
+   * IdPattern names (e.g. "ticket_id").
+   */
+  name: string;
+
+  /**
+   * Regular expression source. A named group "value" limits the entity to
+   * that part of the match.
+   */
+  pattern: string;
+
+  /**
+   * Role assigned to matching entities.
+   */
+  role: Role;
+}
+
+/**
+ * Masking policy parameters.
+ */
+export interface MaskingPolicy {
+  /**
+   * Strictness preset the policy was built from, or null. The preset's
+   * values (PRESET_EXPANSIONS) replace the defaults; fields set explicitly
+   * take precedence over both. The resolved policy holds the expanded
+   * values, so it describes exactly what runs.
+   */
+  preset: Preset | null;
+
+  /**
+   * Industry preset the policy was built from, or null. Its values
+   * (INDUSTRY_PRESET_EXPANSIONS) apply first; the strictness preset's
+   * values override them, and fields set explicitly override both. Like
+   * preset, the resolved policy holds the expanded values, so the policy
+   * hash covers what the preset chose.
+   */
+  industryPreset: IndustryPreset | null;
+
+  /**
+   * Policy version (see POLICY_VERSIONS). v1 preserves the original
+   * canonical output; v2 applies `attributeGranularity`.
+   */
+  version: (typeof POLICY_VERSIONS)[number];
+
+  /**
+   * Granularity of count attributes under a v2 policy: "exact" reports
+   * the integer, "coarse" a "short"/"medium"/"long" bucket, and "none"
+   * omits the attribute. Ignored by v1 policies, which always report
+   * exact counts.
+   */
+  attributeGranularity: (typeof ATTRIBUTE_GRANULARITIES)[number];
+
+  /**
+   * Whether capitalized common English words at the start of a sentence
+   * ("The", "Contract") are skipped during extraction. The leak checks
+   * apply either way. When false, every capitalized run is a candidate.
+   */
+  allowCommonWords: boolean;
+
+  /**
+   * Maximum input size in bytes (UTF-8, summed over all documents). At
+   * least MIN_INPUT_SIZE; values above MAX_INPUT_SIZE_CEILING are clamped.
+   */
+  maxInputSize: number;
+
+  /**
+   * Locales whose national identifier formats are recognized (BCP-47 tags).
+   * A locale with region "US" enables Social Security Number detection.
+   * An empty list disables national identifier recognition.
+   */
+  nationalIdLocales: string[];
+
+  /**
+   * Locales whose number and date conventions the recognizers accept
+   * (BCP-47 tags). A decimal-comma language ("de-DE") adds "1.234,56"
+   * numbers and amounts; a day-first first locale ("en-GB") reads
+   * ambiguous slash dates as DD/MM; a region outside the North American
+   * Numbering Plan accepts 9-digit trunk-prefixed phone numbers. Tokens
+   * that parse differently under two locales take the earlier one.
+   */
+  locales: string[];
+
+  /**
+   * Whether payment card entities may carry a `last4` attribute.
+   * Off by default: no card digits leave the boundary.
+   */
+  allowCardLast4: boolean;
+
+  /**
+   * Whether IBAN entities may carry their ISO country code as a
+   * `country` attribute.
+   */
+  allowIbanCountry: boolean;
+
+  /**
+   * Whether date entities may carry exact `year`, `month`, and `day`
+   * attributes. When off, only the decade and whether a time is present
+   * are reported.
+   */
+  allowExactDates: boolean;
+
+  /**
+   * Whether date entities may carry the exact `year` alone, without
+   * month or day. Implied when allowExactDates is on.
+   */
+  allowDateYear: boolean;
+
+  /**
+   * Whether currency and number entities may carry the exact value as
+   * `numericValue`. When off, only the order of magnitude (and, for
+   * currency, the code) is reported.
+   */
+  allowExactValues: boolean;
+
+  /**
+   * Whether long mixed alphanumeric tokens (serial numbers, asset tags)
+   * are extracted as identifiers. Off by default because the heuristic
+   * can over-trigger on product codes and hashes.
+   */
+  detectSerials: boolean;
+
+  /**
+   * Digit-length guard, or null to leave numbers to the number
+   * recognizer. A standalone number with at least this many digits
+   * (account numbers, case IDs, tracking numbers) is extracted as a
+   * `long_number` Identifier carrying only a digitCount bucket, and its
+   * text, with and without separators, is boundary-checked. Shorter
+   * numbers stay Values; text another recognizer already claimed
+   * (currency, phone, payment card) keeps that type.
+   */
+  maskNumbersOverDigits: number | null;
+
+  /**
+   * Whether long hexadecimal strings (content hashes, commit IDs) may pass
+   * through the secret recognizer. Off by default: hex tokens of 32+
+   * characters are treated as credentials.
+   */
+  allowHexDigests: boolean;
+
+  /**
+   * Whether person entities may carry the normalized honorific ("dr",
+   * "prof") as an attribute. When off, only `honorificPresent` is reported.
+   */
+  allowHonorifics: boolean;
+
+  /**
+   * Additional legal-form suffixes (e.g. "Oy", "K.K.") that mark an
+   * organization name, on top of the built-in list.
+   */
+  organizationSuffixes: string[];
+
+  /**
+   * Whether capitalized runs found in the embedded gazetteer (countries,
+   * major cities, "... River", "... County") become Location entities.
+   * When off, they are treated as names.
+   */
+  detectPlaces: boolean;
+
+  /**
+   * Whether lowercase tokens after a cue word ("user jdoe", "login:
+   * jdoe_1987") are extracted as handles. `@`-prefixed handles are always
+   * recognized; the cue-word heuristic can over-trigger on prose.
+   */
+  detectUsernames: boolean;
+
+  /**
+   * Custom identifier formats extracted as Identifier entities tagged with
+   * the pattern name. Validated when the policy is resolved.
+   */
+  idPatterns: IdPattern[];
+
+  /**
+   * Custom redaction patterns, applied before every built-in recognizer so
+   * they win any overlap. Earlier patterns take precedence over later ones.
+   * Validated when the policy is resolved.
+   */
+  customPatterns: CustomPattern[];
+
+  /**
+   * Literal sensitive terms (codenames, client names) masked wherever they
+   * occur, matched case-insensitively with multi-word terms as phrases.
+   * Matches become Restricted entities and take precedence over every
+   * other recognizer. Terms are never echoed in error messages.
+   */
+  denyTerms: string[];
+
+  /**
+   * Jurisdictions whose license plate formats are recognized (see
+   * PLATE_LOCALES). Empty by default, which disables plate recognition;
+   * short formats such as "us" can also match standard codes ("ISO 9001").
+   */
+  plateLocales: Array<(typeof PLATE_LOCALES)[number]>;
+
+  /**
+   * Whether healthcare recognizers run: NPI numbers and cued medical
+   * record numbers are masked as identifiers, and ICD-10 codes become
+   * Condition entities that carry the code itself.
+   */
+  healthcare: boolean;
+
+  /**
+   * Size of the grid cell reported for coordinates, as decimal places:
+   * 0 for a 1-degree grid, 1 for 0.1 degree, -1 for 10 degrees.
+   */
+  geoPrecision: number;
+
+  /**
+   * Whether postal code entities may carry the first three characters of
+   * the code as `postalPrefix` (US ZIP3, UK outward-code start, Canadian
+   * FSA). The full code is always boundary-checked.
+   */
+  allowPostalPrefix: boolean;
+
+  /**
+   * Entities scoring below this confidence (0-1) are still masked and
+   * boundary-checked but omitted from the output entity list, along with
+   * their relations. 0 keeps everything.
+   */
+  minConfidence: number;
+
+  /**
+   * k-anonymity threshold for descriptive attributes, or null to keep
+   * them all. An attribute value (`tokenCount: 7`) carried by fewer than
+   * k output entities is replaced with "suppressed", since a unique value
+   * can single out an entity even after masking. Structural attributes
+   * (K_ANONYMITY_EXEMPT_ATTRIBUTES) are never suppressed.
+   */
+  kThreshold: number | null;
+
+  /**
+   * Maximum distance, in whitespace-delimited tokens, between occurrences
+   * of two entities for them to be related. 0 relates every pair of
+   * entities in the same document, which grows quadratically.
+   */
+  relationWindow: number;
+
+  /**
+   * Maximum number of distinct entities in one transform. Extraction keeps
+   * the first maxEntities in input order (document order, then position)
+   * and drops the rest, reported as `entitiesTruncated` in the result and
+   * redaction statistics. Under the strict preset, exceeding the limit
+   * fails the transform instead.
+   */
+  maxEntities: number;
+
+  /**
+   * Maximum number of relations in one transform. Exceeding it fails the
+   * transform unless truncateRelations is set.
+   */
+  maxRelations: number;
+
+  /**
+   * Whether to keep the first maxRelations relations (in entity order)
+   * instead of failing when the limit is exceeded. Truncation is reported
+   * as `relationsTruncated` in the result and redaction statistics.
+   */
+  truncateRelations: boolean;
+
+  /**
+   * Additional verbs, lowercase and in the form written ("audited",
+   * "licensed"), that type a relation when they alone separate two
+   * entities, on top of RELATION_VERBS. Verbs are not treated as
+   * identifying and may appear in the output as relation types.
+   */
+  relationVerbs: string[];
+
+  /**
+   * Whether entities from different documents of one request are related.
+   * Off by default: documents are treated as unrelated, so no edge crosses
+   * them. When on, every cross-document pair gets a "co_session" relation.
+   */
+  crossDocumentRelations: boolean;
+
+  /**
+   * Relation types kept in the output ("paid", "signed"); any other
+   * relation is dropped and counted as `relationsFiltered`. null keeps
+   * every type; an empty list drops all relations.
+   */
+  allowedRelationTypes: string[] | null;
+
+  /**
+   * Whether to emit one synthetic "Document" entity per input ("DOC_0000")
+   * and a "mentioned_in" relation from each entity to every document it
+   * occurs in. Off by default since it changes entity counts and hashes.
+   */
+  emitDocumentNodes: boolean;
+
+  /**
+   * Whether a relation may have the same entity at both ends. Off by
+   * default: a self-loop indicates a recognizer bug and fails the transform.
+   */
+  allowSelfRelations: boolean;
+
+  /**
+   * Per-recognizer overrides keyed by entity type ("date", "email").
+   * Types not listed are enabled and masked.
+   */
+  entityControls: Partial<Record<(typeof ENTITY_TYPES)[number], EntityControl>>;
+
+  /**
+   * How masked text renders an entity (see PLACEHOLDER_STYLES), for any
+   * surface that substitutes placeholders into text. A custom template
+   * must contain `{id}` or `{n}` so placeholders stay distinguishable,
+   * start and end with a delimiter, and use only PLACEHOLDER_DELIMITERS
+   * outside its fields.
+   */
+  placeholderStyle: PlaceholderStyle;
+
+  /**
+   * Secret salt for session-stable synthetic IDs (at least 16 UTF-8
+   * bytes), or null for sequential IDs ("ENTITY_0000"). When set, each
+   * surface form's ID is a truncated HMAC-SHA256 keyed by the salt
+   * ("ENTITY_a1b2c3d4"), so the same value gets the same ID on every
+   * request made with this salt. AxiomConfig.idSalt takes precedence.
+   *
+   * Threat model: the ID is a keyed hash, not a plain hash, so anyone
+   * without the salt cannot confirm a guessed value ("is ENTITY_a1b2c3d4
+   * Alice?") or link IDs across deployments with different salts. Anyone
+   * holding the salt can do both, so it is a secret: it is never
+   * serialized into the transformed context, the enclave policy payload
+   * or policy hash, validation messages, or errors. IDs produced with the
+   * same salt are linkable across requests by design; rotate the salt to
+   * break that linkage.
+   */
+  idSalt: string | null;
+}
+
+/**
+ * Default masking policy.
+ */
+export const DEFAULT_MASKING_POLICY: Readonly<MaskingPolicy> = {
+  preset: null,
+  industryPreset: null,
+  version: "v1",
+  attributeGranularity: "coarse",
+  allowCommonWords: true,
+  maxInputSize: 10 * 1024 * 1024, // 10 MB
+  nationalIdLocales: ["en-US"],
+  locales: ["en-US"],
+  allowCardLast4: false,
+  allowIbanCountry: true,
+  allowExactDates: false,
+  allowDateYear: false,
+  allowExactValues: false,
+  detectSerials: false,
+  maskNumbersOverDigits: null,
+  allowHexDigests: false,
+  allowHonorifics: true,
+  organizationSuffixes: [],
+  detectPlaces: true,
+  detectUsernames: true,
+  idPatterns: [],
+  customPatterns: [],
+  denyTerms: [],
+  plateLocales: [],
+  healthcare: false,
+  geoPrecision: 0,
+  allowPostalPrefix: false,
+  minConfidence: 0,
+  kThreshold: null,
+  relationWindow: 16,
+  maxEntities: 10_000,
+  maxRelations: 50_000,
+  truncateRelations: false,
+  relationVerbs: [],
+  crossDocumentRelations: false,
+  allowedRelationTypes: null,
+  emitDocumentNodes: false,
+  allowSelfRelations: false,
+  entityControls: {},
+  placeholderStyle: "angle_id",
+  idSalt: null,
+};
+
+/**
+ * Field values each strictness preset expands to. strict turns off every
+ * optional attribute and the common-word allowance and forbids
+ * passthrough; balanced is the default policy; permissive allows common
+ * words and the optional coarse attributes (card last four, IBAN country,
+ * honorific, postal prefix, 0.1-degree cells).
+ */
+export const PRESET_EXPANSIONS: Readonly<Record<Preset, Readonly<Partial<MaskingPolicy>>>> = {
+  strict: {
+    attributeGranularity: "none",
+    allowCommonWords: false,
+    allowCardLast4: false,
+    allowIbanCountry: false,
+    allowExactDates: false,
+    allowDateYear: false,
+    allowExactValues: false,
+    allowHexDigests: false,
+    allowHonorifics: false,
+    allowPostalPrefix: false,
+    geoPrecision: -1,
+    detectSerials: true,
+  },
+  balanced: {},
+  permissive: {
+    attributeGranularity: "coarse",
+    allowCommonWords: true,
+    allowCardLast4: true,
+    allowIbanCountry: true,
+    allowHonorifics: true,
+    allowPostalPrefix: true,
+    geoPrecision: 1,
+  },
+};
+
+/**
+ * Field values each industry preset expands to. healthcare follows the
+ * HIPAA Safe Harbor identifier set: NPI and medical record number
+ * recognizers on, dates reduced to the year, and coordinates and postal
+ * codes reduced to 10-degree cells with no prefix. finance follows PCI
+ * DSS: no card digits or IBAN country, amounts reduced to their order of
+ * magnitude, and numbers of 8 or more digits (account numbers) masked as
+ * identifiers.
+ */
+export const INDUSTRY_PRESET_EXPANSIONS: Readonly<
+  Record<IndustryPreset, Readonly<Partial<MaskingPolicy>>>
+> = {
+  healthcare: {
+    healthcare: true,
+    allowExactDates: false,
+    allowDateYear: true,
+    geoPrecision: -1,
+    allowPostalPrefix: false,
+  },
+  finance: {
+    allowCardLast4: false,
+    allowIbanCountry: false,
+    allowExactValues: false,
+    maskNumbersOverDigits: 8,
+  },
+};
+
+/**
+ * Diagnostic codes reported by validatePolicy.
+ */
+export type PolicyFindingCode =
+  | "POLICY_PRESET_UNKNOWN"
+  | "POLICY_INDUSTRY_PRESET_UNKNOWN"
+  | "POLICY_VERSION_UNKNOWN"
+  | "POLICY_GRANULARITY_UNKNOWN"
+  | "POLICY_PATTERN_COUNT"
+  | "POLICY_PATTERN_NAME"
+  | "POLICY_PATTERN_DUPLICATE"
+  | "POLICY_PATTERN_LENGTH"
+  | "POLICY_REGEX_INVALID"
+  | "POLICY_REGEX_EMPTY_MATCH"
+  | "POLICY_REGEX_BACKTRACKING"
+  | "POLICY_CHECKSUM_UNKNOWN"
+  | "POLICY_ROLE_UNKNOWN"
+  | "POLICY_DENY_TERM_INVALID"
+  | "POLICY_LOCALE_UNKNOWN"
+  | "POLICY_LOCALE_INVALID"
+  | "POLICY_LOCALES_EMPTY"
+  | "POLICY_GEO_PRECISION_INVALID"
+  | "POLICY_LIMIT_ZERO"
+  | "POLICY_LIMIT_TOO_SMALL"
+  | "POLICY_LIMIT_INVALID"
+  | "POLICY_LIMIT_CLAMPED"
+  | "POLICY_CONFIDENCE_RANGE"
+  | "POLICY_RELATION_VERB_INVALID"
+  | "POLICY_RELATION_TYPE_INVALID"
+  | "POLICY_RELATIONS_DISABLED"
+  | "POLICY_RELATION_TYPE_EXCLUDED"
+  | "POLICY_RECOGNIZER_UNKNOWN"
+  | "POLICY_PASSTHROUGH_REFUSED"
+  | "POLICY_PASSTHROUGH_DISABLED"
+  | "POLICY_SALT_TOO_SHORT"
+  | "POLICY_FIELD_UNKNOWN"
+  | "POLICY_PLACEHOLDER_INVALID";
+
+/**
+ * One problem found in a masking policy. Errors make the policy unusable;
+ * warnings flag settings that are valid but likely unintended. Messages
+ * name patterns but never echo their regex source.
+ */
+export interface PolicyFinding {
+  severity: "error" | "warning";
+  code: PolicyFindingCode;
+  /**
+   * Policy field the finding concerns ("customPatterns", "maxInputSize").
+   * For POLICY_FIELD_UNKNOWN, the path of the unrecognized field
+   * ("allowComonWords", "customPatterns[0].flags").
+   */
+  field: string;
+  message: string;
+}
+
+/**
+ * Result of validatePolicy.
+ */
+export interface PolicyReport {
+  /**
+   * True when there are no error findings (warnings are allowed).
+   */
+  valid: boolean;
+  findings: PolicyFinding[];
+}
+
+/**
+ * Check a masking policy without running a transform. resolveMaskingPolicy
+ * runs the same checks, failing on the first error.
+ *
+ * @param overrides - Partial policy supplied by the caller
+ * @returns Every error and warning, in field order
+ */
+export function validatePolicy(overrides: Partial<MaskingPolicy> = {}): PolicyReport {
+  const findings = collectFindings(mergePolicy(overrides));
+  return {
+    valid: findings.every((finding) => finding.severity !== "error"),
+    findings,
+  };
+}
+
+/**
+ * Resolve a complete masking policy from optional overrides.
+ * Fields not provided fall back to the loaded default policy (see
+ * loadDefaultPolicy), or DEFAULT_MASKING_POLICY when none is loaded.
+ *
+ * @param overrides - Partial policy supplied by the caller
+ * @returns Complete masking policy, without any unknown top-level fields
+ * @throws ConfigurationError with the message of the first error that
+ *         validatePolicy reports, including unknown fields under the
+ *         strict preset
+ */
+export function resolveMaskingPolicy(
+  overrides: Partial<MaskingPolicy> = {}
+): MaskingPolicy {
+  const policy = mergePolicy(overrides);
+  const error = collectFindings(policy).find((finding) => finding.severity === "error");
+  if (error !== undefined) {
+    throw new ConfigurationError(error.message);
+  }
+  for (const key of Object.keys(policy)) {
+    if (!Object.hasOwn(DEFAULT_MASKING_POLICY, key)) {
+      delete (policy as unknown as Record<string, unknown>)[key];
+    }
+  }
+  return policy;
+}
+
+/**
+ * Migrate a v1 policy to an equivalent v2 policy. v1 always reports exact
+ * counts, so the migrated policy pins attributeGranularity to "exact";
+ * every other field carries over unchanged and the transform output
+ * differs only in the echoed version (constraints.policyVersion). A v2
+ * policy is returned as a shallow copy.
+ *
+ * @param policy - Policy of either version
+ * @returns Equivalent v2 policy
+ * @throws ConfigurationError if the policy is invalid, including an
+ *         unknown version
+ */
+export function migratePolicyV1ToV2(policy: MaskingPolicy): MaskingPolicy {
+  if (policy.version !== "v1") {
+    return resolveMaskingPolicy({ ...policy });
+  }
+  return resolveMaskingPolicy({ ...policy, version: "v2", attributeGranularity: "exact" });
+}
+
+/**
+ * Key bytes of the policy's idSalt (UTF-8), or undefined when the policy
+ * uses sequential IDs.
+ */
+export function idSaltBytes(policy: MaskingPolicy): Uint8Array | undefined {
+  return policy.idSalt !== null ? new TextEncoder().encode(policy.idSalt) : undefined;
+}
+
+/**
+ * Process-wide default policy loaded by loadDefaultPolicy, and the
+ * SHA-256 of the file it came from. Null when none is loaded.
+ */
+let loadedDefault: { policy: MaskingPolicy; fileHash: string } | null = null;
+
+/**
+ * Load an organization-wide default policy from a JSON file. The file
+ * holds MaskingPolicy fields (camelCase); fields it omits keep the
+ * built-in defaults. Once loaded, the file's policy replaces
+ * DEFAULT_MASKING_POLICY as the base that every resolveMaskingPolicy call
+ * starts from, so callers' explicit fields (and presets) still win.
+ *
+ * @param path - Path to the policy file
+ * @returns Hex-encoded SHA-256 of the file contents
+ * @throws ConfigurationError if the file cannot be read, is not a JSON
+ *         object, names an unknown field, or fails validatePolicy
+ */
+export function loadDefaultPolicy(path: string): string {
+  let contents: Buffer;
+  try {
+    contents = readFileSync(path);
+  } catch {
+    throw new ConfigurationError(`Policy file not found or unreadable: ${path}`);
+  }
+
+  let parsed: unknown;
+  try {
+    parsed = JSON.parse(contents.toString("utf8"));
+  } catch (error) {
+    throw new ConfigurationError(
+      `Invalid policy file JSON: ${error instanceof Error ? error.message : String(error)}`
+    );
+  }
+  if (typeof parsed !== "object" || parsed === null || Array.isArray(parsed)) {
+    throw new ConfigurationError("Invalid policy file: expected a JSON object");
+  }
+  const policy = mergePolicy(parsed as Partial<MaskingPolicy>, DEFAULT_MASKING_POLICY);
+  // Unknown fields are refused here whatever the preset
+  const unknown = unknownPolicyFields(policy);
+  if (unknown.length > 0) {
+    throw new ConfigurationError(`Invalid policy file: unknown field ${unknown[0]}`);
+  }
+  const error = collectFindings(policy).find((finding) => finding.severity === "error");
+  if (error !== undefined) {
+    throw new ConfigurationError(`Invalid policy file: ${error.message}`);
+  }
+  const fileHash = createHash("sha256").update(contents).digest("hex");
+  loadedDefault = { policy, fileHash };
+  return fileHash;
+}
+
+/**
+ * Drop the policy loaded by loadDefaultPolicy; DEFAULT_MASKING_POLICY is
+ * the base again.
+ */
+export function clearDefaultPolicy(): void {
+  loadedDefault = null;
+}
+
+/**
+ * SHA-256 of the loaded default policy file (hex), or null when no file
+ * is loaded.
+ */
+export function getDefaultPolicyHash(): string | null {
+  return loadedDefault?.fileHash ?? null;
+}
+
+/**
+ * Overlay the industry preset's values, then the strictness preset's,
+ * then caller overrides, on copies of the base policy's arrays. The base
+ * is the loaded default policy, if any.
+ */
+function mergePolicy(
+  overrides: Partial<MaskingPolicy>,
+  base: MaskingPolicy = loadedDefault?.policy ?? DEFAULT_MASKING_POLICY
+): MaskingPolicy {
+  const presets: readonly string[] = POLICY_PRESETS;
+  const preset =
+    overrides.preset != null && presets.includes(overrides.preset)
+      ? PRESET_EXPANSIONS[overrides.preset]
+      : {};
+  const industries: readonly string[] = INDUSTRY_PRESETS;
+  const industry =
+    overrides.industryPreset != null && industries.includes(overrides.industryPreset)
+      ? INDUSTRY_PRESET_EXPANSIONS[overrides.industryPreset]
+      : {};
+  return {
+    ...base,
+    nationalIdLocales: [...base.nationalIdLocales],
+    locales: [...base.locales],
+    organizationSuffixes: [...base.organizationSuffixes],
+    idPatterns: [...base.idPatterns],
+    customPatterns: [...base.customPatterns],
+    denyTerms: [...base.denyTerms],
+    plateLocales: [...base.plateLocales],
+    relationVerbs: [...base.relationVerbs],
+    entityControls: { ...base.entityControls },
+    ...industry,
+    ...preset,
+    ...overrides,
+  };
+}
+
+/**
+ * Check every field of a resolved policy.
+ */
+function collectFindings(policy: MaskingPolicy): PolicyFinding[] {
+  const findings: PolicyFinding[] = [];
+  const error = (field: keyof MaskingPolicy, code: PolicyFindingCode, message: string) =>
+    findings.push({ severity: "error", code, field, message });
+  const warning = (field: keyof MaskingPolicy, code: PolicyFindingCode, message: string) =>
+    findings.push({ severity: "warning", code, field, message });
+
+  if (policy.preset !== null && !POLICY_PRESETS.includes(policy.preset)) {
+    error(
+      "preset",
+      "POLICY_PRESET_UNKNOWN",
+      `Invalid preset: ${policy.preset}. Must be one of ${POLICY_PRESETS.join(", ")}.`
+    );
+  }
+  if (policy.industryPreset !== null && !INDUSTRY_PRESETS.includes(policy.industryPreset)) {
+    error(
+      "industryPreset",
+      "POLICY_INDUSTRY_PRESET_UNKNOWN",
+      `Invalid industryPreset: ${policy.industryPreset}. Must be one of ${INDUSTRY_PRESETS.join(", ")}.`
+    );
+  }
+  if (!POLICY_VERSIONS.includes(policy.version)) {
+    error(
+      "version",
+      "POLICY_VERSION_UNKNOWN",
+      `Invalid policy version: ${policy.version}. Must be one of ${POLICY_VERSIONS.join(", ")}.`
+    );
+  }
+  if (!ATTRIBUTE_GRANULARITIES.includes(policy.attributeGranularity)) {
+    error(
+      "attributeGranularity",
+      "POLICY_GRANULARITY_UNKNOWN",
+      `Invalid attributeGranularity: ${policy.attributeGranularity}. Must be one of ${ATTRIBUTE_GRANULARITIES.join(", ")}.`
+    );
+  }
+  findings.push(...checkPatterns("idPattern", policy.idPatterns));
+  for (const { name, checksum } of policy.idPatterns) {
+    if (checksum !== undefined && !ID_CHECKSUMS.includes(checksum)) {
+      error(
+        "idPatterns",
+        "POLICY_CHECKSUM_UNKNOWN",
+        `Invalid checksum for idPattern ${name}: ${checksum}`
+      );
+    }
+  }
+  findings.push(...checkPatterns("customPattern", policy.customPatterns));
+  const roles: string[] = Object.values(Role).filter((role) => role !== Role.Document);
+  for (const { name, role } of policy.customPatterns) {
+    if (!roles.includes(role)) {
+      error(
+        "customPatterns",
+        "POLICY_ROLE_UNKNOWN",
+        `Invalid role for customPattern ${name}: ${role}`
+      );
+    }
+  }
+  policy.denyTerms.forEach((term, index) => {
+    if (term.trim().length === 0 || term.length > MAX_PATTERN_LENGTH) {
+      error(
+        "denyTerms",
+        "POLICY_DENY_TERM_INVALID",
+        `denyTerms entry ${index} must be 1-${MAX_PATTERN_LENGTH} non-blank characters`
+      );
+    }
+  });
+  for (const locale of policy.plateLocales) {
+    if (!PLATE_LOCALES.includes(locale)) {
+      error(
+        "plateLocales",
+        "POLICY_LOCALE_UNKNOWN",
+        `Invalid plateLocales entry: ${locale}. Must be one of ${PLATE_LOCALES.join(", ")}.`
+      );
+    }
+  }
+  if (policy.locales.length === 0) {
+    error("locales", "POLICY_LOCALES_EMPTY", "locales must name at least one locale.");
+  }
+  for (const locale of policy.locales) {
+    if (!LOCALE_TAG.test(locale)) {
+      error(
+        "locales",
+        "POLICY_LOCALE_INVALID",
+        `Invalid locales entry: ${locale}. Must be a language tag such as en-US.`
+      );
+    }
+  }
+  if (!Number.isInteger(policy.geoPrecision) || Math.abs(policy.geoPrecision) > 1) {
+    error(
+      "geoPrecision",
+      "POLICY_GEO_PRECISION_INVALID",
+      `Invalid geoPrecision: ${policy.geoPrecision}. Must be -1, 0, or 1.`
+    );
+  }
+  if (!Number.isInteger(policy.maxInputSize) || policy.maxInputSize < MIN_INPUT_SIZE) {
+    error(
+      "maxInputSize",
+      policy.maxInputSize === 0
+        ? "POLICY_LIMIT_ZERO"
+        : Number.isInteger(policy.maxInputSize) && policy.maxInputSize > 0
+          ? "POLICY_LIMIT_TOO_SMALL"
+          : "POLICY_LIMIT_INVALID",
+      `Invalid maxInputSize: ${policy.maxInputSize}. Must be an integer of at least ${MIN_INPUT_SIZE} bytes.`
+    );
+  } else if (policy.maxInputSize > MAX_INPUT_SIZE_CEILING) {
+    warning(
+      "maxInputSize",
+      "POLICY_LIMIT_CLAMPED",
+      `maxInputSize ${policy.maxInputSize} exceeds the ${MAX_INPUT_SIZE_CEILING}-byte ceiling and is clamped to it.`
+    );
+  }
+  if (!(policy.minConfidence >= 0 && policy.minConfidence <= 1)) {
+    error(
+      "minConfidence",
+      "POLICY_CONFIDENCE_RANGE",
+      `Invalid minConfidence: ${policy.minConfidence}. Must be between 0 and 1.`
+    );
+  }
+  if (!Number.isInteger(policy.relationWindow) || policy.relationWindow < 0) {
+    error(
+      "relationWindow",
+      "POLICY_LIMIT_INVALID",
+      `Invalid relationWindow: ${policy.relationWindow}. Must be a non-negative integer.`
+    );
+  }
+  if (
+    policy.maskNumbersOverDigits !== null &&
+    (!Number.isInteger(policy.maskNumbersOverDigits) ||
+      policy.maskNumbersOverDigits < 1 ||
+      policy.maskNumbersOverDigits > 255)
+  ) {
+    error(
+      "maskNumbersOverDigits",
+      "POLICY_LIMIT_INVALID",
+      `Invalid maskNumbersOverDigits: ${policy.maskNumbersOverDigits}. Must be an integer from 1 to 255 or null.`
+    );
+  }
+  if (policy.kThreshold !== null && (!Number.isInteger(policy.kThreshold) || policy.kThreshold < 1)) {
+    error(
+      "kThreshold",
+      "POLICY_LIMIT_INVALID",
+      `Invalid kThreshold: ${policy.kThreshold}. Must be a positive integer or null.`
+    );
+  }
+  if (!Number.isInteger(policy.maxEntities) || policy.maxEntities < 1) {
+    error(
+      "maxEntities",
+      "POLICY_LIMIT_INVALID",
+      `Invalid maxEntities: ${policy.maxEntities}. Must be a positive integer.`
+    );
+  }
+  if (!Number.isInteger(policy.maxRelations) || policy.maxRelations < 0) {
+    error(
+      "maxRelations",
+      "POLICY_LIMIT_INVALID",
+      `Invalid maxRelations: ${policy.maxRelations}. Must be a non-negative integer.`
+    );
+  } else if (policy.maxRelations === 0 && policy.truncateRelations) {
+    warning(
+      "maxRelations",
+      "POLICY_RELATIONS_DISABLED",
+      "maxRelations is 0 with truncation on; no relation is kept."
+    );
+  }
+  for (const verb of policy.relationVerbs) {
+    if (!/^[a-z]{2,32}$/.test(verb)) {
+      error(
+        "relationVerbs",
+        "POLICY_RELATION_VERB_INVALID",
+        `Invalid relationVerbs entry: ${verb}. Must be 2-32 lowercase letters.`
+      );
+    }
+  }
+  for (const type of policy.allowedRelationTypes ?? []) {
+    if (!/^[a-z][a-z_]{0,31}$/.test(type)) {
+      error(
+        "allowedRelationTypes",
+        "POLICY_RELATION_TYPE_INVALID",
+        `Invalid allowedRelationTypes entry: ${type}. Must be lowercase letters or underscores.`
+      );
+    }
+  }
+  const allowed = policy.allowedRelationTypes;
+  if (allowed !== null && allowed.length === 0) {
+    warning(
+      "allowedRelationTypes",
+      "POLICY_RELATIONS_DISABLED",
+      "allowedRelationTypes is empty; no relation is kept."
+    );
+  } else if (allowed !== null) {
+    if (policy.crossDocumentRelations && !allowed.includes("co_session")) {
+      warning(
+        "allowedRelationTypes",
+        "POLICY_RELATION_TYPE_EXCLUDED",
+        "crossDocumentRelations is on but allowedRelationTypes excludes co_session."
+      );
+    }
+    if (policy.emitDocumentNodes && !allowed.includes("mentioned_in")) {
+      warning(
+        "allowedRelationTypes",
+        "POLICY_RELATION_TYPE_EXCLUDED",
+        "emitDocumentNodes is on but allowedRelationTypes excludes mentioned_in."
+      );
+    }
+  }
+  const types: readonly string[] = ENTITY_TYPES;
+  const refused: readonly string[] = PASSTHROUGH_REFUSED_TYPES;
+  for (const [type, control] of Object.entries(policy.entityControls)) {
+    if (!types.includes(type)) {
+      error(
+        "entityControls",
+        "POLICY_RECOGNIZER_UNKNOWN",
+        `Unknown entityControls recognizer: ${type}`
+      );
+    } else if (control?.passthrough && refused.includes(type)) {
+      error(
+        "entityControls",
+        "POLICY_PASSTHROUGH_REFUSED",
+        `Passthrough is not allowed for ${type}: it would emit identifying text.`
+      );
+    } else if (control?.passthrough && policy.preset === "strict") {
+      error(
+        "entityControls",
+        "POLICY_PASSTHROUGH_REFUSED",
+        `Passthrough is not allowed for ${type} under the strict preset.`
+      );
+    } else if (control?.passthrough && !control.enabled) {
+      warning(
+        "entityControls",
+        "POLICY_PASSTHROUGH_DISABLED",
+        `entityControls ${type} sets passthrough on a disabled recognizer; nothing is emitted.`
+      );
+    }
+  }
+  const placeholderProblem = checkPlaceholderStyle(policy.placeholderStyle);
+  if (placeholderProblem !== null) {
+    error("placeholderStyle", "POLICY_PLACEHOLDER_INVALID", placeholderProblem);
+  }
+  // The message never includes the salt
+  if (policy.idSalt !== null && Buffer.byteLength(policy.idSalt, "utf8") < 16) {
+    error("idSalt", "POLICY_SALT_TOO_SHORT", "idSalt must be at least 16 bytes.");
+  }
+  // Misspelled or newer fields would otherwise fall back to defaults
+  // unnoticed; the strict preset refuses them
+  for (const path of unknownPolicyFields(policy)) {
+    findings.push({
+      severity: policy.preset === "strict" ? "error" : "warning",
+      code: "POLICY_FIELD_UNKNOWN",
+      field: path,
+      message: `Unknown policy field: ${path}`,
+    });
+  }
+  return findings;
+}
+
+/**
+ * Check a placeholder style: a named style, or a custom template whose
+ * literal characters cannot collide with real text.
+ *
+ * @returns Problem description, or null when the style is usable
+ */
+function checkPlaceholderStyle(style: PlaceholderStyle): string | null {
+  const styles: readonly unknown[] = PLACEHOLDER_STYLES;
+  if (typeof style === "string" || typeof style !== "object" || style === null) {
+    return styles.includes(style)
+      ? null
+      : `Invalid placeholderStyle: ${String(style)}. Must be one of ${PLACEHOLDER_STYLES.join(", ")}, or { custom }.`;
+  }
+  const template = style.custom;
+  if (typeof template !== "string" || template.length === 0 || template.length > MAX_PLACEHOLDER_LENGTH) {
+    return `Custom placeholder must be a string of 1 to ${MAX_PLACEHOLDER_LENGTH} characters.`;
+  }
+  if (!template.includes("{id}") && !template.includes("{n}")) {
+    return "Custom placeholder must contain {id} or {n}.";
+  }
+  const literal = template.replace(/\{(?:id|label|n)\}/g, "\u0000");
+  if (literal.startsWith("\u0000") || literal.endsWith("\u0000")) {
+    return "Custom placeholder must start and end with a delimiter.";
+  }
+  for (const char of literal) {
+    if (char !== "\u0000" && !PLACEHOLDER_DELIMITERS.includes(char)) {
+      return `Custom placeholder may only use ${PLACEHOLDER_DELIMITERS} outside its fields.`;
+    }
+  }
+  return null;
+}
+
+/**
+ * Fields each nested policy object accepts.
+ */
+const NESTED_POLICY_FIELDS = {
+  idPatterns: ["name", "pattern", "checksum"],
+  customPatterns: ["name", "pattern", "role"],
+  entityControls: ["enabled", "passthrough"],
+  placeholderStyle: ["custom"],
+} as const;
+
+/**
+ * Paths of fields the policy schema does not define, top-level and inside
+ * patterns and entity controls. The schema is the key set of
+ * DEFAULT_MASKING_POLICY, which every policy version shares; a new field
+ * is added there (with its default) in the release that introduces it.
+ * Only names are reported, never values.
+ */
+function unknownPolicyFields(policy: MaskingPolicy): string[] {
+  const paths = Object.keys(policy).filter((key) => !Object.hasOwn(DEFAULT_MASKING_POLICY, key));
+  const nested = (path: string, value: unknown, allowed: readonly string[]) => {
+    if (value !== null && typeof value === "object" && !Array.isArray(value)) {
+      for (const key of Object.keys(value)) {
+        if (!allowed.includes(key)) paths.push(`${path}.${key}`);
+      }
+    }
+  };
+  if (Array.isArray(policy.idPatterns)) {
+    policy.idPatterns.forEach((pattern, i) =>
+      nested(`idPatterns[${i}]`, pattern, NESTED_POLICY_FIELDS.idPatterns)
+    );
+  }
+  if (Array.isArray(policy.customPatterns)) {
+    policy.customPatterns.forEach((pattern, i) =>
+      nested(`customPatterns[${i}]`, pattern, NESTED_POLICY_FIELDS.customPatterns)
+    );
+  }
+  for (const [type, control] of Object.entries(policy.entityControls ?? {})) {
+    nested(`entityControls.${type}`, control, NESTED_POLICY_FIELDS.entityControls);
+  }
+  nested("placeholderStyle", policy.placeholderStyle, NESTED_POLICY_FIELDS.placeholderStyle);
+  return paths;
+}
+
+/**
+ * Check caller-supplied patterns before any transformation runs. Each
+ * pattern reports its first problem; messages name the pattern but never
+ * echo its regex source.
+ *
+ * @param kind - Field label used in messages
+ * @param patterns - Named regex sources to check
+ * @returns Errors for patterns that are malformed, too large, can match
+ *          the empty string, or are prone to catastrophic backtracking
+ */
+function checkPatterns(
+  kind: "idPattern" | "customPattern",
+  patterns: Array<{ name: string; pattern: string }>
+): PolicyFinding[] {
+  const field = kind === "idPattern" ? "idPatterns" : "customPatterns";
+  const findings: PolicyFinding[] = [];
+  const error = (code: PolicyFindingCode, message: string) =>
+    findings.push({ severity: "error", code, field, message });
+
+  if (patterns.length > MAX_PATTERNS) {
+    error("POLICY_PATTERN_COUNT", `Too many ${kind}s: ${patterns.length} (maximum ${MAX_PATTERNS})`);
+  }
+
+  const names = new Set<string>();
+  for (const { name, pattern } of patterns) {
+    if (!/^[a-z][a-z0-9_]{0,31}$/.test(name)) {
+      error(
+        "POLICY_PATTERN_NAME",
+        `Invalid ${kind} name: ${name}. Must be lowercase letters, digits, or underscores.`
+      );
+      continue;
+    }
+    if (names.has(name)) {
+      error("POLICY_PATTERN_DUPLICATE", `Duplicate ${kind} name: ${name}`);
+      continue;
+    }
+    names.add(name);
+
+    if (pattern.length === 0 || pattern.length > MAX_PATTERN_LENGTH) {
+      error("POLICY_PATTERN_LENGTH", `${kind} ${name} must be 1-${MAX_PATTERN_LENGTH} characters`);
+      continue;
+    }
+
+    let compiled: RegExp;
+    try {
+      compiled = new RegExp(pattern);
+    } catch {
+      error("POLICY_REGEX_INVALID", `${kind} ${name} is not a valid regular expression`);
+      continue;
+    }
+    if (compiled.test("")) {
+      error("POLICY_REGEX_EMPTY_MATCH", `${kind} ${name} matches the empty string`);
+    } else if (/\\(?:[1-9]|k<)/.test(pattern) || hasNestedQuantifier(pattern)) {
+      error(
+        "POLICY_REGEX_BACKTRACKING",
+        `${kind} ${name} is prone to catastrophic backtracking (backreference or nested quantifier)`
+      );
+    }
+  }
+  return findings;
+}
+
+/**
+ * Detect an unbounded quantifier applied to a group that itself contains
+ * a quantifier ("(a+)+", "(\\d{3}-?)*"), the usual cause of exponential
+ * backtracking.
+ */
+function hasNestedQuantifier(source: string): boolean {
+  // One entry per open group: whether it contains a quantifier
+  const groups: boolean[] = [];
+  let inClass = false;
+
+  for (let i = 0; i < source.length; i++) {
+    const ch = source[i];
+    if (ch === "\\") {
+      i++;
+    } else if (inClass) {
+      inClass = ch !== "]";
+    } else if (ch === "[") {
+      inClass = true;
+    } else if (ch === "(") {
+      groups.push(false);
+    } else if (ch === ")") {
+      const quantified = groups.pop() ?? false;
+      const rest = source.slice(i + 1);
+      if (quantified && /^(?:[*+]|\{\d*,\})/.test(rest)) {
+        return true;
+      }
+      if (groups.length > 0 && (quantified || /^[*+?{]/.test(rest))) {
+        groups[groups.length - 1] = true;
+      }
+    } else if (
+      groups.length > 0 &&
+      "*+?{".includes(ch) &&
+      source[i - 1] !== "("
+    ) {
+      groups[groups.length - 1] = true;
+    }
+  }
+  return false;
+}
+
+/**
+ * Check whether any locale in the list has the given region subtag.
+ * Matching is case-insensitive and accepts "-" or "_" separators.
+ *
+ * @param locales - Locale tags (e.g. "en-US", "de_DE")
+ * @param region - Region subtag to look for (e.g. "US")
+ */
+export function hasLocaleRegion(locales: string[], region: string): boolean {
+  const wanted = region.toUpperCase();
+  return locales.some((locale) => localeRegion(locale) === wanted);
+}
+
+/**
+ * Language subtag of a locale tag, lowercased ("de" for "de-DE").
+ */
+export function localeLanguage(locale: string): string {
+  return locale.split(/[-_]/)[0].toLowerCase();
+}
+
+/**
+ * Region subtag of a locale tag, uppercased ("GB" for "en_GB"), or null
+ * for a language-only tag.
+ */
+export function localeRegion(locale: string): string | null {
+  const subtags = locale.split(/[-_]/);
+  return subtags.length > 1 ? subtags[subtags.length - 1].toUpperCase() : null;
+}
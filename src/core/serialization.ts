@@ -0,0 +1,319 @@
+import type { TransformedContext } from "./config.ts";
+import {
+  HASH_DOMAINS,
+  canonicalByteLength,
+  canonicalize,
+  digestCanonical,
+  domainSeparatedHash,
+  normalizeTransformedContext,
+  hash,
+  isHashAlgorithmAvailable,
+} from "./canonical.ts";
+import type { BindingVersion, CanonicalizationMode, HashAlgorithm } from "./canonical.ts";
+import { ConfigurationError, TransformationError } from "./errors.ts";
+import { createHash } from "crypto";
+
+/**
+ * Output encodings for hashing a transformed context.
+ *
+ * - "json": RFC 8785 canonical JSON (see canonical.ts)
+ * - "cbor": RFC 8949 core deterministic CBOR (see toCanonicalCbor)
+ *
+ * Both encode the same normalized context, so they differ only in bytes.
+ * A JSON encoding starts with "{" and a CBOR one with a map head, so a
+ * digest over one can never be recomputed from the other.
+ */
+export const OUTPUT_ENCODINGS = ["json", "cbor"] as const;
+
+/**
+ * One of OUTPUT_ENCODINGS. Default: "json".
+ */
+export type OutputEncoding = (typeof OUTPUT_ENCODINGS)[number];
+
+/**
+ * CBOR tag for a decimal fraction [exponent, mantissa] (RFC 8949 §3.4.4).
+ */
+const DECIMAL_FRACTION_TAG = 4;
+
+/**
+ * Encode a transformed context as RFC 8949 core deterministic CBOR.
+ *
+ * Rules:
+ * - The context is normalized as for canonical JSON (entity and relation
+ *   order, rounded numbers, -0 as 0)
+ * - Shortest-form heads and definite lengths throughout
+ * - Map keys sorted bytewise by their encoded form; absent fields omitted
+ * - No floats: safe integers are CBOR integers, and any other finite
+ *   number is a tag-4 decimal fraction [exponent, mantissa] built from its
+ *   shortest round-trip decimal form, with trailing zeros moved into the
+ *   exponent (0.85 → 4([-2, 85]), 1e21 → 4([21, 1]))
+ *
+ * @param context - The transformed context to encode
+ * @returns Canonical CBOR bytes
+ * @throws TransformationError for a non-finite number or a lone surrogate
+ */
+export function toCanonicalCbor(context: TransformedContext): Uint8Array {
+  const out: number[] = [];
+  encodeValue(normalizeTransformedContext(context, "jcs"), out);
+  return Uint8Array.from(out);
+}
+
+/**
+ * Encode a transformed context in the given output encoding.
+ * @param context - The transformed context to encode
+ * @param encoding - Output encoding (default "json")
+ * @param mode - Canonicalization mode for "json" (default "jcs")
+ * @returns Encoded bytes
+ */
+export function encodeContext(
+  context: TransformedContext,
+  encoding: OutputEncoding = "json",
+  mode: CanonicalizationMode = "jcs"
+): Uint8Array {
+  if (encoding === "cbor") {
+    return toCanonicalCbor(context);
+  }
+  return new TextEncoder().encode(canonicalize(context, mode));
+}
+
+/**
+ * Text renderings of a digest. Only the rendering differs; every
+ * encoding decodes to the same bytes, and report_data always binds the
+ * bytes.
+ *
+ * - "hex": lowercase base16
+ * - "base64url": RFC 4648 §5 without padding
+ * - "multibase": "u" followed by unpadded base64url
+ */
+export const HASH_ENCODINGS = ["hex", "base64url", "multibase"] as const;
+
+/**
+ * One of HASH_ENCODINGS. Default: "hex".
+ */
+export type HashEncoding = (typeof HASH_ENCODINGS)[number];
+
+/**
+ * Render digest bytes in the given encoding.
+ * @param digest - Raw digest bytes
+ * @param encoding - Text encoding (default "hex")
+ * @returns Encoded digest
+ */
+export function encodeDigest(digest: Uint8Array, encoding: HashEncoding = "hex"): string {
+  const bytes = Buffer.from(digest);
+  switch (encoding) {
+    case "hex":
+      return bytes.toString("hex");
+    case "base64url":
+      return bytes.toString("base64url");
+    case "multibase":
+      return `u${bytes.toString("base64url")}`;
+  }
+}
+
+/**
+ * Decode a digest rendered in the given encoding. Hex is accepted in
+ * either case and base64url with or without padding. Multibase accepts the
+ * base16 ("f", "F") and base64url ("u", "U") prefixes.
+ * @param text - Encoded digest
+ * @param encoding - Text encoding (default "hex")
+ * @returns Raw digest bytes
+ * @throws ConfigurationError if text is not valid in that encoding
+ */
+export function decodeDigest(text: string, encoding: HashEncoding = "hex"): Buffer {
+  if (encoding === "multibase") {
+    const base = text.charAt(0);
+    if (base === "f" || base === "F") {
+      return decodeDigest(text.slice(1), "hex");
+    }
+    if (base === "u" || base === "U") {
+      return decodeDigest(text.slice(1), "base64url");
+    }
+    throw new ConfigurationError(`Unsupported multibase prefix: ${JSON.stringify(base)}`);
+  }
+  if (encoding === "hex") {
+    if (!/^(?:[0-9a-fA-F]{2})*$/.test(text)) {
+      throw new ConfigurationError("Invalid hex digest");
+    }
+    return Buffer.from(text, "hex");
+  }
+  // Buffer skips characters it cannot decode, so check the alphabet and
+  // that the text is the canonical rendering of what it decodes to
+  const unpadded = text.replace(/={1,2}$/, "");
+  const padding = "=".repeat((4 - (unpadded.length % 4)) % 4);
+  const bytes = Buffer.from(unpadded, "base64url");
+  if (
+    !/^[A-Za-z0-9_-]*$/.test(unpadded) ||
+    bytes.toString("base64url") !== unpadded ||
+    (text !== unpadded && text !== unpadded + padding)
+  ) {
+    throw new ConfigurationError("Invalid base64url digest");
+  }
+  return bytes;
+}
+
+/**
+ * Hash a transformed context over the given output encoding.
+ * @param context - The transformed context to hash
+ * @param encoding - Output encoding (default "json")
+ * @param algorithm - Hash algorithm (default "sha256")
+ * @param mode - Canonicalization mode for "json" (default "jcs")
+ * @param bindingVersion - Binding formula; 2 and 3 hash the encoding under
+ *        HASH_DOMAINS.context (default 1)
+ * @returns Hex-encoded digest
+ * @throws ConfigurationError if the algorithm is not available
+ */
+export function hashOutput(
+  context: TransformedContext,
+  encoding: OutputEncoding = "json",
+  algorithm: HashAlgorithm = "sha256",
+  mode: CanonicalizationMode = "jcs",
+  bindingVersion: BindingVersion = 1
+): string {
+  if (bindingVersion !== 1 && encoding === "json") {
+    // domainSeparatedHash framing, streamed twice (length prefix, then
+    // content) so the canonical string is never held whole
+    if (!isHashAlgorithmAvailable(algorithm)) {
+      throw new ConfigurationError(`Hash algorithm ${algorithm} is not available in this runtime`);
+    }
+    const length = Buffer.alloc(8);
+    length.writeBigUInt64BE(BigInt(canonicalByteLength(context, mode)), 0);
+    const digest = createHash(algorithm).update(HASH_DOMAINS.context, "utf8").update(length);
+    digestCanonical(context, digest, mode);
+    return digest.digest("hex");
+  }
+  if (bindingVersion !== 1) {
+    return domainSeparatedHash(
+      HASH_DOMAINS.context,
+      [encodeContext(context, encoding, mode)],
+      algorithm
+    ).toString("hex");
+  }
+  if (encoding === "json") {
+    return hash(context, mode, algorithm);
+  }
+  if (!isHashAlgorithmAvailable(algorithm)) {
+    throw new ConfigurationError(`Hash algorithm ${algorithm} is not available in this runtime`);
+  }
+  return createHash(algorithm).update(toCanonicalCbor(context)).digest("hex");
+}
+
+function encodeValue(value: unknown, out: number[]): void {
+  if (value === null) {
+    out.push(0xf6);
+    return;
+  }
+  switch (typeof value) {
+    case "boolean":
+      out.push(value ? 0xf5 : 0xf4);
+      return;
+    case "number":
+      encodeNumber(value, out);
+      return;
+    case "string":
+      encodeText(value, out);
+      return;
+    case "object": {
+      if (Array.isArray(value)) {
+        encodeHead(4, BigInt(value.length), out);
+        for (const element of value) {
+          encodeValue(element ?? null, out);
+        }
+        return;
+      }
+      const record = value as Record<string, unknown>;
+      const entries = Object.keys(record)
+        .filter((key) => record[key] !== undefined)
+        .map((key) => {
+          const encodedKey: number[] = [];
+          encodeText(key, encodedKey);
+          return { encodedKey, value: record[key] };
+        })
+        .sort((a, b) => compareBytes(a.encodedKey, b.encodedKey));
+      encodeHead(5, BigInt(entries.length), out);
+      for (const entry of entries) {
+        out.push(...entry.encodedKey);
+        encodeValue(entry.value, out);
+      }
+      return;
+    }
+    default:
+      throw new TransformationError(`Cannot encode a ${typeof value} value as CBOR`);
+  }
+}
+
+function encodeNumber(value: number, out: number[]): void {
+  if (!Number.isFinite(value)) {
+    throw new TransformationError(`Cannot encode non-finite number as CBOR: ${value}`);
+  }
+  if (Number.isSafeInteger(value)) {
+    encodeInteger(BigInt(value), out);
+    return;
+  }
+
+  // Shortest round-trip decimal form, e.g. "0.85", "-1.5e-7", "1e+21"
+  const match = /^(-?)(\d+)(?:\.(\d+))?(?:e([+-]\d+))?$/.exec(String(value));
+  if (!match) {
+    throw new TransformationError(`Cannot encode number as CBOR: ${value}`);
+  }
+  const [, sign, integerDigits, fractionDigits = "", exponentDigits = "0"] = match;
+  let digits = (integerDigits + fractionDigits).replace(/^0+(?=\d)/, "");
+  let exponent = Number(exponentDigits) - fractionDigits.length;
+  while (digits.length > 1 && digits.endsWith("0")) {
+    digits = digits.slice(0, -1);
+    exponent += 1;
+  }
+  const mantissa = BigInt(digits) * (sign === "-" ? -1n : 1n);
+
+  encodeHead(6, BigInt(DECIMAL_FRACTION_TAG), out);
+  encodeHead(4, 2n, out);
+  encodeInteger(BigInt(exponent), out);
+  encodeInteger(mantissa, out);
+}
+
+function encodeInteger(value: bigint, out: number[]): void {
+  if (value >= 0n) {
+    encodeHead(0, value, out);
+  } else {
+    encodeHead(1, -1n - value, out);
+  }
+}
+
+function encodeText(value: string, out: number[]): void {
+  if (!value.isWellFormed()) {
+    throw new TransformationError("Cannot encode a string with a lone surrogate as CBOR");
+  }
+  const bytes = new TextEncoder().encode(value);
+  encodeHead(3, BigInt(bytes.length), out);
+  for (const byte of bytes) {
+    out.push(byte);
+  }
+}
+
+/**
+ * Write a head in its shortest form (RFC 8949 §4.2.1).
+ */
+function encodeHead(major: number, argument: bigint, out: number[]): void {
+  const type = major << 5;
+  if (argument < 24n) {
+    out.push(type | Number(argument));
+    return;
+  }
+  const width = argument < 0x100n ? 1 : argument < 0x10000n ? 2 : argument < 0x100000000n ? 4 : 8;
+  out.push(type | { 1: 24, 2: 25, 4: 26, 8: 27 }[width]!);
+  for (let shift = BigInt((width - 1) * 8); shift >= 0n; shift -= 8n) {
+    out.push(Number((argument >> shift) & 0xffn));
+  }
+}
+
+/**
+ * Bytewise lexicographic order, as deterministic CBOR sorts map keys.
+ */
+function compareBytes(a: number[], b: number[]): number {
+  const length = Math.min(a.length, b.length);
+  for (let i = 0; i < length; i++) {
+    if (a[i] !== b[i]) {
+      return a[i] - b[i];
+    }
+  }
+  return a.length - b.length;
+}
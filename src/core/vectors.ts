@@ -0,0 +1,249 @@
+import type { TransformedContext } from "./config.ts";
+import { canonicalize } from "./canonical.ts";
+import { createHash } from "crypto";
+
+/**
+ * Canonicalization test vectors shared with the native runner.
+ *
+ * Both hashers generate the same list (the runner exports it as
+ * get_hash_test_vectors), so either side can check the other's output
+ * without hand-copied fixtures. Adding a case here means adding the same
+ * case to the runner.
+ */
+
+/**
+ * One canonicalization test vector.
+ */
+export interface TestVector {
+  /**
+   * Stable case name (snake_case).
+   */
+  name: string;
+
+  /**
+   * Input TransformedContext as JSON.
+   */
+  context: string;
+
+  /**
+   * Expected canonical form (see canonicalize).
+   */
+  canonical: string;
+
+  /**
+   * Hex SHA-256 of the canonical form's UTF-8 bytes.
+   */
+  sha256: string;
+}
+
+const entity = (
+  syntheticId: string,
+  attributes: Record<string, string | number | boolean> = { type: "name" },
+  role: string = "Actor"
+): TransformedContext["entities"][number] => ({ syntheticId, role, attributes });
+
+/**
+ * Vector inputs, in the order they are published.
+ */
+const CASES: Array<[string, TransformedContext]> = [
+  ["empty_context", { entities: [], relations: [], task: "t" }],
+  [
+    "empty_entities_with_relations",
+    { entities: [], relations: [{ from: "ENTITY_0000", to: "ENTITY_0001", type: "related" }], task: "t" },
+  ],
+  ["single_entity", { entities: [entity("ENTITY_0000")], relations: [], task: "t" }],
+  [
+    "unicode_attribute_values",
+    {
+      entities: [entity("ENTITY_0000", { type: "name", script: "Дмитрий", cjk: "中文", emoji: "😀", accent: "José" })],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "unicode_attribute_keys",
+    {
+      entities: [entity("ENTITY_0000", { "€": 1, "ö": 2, "\u0080": 3, "1": 4, "😀": 5, "דּ": 6, type: "name" })],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "escaped_characters",
+    {
+      entities: [entity("ENTITY_0000", { type: "name", text: 'quote " backslash \\ slash / tab \t nl \n ctl \u0001' })],
+      relations: [],
+      task: "t",
+    },
+  ],
+  ["line_separators", { entities: [], relations: [], task: "a\u2028b\u2029c\u007fd" }],
+  [
+    "nested_constraints",
+    {
+      entities: [entity("ENTITY_0000"), entity("ENTITY_0001", { type: "amount" }, "Value")],
+      relations: [],
+      task: "t",
+      constraints: {
+        maxRelationDegree: 4,
+        policyVersion: "v2",
+        roleCounts: { Value: 1, Actor: 1, Organization: 0, Credential: 0, Document: 0 },
+      },
+    },
+  ],
+  [
+    "constraints_empty_role_counts",
+    {
+      entities: [],
+      relations: [],
+      task: "t",
+      constraints: { maxRelationDegree: 0, policyVersion: "v1", roleCounts: {} },
+    },
+  ],
+  [
+    "large_integers",
+    {
+      entities: [
+        entity("ENTITY_0000", {
+          type: "number",
+          maxSafe: Number.MAX_SAFE_INTEGER,
+          minSafe: Number.MIN_SAFE_INTEGER,
+          beyondSafe: 2 ** 53 + 2,
+        }),
+      ],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "large_floats",
+    {
+      entities: [entity("ENTITY_0000", { type: "number", e21: 1e21, e300: 1e300, max: Number.MAX_VALUE })],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "small_numbers",
+    {
+      entities: [entity("ENTITY_0000", { type: "number", tiny: 1e-7, sum: 0.1 + 0.2, belowPrecision: 1e-11 })],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "negative_zero",
+    {
+      entities: [entity("ENTITY_0000", { type: "number", amount: -0 })],
+      relations: [{ from: "ENTITY_0000", to: "ENTITY_0000", type: "self", weight: -0 }],
+      task: "t",
+    },
+  ],
+  [
+    "confidence_rounding",
+    {
+      entities: [
+        entity("ENTITY_0000", { type: "name", confidence: 0.854 }),
+        entity("ENTITY_0001", { type: "name", confidence: 0.855 }),
+        entity("ENTITY_0002", { type: "name", confidence: 1 }),
+      ],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "boolean_attributes",
+    {
+      entities: [entity("ENTITY_0000", { type: "card", luhnValid: true, expired: false })],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "numeric_string_attributes",
+    {
+      entities: [entity("ENTITY_0000", { type: "number", padded: "0010", exponent: "1e5", zero: "-0" })],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "duplicate_looking_relations",
+    {
+      entities: [entity("ENTITY_0000"), entity("ENTITY_0001")],
+      relations: [
+        { from: "ENTITY_0000", to: "ENTITY_0001", type: "paid" },
+        { from: "ENTITY_0000", to: "ENTITY_0001", type: "owes" },
+        { from: "ENTITY_0001", to: "ENTITY_0000", type: "paid" },
+      ],
+      task: "t",
+    },
+  ],
+  [
+    "duplicate_relations",
+    {
+      entities: [entity("ENTITY_0000"), entity("ENTITY_0001")],
+      relations: [
+        { from: "ENTITY_0000", to: "ENTITY_0001", type: "paid", weight: 0.5 },
+        { from: "ENTITY_0000", to: "ENTITY_0001", type: "paid", weight: 0.5 },
+      ],
+      task: "t",
+    },
+  ],
+  [
+    "relation_weights",
+    {
+      entities: [],
+      relations: [
+        { from: "ENTITY_0002", to: "ENTITY_0000", type: "related", weight: 0.25 },
+        { from: "ENTITY_0000", to: "ENTITY_0001", type: "related", weight: 1 },
+        { from: "ENTITY_0001", to: "ENTITY_0002", type: "related" },
+      ],
+      task: "t",
+    },
+  ],
+  [
+    "entity_order_code_units",
+    {
+      entities: [entity("ENTITY_b"), entity("ENTITY_B"), entity("ENTITY_a"), entity("ENTITY_é")],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "entity_order_numeric_ids",
+    {
+      entities: [entity("ENTITY_0100"), entity("ENTITY_0002"), entity("ENTITY_0010")],
+      relations: [],
+      task: "t",
+    },
+  ],
+  [
+    "attribute_order_shuffled",
+    {
+      entities: [entity("ENTITY_0000", { type: "name", position: 3, confidence: 0.9, occurrences: 2, sourceDoc: "doc_0" })],
+      relations: [],
+      task: "t",
+    },
+  ],
+  ["model_present", { entities: [], relations: [], task: "t", model: "llama3" }],
+  [
+    "task_whitespace_and_unicode",
+    { entities: [], relations: [], task: "  Résumé the “memo”\twith\r\nbreaks  " },
+  ],
+];
+
+/**
+ * Generate the canonicalization test vectors.
+ * @returns One vector per case, in publication order
+ */
+export function generateTestVectors(): TestVector[] {
+  return CASES.map(([name, context]) => {
+    const canonical = canonicalize(context);
+    return {
+      name,
+      context: JSON.stringify(context),
+      canonical,
+      sha256: createHash("sha256").update(canonical, "utf8").digest("hex"),
+    };
+  });
+}
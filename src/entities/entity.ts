@@ -19,11 +19,80 @@ export interface Entity {
 
   /**
    * Attributes associated with this entity.
-   * Keys are attribute names, values are strings or numbers.
+   * Keys are attribute names, values are strings, numbers, or booleans.
    */
-  attributes: Record<string, string | number>;
+  attributes: Record<string, string | number | boolean>;
+
+  /**
+   * Additional raw forms (components, alternate spellings) that must never
+   * appear in the masked output. Only present before masking.
+   */
+  boundaryTerms?: string[];
+
+  /**
+   * Number of times the same value occurred in the input; repeats are
+   * merged into one entity. Absent means one occurrence.
+   */
+  occurrences?: number;
+
+  /**
+   * Whitespace-token index of each occurrence within its document, used
+   * for relation windows. Only present before masking; never serialized.
+   */
+  tokenPositions?: number[];
+
+  /**
+   * Every document the entity occurs in, ascending, when it occurs in more
+   * than one. Only present before masking; never serialized.
+   */
+  sourceDocs?: number[];
 }
 
+/**
+ * Entity types the distiller detects, one per recognizer. Also the keys of
+ * the masking policy's entityControls.
+ */
+export const ENTITY_TYPES = [
+  "name",
+  "number",
+  "date",
+  "currency",
+  "identifier",
+  "email",
+  "phone",
+  "ssn",
+  "national_id",
+  "vin",
+  "license_plate",
+  "npi",
+  "mrn",
+  "icd10",
+  "coordinates",
+  "postal_code",
+  "custom",
+  "restricted",
+  "payment_card",
+  "iban",
+  "url",
+  "ip_address",
+  "mac",
+  "serial",
+  "long_number",
+  "handle",
+  "file_path",
+  "address",
+  "uuid",
+  "secret",
+  "jwt",
+  "organization",
+  "place",
+] as const;
+
+/**
+ * One of ENTITY_TYPES.
+ */
+export type EntityType = (typeof ENTITY_TYPES)[number];
+
 /**
  * Internal representation of an extracted entity before role assignment.
  * Used during the distillation phase.
@@ -37,11 +106,70 @@ export interface RawEntity {
   /**
    * The type of entity detected by heuristics.
    */
-  entityType: "name" | "number" | "date" | "currency" | "identifier";
+  entityType: EntityType;
 
   /**
    * Position in the original text (for relation building).
    */
   position: number;
+
+  /**
+   * Additional raw forms derived from the match that must be
+   * boundary-checked alongside the original text.
+   */
+  boundaryTerms?: string[];
+
+  /**
+   * Number of times the same value occurred in the input; repeats are
+   * merged into the first occurrence. Absent means one occurrence.
+   */
+  occurrences?: number;
+
+  /**
+   * Name of the policy pattern that matched (national_id and custom
+   * entities only).
+   */
+  patternName?: string;
+
+  /**
+   * Role requested by the matching custom pattern (custom entities only).
+   */
+  customRole?: Role;
+
+  /**
+   * Role inferred from neighboring cue words ("in", "works at", "on");
+   * name entities only. Absent means the default Actor role.
+   */
+  inferredRole?: Role;
+
+  /**
+   * Fixed confidence score from the recognizer (see ENTITY_CONFIDENCE);
+   * the highest score when repeats were merged.
+   */
+  confidence?: number;
+
+  /**
+   * Jurisdiction whose plate format matched (license_plate entities only).
+   */
+  plateLocale?: string;
+
+  /**
+   * Index of the input document the entity was found in (first
+   * occurrence); set only for multi-document input.
+   */
+  sourceDoc?: number;
+
+  /**
+   * Whitespace-token index of each occurrence within the document
+   * (occurrences in other documents are not included). Internal to
+   * relation building; never serialized.
+   */
+  tokenPositions?: number[];
+
+  /**
+   * Every document the value occurs in, ascending, when repeats span more
+   * than one document. Internal; never serialized.
+   */
+  sourceDocs?: number[];
 }
 
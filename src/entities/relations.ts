@@ -5,7 +5,8 @@
 export interface Relation {
   /**
    * The type of relationship.
-   * Examples: "owns", "references", "precedes", "contains"
+   * Examples: "owns", "references", "before"/"after" between dates, or a
+   * connecting verb such as "paid" (from subject to object)
    */
   type: string;
 
@@ -18,5 +19,12 @@ export interface Relation {
    * The ID of the target entity.
    */
   to: string;
+
+  /**
+   * Number of observations collapsed into this relation: verb matches
+   * for a verb type, otherwise co-occurring mention pairs. Absent means
+   * one.
+   */
+  weight?: number;
 }
 
@@ -8,6 +8,11 @@ export const Role = {
    */
   Actor: "Actor",
 
+  /**
+   * A company or other legal entity.
+   */
+  Organization: "Organization",
+
   /**
    * An entity involved in but not driving actions.
    */
@@ -27,6 +32,56 @@ export const Role = {
    * A time-related entity (dates, durations, deadlines).
    */
   Temporal: "Temporal",
+
+  /**
+   * A contact channel (email address, phone number).
+   */
+  Contact: "Contact",
+
+  /**
+   * An identifier assigned to a person, card, device, or record (SSN, national ID, VIN, license plate, MAC address, UUID, @handle).
+   */
+  Identifier: "Identifier",
+
+  /**
+   * A financial account (IBAN).
+   */
+  Account: "Account",
+
+  /**
+   * A network or file resource (URL, file path).
+   */
+  Resource: "Resource",
+
+  /**
+   * A network endpoint (IP address).
+   */
+  NetworkEndpoint: "NetworkEndpoint",
+
+  /**
+   * A physical location (postal address, postal code, known place name, coordinates).
+   */
+  Location: "Location",
+
+  /**
+   * A secret that grants access (API key, JWT, private key).
+   */
+  Credential: "Credential",
+
+  /**
+   * A medical condition (ICD-10 code).
+   */
+  Condition: "Condition",
+
+  /**
+   * A term the masking policy marks as sensitive (deny list).
+   */
+  Restricted: "Restricted",
+
+  /**
+   * A synthetic node standing for one input document (document nodes only).
+   */
+  Document: "Document",
 } as const;
 
 /**
@@ -10,27 +10,245 @@
  */
 
 export { Axiom } from "./core/axiom.ts";
-export { AttestationVerifier, createVerifier } from "./attestation/verifier.ts";
+export {
+  AttestationVerifier,
+  createVerifier,
+  verifyAttestationReport,
+  verifyAttestationReportWithCrl,
+} from "./attestation/verifier.ts";
+export {
+  AMD_KDS_URL,
+  CERT_TABLE_GUIDS,
+  VCEK_PRODUCTS,
+  createCertTable,
+  crlUrl,
+  extractChipId,
+  extractReportedTcb,
+  fetchCrl,
+  fetchVcek,
+  parseCertTable,
+  vcekUrl,
+} from "./attestation/certs.ts";
+export type { CertChain, ReportedTcb, VcekOptions, VcekProduct } from "./attestation/certs.ts";
+export {
+  CRL_STATUSES,
+  DEFAULT_CRL_MAX_AGE_MS,
+  crlStatus,
+  normalizeSerial,
+  parseCrl,
+  verifyCrlSignature,
+} from "./attestation/crl.ts";
+export type { CertificateRevocationList, CrlStatus } from "./attestation/crl.ts";
+export {
+  createEvidenceEnvelope,
+  evidenceFromJson,
+  evidenceKind,
+  evidenceToJson,
+  expectedForEvidence,
+  openEvidence,
+  resolveEvidence,
+} from "./attestation/evidence.ts";
+export type { OpenedEvidence } from "./attestation/evidence.ts";
+export {
+  SDK_VERSION,
+  VERIFICATION_BUNDLE_VERSION,
+  createVerificationBundle,
+  verificationBundleFromJson,
+  verifyVerificationBundle,
+} from "./attestation/bundle.ts";
+export type {
+  BundleRequestFields,
+  BundleVerificationOptions,
+  VerificationBundle,
+} from "./attestation/bundle.ts";
+export {
+  COSE_ALG_EDDSA,
+  COSE_SIGN1_TAG,
+  coseExpectedBinding,
+  createCoseEvidence,
+  decodeCoseEvidence,
+  verifyCoseEvidence,
+} from "./attestation/cose.ts";
+export {
+  AMD_OEMID,
+  EAT_CLAIM_KEYS,
+  EAT_SWNAME,
+  UCCS_TAG,
+  createEatToken,
+  decodeEatToken,
+  eatClaimsFor,
+  getEatToken,
+  verifyEatToken,
+} from "./attestation/eat.ts";
+export {
+  TOKEN_CLOCK_SKEW_MS,
+  TOKEN_ERROR_CODES,
+  TOKEN_ISSUER,
+  decodeAttestationToken,
+  verifyAttestationToken,
+} from "./attestation/token.ts";
+export type { TokenErrorCode } from "./attestation/token.ts";
+export {
+  SIMULATOR_QE_VENDOR_ID,
+  TDX_QUOTE_VERSION,
+  TDX_SIGNED_QUOTE_BYTES,
+  TDX_TEE_TYPE,
+  isSimulatorQuote,
+  isTdxQuote,
+  parseTdxQuote,
+  tdxQuoteToBytes,
+} from "./attestation/tdx.ts";
+export type { TdxQuote } from "./attestation/tdx.ts";
+export {
+  MEASUREMENT_REGISTRY_VERSION,
+  loadMeasurementRegistry,
+  loadRegistryKey,
+  signMeasurementRegistry,
+} from "./attestation/registry.ts";
+export type { MeasurementRegistry } from "./attestation/registry.ts";
+export {
+  REPORT_BYTES,
+  REPORT_VERSION,
+  REPORT_VERSIONS,
+  SIMULATOR_REPORT_VERSION,
+  SIMULATOR_REPORT_V3_VERSION,
+  emptyReport,
+  extractPlatformEvidence,
+  isSimulatorVersion,
+  parseReport,
+  reportFromJson,
+  reportLayout,
+  reportToBytes,
+  reportToJson,
+  signatureFromEd25519,
+  signatureFromP1363,
+  signatureToEd25519,
+  signatureToP1363,
+  tcbBelowMinimum,
+} from "./attestation/report.ts";
+export type {
+  AttestationReport,
+  FirmwareVersion,
+  MinimumTcb,
+  PlatformEvidence,
+  ReportSignature,
+  ReportVersion,
+  TcbVersion,
+} from "./attestation/report.ts";
 export type {
   AxiomConfig,
   ReasonInput,
   ReasonResult,
   TransformedContext,
+  ContextConstraints,
   AttestationEvidence,
 } from "./core/config.ts";
+export type {
+  CustomPattern,
+  IdPattern,
+  MaskingPolicy,
+  PolicyFinding,
+  PolicyFindingCode,
+  PolicyReport,
+  Preset,
+  IndustryPreset,
+  PlaceholderStyle,
+} from "./core/policy.ts";
+export {
+  DEFAULT_MASKING_POLICY,
+  INDUSTRY_PRESET_EXPANSIONS,
+  clearDefaultPolicy,
+  getDefaultPolicyHash,
+  loadDefaultPolicy,
+  migratePolicyV1ToV2,
+  validatePolicy,
+} from "./core/policy.ts";
 export type {
   VerificationVerdict,
   VerificationOptions,
   SessionMetadata,
+  ExpectedBinding,
+  ReportCheck,
+  ReportCheckName,
+  ReportVerificationResult,
+  SimulatorVerificationKey,
+  TokenizedAttestation,
+  BindingFormula,
+  CoseEvidencePayload,
+  AttestationTokenClaims,
+  CoseVerificationResult,
+  EatClaims,
+  EatVerificationResult,
+  EvidenceEnvelope,
+  EvidenceFormat,
+  EvidencePackaging,
+  EvidencePlatform,
+  AttestationMode,
+  SessionAttestation,
+} from "./attestation/types.ts";
+export {
+  ATTESTATION_MODES,
+  BINDING_FORMULAS,
+  EVIDENCE_FORMATS,
+  EVIDENCE_PACKAGINGS,
+  EVIDENCE_PLATFORMS,
+  REPORT_CHECKS,
+  SIMULATOR_KEY_LABEL,
 } from "./attestation/types.ts";
 export {
   BoundaryViolationError,
   TransformationError,
   ConfigurationError,
   SecurityInvariantError,
+  AttestationError,
+  InvalidInputError,
+  TokenError,
+  MeasurementMismatchError,
 } from "./core/errors.ts";
 
 // Utilities for advanced usage
-export { hash, canonicalize, verifyEquivalence } from "./core/canonical.ts";
-export { Session } from "./runtime/session.ts";
+export {
+  hash,
+  canonicalize,
+  verifyEquivalence,
+  CANONICALIZATION_MODES,
+  CONTEXT_SCHEMA_VERSIONS,
+  getCanonicalContext,
+  computeContextHash,
+  HASH_ALGORITHMS,
+  HASH_SCOPES,
+  BINDING_VERSIONS,
+  HASH_DOMAINS,
+  domainSeparatedHash,
+  isHashAlgorithmAvailable,
+} from "./core/canonical.ts";
+export type {
+  BindingVersion,
+  CanonicalizationMode,
+  HashAlgorithm,
+  HashScope,
+} from "./core/canonical.ts";
+export {
+  OUTPUT_ENCODINGS,
+  HASH_ENCODINGS,
+  toCanonicalCbor,
+  hashOutput,
+  encodeDigest,
+  decodeDigest,
+} from "./core/serialization.ts";
+export type { HashEncoding, OutputEncoding } from "./core/serialization.ts";
+export { generateTestVectors } from "./core/vectors.ts";
+export type { TestVector } from "./core/vectors.ts";
+export {
+  Session,
+  decodeNonce,
+  validateNonce,
+  NONCE_MIN_BYTES,
+  NONCE_MAX_BYTES,
+  NONCE_ERROR_CODES,
+  createSessionKeyReportData,
+} from "./runtime/session.ts";
+export { DEFAULT_ATTESTATION_WINDOW_MS } from "./runtime/session-attestation.ts";
+export type { NonceErrorCode } from "./runtime/session.ts";
+export { hashPolicy, validateRequest } from "./runtime/enclave-bridge.ts";
 
@@ -0,0 +1,362 @@
+/**
+ * Attestation backends: what produces the evidence for a transform, so a
+ * runner's execute path is the same whatever platform attests it. The
+ * simulator's backends live here; the hardware ones (SEV-SNP, TDX) live in
+ * the private runner behind the same interface.
+ */
+import { createHash, createHmac, randomBytes, sign } from "crypto";
+import type { KeyObject } from "crypto";
+import { readFileSync } from "fs";
+import { ConfigurationError } from "../core/errors.ts";
+import type { BindingVersion, HashAlgorithm } from "../core/canonical.ts";
+import { createCertTable, parseCertTable } from "../attestation/certs.ts";
+import type { CertChain } from "../attestation/certs.ts";
+import {
+  SIGNATURE_ALGO_SIMULATOR_ED25519,
+  SIGNED_REPORT_BYTES,
+  SIMULATOR_REPORT_V3_VERSION,
+  SIMULATOR_REPORT_VERSION,
+  emptyReport,
+  parseReport,
+  reportToBytes,
+  signatureFromEd25519,
+} from "../attestation/report.ts";
+import type { ReportVersion, TcbVersion } from "../attestation/report.ts";
+import {
+  SIMULATOR_QE_VENDOR_ID,
+  TDX_SIGNED_QUOTE_BYTES,
+  emptyTdxQuote,
+  isTdxQuote,
+  parseTdxQuote,
+  tdxQuoteToBytes,
+} from "../attestation/tdx.ts";
+import { createReportData, createSessionKeyReportData } from "./session.ts";
+
+/**
+ * TEE backends a runner can attest with: SEV-SNP reports, or TDX quotes
+ * (the runner's `tdx` feature).
+ */
+export const ENCLAVE_BACKENDS = ["sev-snp", "tdx"] as const;
+
+export type EnclaveBackend = (typeof ENCLAVE_BACKENDS)[number];
+
+/**
+ * Platform a runner reports: its backend, with "-simulator" appended for
+ * the simulator.
+ */
+export type EnclavePlatform = EnclaveBackend | "sev-snp-simulator" | "tdx-simulator";
+
+/**
+ * What report_data binds, as a runner computed it for one transform.
+ */
+export interface BindingInput {
+  sessionId: Uint8Array;
+  nonce: Uint8Array;
+  configHash: Uint8Array;
+  policyHash: Uint8Array;
+  outputHash: Uint8Array;
+  timestamp: number;
+  legacyReportData: boolean;
+  hashAlgorithm: HashAlgorithm;
+  inputHash?: Uint8Array;
+
+  /**
+   * Output hash of the previous step, hex-encoded, if chained.
+   */
+  chainedFrom?: string;
+
+  bindingVersion: BindingVersion;
+
+  /**
+   * Session key to bind instead of the output, for a "session_cached"
+   * session report (see createSessionKeyReportData).
+   */
+  sessionKey?: { publicKey: Uint8Array; windowStart: number; expiresAt: number };
+}
+
+/**
+ * Evidence a backend produced: a raw report or quote, and the certificates
+ * that came with it.
+ */
+export interface GeneratedEvidence {
+  report: Uint8Array;
+  certificates?: CertChain;
+}
+
+/**
+ * Source of attestation evidence for a runner. One is selected when the
+ * runner is initialized; execute() calls it and checks that the evidence
+ * carries the binding's report_data.
+ */
+export interface AttestationBackend {
+  /**
+   * Whether the backend can produce evidence on this machine.
+   */
+  isAvailable(): boolean;
+
+  /**
+   * Measurement the backend reports with each response.
+   */
+  measurement(): string;
+
+  /**
+   * Produce evidence whose report_data is bindingReportData(binding).
+   */
+  generateEvidence(binding: BindingInput): GeneratedEvidence;
+
+  /**
+   * Platform the runner reports (see EnclaveBridge.getPlatform).
+   */
+  platformId(): EnclavePlatform;
+}
+
+/**
+ * report_data for a binding under its binding version (see
+ * createReportData): 32 bytes for versions 1 and 2, 64 for version 3, or
+ * for a session key, createSessionKeyReportData. The formula is the same
+ * for every backend.
+ */
+export function bindingReportData(binding: BindingInput): Buffer {
+  if (binding.sessionKey !== undefined) {
+    return createSessionKeyReportData(
+      binding.sessionId,
+      binding.sessionKey.publicKey,
+      binding.sessionKey.windowStart,
+      binding.sessionKey.expiresAt
+    );
+  }
+  return createReportData(
+    binding.sessionId,
+    binding.configHash,
+    binding.policyHash,
+    binding.outputHash,
+    binding.timestamp,
+    binding.legacyReportData,
+    binding.hashAlgorithm,
+    binding.inputHash,
+    binding.chainedFrom !== undefined ? Buffer.from(binding.chainedFrom, "hex") : undefined,
+    binding.bindingVersion
+  );
+}
+
+/**
+ * The 64-byte report_data field of a SEV-SNP report or TDX quote.
+ * @throws AttestationError if the evidence does not parse
+ */
+export function evidenceReportData(report: Uint8Array): Uint8Array {
+  return isTdxQuote(report) ? parseTdxQuote(report).reportData : parseReport(report).reportData;
+}
+
+/**
+ * Environment variable that overrides the simulator measurement with 96
+ * hex characters, so CI gets the same measurement on every machine.
+ */
+export const SIMULATOR_MEASUREMENT_ENV = "AXIOM_SIMULATOR_MEASUREMENT";
+
+let executableMeasurement: string | undefined;
+
+/**
+ * Measurement simulator backends report and embed: SHA-384 of the running
+ * executable (process.execPath), as 96 lowercase hex characters, computed
+ * once per process. AXIOM_SIMULATOR_MEASUREMENT overrides it.
+ * @throws ConfigurationError if the override is not 48 bytes of hex, or
+ *         the executable cannot be read
+ */
+export function simulatorMeasurement(): string {
+  const override = process.env[SIMULATOR_MEASUREMENT_ENV];
+  if (override !== undefined && override !== "") {
+    if (!/^[0-9a-f]{96}$/i.test(override)) {
+      throw new ConfigurationError(
+        `Invalid ${SIMULATOR_MEASUREMENT_ENV}: must be 96 hex characters (48 bytes)`
+      );
+    }
+    return override.toLowerCase();
+  }
+  if (executableMeasurement === undefined) {
+    let binary: Buffer;
+    try {
+      binary = readFileSync(process.execPath);
+    } catch (error) {
+      const reason = error instanceof Error ? error.message : String(error);
+      throw new ConfigurationError(
+        `Cannot read ${process.execPath} for the simulator measurement: ${reason}`
+      );
+    }
+    executableMeasurement = createHash("sha384").update(binary).digest("hex");
+  }
+  return executableMeasurement;
+}
+
+// Placeholder certificate table entries, so simulator responses have the
+// extended-report shape. Not DER; nothing can verify against them.
+const SIMULATOR_CERT_CHAIN = {
+  vcek: new TextEncoder().encode("AXIOM SIMULATOR VCEK"),
+  ask: new TextEncoder().encode("AXIOM SIMULATOR ASK"),
+  ark: new TextEncoder().encode("AXIOM SIMULATOR ARK"),
+};
+
+// Fixed platform fields for simulator reports, in the range real Milan
+// parts report, so platform evidence can be exercised without hardware.
+const SIMULATOR_TCB: TcbVersion = { bootLoader: 3, tee: 0, snp: 8, microcode: 115 };
+const SIMULATOR_PLATFORM_INFO = 1n; // SMT enabled
+// CPUID of a Milan part, for version 3 reports
+const SIMULATOR_CPUID = { family: 0x19, model: 0x01, stepping: 0x01 };
+
+// Key for deriving the variable report fields in deterministic mode. Public
+// on purpose: deterministic reports are test fixtures, not evidence.
+const DETERMINISTIC_REPORT_KEY = "axiom-simulator-deterministic-report-v1";
+
+/**
+ * Options shared by the simulator backends.
+ */
+export interface SimulatorBackendOptions {
+  /**
+   * SEV-SNP report layout to build.
+   */
+  reportVersion: ReportVersion;
+
+  /**
+   * Derive the fields hardware varies per call from the session ID and
+   * nonce (true), or draw them from OS randomness (false).
+   */
+  deterministic: boolean;
+
+  /**
+   * Ed25519 key that signs simulator evidence.
+   */
+  signingKey: KeyObject;
+}
+
+/**
+ * Fields real hardware varies: derived in deterministic mode, random in
+ * realistic mode.
+ */
+function variableBytes(
+  options: SimulatorBackendOptions,
+  label: string,
+  length: number,
+  binding: BindingInput
+): Uint8Array {
+  return options.deterministic
+    ? createHmac("sha512", DETERMINISTIC_REPORT_KEY)
+        .update(label)
+        .update(binding.sessionId)
+        .update(binding.nonce)
+        .digest()
+        .subarray(0, length)
+    : randomBytes(length);
+}
+
+/**
+ * Simulator backend: fake SEV-SNP reports, clearly marked. NO SECURITY
+ * GUARANTEES.
+ */
+export class SimulatorBackend implements AttestationBackend {
+  private readonly options: SimulatorBackendOptions;
+
+  constructor(options: SimulatorBackendOptions) {
+    this.options = options;
+  }
+
+  isAvailable(): boolean {
+    return true; // Always available
+  }
+
+  measurement(): string {
+    return simulatorMeasurement();
+  }
+
+  platformId(): "sev-snp-simulator" {
+    return "sev-snp-simulator";
+  }
+
+  /**
+   * Generate a fake attestation report for simulator mode.
+   * Structure mimics real report but clearly marked as fake.
+   */
+  generateEvidence(binding: BindingInput): GeneratedEvidence {
+    // A real report layout, marked by its version ("FAKE" or "FAK3"),
+    // with the simulator measurement and fixed platform fields
+    const version3 = this.options.reportVersion === 3;
+    const report = emptyReport(version3 ? SIMULATOR_REPORT_V3_VERSION : SIMULATOR_REPORT_VERSION);
+    if (version3) {
+      report.cpuidFamId = SIMULATOR_CPUID.family;
+      report.cpuidModId = SIMULATOR_CPUID.model;
+      report.cpuidStep = SIMULATOR_CPUID.stepping;
+    }
+    report.currentTcb = { ...SIMULATOR_TCB };
+    report.reportedTcb = { ...SIMULATOR_TCB };
+    report.committedTcb = { ...SIMULATOR_TCB };
+    report.launchTcb = { ...SIMULATOR_TCB };
+    report.platformInfo = SIMULATOR_PLATFORM_INFO;
+    report.measurement.set(Buffer.from(this.measurement(), "hex"));
+
+    report.chipId.set(variableBytes(this.options, "chip_id", 64, binding));
+    report.reportId.set(variableBytes(this.options, "report_id", 32, binding));
+
+    // Embed custom data
+    report.reportData.set(bindingReportData(binding));
+
+    // Sign everything before the signature, as the hardware does
+    report.signatureAlgo = SIGNATURE_ALGO_SIMULATOR_ED25519;
+    const body = reportToBytes(report).subarray(0, SIGNED_REPORT_BYTES);
+    report.signature = signatureFromEd25519(sign(null, body, this.options.signingKey));
+
+    return {
+      report: reportToBytes(report),
+      certificates: parseCertTable(createCertTable(SIMULATOR_CERT_CHAIN)),
+    };
+  }
+}
+
+/**
+ * Simulator backend for TDX: fake version 4 quotes, for schema testing.
+ * NO SECURITY GUARANTEES.
+ */
+export class TdxSimulatorBackend implements AttestationBackend {
+  private readonly options: SimulatorBackendOptions;
+
+  constructor(options: SimulatorBackendOptions) {
+    this.options = options;
+  }
+
+  isAvailable(): boolean {
+    return true; // Always available
+  }
+
+  measurement(): string {
+    return simulatorMeasurement();
+  }
+
+  platformId(): "tdx-simulator" {
+    return "tdx-simulator";
+  }
+
+  /**
+   * Generate a fake TDX quote: the version 4 layout, marked by its
+   * qe_vendor_id, with the simulator measurement as mr_td and the other
+   * measurements zero; its signature data is an Ed25519 signature over
+   * header and body, not an ECDSA quote signature.
+   */
+  generateEvidence(binding: BindingInput): GeneratedEvidence {
+    const quote = emptyTdxQuote();
+    quote.qeVendorId = new Uint8Array(SIMULATOR_QE_VENDOR_ID);
+    quote.mrTd = new Uint8Array(Buffer.from(this.measurement(), "hex"));
+    quote.userData = new Uint8Array(variableBytes(this.options, "user_data", 20, binding));
+    quote.reportData.set(bindingReportData(binding));
+
+    const body = tdxQuoteToBytes(quote).subarray(0, TDX_SIGNED_QUOTE_BYTES);
+    quote.signatureData = new Uint8Array(sign(null, body, this.options.signingKey));
+    return { report: tdxQuoteToBytes(quote) };
+  }
+}
+
+/**
+ * The simulator backend for a platform backend.
+ */
+export function simulatorBackendFor(
+  backend: EnclaveBackend,
+  options: SimulatorBackendOptions
+): AttestationBackend {
+  return backend === "tdx" ? new TdxSimulatorBackend(options) : new SimulatorBackend(options);
+}
@@ -1,4 +1,34 @@
 import type { MaskedRepresentation } from "../transform/masking.ts";
+import {
+  KEYED_ID_PATTERN,
+  SHAPED_ATTRIBUTE_VALUES,
+  SUPPRESSED_ATTRIBUTE_VALUE,
+  withoutLiterals,
+} from "../transform/masking.ts";
+import {
+  CARD_NETWORKS,
+  FILE_EXTENSIONS,
+  RELATION_VERBS,
+  TLD_BUCKETS,
+} from "../transform/abstraction.ts";
+import {
+  CURRENCY_CODES,
+  HONORIFICS,
+  IBAN_LENGTHS,
+  NAME_SCRIPTS,
+  POSTAL_COUNTRIES,
+  SECRET_PROVIDERS,
+  URL_SCHEMES,
+  foldForMatching,
+} from "../transform/distiller.ts";
+import { PLACE_REGIONS } from "../transform/gazetteer.ts";
+import type { MaskingPolicy } from "../core/policy.ts";
+import {
+  DEFAULT_MASKING_POLICY,
+  PLATE_LOCALES,
+  POLICY_VERSIONS,
+  isPassthrough,
+} from "../core/policy.ts";
 import { BoundaryViolationError } from "../core/errors.ts";
 
 /**
@@ -14,8 +44,54 @@ const ALLOWED_SERIALIZABLE_FIELDS = new Set([
   "to",
   "entities",
   "relations",
+  "constraints",
+  "weight",
+  "index",
+  "lengthBucket",
+  "literal",
   "position",
   "numericValue",
+  "domainTld",
+  "digitCount",
+  "hasCountryCode",
+  "network",
+  "last4",
+  "country",
+  "yearBucket",
+  "hasTime",
+  "year",
+  "month",
+  "day",
+  "currency",
+  "magnitude",
+  "scheme",
+  "pathDepth",
+  "family",
+  "isPrivate",
+  "prefixLength",
+  "ouiKnown",
+  "hasZip",
+  "countryHint",
+  "version",
+  "occurrences",
+  "confidence",
+  "sourceDoc",
+  "negative",
+  "percent",
+  "providerHint",
+  "algPresent",
+  "tokenCount",
+  "honorificPresent",
+  "honorific",
+  "region",
+  "script",
+  "extension",
+  "idPattern",
+  "locale",
+  "code",
+  "geoCell",
+  "postalPrefix",
+  "customPattern",
 ]);
 
 /**
@@ -27,25 +103,96 @@ const ALLOWED_ATTRIBUTE_VALUES = new Set([
   "date",
   "currency",
   "identifier",
+  "email",
+  "phone",
+  "ssn",
+  "national_id",
+  "vin",
+  "license_plate",
+  "npi",
+  "mrn",
+  "icd10",
+  "coordinates",
+  "postal_code",
+  "custom",
+  "restricted",
+  "payment_card",
+  "iban",
+  "url",
+  "ip_address",
+  "ipv4",
+  "ipv6",
+  "mac",
+  "serial",
+  "long_number",
+  "handle",
+  "file_path",
+  "address",
+  "uuid",
+  "secret",
+  "jwt",
+  "organization",
+  "place",
+  "US",
+  ...TLD_BUCKETS,
+  ...CARD_NETWORKS,
+  ...FILE_EXTENSIONS,
+  ...Object.keys(IBAN_LENGTHS),
+  ...CURRENCY_CODES,
+  ...URL_SCHEMES,
+  ...SECRET_PROVIDERS,
+  ...HONORIFICS,
+  ...PLACE_REGIONS,
+  ...NAME_SCRIPTS,
+  ...PLATE_LOCALES,
+  ...POSTAL_COUNTRIES,
   "Actor",
+  "Organization",
   "Participant",
   "Obligation",
   "Value",
   "Temporal",
+  "Contact",
+  "Identifier",
+  "Account",
+  "Resource",
+  "NetworkEndpoint",
+  "Location",
+  "Credential",
+  "Condition",
+  "Restricted",
+  "Document",
+  "mentioned_in",
   "owns",
   "references",
   "scheduled",
   "dated",
   "precedes",
+  "before",
+  "after",
   "related",
+  "co_session",
+  SUPPRESSED_ATTRIBUTE_VALUE,
+  ...RELATION_VERBS,
 ]);
 
+/**
+ * Fields allowed inside the constraints object.
+ */
+const CONSTRAINT_FIELDS = new Set(["roleCounts", "maxRelationDegree", "policyVersion"]);
+
 /**
  * BoundaryValidator ensures transformed context is safe to cross the local-to-cloud boundary.
  * Validates that no raw input data can be serialized.
  * Enforces explicit allow-list serialization.
  */
 export class BoundaryValidator {
+  private readonly policy: MaskingPolicy;
+
+  constructor(policy: MaskingPolicy = DEFAULT_MASKING_POLICY) {
+    this.policy = policy;
+  }
+
   /**
    * Validate that the masked representation is safe for boundary crossing.
    *
@@ -57,11 +204,15 @@ export class BoundaryValidator {
     // Validate structure against allow-list
     this.validateStructure(masked);
 
-    // Validate no raw input substrings appear in output
-    this.validateNoRawSubstrings(masked, rawInputs);
+    // Validate no raw input substrings appear in output; passthrough
+    // literals are exempt
+    this.validateNoRawSubstrings(withoutLiterals(masked, this.policy), rawInputs);
 
     // Validate all string values are from allow-list or synthetic IDs
     this.validateStringValues(masked);
+
+    // Validate constraints carry aggregates only
+    this.validateConstraints(masked);
   }
 
   /**
@@ -119,12 +270,29 @@ export class BoundaryValidator {
     masked: MaskedRepresentation,
     rawInputs: string[]
   ): void {
-    const serialized = JSON.stringify(masked);
+    const serialized = JSON.stringify(masked).replace(KEYED_ID_PATTERN, "ENTITY_");
+    const foldedValues = foldForMatching(
+      this.collectStringValues(masked).replace(KEYED_ID_PATTERN, "ENTITY_")
+    );
+
+    for (const input of rawInputs.map((raw) => raw.normalize("NFC"))) {
+      // Accented words must not appear even in folded form ("José" as "jose")
+      for (const word of input.match(/[\p{L}\p{M}]{3,}/gu) ?? []) {
+        const folded = foldForMatching(word);
+        if (
+          folded !== word.toLowerCase() &&
+          !this.isSafeValue(folded) &&
+          foldedValues.includes(folded)
+        ) {
+          throw new BoundaryViolationError(
+            `Raw input substring detected in output: boundary violation`
+          );
+        }
+      }
 
-    for (const input of rawInputs) {
       // Extract significant substrings (names, identifiers)
       const significantPatterns = [
-        /\b[A-Z][a-z]{2,}(?:\s+[A-Z][a-z]+)*\b/g, // Names
+        /(?<![\p{L}\p{M}\p{N}_])\p{Lu}[\p{Ll}\p{M}]{2,}(?:\s+\p{Lu}[\p{Ll}\p{M}]+)*(?![\p{L}\p{M}\p{N}_])/gu, // Names
         /\b\d{3}-\d{2}-\d{4}\b/g, // SSN
         /\b[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}\b/g, // Email
         /\b\d{3}[-.\s]?\d{3}[-.\s]?\d{4}\b/g, // Phone
@@ -149,6 +317,57 @@ export class BoundaryValidator {
     }
   }
 
+  /**
+   * Join every string value in the masked output (keys excluded).
+   */
+  private collectStringValues(masked: MaskedRepresentation): string {
+    const values: string[] = [];
+    for (const entity of masked.entities) {
+      values.push(entity.syntheticId, entity.role);
+      for (const value of Object.values(entity.attributes)) {
+        if (typeof value === "string") values.push(value);
+      }
+    }
+    for (const relation of masked.relations) {
+      values.push(relation.type, relation.from, relation.to);
+    }
+    if (masked.constraints) {
+      values.push(...Object.keys(masked.constraints.roleCounts ?? {}));
+      values.push(String(masked.constraints.policyVersion));
+    }
+    return values.join("\n");
+  }
+
+  /**
+   * Validate that constraints hold only role counts, a degree, and a
+   * known policy version.
+   */
+  private validateConstraints(masked: MaskedRepresentation): void {
+    const constraints = masked.constraints;
+    if (constraints === undefined) {
+      return;
+    }
+    const isCount = (value: unknown) => Number.isInteger(value) && (value as number) >= 0;
+    const versions: readonly string[] = POLICY_VERSIONS;
+
+    for (const key of Object.keys(constraints)) {
+      if (!CONSTRAINT_FIELDS.has(key)) {
+        throw new BoundaryViolationError(`Unexpected field in constraints: ${key}`);
+      }
+    }
+    for (const [role, count] of Object.entries(constraints.roleCounts ?? {})) {
+      if (!ALLOWED_ATTRIBUTE_VALUES.has(role) || !isCount(count)) {
+        throw new BoundaryViolationError(`Invalid role count in constraints: ${role}`);
+      }
+    }
+    if (!isCount(constraints.maxRelationDegree)) {
+      throw new BoundaryViolationError("Invalid maxRelationDegree in constraints");
+    }
+    if (!versions.includes(constraints.policyVersion)) {
+      throw new BoundaryViolationError("Invalid policyVersion in constraints");
+    }
+  }
+
   /**
    * Validate all string values are from allow-list or are synthetic IDs.
    */
@@ -170,7 +389,19 @@ export class BoundaryValidator {
 
       // Validate attribute values
       for (const [key, value] of Object.entries(entity.attributes)) {
-        if (typeof value === "string" && !ALLOWED_ATTRIBUTE_VALUES.has(value)) {
+        if (key === "literal") {
+          if (!isPassthrough(this.policy, String(entity.attributes.type))) {
+            throw new BoundaryViolationError(
+              `Literal attribute without passthrough for ${String(entity.attributes.type)}`
+            );
+          }
+          continue;
+        }
+        if (
+          typeof value === "string" &&
+          !ALLOWED_ATTRIBUTE_VALUES.has(value) &&
+          !SHAPED_ATTRIBUTE_VALUES[key]?.test(value)
+        ) {
           throw new BoundaryViolationError(
             `Invalid attribute value for ${key}: not in allow-list`
           );
@@ -180,7 +411,10 @@ export class BoundaryValidator {
 
     for (const relation of masked.relations) {
       // Validate relation type
-      if (!ALLOWED_ATTRIBUTE_VALUES.has(relation.type)) {
+      if (
+        !ALLOWED_ATTRIBUTE_VALUES.has(relation.type) &&
+        !this.policy.relationVerbs.includes(relation.type)
+      ) {
         throw new BoundaryViolationError(
           `Invalid relation type: ${relation.type}`
         );
@@ -204,14 +438,18 @@ export class BoundaryValidator {
    * Check if a value is a valid synthetic ID.
    */
   private isSyntheticId(value: string): boolean {
-    return /^ENTITY_\d{4}$/.test(value);
+    return /^(?:ENTITY_(?:\d{4}|[0-9a-f]{8})|DOC_\d{4})$/.test(value);
   }
 
   /**
    * Check if a value is safe (in allow-list or synthetic).
    */
   private isSafeValue(value: string): boolean {
-    return ALLOWED_ATTRIBUTE_VALUES.has(value) || this.isSyntheticId(value);
+    return (
+      ALLOWED_ATTRIBUTE_VALUES.has(value) ||
+      this.policy.relationVerbs.includes(value.toLowerCase()) ||
+      this.isSyntheticId(value)
+    );
   }
 }
 
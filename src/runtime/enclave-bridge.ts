@@ -6,22 +6,305 @@ import type {
   EnclaveRequest,
   EnclaveResponse,
   AttestationEvidence,
+  AttestationTokenClaims,
+  CoseVerificationResult,
+  EvidenceEnvelope,
+  EvidencePlatform,
+  ExpectedBinding,
+  ReportCheck,
+  ReportCheckName,
+  ReportVerificationResult,
+  SessionAttestation,
+  SimulatorVerificationKey,
+  TokenizedAttestation,
 } from "../attestation/types.ts";
+import {
+  ATTESTATION_MODES,
+  EVIDENCE_PACKAGINGS,
+  REPORT_CHECKS,
+  SIMULATOR_KEY_LABEL,
+} from "../attestation/types.ts";
+import { verifyAttestationReport } from "../attestation/verifier.ts";
+import { createVerificationBundle, verifyVerificationBundle } from "../attestation/bundle.ts";
+import type { BundleVerificationOptions } from "../attestation/bundle.ts";
+import { CRL_STATUSES } from "../attestation/crl.ts";
+import type { CrlStatus } from "../attestation/crl.ts";
 import type { TransformedContext } from "../core/config.ts";
-import { ConfigurationError } from "../core/errors.ts";
-import { canonicalize, hash as hashContext } from "../core/canonical.ts";
-import { createHash } from "crypto";
+import {
+  AttestationError,
+  ConfigurationError,
+  MeasurementMismatchError,
+  SecurityInvariantError,
+  TokenError,
+} from "../core/errors.ts";
+import {
+  HASH_SCOPES,
+  canonicalJson,
+  canonicalize,
+  computeContextHash,
+  getCanonicalContext,
+  isHexDigest,
+} from "../core/canonical.ts";
+import type { HashAlgorithm } from "../core/canonical.ts";
+import { HASH_ENCODINGS, decodeDigest, hashOutput } from "../core/serialization.ts";
+import { generateTestVectors } from "../core/vectors.ts";
+import type { TestVector } from "../core/vectors.ts";
+import { validateTransformedContext } from "../transform/masking.ts";
+import type { MaskingPolicy } from "../core/policy.ts";
+import { inputSizeLimit } from "../core/policy.ts";
+import {
+  computeInputHash,
+  generateNonce,
+  validateNonce,
+} from "./session.ts";
+import {
+  ENCLAVE_BACKENDS,
+  bindingReportData,
+  evidenceReportData,
+  simulatorBackendFor,
+} from "./attestation-backend.ts";
+import type {
+  AttestationBackend,
+  BindingInput,
+  GeneratedEvidence,
+  EnclaveBackend,
+  EnclavePlatform,
+} from "./attestation-backend.ts";
+import {
+  derToPem,
+  fetchVcek,
+  isCertificate,
+  parseCertTable,
+} from "../attestation/certs.ts";
+import type { VcekOptions } from "../attestation/certs.ts";
+import {
+  createEvidenceEnvelope,
+  evidenceKind,
+  openEvidence,
+  resolveEvidence,
+} from "../attestation/evidence.ts";
+import {
+  createCoseEvidence,
+  decodeCoseEvidence,
+  verifyCoseEvidence,
+} from "../attestation/cose.ts";
+import { createEatToken, decodeEatToken, eatClaimsFor } from "../attestation/eat.ts";
+import {
+  TOKEN_ISSUER,
+  decodeAttestationToken,
+  loadTokenKey,
+  mintAttestationToken,
+  verificationTime,
+} from "../attestation/token.ts";
+import { loadMeasurementRegistry, loadRegistryKey } from "../attestation/registry.ts";
+import { DEFAULT_ATTESTATION_WINDOW_MS, SessionAttestationCache } from "./session-attestation.ts";
+import { isSimulatorReport } from "../attestation/parser.ts";
+import {
+  REPORT_BYTES,
+  REPORT_VERSIONS,
+  extractPlatformEvidence,
+  parseReport,
+  reportFromJson,
+} from "../attestation/report.ts";
+import type { AttestationReport, ReportVersion } from "../attestation/report.ts";
+import { isTdxQuote } from "../attestation/tdx.ts";
+import {
+  createHash,
+  createPrivateKey,
+  createPublicKey,
+  generateKeyPairSync,
+  timingSafeEqual,
+  X509Certificate,
+} from "crypto";
+import type { KeyObject } from "crypto";
+import { readFileSync } from "fs";
 import { createRequire } from "module";
 const require = createRequire(import.meta.url);
 
-const SIMULATOR_MEASUREMENT =
-  "simulator_measurement_0000000000000000000000000000000000000000000000000000000000000000";
+/**
+ * How the simulator fills the report fields that vary per call on real
+ * hardware (chip_id, report_id). "deterministic" derives them from the
+ * session ID and nonce, so identical requests give byte-identical
+ * reports; "realistic" uses OS randomness.
+ */
+export const SIMULATOR_REPORT_MODES = ["deterministic", "realistic"] as const;
+
+export type SimulatorReportMode = (typeof SIMULATOR_REPORT_MODES)[number];
+
+/**
+ * Options for the simulator runner.
+ */
+export interface SimulatorOptions {
+  /**
+   * Report layout the simulator builds (see REPORT_VERSIONS). Default: 2.
+   */
+  reportVersion?: ReportVersion;
+
+  /**
+   * How variable report fields are filled (see SIMULATOR_REPORT_MODES).
+   * Recorded in each response's metadata as simulator_report_mode.
+   * Default: "deterministic".
+   */
+  reportMode?: SimulatorReportMode;
+
+  /**
+   * Path of a PEM (PKCS#8) Ed25519 private key to sign reports with, so
+   * the verification key is stable across runs. Default: the
+   * AXIOM_SIMULATOR_KEY environment variable; without either, a key is
+   * generated per runner.
+   */
+  signingKeyPath?: string;
+
+  /**
+   * Path of a PEM P-256 private key that attestAndTokenize signs its ES256
+   * JWTs with. No default: without it attestAndTokenize fails with
+   * TokenError TOKEN_KEY_MISSING.
+   */
+  tokenKeyPath?: string;
+
+  /**
+   * Backend the simulator imitates (see ENCLAVE_BACKENDS): "tdx" emits
+   * fake TDX quotes instead of SEV-SNP reports. Default: "sev-snp".
+   */
+  backend?: EnclaveBackend;
+
+  /**
+   * Path of a signed measurement registry (see loadMeasurementRegistry).
+   * When set, the runner is unavailable and refuses to attest unless its
+   * own measurement is listed. No default: without it any measurement is
+   * accepted.
+   */
+  measurementRegistry?: string;
+
+  /**
+   * Path of the PEM Ed25519 public key measurementRegistry must be signed
+   * with. Required with measurementRegistry.
+   */
+  measurementRegistryKeyPath?: string;
+
+  /**
+   * Freshness window of attestationMode "session_cached", in milliseconds:
+   * a session report is reused until its window ends. Default: 60000.
+   */
+  attestationWindowMs?: number;
+
+  /**
+   * Evidence source to use instead of the simulator backend that backend
+   * selects, such as a test double. Simulator runner only; not sent to a
+   * native runner.
+   */
+  attestationBackend?: AttestationBackend;
+
+  /**
+   * Clock the session window is taken from, in milliseconds since epoch,
+   * such as a test double. Simulator runner only. Default: Date.now.
+   */
+  clock?: () => number;
+}
+
+function validateSimulatorOptions(options: SimulatorOptions): void {
+  const { reportVersion, reportMode, backend, attestationWindowMs } = options;
+  if (reportVersion !== undefined && !REPORT_VERSIONS.includes(reportVersion)) {
+    throw new ConfigurationError(
+      `Invalid simulator reportVersion: ${reportVersion}. Must be one of ${REPORT_VERSIONS.join(", ")}.`
+    );
+  }
+  if (reportMode !== undefined && !SIMULATOR_REPORT_MODES.includes(reportMode)) {
+    throw new ConfigurationError(
+      `Invalid simulator reportMode: ${reportMode}. Must be one of ${SIMULATOR_REPORT_MODES.join(", ")}.`
+    );
+  }
+  if (backend !== undefined && !ENCLAVE_BACKENDS.includes(backend)) {
+    throw new ConfigurationError(
+      `Invalid backend: ${backend}. Must be one of ${ENCLAVE_BACKENDS.join(", ")}.`
+    );
+  }
+  if (
+    attestationWindowMs !== undefined &&
+    !(Number.isSafeInteger(attestationWindowMs) && attestationWindowMs > 0)
+  ) {
+    throw new ConfigurationError(
+      `Invalid attestationWindowMs: ${attestationWindowMs}. Must be a positive integer.`
+    );
+  }
+}
+
+function publicKeyPem(privateKey: KeyObject): string {
+  return createPublicKey(privateKey).export({ format: "pem", type: "spki" }).toString();
+}
+
+function spkiPem(key: string | Uint8Array | undefined): string | null {
+  return key instanceof Uint8Array
+    ? createPublicKey({ key: Buffer.from(key), format: "der", type: "spki" })
+        .export({ format: "pem", type: "spki" })
+        .toString()
+    : key ?? null;
+}
+
+/**
+ * A verification result from the runner, checked: unknown check names are
+ * rejected and validity is recomputed rather than trusting its summary.
+ */
+function nativeVerificationResult(json: string): ReportVerificationResult {
+  const result = JSON.parse(json) as {
+    valid: boolean;
+    simulator: boolean;
+    checks: Array<{ name: string; passed: boolean; reason?: string | null }>;
+    crl_status?: string | null;
+  };
+  const checks: ReportCheck[] = result.checks.map((check) => {
+    if (!REPORT_CHECKS.includes(check.name as ReportCheckName)) {
+      throw new SecurityInvariantError(`Unknown report check from enclave: ${check.name}`);
+    }
+    return check.passed
+      ? { name: check.name as ReportCheckName, passed: true }
+      : { name: check.name as ReportCheckName, passed: false, reason: check.reason ?? "" };
+  });
+  const crlStatus = result.crl_status ?? undefined;
+  if (crlStatus !== undefined && !CRL_STATUSES.includes(crlStatus as CrlStatus)) {
+    throw new SecurityInvariantError(`Unknown CRL status from enclave: ${crlStatus}`);
+  }
+  return {
+    valid: checks.length > 0 && checks.every((check) => check.passed),
+    simulator: result.simulator === true,
+    checks,
+    ...(crlStatus !== undefined ? { crlStatus: crlStatus as CrlStatus } : {}),
+  };
+}
+
+function loadSimulatorKey(path: string | undefined): KeyObject {
+  if (path === undefined || path === "") {
+    return generateKeyPairSync("ed25519").privateKey;
+  }
+  let key: KeyObject;
+  try {
+    key = createPrivateKey(readFileSync(path, "utf8"));
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    throw new ConfigurationError(`Cannot load simulator signing key from ${path}: ${reason}`);
+  }
+  if (key.asymmetricKeyType !== "ed25519") {
+    throw new ConfigurationError(`Simulator signing key at ${path} is not an Ed25519 key`);
+  }
+  return key;
+}
 
 type NativeRunnerModule = {
   initialize?: () => string;
+  initialize_with_config?: (configJson: string) => string;
   transform: (requestJson: string) => Promise<string> | string;
   get_measurement?: () => string;
   check_availability?: () => boolean;
+  get_canonical_context?: (transformedContextJson: string) => string;
+  compute_context_hash?: (transformedContextJson: string, hashAlgorithm: string) => string;
+  get_hash_test_vectors?: () => string;
+  parse_report?: (report: Buffer) => string;
+  verify_attestation_report?: (reportBase64: string, expectedJson: string) => string;
+  verify_bundle?: (bundle: string, expectedOutputHash: string, optionsJson: string) => string;
+  get_simulator_verification_key?: () => string;
+  generate_nonce?: () => string;
+  verify_cose_evidence?: (coseBase64: string, simulatorKeyPem: string | null) => string;
+  attest_and_tokenize?: (requestJson: string) => Promise<string> | string;
 };
 
 /**
@@ -47,7 +330,230 @@ export interface IEnclaveRunner {
   /**
    * Get platform information.
    */
-  getPlatform(): "sev-snp" | "sev-snp-simulator";
+  getPlatform(): EnclavePlatform;
+
+  /**
+   * Canonical form of a transformed context (JSON), as the runner
+   * canonicalizes it before hashing.
+   */
+  getCanonicalContext(transformedContextJson: string): string;
+
+  /**
+   * Hex digest of a transformed context (JSON) over its canonical form.
+   */
+  computeContextHash(transformedContextJson: string, hashAlgorithm?: HashAlgorithm): string;
+
+  /**
+   * Canonicalization test vectors, as generated by the runner's hasher.
+   */
+  getHashTestVectors(): TestVector[];
+
+  /**
+   * Typed view of a raw attestation report, as the runner parses it.
+   */
+  parseReport(report: Uint8Array): AttestationReport;
+
+  /**
+   * Verify a raw attestation report, as the runner's verifier does.
+   */
+  verifyAttestationReport(report: Uint8Array, expected: ExpectedBinding): ReportVerificationResult;
+
+  /**
+   * Verify a verification bundle offline, as the runner's verifier does.
+   */
+  verifyBundle(
+    bundle: string,
+    expectedOutputHash: string,
+    options: BundleVerificationOptions
+  ): ReportVerificationResult;
+
+  /**
+   * Public key simulator reports are signed with.
+   */
+  getSimulatorVerificationKey(): SimulatorVerificationKey;
+
+  /**
+   * Reconfigure simulator report generation. Options left out keep their
+   * current value.
+   */
+  initializeWithConfig(options: SimulatorOptions): void;
+
+  /**
+   * 32 bytes of OS randomness, hex-encoded, for EnclaveRequest.nonce.
+   */
+  generateNonce(): string;
+
+  /**
+   * Verify COSE evidence (see verifyCoseEvidence).
+   */
+  verifyCoseEvidence(cose: Uint8Array, simulatorKey?: string | Uint8Array): CoseVerificationResult;
+
+  /**
+   * Transform, verify the report locally, and mint a JWT for it.
+   */
+  attestAndTokenize(request: EnclaveRequest): Promise<{ response: EnclaveResponse; token: string }>;
+}
+
+// A pinned measurement: 48 bytes of hex, or the simulator marker
+const MEASUREMENT_PATTERN = /^(?:[0-9a-f]{96}|simulator_measurement_[0-9a-f]{64})$/i;
+
+/**
+ * Compare a pinned measurement with the enclave's, ignoring hex case, in
+ * constant time.
+ * @throws MeasurementMismatchError if they differ
+ */
+function checkMeasurement(expected: string, actual: string): void {
+  const pinned = Buffer.from(expected.toLowerCase(), "utf8");
+  const measured = Buffer.from(actual.toLowerCase(), "utf8");
+  if (pinned.length !== measured.length || !timingSafeEqual(pinned, measured)) {
+    throw new MeasurementMismatchError(expected.toLowerCase(), actual.toLowerCase());
+  }
+}
+
+/**
+ * Check that evidence carries report_data for the binding (see
+ * bindingReportData), zero-padded to the report's field.
+ * @param source - Who produced the evidence, for error messages
+ * @throws AttestationError if the evidence does not parse
+ * @throws SecurityInvariantError if report_data does not match
+ */
+function checkEvidenceBinding(report: Uint8Array, binding: BindingInput, source: string): void {
+  let reportData: Uint8Array;
+  try {
+    reportData = evidenceReportData(report);
+  } catch (error) {
+    const reason = error instanceof Error ? error.message : String(error);
+    throw new AttestationError(`${source} returned unparseable evidence: ${reason}`);
+  }
+  const expectedReportData = Buffer.alloc(reportData.length);
+  expectedReportData.set(bindingReportData(binding));
+  if (!expectedReportData.equals(reportData)) {
+    throw new SecurityInvariantError(`${source} evidence does not carry the request's report_data`);
+  }
+}
+
+/**
+ * Check an enclave request before it reaches a runner: the nonce (see
+ * validateNonce), the chain link, the hash encoding, the binding version,
+ * the pinned measurement, and the attestation mode. EnclaveBridge.execute()
+ * runs it on every request.
+ * @param request - Enclave execution request
+ * @throws InvalidInputError for a rejected nonce
+ * @throws ConfigurationError for an invalid option or combination
+ */
+export function validateRequest(request: EnclaveRequest): void {
+  validateNonce(request.nonce);
+  if (request.previousOutputHash !== undefined) {
+    if (!isHexDigest(request.previousOutputHash)) {
+      throw new ConfigurationError(
+        "Invalid previousOutputHash: must be a 32- or 48-byte hex string"
+      );
+    }
+    if (request.legacyReportData) {
+      throw new ConfigurationError("previousOutputHash cannot be combined with legacyReportData");
+    }
+  }
+  if (request.hashEncoding !== undefined && !HASH_ENCODINGS.includes(request.hashEncoding)) {
+    throw new ConfigurationError(
+      `Invalid hashEncoding: ${request.hashEncoding}. Must be one of ${HASH_ENCODINGS.join(", ")}.`
+    );
+  }
+  if ((request.bindingVersion ?? 1) !== 1 && request.legacyReportData) {
+    throw new ConfigurationError(
+      `bindingVersion ${request.bindingVersion} cannot be combined with legacyReportData`
+    );
+  }
+  if (
+    request.evidenceFormat !== undefined &&
+    !EVIDENCE_PACKAGINGS.includes(request.evidenceFormat)
+  ) {
+    throw new ConfigurationError(
+      `Invalid evidenceFormat: ${request.evidenceFormat}. Must be one of ${EVIDENCE_PACKAGINGS.join(", ")}.`
+    );
+  }
+  if (
+    request.expectedMeasurement !== undefined &&
+    !MEASUREMENT_PATTERN.test(request.expectedMeasurement)
+  ) {
+    throw new ConfigurationError(
+      "Invalid expectedMeasurement: must be a 48-byte hex string or the simulator measurement"
+    );
+  }
+  if (
+    request.attestationMode !== undefined &&
+    !ATTESTATION_MODES.includes(request.attestationMode)
+  ) {
+    throw new ConfigurationError(
+      `Invalid attestationMode: ${request.attestationMode}. Must be one of ${ATTESTATION_MODES.join(", ")}.`
+    );
+  }
+  const packaging = request.evidenceFormat ?? "raw";
+  if (request.attestationMode === "session_cached" && packaging !== "raw") {
+    throw new ConfigurationError(
+      `evidenceFormat ${packaging} cannot be combined with attestationMode session_cached`
+    );
+  }
+}
+
+/**
+ * Serialize a masking policy into the snake_case form the enclave receives.
+ * This object is also what the policy hash covers.
+ */
+export function serializePolicy(policy: MaskingPolicy): Record<string, unknown> {
+  return {
+    preset: policy.preset,
+    industry_preset: policy.industryPreset,
+    version: policy.version,
+    attribute_granularity: policy.attributeGranularity,
+    allow_common_words: policy.allowCommonWords,
+    max_input_size: inputSizeLimit(policy),
+    national_id_locales: policy.nationalIdLocales,
+    locales: policy.locales,
+    allow_card_last4: policy.allowCardLast4,
+    allow_iban_country: policy.allowIbanCountry,
+    allow_exact_dates: policy.allowExactDates,
+    allow_date_year: policy.allowDateYear,
+    allow_exact_values: policy.allowExactValues,
+    detect_serials: policy.detectSerials,
+    mask_numbers_over_digits: policy.maskNumbersOverDigits,
+    allow_hex_digests: policy.allowHexDigests,
+    allow_honorifics: policy.allowHonorifics,
+    organization_suffixes: policy.organizationSuffixes,
+    detect_places: policy.detectPlaces,
+    detect_usernames: policy.detectUsernames,
+    id_patterns: policy.idPatterns,
+    custom_patterns: policy.customPatterns,
+    deny_terms: policy.denyTerms,
+    plate_locales: policy.plateLocales,
+    healthcare: policy.healthcare,
+    geo_precision: policy.geoPrecision,
+    allow_postal_prefix: policy.allowPostalPrefix,
+    min_confidence: policy.minConfidence,
+    k_threshold: policy.kThreshold,
+    relation_window: policy.relationWindow,
+    max_entities: policy.maxEntities,
+    max_relations: policy.maxRelations,
+    truncate_relations: policy.truncateRelations,
+    relation_verbs: policy.relationVerbs,
+    cross_document_relations: policy.crossDocumentRelations,
+    allowed_relation_types: policy.allowedRelationTypes,
+    emit_document_nodes: policy.emitDocumentNodes,
+    allow_self_relations: policy.allowSelfRelations,
+    entity_controls: policy.entityControls,
+    placeholder_style: policy.placeholderStyle,
+    // idSalt is deliberately omitted: it travels only as the request's
+    // id_salt and must never reach the policy hash
+  };
+}
+
+/**
+ * Compute the policy hash bound into report_data: SHA-256 over the
+ * canonical JSON (sorted keys, no whitespace) of the serialized policy.
+ * @param policy - The effective (resolved) masking policy
+ * @returns Hex-encoded SHA-256 hash
+ */
+export function hashPolicy(policy: MaskingPolicy): string {
+  return createHash("sha256").update(canonicalJson(serializePolicy(policy)), "utf8").digest("hex");
 }
 
 /**
@@ -56,6 +562,7 @@ export interface IEnclaveRunner {
  */
 class NativeEnclaveRunner implements IEnclaveRunner {
   private nativeModule: NativeRunnerModule | null = null;
+  private selectedBackend: EnclaveBackend | undefined;
 
   constructor() {
     this.loadNativeModule();
@@ -70,10 +577,28 @@ class NativeEnclaveRunner implements IEnclaveRunner {
     }
   }
 
+  /**
+   * Backend the runner selected, as its initialize() reports it. Runners
+   * that predate backend selection export no initialize() and attest with
+   * SEV-SNP.
+   */
+  private backend(): EnclaveBackend {
+    if (this.selectedBackend === undefined) {
+      const backend = this.nativeModule?.initialize?.() ?? "sev-snp";
+      if (!ENCLAVE_BACKENDS.includes(backend as EnclaveBackend)) {
+        throw new SecurityInvariantError(`Unknown enclave backend: ${backend}`);
+      }
+      this.selectedBackend = backend as EnclaveBackend;
+    }
+    return this.selectedBackend;
+  }
+
   async isAvailable(): Promise<boolean> {
     if (!this.nativeModule) return false;
 
     try {
+      // A backend the SDK does not know is unavailable, not a fallback
+      this.backend();
       if (this.nativeModule.check_availability) {
         return this.nativeModule.check_availability();
       }
@@ -90,29 +615,282 @@ class NativeEnclaveRunner implements IEnclaveRunner {
       );
     }
 
+    this.checkPinnedMeasurement(request);
     const requestJson = this.serializeRequest(request);
     const responseJson = await this.nativeModule.transform(requestJson);
     return this.deserializeResponse(responseJson, request);
   }
 
-  getPlatform(): "sev-snp" {
-    return "sev-snp";
+  /**
+   * Compare a pinned measurement with get_measurement before the request
+   * is sent. The runner checks it again before reading raw_context.
+   */
+  private checkPinnedMeasurement(request: EnclaveRequest): void {
+    if (request.expectedMeasurement === undefined) {
+      return;
+    }
+    if (!this.nativeModule?.get_measurement) {
+      throw new ConfigurationError(
+        "Native enclave runner does not export get_measurement, needed for expectedMeasurement"
+      );
+    }
+    checkMeasurement(request.expectedMeasurement, this.nativeModule.get_measurement());
+  }
+
+  getPlatform(): EnclaveBackend {
+    return this.backend();
+  }
+
+  getCanonicalContext(transformedContextJson: string): string {
+    if (!this.nativeModule?.get_canonical_context) {
+      throw new ConfigurationError("Native enclave runner does not export get_canonical_context");
+    }
+    return this.nativeModule.get_canonical_context(transformedContextJson);
+  }
+
+  computeContextHash(
+    transformedContextJson: string,
+    hashAlgorithm: HashAlgorithm = "sha256"
+  ): string {
+    if (!this.nativeModule?.compute_context_hash) {
+      throw new ConfigurationError("Native enclave runner does not export compute_context_hash");
+    }
+    return this.nativeModule.compute_context_hash(transformedContextJson, hashAlgorithm);
+  }
+
+  getHashTestVectors(): TestVector[] {
+    if (!this.nativeModule?.get_hash_test_vectors) {
+      throw new ConfigurationError("Native enclave runner does not export get_hash_test_vectors");
+    }
+    return JSON.parse(this.nativeModule.get_hash_test_vectors()) as TestVector[];
+  }
+
+  parseReport(report: Uint8Array): AttestationReport {
+    if (!this.nativeModule?.parse_report) {
+      throw new ConfigurationError("Native enclave runner does not export parse_report");
+    }
+    return reportFromJson(JSON.parse(this.nativeModule.parse_report(Buffer.from(report))));
+  }
+
+  verifyAttestationReport(
+    report: Uint8Array,
+    expected: ExpectedBinding
+  ): ReportVerificationResult {
+    if (!this.nativeModule?.verify_attestation_report) {
+      throw new ConfigurationError(
+        "Native enclave runner does not export verify_attestation_report"
+      );
+    }
+    const pem = (certificate: string | Uint8Array | undefined) =>
+      certificate instanceof Uint8Array ? derToPem(certificate) : certificate ?? null;
+    const expectedJson = JSON.stringify({
+      session_id: expected.sessionId,
+      config_hash: expected.configHash,
+      policy_hash: expected.policyHash ?? null,
+      output_hash: expected.outputHash,
+      timestamp: expected.timestamp,
+      input_hash: expected.inputHash ?? null,
+      chained_from: expected.chainedFrom ?? null,
+      hash_algorithm: expected.hashAlgorithm ?? "sha256",
+      binding_version: expected.bindingVersion ?? 1,
+      accept_short_report_data: expected.acceptShortReportData === true,
+      measurement: expected.measurement ?? null,
+      allow_simulator: expected.allowSimulator === true,
+      certificates: expected.certificates
+        ? {
+            vcek: pem(expected.certificates.vcek),
+            ask: pem(expected.certificates.ask),
+            ark: pem(expected.certificates.ark),
+          }
+        : null,
+      trusted_ark: pem(expected.trustedArk),
+      crl:
+        expected.crl instanceof Uint8Array
+          ? derToPem(expected.crl, "X509 CRL")
+          : expected.crl ?? null,
+      enforce_crl: expected.enforceCrl === true,
+      simulator_key: spkiPem(expected.simulatorKey),
+      minimum_tcb: expected.minimumTcb
+        ? {
+            boot_loader: expected.minimumTcb.bootLoader ?? null,
+            tee: expected.minimumTcb.tee ?? null,
+            snp: expected.minimumTcb.snp ?? null,
+            microcode: expected.minimumTcb.microcode ?? null,
+          }
+        : null,
+      session_attestation: expected.sessionAttestation
+        ? {
+            public_key: Buffer.from(expected.sessionAttestation.publicKey).toString("hex"),
+            window_start: expected.sessionAttestation.windowStart,
+            expires_at: expected.sessionAttestation.expiresAt,
+            signature: Buffer.from(expected.sessionAttestation.signature).toString("hex"),
+          }
+        : null,
+    });
+    return nativeVerificationResult(
+      this.nativeModule.verify_attestation_report(
+        Buffer.from(report).toString("base64"),
+        expectedJson
+      )
+    );
+  }
+
+  verifyBundle(
+    bundle: string,
+    expectedOutputHash: string,
+    options: BundleVerificationOptions
+  ): ReportVerificationResult {
+    if (!this.nativeModule?.verify_bundle) {
+      throw new ConfigurationError("Native enclave runner does not export verify_bundle");
+    }
+    const optionsJson = JSON.stringify({
+      trusted_ark:
+        options.trustedArk instanceof Uint8Array
+          ? derToPem(options.trustedArk)
+          : options.trustedArk ?? null,
+      measurement: options.measurement ?? null,
+      allow_simulator: options.allowSimulator === true,
+      simulator_key: spkiPem(options.simulatorKey),
+    });
+    return nativeVerificationResult(
+      this.nativeModule.verify_bundle(bundle, expectedOutputHash, optionsJson)
+    );
+  }
+
+  getSimulatorVerificationKey(): SimulatorVerificationKey {
+    if (!this.nativeModule?.get_simulator_verification_key) {
+      throw new ConfigurationError(
+        "Native enclave runner does not export get_simulator_verification_key"
+      );
+    }
+    const key = JSON.parse(this.nativeModule.get_simulator_verification_key()) as {
+      algorithm: string;
+      public_key: string;
+      production: boolean;
+      label?: string;
+    };
+    if (key.production !== false) {
+      throw new SecurityInvariantError("Simulator verification key is not marked non-production");
+    }
+    const publicKey = createPublicKey(key.public_key);
+    if (key.algorithm !== "ed25519" || publicKey.asymmetricKeyType !== "ed25519") {
+      throw new SecurityInvariantError("Simulator verification key is not an Ed25519 key");
+    }
+    return {
+      algorithm: "ed25519",
+      publicKey: key.public_key,
+      production: false,
+      label: key.label ?? SIMULATOR_KEY_LABEL,
+    };
+  }
+
+  initializeWithConfig(options: SimulatorOptions): void {
+    if (!this.nativeModule?.initialize_with_config) {
+      throw new ConfigurationError("Native enclave runner does not export initialize_with_config");
+    }
+    this.nativeModule.initialize_with_config(
+      JSON.stringify({
+        report_mode: options.reportMode ?? null,
+        report_version: options.reportVersion ?? null,
+        signing_key_path: options.signingKeyPath ?? null,
+        token_key_path: options.tokenKeyPath ?? null,
+        backend: options.backend ?? null,
+        measurement_registry: options.measurementRegistry ?? null,
+        measurement_registry_key_path: options.measurementRegistryKeyPath ?? null,
+        attestation_window_ms: options.attestationWindowMs ?? null,
+      })
+    );
+    if (options.backend !== undefined) {
+      this.selectedBackend = undefined;
+    }
+  }
+
+  generateNonce(): string {
+    if (!this.nativeModule?.generate_nonce) {
+      throw new ConfigurationError("Native enclave runner does not export generate_nonce");
+    }
+    const nonce = this.nativeModule.generate_nonce();
+    if (!/^[0-9a-f]{64}$/.test(nonce)) {
+      throw new SecurityInvariantError("Native nonce is not 32 hex-encoded bytes");
+    }
+    try {
+      validateNonce(Buffer.from(nonce, "hex"));
+    } catch (error) {
+      const reason = error instanceof Error ? error.message : String(error);
+      throw new SecurityInvariantError(`Native nonce rejected: ${reason}`);
+    }
+    return nonce;
+  }
+
+  verifyCoseEvidence(cose: Uint8Array, simulatorKey?: string | Uint8Array): CoseVerificationResult {
+    if (!this.nativeModule?.verify_cose_evidence) {
+      throw new ConfigurationError(
+        "Native enclave runner does not export verify_cose_evidence (built without the cose feature?)"
+      );
+    }
+    const result = JSON.parse(
+      this.nativeModule.verify_cose_evidence(
+        Buffer.from(cose).toString("base64"),
+        spkiPem(simulatorKey)
+      )
+    ) as { valid: boolean; signed: boolean; reason?: string | null };
+    if (result.valid !== true) {
+      return { valid: false, signed: result.signed === true, reason: result.reason ?? "" };
+    }
+    // The payload is read by the SDK's own decoder, which must agree
+    let decoded: ReturnType<typeof decodeCoseEvidence>;
+    try {
+      decoded = decodeCoseEvidence(cose);
+    } catch (error) {
+      const reason = error instanceof Error ? error.message : String(error);
+      throw new SecurityInvariantError(`Enclave accepted COSE evidence the SDK rejects: ${reason}`);
+    }
+    if (decoded.signed !== (result.signed === true)) {
+      throw new SecurityInvariantError("Enclave and SDK disagree on whether COSE evidence is signed");
+    }
+    return { valid: true, signed: decoded.signed, payload: decoded.payload };
+  }
+
+  async attestAndTokenize(
+    request: EnclaveRequest
+  ): Promise<{ response: EnclaveResponse; token: string }> {
+    if (!this.nativeModule?.attest_and_tokenize) {
+      throw new ConfigurationError("Native enclave runner does not export attest_and_tokenize");
+    }
+    this.checkPinnedMeasurement(request);
+    const result = JSON.parse(
+      await this.nativeModule.attest_and_tokenize(this.serializeRequest(request))
+    ) as { response: unknown; token: string };
+    if (typeof result.token !== "string") {
+      throw new SecurityInvariantError("Native attest_and_tokenize returned no token");
+    }
+    return {
+      response: this.deserializeResponse(JSON.stringify(result.response), request),
+      token: result.token,
+    };
   }
 
   private serializeRequest(request: EnclaveRequest): string {
-    const rawContext = new TextDecoder().decode(request.rawContext);
+    const decoder = new TextDecoder();
     const payload = {
-      raw_context: [rawContext],
+      raw_context: request.rawContext.map((document) => decoder.decode(document)),
       task_hint: request.taskHint ?? null,
-      policy: {
-        version: request.policy.version,
-        allow_common_words: request.policy.allowCommonWords,
-        max_input_size: request.policy.maxInputSize,
-      },
+      policy: serializePolicy(request.policy),
       session_id: Buffer.from(request.sessionId).toString("hex"),
       config_hash: request.configHash,
       nonce: Buffer.from(request.nonce).toString("hex"),
       timestamp: request.timestamp,
+      legacy_report_data: request.legacyReportData === true,
+      hash_algorithm: request.hashAlgorithm ?? "sha256",
+      output_encoding: request.outputEncoding ?? "json",
+      binding_version: request.bindingVersion ?? 1,
+      hash_encoding: request.hashEncoding ?? "hex",
+      previous_output_hash: request.previousOutputHash?.toLowerCase() ?? null,
+      id_salt: request.idSalt ? Buffer.from(request.idSalt).toString("hex") : null,
+      require_vcek: request.requireVcek === true,
+      evidence_format: request.evidenceFormat ?? "raw",
+      expected_measurement: request.expectedMeasurement?.toLowerCase() ?? null,
+      attestation_mode: request.attestationMode ?? "per_request",
     };
 
     return JSON.stringify(payload);
@@ -133,24 +911,63 @@ class NativeEnclaveRunner implements IEnclaveRunner {
           relation_type: string;
           from: string;
           to: string;
+          weight?: number;
         }>;
+        constraints?: {
+          role_counts: Record<string, number>;
+          max_relation_degree: number;
+          policy_version: string;
+        };
+        metadata?: Record<string, unknown>;
       };
       output_hash: string;
+      hash_algorithm?: string;
+      output_encoding?: string;
+      hash_scope?: string;
+      binding_version?: number;
+      hash_encoding?: string;
+      input_hash?: string;
+      chained_from?: string;
+      policy_hash?: string;
       attestation_report: number[];
+      vcek_pem?: string;
+      cert_table?: number[];
+      evidence?: EvidenceEnvelope;
+      cose_evidence?: number[];
+      eat_token?: number[];
+      session_attestation?: {
+        public_key: string;
+        window_start: number;
+        expires_at: number;
+        signature: string;
+      };
       redaction_stats: {
         entity_count: number;
+        document_count?: number;
         relation_count: number;
         identifiers_replaced: number;
+        pseudonym_count?: number;
+        suppressed_low_confidence?: number;
+        attributes_suppressed?: number;
+        entities_truncated?: boolean;
+        relations_truncated?: boolean;
+        relations_filtered?: number;
+        credentials_found?: number;
       };
       measurement: string;
       signature?: number[];
+      warnings?: string[];
     };
 
     const transformedContext: TransformedContext = {
       entities: response.transformed_context.entities.map((entity) => {
-        const attributes: Record<string, string | number> = {};
+        const attributes: Record<string, string | number | boolean> = {};
         for (const [key, value] of Object.entries(entity.attributes ?? {})) {
-          if (typeof value === "string" || typeof value === "number") {
+          if (
+            typeof value === "string" ||
+            typeof value === "number" ||
+            typeof value === "boolean"
+          ) {
             attributes[key] = value;
           } else {
             attributes[key] = JSON.stringify(value);
@@ -166,27 +983,219 @@ class NativeEnclaveRunner implements IEnclaveRunner {
         type: relation.relation_type,
         from: relation.from,
         to: relation.to,
+        ...(typeof relation.weight === "number" ? { weight: relation.weight } : {}),
       })),
       task: request.taskHint ?? "transform",
       model: undefined,
+      ...(response.transformed_context.constraints
+        ? {
+            constraints: {
+              roleCounts: response.transformed_context.constraints.role_counts,
+              maxRelationDegree: response.transformed_context.constraints.max_relation_degree,
+              policyVersion: response.transformed_context.constraints.policy_version,
+            },
+          }
+        : {}),
     };
 
-    const canonicalJson = canonicalize(transformedContext);
-    const transformedBytes = new TextEncoder().encode(canonicalJson);
+    // The enclave's output gets the same integrity check as a local transform
+    validateTransformedContext(
+      {
+        entities: transformedContext.entities,
+        relations: transformedContext.relations,
+      },
+      request.policy
+    );
+
+    // Digests arrive rendered as requested; only their bytes are used from here on
+    const hashEncoding = request.hashEncoding ?? "hex";
+    const responseHashEncoding = response.hash_encoding ?? "hex";
+    if (responseHashEncoding !== hashEncoding) {
+      throw new SecurityInvariantError(
+        `Enclave hash encoding mismatch: requested ${hashEncoding}, enclave used ${responseHashEncoding}`
+      );
+    }
+    const outputHash = decodeDigest(response.output_hash, hashEncoding);
+    const responsePolicyHash =
+      response.policy_hash !== undefined
+        ? decodeDigest(response.policy_hash, hashEncoding).toString("hex")
+        : undefined;
+    const responseInputHash =
+      response.input_hash !== undefined
+        ? decodeDigest(response.input_hash, hashEncoding).toString("hex")
+        : undefined;
+
+    // The enclave hashes the policy it applied; it must be the one we sent
+    const policyHash = hashPolicy(request.policy);
+    if (responsePolicyHash !== undefined && responsePolicyHash !== policyHash) {
+      throw new SecurityInvariantError(
+        `Enclave policy hash mismatch: sent ${policyHash}, enclave applied ${responsePolicyHash}`
+      );
+    }
+
+    // Runners that predate algorithm tagging only produce SHA-256
+    const hashAlgorithm = request.hashAlgorithm ?? "sha256";
+    const responseAlgorithm = response.hash_algorithm ?? "sha256";
+    if (responseAlgorithm !== hashAlgorithm) {
+      throw new SecurityInvariantError(
+        `Enclave hash algorithm mismatch: requested ${hashAlgorithm}, enclave used ${responseAlgorithm}`
+      );
+    }
+    const outputEncoding = request.outputEncoding ?? "json";
+    const responseEncoding = response.output_encoding ?? "json";
+    if (responseEncoding !== outputEncoding) {
+      throw new SecurityInvariantError(
+        `Enclave output encoding mismatch: requested ${outputEncoding}, enclave used ${responseEncoding}`
+      );
+    }
+    // Runners that predate binding versions use the version 1 formula
+    const bindingVersion = request.bindingVersion ?? 1;
+    const responseBindingVersion = response.binding_version ?? 1;
+    if (responseBindingVersion !== bindingVersion) {
+      throw new SecurityInvariantError(
+        `Enclave binding version mismatch: requested ${bindingVersion}, enclave used ${responseBindingVersion}`
+      );
+    }
+    // Runners that predate scope recording hash the v1 scope
+    const hashScope = HASH_SCOPES.find((scope) => scope === (response.hash_scope ?? "context-v1"));
+    if (hashScope === undefined) {
+      throw new SecurityInvariantError(`Unsupported enclave hash scope: ${response.hash_scope}`);
+    }
+
+    // Metadata is outside the hash scope; keep scalar values only
+    let metadata: Record<string, string | number | boolean> | undefined;
+    if (response.transformed_context.metadata) {
+      metadata = {};
+      for (const [key, value] of Object.entries(response.transformed_context.metadata)) {
+        if (typeof value === "string" || typeof value === "number" || typeof value === "boolean") {
+          metadata[key] = value;
+        }
+      }
+    }
+
+    // The input commitment must cover exactly what we sent
+    const inputHash = computeInputHash(request.rawContext, request.sessionId, request.nonce);
+    if (responseInputHash !== undefined && responseInputHash !== inputHash.toString("hex")) {
+      throw new SecurityInvariantError(
+        `Enclave input hash mismatch: expected ${inputHash.toString("hex")}, enclave committed to ${responseInputHash}`
+      );
+    }
+
+    // A chained request must be bound to exactly the hash we sent
+    const chainedFrom = request.previousOutputHash?.toLowerCase();
+    if (response.chained_from !== chainedFrom) {
+      throw new SecurityInvariantError(
+        `Enclave chain mismatch: sent ${chainedFrom ?? "none"}, enclave chained from ${response.chained_from ?? "none"}`
+      );
+    }
+
+    const transformedBytes = new TextEncoder().encode(canonicalize(transformedContext));
+
+    // A runner that fell back to a plain report sends no table; one whose
+    // table holds no known certificate is treated the same way
+    const certificates = response.cert_table
+      ? parseCertTable(Uint8Array.from(response.cert_table))
+      : undefined;
+    const hasCertificates =
+      certificates !== undefined && Object.keys(certificates).length > 0;
+
+    // A pinned measurement was checked before the transform; the response
+    // must carry the same one
+    const pinned = request.expectedMeasurement?.toLowerCase();
+    if (pinned !== undefined && response.measurement.toLowerCase() !== pinned) {
+      throw new SecurityInvariantError(
+        `Enclave measurement changed: pinned ${pinned}, response carries ${response.measurement}`
+      );
+    }
+
+    // The runner must attest with the backend it selected
+    const attestationReport = Uint8Array.from(response.attestation_report);
+    const backend = this.backend();
+    if (isTdxQuote(attestationReport) !== (backend === "tdx")) {
+      const returned = backend === "tdx" ? "no TDX quote" : "a TDX quote";
+      throw new SecurityInvariantError(
+        `Enclave backend is ${backend}, but it returned ${returned}`
+      );
+    }
+
+    // The report must bind this response to this request, as the simulator
+    // requires of its backend; in session_cached mode it binds the session key
+    const session = response.session_attestation;
+    checkEvidenceBinding(
+      attestationReport,
+      {
+        sessionId: request.sessionId,
+        nonce: request.nonce,
+        configHash: Buffer.from(request.configHash, "hex"),
+        policyHash: Buffer.from(policyHash, "hex"),
+        outputHash,
+        timestamp: request.timestamp,
+        legacyReportData: request.legacyReportData === true,
+        hashAlgorithm,
+        ...(responseInputHash !== undefined ? { inputHash } : {}),
+        ...(chainedFrom !== undefined ? { chainedFrom } : {}),
+        bindingVersion,
+        ...(session !== undefined
+          ? {
+              sessionKey: {
+                publicKey: Buffer.from(session.public_key, "hex"),
+                windowStart: session.window_start,
+                expiresAt: session.expires_at,
+              },
+            }
+          : {}),
+      },
+      "Enclave"
+    );
 
     return {
       transformedContext: transformedBytes,
-      outputHash: Buffer.from(response.output_hash, "hex"),
-      attestationReport: Uint8Array.from(response.attestation_report),
+      outputHash,
+      hashAlgorithm,
+      outputEncoding,
+      hashScope,
+      bindingVersion,
+      ...(metadata ? { metadata } : {}),
+      ...(responseInputHash !== undefined ? { inputHash } : {}),
+      ...(chainedFrom !== undefined ? { chainedFrom } : {}),
+      policyHash: Buffer.from(policyHash, "hex"),
+      attestationReport,
+      ...(typeof response.vcek_pem === "string" ? { vcekPem: response.vcek_pem } : {}),
+      ...(hasCertificates ? { certificates } : {}),
+      ...(response.evidence !== undefined ? { evidence: response.evidence } : {}),
+      ...(response.cose_evidence !== undefined
+        ? { coseEvidence: Uint8Array.from(response.cose_evidence) }
+        : {}),
+      ...(response.eat_token !== undefined ? { eatToken: Uint8Array.from(response.eat_token) } : {}),
+      ...(response.session_attestation !== undefined
+        ? {
+            sessionAttestation: {
+              publicKey: Buffer.from(response.session_attestation.public_key, "hex"),
+              windowStart: response.session_attestation.window_start,
+              expiresAt: response.session_attestation.expires_at,
+              signature: Buffer.from(response.session_attestation.signature, "hex"),
+            },
+          }
+        : {}),
       redactionStats: {
         entityCount: response.redaction_stats.entity_count,
+        documentCount: response.redaction_stats.document_count ?? 0,
         relationCount: response.redaction_stats.relation_count,
         identifiersReplaced: response.redaction_stats.identifiers_replaced,
+        pseudonymCount:
+          response.redaction_stats.pseudonym_count ?? response.redaction_stats.entity_count,
+        suppressedLowConfidence: response.redaction_stats.suppressed_low_confidence ?? 0,
+        attributesSuppressed: response.redaction_stats.attributes_suppressed ?? 0,
+        entitiesTruncated: response.redaction_stats.entities_truncated ?? false,
+        relationsTruncated: response.redaction_stats.relations_truncated ?? false,
+        relationsFiltered: response.redaction_stats.relations_filtered ?? 0,
+        credentialsFound: response.redaction_stats.credentials_found ?? 0,
       },
       measurement: response.measurement,
       signature: response.signature
         ? Uint8Array.from(response.signature)
         : undefined,
+      ...(response.warnings?.length ? { warnings: [...response.warnings] } : {}),
     };
   }
 }
@@ -196,13 +1205,170 @@ class NativeEnclaveRunner implements IEnclaveRunner {
  * NO SECURITY GUARANTEES - for testing architecture only.
  */
 class SimulatorEnclaveRunner implements IEnclaveRunner {
+  private reportVersion: ReportVersion;
+  private reportMode: SimulatorReportMode;
+  private signingKey: KeyObject;
+  private publicKeyPem: string;
+  private tokenKey: KeyObject | undefined;
+  private backend: EnclaveBackend;
+  private attestationBackend: AttestationBackend;
+  private customBackend: AttestationBackend | undefined;
+  private registryKeyPath: string | undefined;
+  private approvedMeasurements: Set<string> | undefined;
+  private attestationWindowMs: number;
+  private sessionCache: SessionAttestationCache;
+  private clock: () => number;
+
+  constructor(options: SimulatorOptions = {}) {
+    this.reportVersion = options.reportVersion ?? 2;
+    this.reportMode = options.reportMode ?? "deterministic";
+    this.backend = options.backend ?? "sev-snp";
+    this.signingKey = loadSimulatorKey(options.signingKeyPath ?? process.env.AXIOM_SIMULATOR_KEY);
+    this.publicKeyPem = publicKeyPem(this.signingKey);
+    if (options.tokenKeyPath !== undefined) {
+      this.tokenKey = loadTokenKey(options.tokenKeyPath);
+    }
+    const registry = this.loadRegistry(options);
+    this.registryKeyPath = registry.keyPath;
+    this.approvedMeasurements = registry.approved;
+    this.customBackend = options.attestationBackend;
+    this.attestationBackend =
+      this.customBackend ??
+      simulatorBackendFor(this.backend, {
+        reportVersion: this.reportVersion,
+        deterministic: this.reportMode === "deterministic",
+        signingKey: this.signingKey,
+      });
+    this.attestationWindowMs = options.attestationWindowMs ?? DEFAULT_ATTESTATION_WINDOW_MS;
+    this.sessionCache = new SessionAttestationCache(this.attestationWindowMs);
+    this.clock = options.clock ?? Date.now;
+  }
+
+  initializeWithConfig(options: SimulatorOptions): void {
+    // Keys and the registry load before any field changes, so a rejected
+    // one leaves the runner as it was
+    const reportVersion = options.reportVersion ?? this.reportVersion;
+    const reportMode = options.reportMode ?? this.reportMode;
+    const backend = options.backend ?? this.backend;
+    const signingKey =
+      options.signingKeyPath !== undefined
+        ? loadSimulatorKey(options.signingKeyPath)
+        : this.signingKey;
+    const signingKeyPem = publicKeyPem(signingKey);
+    const tokenKey =
+      options.tokenKeyPath !== undefined ? loadTokenKey(options.tokenKeyPath) : this.tokenKey;
+    const registry = this.loadRegistry(options);
+    const customBackend = options.attestationBackend ?? this.customBackend;
+    const attestationBackend =
+      customBackend ??
+      simulatorBackendFor(backend, {
+        reportVersion,
+        deterministic: reportMode === "deterministic",
+        signingKey,
+      });
+
+    this.reportVersion = reportVersion;
+    this.reportMode = reportMode;
+    this.backend = backend;
+    this.signingKey = signingKey;
+    this.publicKeyPem = signingKeyPem;
+    this.tokenKey = tokenKey;
+    this.registryKeyPath = registry.keyPath;
+    this.approvedMeasurements = registry.approved;
+    this.customBackend = customBackend;
+    this.attestationBackend = attestationBackend;
+    // Cached session reports came from the old backend and key
+    this.attestationWindowMs = options.attestationWindowMs ?? this.attestationWindowMs;
+    this.sessionCache = new SessionAttestationCache(this.attestationWindowMs);
+    this.clock = options.clock ?? this.clock;
+  }
+
+  /**
+   * Load and verify the measurement registry, if one is configured. Sets
+   * nothing; without a new registry the current one is kept.
+   * @returns Registry key path and approved measurements to use
+   */
+  private loadRegistry(options: SimulatorOptions): {
+    keyPath: string | undefined;
+    approved: Set<string> | undefined;
+  } {
+    const keyPath = options.measurementRegistryKeyPath ?? this.registryKeyPath;
+    if (options.measurementRegistry === undefined) {
+      return { keyPath, approved: this.approvedMeasurements };
+    }
+    if (keyPath === undefined) {
+      throw new ConfigurationError("measurementRegistry needs measurementRegistryKeyPath");
+    }
+    const approved = loadMeasurementRegistry(
+      options.measurementRegistry,
+      loadRegistryKey(keyPath)
+    );
+    return { keyPath, approved };
+  }
+
+  /**
+   * Whether the backend's measurement is in the registry (always, without
+   * one).
+   */
+  private measurementApproved(): boolean {
+    return (
+      this.approvedMeasurements === undefined ||
+      this.approvedMeasurements.has(this.attestationBackend.measurement())
+    );
+  }
+
   async isAvailable(): Promise<boolean> {
-    return true; // Always available
+    return this.attestationBackend.isAvailable() && this.measurementApproved();
+  }
+
+  generateNonce(): string {
+    return generateNonce();
+  }
+
+  getSimulatorVerificationKey(): SimulatorVerificationKey {
+    return {
+      algorithm: "ed25519",
+      publicKey: this.publicKeyPem,
+      production: false,
+      label: SIMULATOR_KEY_LABEL,
+    };
+  }
+
+  /**
+   * Evidence from the selected backend, which must carry report_data for
+   * the binding (see bindingReportData).
+   */
+  private generateEvidence(binding: BindingInput): GeneratedEvidence {
+    const evidence = this.attestationBackend.generateEvidence(binding);
+    checkEvidenceBinding(evidence.report, binding, "Attestation backend");
+    return evidence;
   }
 
   async execute(request: EnclaveRequest): Promise<EnclaveResponse> {
+    if (!this.measurementApproved()) {
+      const measurement = this.attestationBackend.measurement();
+      throw new ConfigurationError(
+        `Enclave measurement ${measurement} is not in the measurement registry`
+      );
+    }
+
+    // A pinned measurement is checked before rawContext is read
+    if (request.expectedMeasurement !== undefined) {
+      checkMeasurement(request.expectedMeasurement, this.attestationBackend.measurement());
+    }
+
+    // COSE evidence and EAT tokens carry SEV-SNP reports only
+    const packaging = request.evidenceFormat ?? "raw";
+    const platform = this.attestationBackend.platformId();
+    if ((platform === "tdx" || platform === "tdx-simulator") && packaging !== "raw") {
+      throw new ConfigurationError(
+        `evidenceFormat ${packaging} is not supported with the tdx backend`
+      );
+    }
+
     // Simulate transformation by running standard pipeline
-    const rawContext = new TextDecoder().decode(request.rawContext);
+    const decoder = new TextDecoder();
+    const documents = request.rawContext.map((document) => decoder.decode(document));
 
     // Import transform modules
     const { Distiller } = await import("../transform/distiller.ts");
@@ -210,13 +1376,13 @@ class SimulatorEnclaveRunner implements IEnclaveRunner {
     const { Masker } = await import("../transform/masking.ts");
 
     // Execute transformation
-    const distiller = new Distiller();
-    const abstractor = new Abstractor();
-    const masker = new Masker();
+    const distiller = new Distiller(request.policy);
+    const abstractor = new Abstractor(request.policy);
+    const masker = new Masker(request.policy);
 
-    const rawEntities = distiller.distill(rawContext);
-    const semanticRep = abstractor.abstract(rawEntities, rawContext);
-    const masked = masker.mask(semanticRep, [rawContext]);
+    const rawEntities = distiller.distill(documents);
+    const semanticRep = abstractor.abstract(rawEntities, documents);
+    const masked = masker.mask(semanticRep, documents, request.idSalt);
 
     // Build transformed context
     const transformedContext: TransformedContext = {
@@ -224,88 +1390,270 @@ class SimulatorEnclaveRunner implements IEnclaveRunner {
       relations: masked.relations,
       task: request.taskHint ?? "transform",
       model: undefined,
+      constraints: masked.constraints,
     };
 
     // Serialize to canonical JSON
-    const { canonicalize } = await import("../core/canonical.ts");
-    const canonicalJson = canonicalize(transformedContext);
-    const transformedBytes = new TextEncoder().encode(canonicalJson);
+    const transformedBytes = new TextEncoder().encode(canonicalize(transformedContext));
 
-    // Compute output hash
-    const outputHashStr = hashContext(transformedContext);
+    // Compute output and policy hashes
+    const hashAlgorithm = request.hashAlgorithm ?? "sha256";
+    const outputEncoding = request.outputEncoding ?? "json";
+    const bindingVersion = request.bindingVersion ?? 1;
+    const outputHashStr = hashOutput(
+      transformedContext,
+      outputEncoding,
+      hashAlgorithm,
+      "jcs",
+      bindingVersion
+    );
     const outputHash = Buffer.from(outputHashStr, "hex");
+    const policyHash = Buffer.from(hashPolicy(request.policy), "hex");
+    const inputHash = computeInputHash(request.rawContext, request.sessionId, request.nonce);
+    const chainedFrom = request.previousOutputHash?.toLowerCase();
 
-    // Generate fake attestation report (clearly marked)
-    const fakeReport = this.generateFakeAttestationReport(
-      request.sessionId,
-      Buffer.from(request.configHash, "hex"),
+    // What report_data binds for this transform
+    const binding: BindingInput = {
+      sessionId: request.sessionId,
+      nonce: request.nonce,
+      configHash: Buffer.from(request.configHash, "hex"),
+      policyHash,
       outputHash,
-      request.timestamp
-    );
+      timestamp: request.timestamp,
+      legacyReportData: request.legacyReportData === true,
+      hashAlgorithm,
+      inputHash,
+      ...(chainedFrom !== undefined ? { chainedFrom } : {}),
+      bindingVersion,
+    };
+    // In session_cached mode the report binds a session key instead, and
+    // the key signs this binding
+    let evidence: GeneratedEvidence;
+    let sessionAttestation: SessionAttestation | undefined;
+    if (request.attestationMode === "session_cached") {
+      // The window comes from the runner's clock; a binding whose
+      // timestamp falls outside it is refused rather than signed
+      const now = this.clock();
+      const { windowStart, expiresAt } = this.sessionCache.window(now);
+      if (request.timestamp < windowStart || request.timestamp >= expiresAt) {
+        throw new SecurityInvariantError(
+          `Request timestamp ${request.timestamp} is outside the session window ` +
+            `[${windowStart}, ${expiresAt})`
+        );
+      }
+      const session = await this.sessionCache.get(
+        request.sessionId,
+        now,
+        (publicKey, windowStart, expiresAt) =>
+          this.generateEvidence({ ...binding, sessionKey: { publicKey, windowStart, expiresAt } })
+      );
+      evidence = session.evidence;
+      sessionAttestation = {
+        publicKey: session.publicKey,
+        windowStart: session.windowStart,
+        expiresAt: session.expiresAt,
+        signature: session.signBinding(bindingReportData(binding)),
+      };
+    } else {
+      evidence = this.generateEvidence(binding);
+    }
+    const fakeReport = evidence.report;
+
+    const measurement = this.attestationBackend.measurement();
 
-    // Fake measurement (deterministic for testing)
-    const measurement = SIMULATOR_MEASUREMENT;
+    // Signed with the simulator key, like the report itself
+    const coseEvidence =
+      request.evidenceFormat === "cose"
+        ? createCoseEvidence(
+            {
+              platform: "simulator",
+              report: fakeReport,
+              measurement,
+              sessionId: Buffer.from(request.sessionId).toString("hex"),
+              configHash: request.configHash.toLowerCase(),
+              policyHash: policyHash.toString("hex"),
+              outputHash: outputHashStr,
+              timestamp: request.timestamp,
+              inputHash: inputHash.toString("hex"),
+              ...(chainedFrom !== undefined ? { chainedFrom } : {}),
+              hashAlgorithm,
+              bindingVersion,
+            },
+            this.signingKey
+          )
+        : undefined;
+    const eatToken =
+      request.evidenceFormat === "eat"
+        ? createEatToken(
+            eatClaimsFor(fakeReport, {
+              nonce: request.nonce,
+              timestamp: request.timestamp,
+              outputHash: outputHashStr,
+              hashAlgorithm,
+              bindingVersion,
+            }),
+            this.signingKey
+          )
+        : undefined;
+
+    // Document nodes are not extracted values and are counted apart
+    const extracted = masked.entities.filter((entity) => entity.role !== "Document");
+
+    // Repeated values are one entity; every occurrence was replaced,
+    // except passthrough matches, which are kept verbatim
+    const identifiersReplaced = extracted
+      .filter((entity) => entity.attributes.literal === undefined)
+      .reduce(
+        (total, entity) =>
+          total +
+          (typeof entity.attributes.occurrences === "number" ? entity.attributes.occurrences : 1),
+        0
+      );
+    const suppressedLowConfidence = semanticRep.entities.filter(
+      (entity) => !masker.meetsConfidence(entity)
+    ).length;
+    const credentialsFound = extracted.filter(
+      (entity) => entity.role === "Credential"
+    ).length;
 
     return {
       transformedContext: transformedBytes,
       outputHash,
+      hashAlgorithm,
+      outputEncoding,
+      hashScope: "context-v1",
+      bindingVersion,
+      // Lets a verifier tell fixture reports from live-like ones
+      metadata: {
+        simulator_report_mode: this.reportMode,
+        ...(this.reportMode === "realistic" ? { simulator_generated_at: Date.now() } : {}),
+      },
+      inputHash,
+      ...(chainedFrom !== undefined ? { chainedFrom } : {}),
+      policyHash,
       attestationReport: fakeReport,
+      ...(evidence.certificates !== undefined ? { certificates: evidence.certificates } : {}),
+      ...(coseEvidence !== undefined ? { coseEvidence } : {}),
+      ...(eatToken !== undefined ? { eatToken } : {}),
+      ...(sessionAttestation !== undefined ? { sessionAttestation } : {}),
       redactionStats: {
-        entityCount: masked.entities.length,
+        entityCount: extracted.length,
+        documentCount: masked.entities.length - extracted.length,
         relationCount: masked.relations.length,
-        identifiersReplaced: masked.entities.length,
+        identifiersReplaced,
+        pseudonymCount: new Set(extracted.map((entity) => entity.syntheticId)).size,
+        suppressedLowConfidence,
+        attributesSuppressed: masker.attributesSuppressed,
+        entitiesTruncated: distiller.entitiesTruncated,
+        relationsTruncated: semanticRep.relationsTruncated === true,
+        relationsFiltered: semanticRep.relationsFiltered ?? 0,
+        credentialsFound,
       },
       measurement,
       signature: undefined,
     };
   }
 
-  getPlatform(): "sev-snp-simulator" {
-    return "sev-snp-simulator";
+  getPlatform(): EnclavePlatform {
+    return this.attestationBackend.platformId();
   }
 
-  /**
-   * Generate a fake attestation report for simulator mode.
-   * Structure mimics real report but clearly marked as fake.
-   */
-  private generateFakeAttestationReport(
-    sessionId: Uint8Array,
-    configHash: Buffer,
-    outputHash: Buffer,
-    timestamp: number
-  ): Uint8Array {
-    // Create a fake report structure
-    // In real implementation, this would be actual SEV-SNP report format
+  getCanonicalContext(transformedContextJson: string): string {
+    return getCanonicalContext(transformedContextJson);
+  }
 
-    const report = Buffer.alloc(1184); // Real SEV-SNP report is 1184 bytes
+  computeContextHash(
+    transformedContextJson: string,
+    hashAlgorithm: HashAlgorithm = "sha256"
+  ): string {
+    return computeContextHash(transformedContextJson, hashAlgorithm);
+  }
 
-    // Magic marker for simulator (first 4 bytes)
-    report.write("FAKE", 0, 4, "ascii");
+  getHashTestVectors(): TestVector[] {
+    return generateTestVectors();
+  }
 
-    // Version
-    report.writeUInt32LE(1, 4);
+  parseReport(report: Uint8Array): AttestationReport {
+    return parseReport(report);
+  }
 
-    // Embed measurement at standard offset (48..96)
-    const measurementBytes = Buffer.from(measurement, "hex");
-    if (measurementBytes.length === 48) {
-      measurementBytes.copy(report, 48);
-    }
+  verifyAttestationReport(
+    report: Uint8Array,
+    expected: ExpectedBinding
+  ): ReportVerificationResult {
+    return verifyAttestationReport(report, {
+      ...expected,
+      simulatorKey: expected.simulatorKey ?? this.publicKeyPem,
+    });
+  }
+
+  verifyBundle(
+    bundle: string,
+    expectedOutputHash: string,
+    options: BundleVerificationOptions
+  ): ReportVerificationResult {
+    return verifyVerificationBundle(bundle, expectedOutputHash, {
+      ...options,
+      simulatorKey: options.simulatorKey ?? this.publicKeyPem,
+    });
+  }
 
-    // Embed custom data: SHA-256(sessionId || configHash || outputHash || timestamp)
-    const reportData = createHash("sha256");
-    reportData.update(sessionId);
-    reportData.update(configHash);
-    reportData.update(outputHash);
-    const timestampBytes = Buffer.alloc(8);
-    timestampBytes.writeBigUInt64BE(BigInt(timestamp), 0);
-    reportData.update(timestampBytes);
-    const reportDataHash = reportData.digest();
-    reportDataHash.copy(report, 8); // Offset 8 for report_data
+  verifyCoseEvidence(cose: Uint8Array, simulatorKey?: string | Uint8Array): CoseVerificationResult {
+    return verifyCoseEvidence(cose, simulatorKey ?? this.publicKeyPem);
+  }
 
-    // Mark as simulator in multiple places
-    report.write("SIMULATOR", 40, 9, "ascii");
+  async attestAndTokenize(
+    request: EnclaveRequest
+  ): Promise<{ response: EnclaveResponse; token: string }> {
+    const tokenKey = this.tokenKey;
+    if (tokenKey === undefined) {
+      throw new TokenError(
+        "TOKEN_KEY_MISSING",
+        "No token signing key configured; set tokenKeyPath with initializeWithConfig"
+      );
+    }
+    const response = await this.execute(request);
 
-    return new Uint8Array(report);
+    // The token vouches only for a report that verifies here first
+    const hex = (bytes: Uint8Array) => Buffer.from(bytes).toString("hex");
+    const sessionId = hex(request.sessionId);
+    const verification = this.verifyAttestationReport(response.attestationReport, {
+      sessionId,
+      configHash: request.configHash,
+      ...(request.legacyReportData ? {} : { policyHash: hex(response.policyHash) }),
+      outputHash: hex(response.outputHash),
+      timestamp: request.timestamp,
+      ...(response.inputHash !== undefined ? { inputHash: hex(response.inputHash) } : {}),
+      ...(response.chainedFrom !== undefined ? { chainedFrom: response.chainedFrom } : {}),
+      hashAlgorithm: response.hashAlgorithm,
+      bindingVersion: response.bindingVersion,
+      acceptShortReportData: response.bindingVersion !== 3,
+      allowSimulator: true,
+      ...(response.sessionAttestation !== undefined
+        ? { sessionAttestation: response.sessionAttestation, verifiedAt: this.clock() }
+        : {}),
+    });
+    if (!verification.valid) {
+      const failed = verification.checks
+        .filter((check) => !check.passed)
+        .map((check) => `${check.name}: ${check.reason}`);
+      throw new AttestationError(`Local verification failed: ${failed.join("; ")}`);
+    }
+
+    const verifiedAt = verificationTime(request.timestamp);
+    const token = mintAttestationToken(
+      {
+        iss: TOKEN_ISSUER,
+        iat: Math.floor(verifiedAt / 1000),
+        session_id: sessionId,
+        nonce: hex(request.nonce),
+        output_hash: hex(response.outputHash),
+        measurement: response.measurement,
+        platform: "simulator",
+      },
+      tokenKey
+    );
+    return { response, token };
   }
 }
 
@@ -315,13 +1663,30 @@ class SimulatorEnclaveRunner implements IEnclaveRunner {
 export class EnclaveBridge {
   private runner: IEnclaveRunner;
   private mode: "native" | "simulator";
+  private vcekOptions: VcekOptions;
+  private simulatorOptions: SimulatorOptions;
 
-  constructor(preferNative: boolean = true) {
+  /**
+   * @param preferNative - Use the native runner rather than the simulator
+   * @param vcekOptions - KDS, cache, timeout, and retry options for VCEK
+   *                      certificates of hardware reports
+   * @param simulatorOptions - Options for the simulator runner
+   * @throws ConfigurationError on an unsupported simulator report version
+   *         or mode
+   */
+  constructor(
+    preferNative: boolean = true,
+    vcekOptions: VcekOptions = {},
+    simulatorOptions: SimulatorOptions = {}
+  ) {
+    validateSimulatorOptions(simulatorOptions);
+    this.vcekOptions = vcekOptions;
+    this.simulatorOptions = simulatorOptions;
     if (preferNative) {
       this.runner = new NativeEnclaveRunner();
       this.mode = "native";
     } else {
-      this.runner = new SimulatorEnclaveRunner();
+      this.runner = new SimulatorEnclaveRunner(simulatorOptions);
       this.mode = "simulator";
     }
   }
@@ -343,7 +1708,7 @@ export class EnclaveBridge {
   /**
    * Get platform type.
    */
-  getPlatform(): "sev-snp" | "sev-snp-simulator" {
+  getPlatform(): EnclavePlatform {
     return this.runner.getPlatform();
   }
 
@@ -353,6 +1718,8 @@ export class EnclaveBridge {
    * @returns Enclave execution response with attestation
    */
   async execute(request: EnclaveRequest): Promise<EnclaveResponse> {
+    validateRequest(request);
+
     const available = await this.isAvailable();
     if (!available) {
       throw new ConfigurationError(
@@ -360,14 +1727,422 @@ export class EnclaveBridge {
       );
     }
 
-    return await this.runner.execute(request);
+    return this.completeResponse(await this.runner.execute(request), request);
+  }
+
+  /**
+   * Execute a transformation, verify its report inside the runner, and
+   * mint a JWT (ES256, with the key set as tokenKeyPath) for backends
+   * that verify JWTs rather than SEV-SNP reports. The token's claims are
+   * checked against the response before it is returned.
+   * @param request - Enclave execution request
+   * @returns Response, token, and the token's claims
+   * @throws TokenError if no token key is configured or the clock is
+   *         unset or behind the attestation
+   * @throws AttestationError if local verification fails
+   * @throws ConfigurationError if the native module lacks the export
+   * @throws SecurityInvariantError if the token's claims differ from the
+   *         response
+   */
+  async attestAndTokenize(request: EnclaveRequest): Promise<TokenizedAttestation> {
+    validateRequest(request);
+
+    const available = await this.isAvailable();
+    if (!available) {
+      throw new ConfigurationError(
+        `Enclave not available in ${this.mode} mode`
+      );
+    }
+
+    const result = await this.runner.attestAndTokenize(request);
+    const response = await this.completeResponse(result.response, request);
+    let claims: AttestationTokenClaims;
+    try {
+      claims = decodeAttestationToken(result.token);
+    } catch (error) {
+      const reason = error instanceof Error ? error.message : String(error);
+      throw new SecurityInvariantError(`Invalid enclave attestation token: ${reason}`);
+    }
+    const hex = (bytes: Uint8Array) => Buffer.from(bytes).toString("hex");
+    let platform: EvidencePlatform | undefined;
+    try {
+      platform = evidenceKind(response.attestationReport).platform;
+    } catch {
+      platform = undefined;
+    }
+    const mismatched = [
+      claims.session_id !== hex(request.sessionId) ? "session_id" : undefined,
+      claims.nonce !== hex(request.nonce) ? "nonce" : undefined,
+      claims.output_hash !== hex(response.outputHash) ? "output_hash" : undefined,
+      claims.measurement !== response.measurement ? "measurement" : undefined,
+      claims.platform !== platform ? "platform" : undefined,
+    ].filter((claim) => claim !== undefined);
+    if (mismatched.length > 0) {
+      throw new SecurityInvariantError(
+        `Enclave attestation token differs from the response: ${mismatched.join(", ")}`
+      );
+    }
+    return { response, token: result.token, claims };
+  }
+
+  /**
+   * Attach VCEK, platform fields, and evidence to a runner response, and
+   * check its packaged evidence.
+   */
+  private async completeResponse(
+    response: EnclaveResponse,
+    request: EnclaveRequest
+  ): Promise<EnclaveResponse> {
+    const withVcek = await this.attachVcek(response, request);
+    return this.checkPackagedEvidence(
+      this.attachEvidence(this.attachPlatform(withVcek, request)),
+      request
+    );
+  }
+
+  /**
+   * COSE evidence, EAT tokens, and session attestations must be present
+   * exactly when requested; the first two must carry the response's own
+   * report (an EAT also the request's nonce and the response's output
+   * hash). Signatures are checked by verifyCoseEvidence, verifyEatToken,
+   * and verifyAttestationReport, not here.
+   */
+  private checkPackagedEvidence(
+    response: EnclaveResponse,
+    request: EnclaveRequest
+  ): EnclaveResponse {
+    const report = Buffer.from(response.attestationReport);
+    if (response.coseEvidence === undefined && request.evidenceFormat === "cose") {
+      throw new SecurityInvariantError(
+        "COSE evidence requested, but the enclave returned none (runner built without the cose feature?)"
+      );
+    }
+    if (response.eatToken === undefined && request.evidenceFormat === "eat") {
+      throw new SecurityInvariantError("EAT token requested, but the enclave returned none");
+    }
+    const sessionCached = request.attestationMode === "session_cached";
+    if ((response.sessionAttestation !== undefined) !== sessionCached) {
+      throw new SecurityInvariantError(
+        sessionCached
+          ? "Session-cached attestation requested, but the enclave returned no session attestation"
+          : "Enclave returned a session attestation for a per_request attestation"
+      );
+    }
+
+    if (response.coseEvidence !== undefined) {
+      let decoded: ReturnType<typeof decodeCoseEvidence>;
+      try {
+        decoded = decodeCoseEvidence(response.coseEvidence);
+      } catch (error) {
+        const reason = error instanceof Error ? error.message : String(error);
+        throw new SecurityInvariantError(`Invalid enclave COSE evidence: ${reason}`);
+      }
+      if (!report.equals(decoded.payload.report)) {
+        throw new SecurityInvariantError(
+          "Enclave COSE evidence report differs from attestation_report"
+        );
+      }
+    }
+
+    if (response.eatToken !== undefined) {
+      let decoded: ReturnType<typeof decodeEatToken>;
+      try {
+        decoded = decodeEatToken(response.eatToken);
+      } catch (error) {
+        const reason = error instanceof Error ? error.message : String(error);
+        throw new SecurityInvariantError(`Invalid enclave EAT token: ${reason}`);
+      }
+      const { claims } = decoded;
+      if (!report.equals(claims.report)) {
+        throw new SecurityInvariantError("Enclave EAT report differs from attestation_report");
+      }
+      if (!Buffer.from(request.nonce).equals(claims.nonce)) {
+        throw new SecurityInvariantError("Enclave EAT eat_nonce differs from the request nonce");
+      }
+      if (claims.outputHash !== Buffer.from(response.outputHash).toString("hex")) {
+        throw new SecurityInvariantError("Enclave EAT output hash differs from output_hash");
+      }
+    }
+    return response;
+  }
+
+  /**
+   * Wrap the report in an evidence envelope with its certificates (the
+   * VCEK included), unless the runner already did, in which case the
+   * runner's envelope must carry the same report and binding version.
+   */
+  private attachEvidence(response: EnclaveResponse): EnclaveResponse {
+    if (response.evidence !== undefined) {
+      let opened: ReturnType<typeof openEvidence>;
+      try {
+        opened = openEvidence(response.evidence);
+      } catch (error) {
+        const reason = error instanceof Error ? error.message : String(error);
+        throw new SecurityInvariantError(`Invalid enclave evidence: ${reason}`);
+      }
+      if (!Buffer.from(opened.report).equals(Buffer.from(response.attestationReport))) {
+        throw new SecurityInvariantError("Enclave evidence report differs from attestation_report");
+      }
+      if (opened.bindingVersion !== response.bindingVersion) {
+        throw new SecurityInvariantError(
+          `Enclave evidence binding formula ${response.evidence.bindingFormula} differs from binding version ${response.bindingVersion}`
+        );
+      }
+      return response;
+    }
+
+    try {
+      const certificates = { ...response.certificates };
+      if (certificates.vcek === undefined && response.vcekPem !== undefined) {
+        certificates.vcek = new Uint8Array(new X509Certificate(response.vcekPem).raw);
+      }
+      return {
+        ...response,
+        evidence: createEvidenceEnvelope(
+          response.attestationReport,
+          response.bindingVersion,
+          certificates
+        ),
+      };
+    } catch {
+      return response;
+    }
+  }
+
+  /**
+   * Attach the report's platform fields. chip_id is hashed unless the
+   * request sets rawChipId.
+   */
+  private attachPlatform(response: EnclaveResponse, request: EnclaveRequest): EnclaveResponse {
+    let report: AttestationReport;
+    try {
+      report = parseReport(response.attestationReport);
+    } catch {
+      return response;
+    }
+    return { ...response, platform: extractPlatformEvidence(report, request.rawChipId === true) };
+  }
+
+  /**
+   * Attach the VCEK certificate for a hardware report, unless the runner
+   * already did. A VCEK in the extended report's certificate table is
+   * used before fetching from KDS. A failed fetch degrades to vcekUnavailable and a warning
+   * unless the request sets requireVcek.
+   */
+  private async attachVcek(
+    response: EnclaveResponse,
+    request: EnclaveRequest
+  ): Promise<EnclaveResponse> {
+    if (response.vcekPem !== undefined) {
+      return response;
+    }
+
+    const suppliedVcek = response.certificates?.vcek;
+    if (suppliedVcek !== undefined && isCertificate(suppliedVcek)) {
+      return { ...response, vcekPem: derToPem(suppliedVcek) };
+    }
+
+    const report = response.attestationReport;
+    if (report.length !== REPORT_BYTES || isSimulatorReport(report)) {
+      if (request.requireVcek) {
+        throw new SecurityInvariantError(
+          "VCEK certificate required, but the attestation report is not a SEV-SNP hardware report"
+        );
+      }
+      return response;
+    }
+
+    try {
+      return { ...response, vcekPem: await fetchVcek(report, this.vcekOptions) };
+    } catch (error) {
+      const reason = error instanceof Error ? error.message : String(error);
+      if (request.requireVcek) {
+        throw new SecurityInvariantError(`VCEK certificate required but unavailable: ${reason}`);
+      }
+      return {
+        ...response,
+        vcekUnavailable: true,
+        warnings: [...(response.warnings ?? []), `VCEK certificate unavailable: ${reason}`],
+      };
+    }
+  }
+
+  /**
+   * Canonicalize a transformed context (JSON) with the runner's own
+   * canonicalization, so a verifier sees the exact bytes that were hashed.
+   * @param transformedContextJson - TransformedContext as JSON
+   * @returns Canonical JSON string
+   * @throws ConfigurationError on an unknown schemaVersion, or if the
+   *         native module lacks the export
+   */
+  getCanonicalContext(transformedContextJson: string): string {
+    return this.runner.getCanonicalContext(transformedContextJson);
+  }
+
+  /**
+   * Hash a transformed context (JSON) over the runner's canonical form.
+   * @param transformedContextJson - TransformedContext as JSON
+   * @param hashAlgorithm - Hash algorithm (default "sha256")
+   * @returns Hex-encoded digest
+   * @throws ConfigurationError on an unknown schemaVersion, or if the
+   *         native module lacks the export
+   */
+  computeContextHash(
+    transformedContextJson: string,
+    hashAlgorithm: HashAlgorithm = "sha256"
+  ): string {
+    return this.runner.computeContextHash(transformedContextJson, hashAlgorithm);
+  }
+
+  /**
+   * Canonicalization test vectors from the runner's own hasher, for
+   * checking parity with the SDK's canonicalize().
+   * @returns Vectors in the runner's publication order
+   * @throws ConfigurationError if the native module lacks the export
+   */
+  getHashTestVectors(): TestVector[] {
+    return this.runner.getHashTestVectors();
+  }
+
+  /**
+   * Parse a raw attestation report with the runner's own parser, for
+   * checking parity with the SDK's parseReport().
+   * @param report - Raw attestation report
+   * @returns Typed report
+   * @throws Error on a malformed report, or ConfigurationError if the
+   *         native module lacks the export
+   */
+  parseReport(report: Uint8Array): AttestationReport {
+    return this.runner.parseReport(report);
+  }
+
+  /**
+   * Verify an attestation report with the runner's own verifier (see
+   * verifyAttestationReport for the checks). An evidence envelope is
+   * opened first and fails structure if it contradicts its report.
+   * @param evidence - Raw attestation report or evidence envelope
+   * @param expected - Binding fields, measurement, and certificates
+   * @returns Named pass/fail checks; valid only if all passed
+   * @throws ConfigurationError if the native module lacks the export
+   */
+  verifyAttestationReport(
+    evidence: Uint8Array | EvidenceEnvelope,
+    expected: ExpectedBinding
+  ): ReportVerificationResult {
+    let resolved: { report: Uint8Array; expected: ExpectedBinding };
+    try {
+      resolved = resolveEvidence(evidence, expected);
+    } catch (error) {
+      const reason = error instanceof Error ? error.message : String(error);
+      return {
+        valid: false,
+        simulator: false,
+        checks: [{ name: "structure", passed: false, reason }],
+      };
+    }
+    return this.runner.verifyAttestationReport(resolved.report, resolved.expected);
+  }
+
+  /**
+   * Export a response's evidence as a verification bundle: one JSON
+   * document with the evidence envelope and its certificates, the
+   * measurement, the binding inputs (hashes only), and the SDK and runner
+   * versions. Bundles of simulator evidence also carry the simulator key.
+   * @param response - Response from execute (which attaches the evidence)
+   * @param request - The request it answered
+   * @returns Bundle JSON, for verifyBundle
+   * @throws AttestationError if the response carries no evidence envelope
+   * @throws ConfigurationError if simulator evidence is bundled and the
+   *         native module does not export its key
+   */
+  exportVerificationBundle(response: EnclaveResponse, request: EnclaveRequest): string {
+    const simulatorKey =
+      response.evidence?.platform === "simulator"
+        ? this.runner.getSimulatorVerificationKey().publicKey
+        : undefined;
+    return createVerificationBundle(response, request, simulatorKey);
+  }
+
+  /**
+   * Verify a verification bundle with the runner's own verifier, without
+   * network access (see verifyVerificationBundle). The output hash is the
+   * auditor's, not the bundle's.
+   * @param bundle - Bundle JSON from exportVerificationBundle
+   * @param expectedOutputHash - Hex output hash to verify against
+   * @param options - Trust anchors and simulator handling
+   * @returns Named pass/fail checks; valid only if all passed
+   * @throws ConfigurationError if the native module lacks the export
+   */
+  verifyBundle(
+    bundle: string,
+    expectedOutputHash: string,
+    options: BundleVerificationOptions = {}
+  ): ReportVerificationResult {
+    return this.runner.verifyBundle(bundle, expectedOutputHash, options);
+  }
+
+  /**
+   * Public key the simulator signs its reports with, to pass as
+   * ExpectedBinding.simulatorKey. It is labeled non-production and must
+   * never be trusted for real evidence.
+   * @returns Ed25519 public key and its non-production label
+   * @throws ConfigurationError if the native module lacks the export
+   * @throws SecurityInvariantError if the runner's key is not an Ed25519
+   *         key marked non-production
+   */
+  getSimulatorVerificationKey(): SimulatorVerificationKey {
+    return this.runner.getSimulatorVerificationKey();
+  }
+
+  /**
+   * Fresh nonce for EnclaveRequest.nonce: 32 bytes of OS randomness from
+   * the runner, hex-encoded. Decode it with decodeNonce().
+   * @returns Hex-encoded nonce
+   * @throws ConfigurationError if the native module lacks the export
+   * @throws SecurityInvariantError if the runner's nonce is not 32 bytes
+   *         of hex or fails validateNonce
+   */
+  generateNonce(): string {
+    return this.runner.generateNonce();
+  }
+
+  /**
+   * Verify COSE evidence (EnclaveResponse.coseEvidence) with the runner's
+   * own verifier: the COSE_Sign1 structure, the payload, and for simulator
+   * evidence the Ed25519 signature. Check the payload's report afterwards
+   * with verifyAttestationReport and coseExpectedBinding.
+   * @param cose - CBOR-encoded COSE_Sign1
+   * @param simulatorKey - Simulator verification key. Default for the
+   *        simulator runner: its own key.
+   * @returns Validity, signed flag, and the payload when valid
+   * @throws ConfigurationError if the native module lacks the export
+   * @throws SecurityInvariantError if the runner accepts evidence the
+   *         SDK's decoder rejects
+   */
+  verifyCoseEvidence(cose: Uint8Array, simulatorKey?: string | Uint8Array): CoseVerificationResult {
+    return this.runner.verifyCoseEvidence(cose, simulatorKey);
+  }
+
+  /**
+   * Reconfigure simulator report generation (report mode, version, and
+   * signing key). Options left out keep their current value, and carry
+   * over to a later useSimulator().
+   * @param options - Simulator options to change
+   * @throws ConfigurationError on an unsupported report version or mode,
+   *         or if the native module lacks the export
+   */
+  initializeWithConfig(options: SimulatorOptions): void {
+    validateSimulatorOptions(options);
+    this.runner.initializeWithConfig(options);
+    const changed = Object.entries(options).filter(([, value]) => value !== undefined);
+    this.simulatorOptions = { ...this.simulatorOptions, ...Object.fromEntries(changed) };
   }
 
   /**
    * Switch to simulator mode (for testing).
    */
   useSimulator(): void {
-    this.runner = new SimulatorEnclaveRunner();
+    this.runner = new SimulatorEnclaveRunner(this.simulatorOptions);
     this.mode = "simulator";
   }
 
@@ -389,8 +2164,18 @@ export class EnclaveBridge {
       report: response.attestationReport,
       measurement: response.measurement,
       configHash,
+      policyHash: Buffer.from(response.policyHash).toString("hex"),
       sessionId,
       outputHash: Buffer.from(response.outputHash).toString("hex"),
+      hashAlgorithm: response.hashAlgorithm,
+      outputEncoding: response.outputEncoding,
+      hashScope: response.hashScope,
+      bindingVersion: response.bindingVersion,
+      ...(response.inputHash ? { inputHash: Buffer.from(response.inputHash).toString("hex") } : {}),
+      ...(response.chainedFrom !== undefined ? { chainedFrom: response.chainedFrom } : {}),
+      ...(response.sessionAttestation !== undefined
+        ? { sessionAttestation: response.sessionAttestation }
+        : {}),
       timestamp,
       signature: response.signature,
       version: "1.0",
@@ -404,9 +2189,11 @@ export class EnclaveBridge {
  */
 export async function createEnclaveBridge(
   preferNative: boolean = true,
-  allowSimulatorFallback: boolean = true
+  allowSimulatorFallback: boolean = true,
+  vcekOptions: VcekOptions = {},
+  simulatorOptions: SimulatorOptions = {}
 ): Promise<EnclaveBridge> {
-  const bridge = new EnclaveBridge(preferNative);
+  const bridge = new EnclaveBridge(preferNative, vcekOptions, simulatorOptions);
 
   // If native mode requested but not available, optionally fallback
   if (preferNative && allowSimulatorFallback) {
@@ -5,10 +5,13 @@ import { Masker } from "../transform/masking.ts";
 import { BoundaryValidator } from "./boundary.ts";
 import { clearReference } from "./memory.ts";
 import { EnclaveBridge, createEnclaveBridge } from "./enclave-bridge.ts";
+import { decodeNonce } from "./session.ts";
 import type { Session } from "./session.ts";
 import type { EnclaveRequest } from "../attestation/types.ts";
 import { ConfigurationError } from "../core/errors.ts";
-import { hash as hashContext } from "../core/canonical.ts";
+import { hashOutput } from "../core/serialization.ts";
+import type { MaskingPolicy } from "../core/policy.ts";
+import { idSaltBytes, resolveMaskingPolicy, validatePolicy } from "../core/policy.ts";
 
 /**
  * Executor coordinates the semantic transformation pipeline.
@@ -22,14 +25,30 @@ export class Executor {
   private readonly masker: Masker;
   private readonly boundaryValidator: BoundaryValidator;
   private readonly config: AxiomConfig;
+  private readonly policy: MaskingPolicy;
+  private readonly idSalt?: Uint8Array;
+  private readonly policyWarnings: readonly string[];
   private enclaveBridge?: EnclaveBridge;
 
   constructor(config: AxiomConfig) {
     this.config = config;
-    this.distiller = new Distiller();
-    this.abstractor = new Abstractor();
-    this.masker = new Masker();
-    this.boundaryValidator = new BoundaryValidator();
+    this.policy = resolveMaskingPolicy({
+      ...config.maskingPolicy,
+      version: config.policyVersion,
+    });
+    // Unknown fields a non-strict policy accepted, reported on each result
+    this.policyWarnings = validatePolicy({
+      ...config.maskingPolicy,
+      version: config.policyVersion,
+    })
+      .findings.filter((finding) => finding.code === "POLICY_FIELD_UNKNOWN")
+      .map((finding) => finding.message);
+    // AxiomConfig.idSalt takes precedence over the policy's salt
+    this.idSalt = config.idSalt ?? idSaltBytes(this.policy);
+    this.distiller = new Distiller(this.policy);
+    this.abstractor = new Abstractor(this.policy);
+    this.masker = new Masker(this.policy);
+    this.boundaryValidator = new BoundaryValidator(this.policy);
   }
 
   /**
@@ -38,30 +57,30 @@ export class Executor {
    * @param context - Raw context input (string or array of strings)
    * @param task - The reasoning task to perform
    * @param model - Optional model identifier
-   * @returns Transformed context safe for boundary crossing
+   * @returns Result with the transformed context safe for boundary crossing
    */
   execute(
     context: string | string[],
     task: string,
     model?: string
-  ): TransformedContext {
+  ): ReasonResult {
     // Normalize input to array for consistent processing
     const rawInputs = Array.isArray(context) ? context : [context];
-    const combinedContext = rawInputs.join("\n");
 
-    // Stage 1: Distillation - extract entities from raw text
-    const rawEntities = this.distiller.distill(combinedContext);
+    // Stage 1: Distillation - extract entities from each document
+    const rawEntities = this.distiller.distill(rawInputs);
 
     // Stage 2: Abstraction - assign roles and build relations
     const semanticRepresentation = this.abstractor.abstract(
       rawEntities,
-      combinedContext
+      rawInputs
     );
 
     // Stage 3: Masking - remove identifiers, replace with synthetic IDs
     const maskedRepresentation = this.masker.mask(
       semanticRepresentation,
-      rawInputs
+      rawInputs,
+      this.idSalt
     );
 
     // Stage 4: Boundary validation - ensure no raw data leaks
@@ -73,13 +92,21 @@ export class Executor {
       relations: maskedRepresentation.relations,
       task,
       model,
+      ...(maskedRepresentation.constraints ? { constraints: maskedRepresentation.constraints } : {}),
     };
 
+    const entitiesTruncated = this.distiller.entitiesTruncated;
+    const relationsTruncated = semanticRepresentation.relationsTruncated === true;
+
     // Clear intermediate references (best-effort zero-retention)
     clearReference(rawEntities);
     clearReference(semanticRepresentation);
 
-    return transformedContext;
+    return {
+      transformedContext,
+      ...(entitiesTruncated ? { entitiesTruncated } : {}),
+      ...(relationsTruncated ? { relationsTruncated } : {}),
+    };
   }
 
   /**
@@ -89,13 +116,15 @@ export class Executor {
    * @param task - The reasoning task
    * @param model - Optional model identifier
    * @param session - Session for binding attestation
+   * @param previousOutputHash - Output hash of the previous step to chain to
    * @returns Result with transformed context and attestation evidence
    */
   async executeAttested(
     context: string | string[],
     task: string,
     model: string | undefined,
-    session: Session
+    session: Session,
+    previousOutputHash?: string
   ): Promise<ReasonResult> {
     // Initialize enclave bridge if not already done
     if (!this.enclaveBridge) {
@@ -111,8 +140,7 @@ export class Executor {
         );
       }
       // Fallback to standard execution if enclave is "auto"
-      const transformedContext = this.execute(context, task, model);
-      return { transformedContext };
+      return this.execute(context, task, model);
     }
     if (this.config.enclave === "required" && this.enclaveBridge.getMode() !== "native") {
       throw new ConfigurationError("Enclave execution required but native mode unavailable");
@@ -120,20 +148,33 @@ export class Executor {
 
     // Prepare enclave request
     const rawInputs = Array.isArray(context) ? context : [context];
-    const combinedContext = rawInputs.join("\n");
-    
+    const encoder = new TextEncoder();
+
+    const rawContext = rawInputs.map((input) => encoder.encode(input));
+    Distiller.checkInputSize(
+      rawContext.reduce((total, document) => total + document.length, 0),
+      this.policy
+    );
+
+    // A cached session report only covers bindings stamped inside its
+    // window, so those are stamped now rather than at session creation
+    const sessionCached = this.config.attestationMode === "session_cached";
     const request: EnclaveRequest = {
-      rawContext: new TextEncoder().encode(combinedContext),
+      rawContext,
       taskHint: task,
-      policy: {
-        version: "v1",
-        allowCommonWords: true,
-        maxInputSize: 10 * 1024 * 1024, // 10 MB
-      },
+      policy: this.policy,
       sessionId: Buffer.from(session.sessionId, "hex"),
       configHash: session.configHash,
-      nonce: Buffer.from(session.nonce || "00".repeat(32), "hex"),
-      timestamp: session.createdAt,
+      nonce: decodeNonce(session.nonce),
+      timestamp: sessionCached ? Date.now() : session.createdAt,
+      legacyReportData: this.config.legacyReportData,
+      hashAlgorithm: this.config.hashAlgorithm,
+      outputEncoding: this.config.outputEncoding,
+      bindingVersion: this.config.bindingVersion ?? (this.config.legacyReportData ? 1 : 3),
+      ...(previousOutputHash !== undefined ? { previousOutputHash } : {}),
+      idSalt: this.idSalt,
+      requireVcek: this.config.requireVcek,
+      attestationMode: this.config.attestationMode,
     };
 
     // Execute in enclave
@@ -146,10 +187,20 @@ export class Executor {
     // Add task and model (enclave doesn't include these)
     transformedContext.task = task;
     transformedContext.model = model;
+    if (response.metadata) {
+      transformedContext.metadata = response.metadata;
+    }
 
     // Compute and verify output hash
-    const outputHash = hashContext(transformedContext);
+    const outputHash = hashOutput(
+      transformedContext,
+      response.outputEncoding,
+      response.hashAlgorithm,
+      "jcs",
+      response.bindingVersion
+    );
     session.setOutputHash(outputHash);
+    session.setPolicyHash(Buffer.from(response.policyHash).toString("hex"));
 
     // Mark session as using enclave
     session.setEnclaveUsed(this.enclaveBridge.getPlatform() === "sev-snp" ? "sev-snp" : "sev-snp");
@@ -169,6 +220,10 @@ export class Executor {
     return {
       transformedContext,
       attestationEvidence,
+      ...(response.redactionStats.entitiesTruncated ? { entitiesTruncated: true } : {}),
+      ...(response.redactionStats.relationsTruncated ? { relationsTruncated: true } : {}),
+      ...(response.warnings ? { warnings: response.warnings } : {}),
+      ...(response.inputHash ? { inputNonce: Buffer.from(request.nonce).toString("hex") } : {}),
       verificationHint: {
         expectedMeasurement: response.measurement,
         platform: "sev-snp",
@@ -177,6 +232,20 @@ export class Executor {
     };
   }
 
+  /**
+   * Warnings for unknown policy fields, one per field.
+   */
+  getPolicyWarnings(): readonly string[] {
+    return this.policyWarnings;
+  }
+
+  /**
+   * Whether the resolved policy uses the strict preset.
+   */
+  isStrict(): boolean {
+    return this.policy.preset === "strict";
+  }
+
   /**
    * Initialize enclave bridge based on configuration.
    * @private
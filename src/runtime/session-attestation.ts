@@ -0,0 +1,187 @@
+/**
+ * Session attestations for attestationMode "session_cached": one platform
+ * report per session and freshness window, binding an Ed25519 session key
+ * that then signs each response's binding. Report generation has real
+ * latency on hardware; a signature does not.
+ */
+import { createPublicKey, generateKeyPairSync, sign } from "crypto";
+import type { KeyObject } from "crypto";
+import type { GeneratedEvidence } from "./attestation-backend.ts";
+
+/**
+ * Default freshness window: how long a session report is reused.
+ */
+export const DEFAULT_ATTESTATION_WINDOW_MS = 60 * 1000;
+
+/**
+ * Sessions cached at once; beyond this the least recently used is evicted.
+ */
+export const MAX_CACHED_SESSIONS = 1024;
+
+/**
+ * A session report and the key it binds.
+ */
+export interface CachedSessionAttestation {
+  evidence: GeneratedEvidence;
+
+  /**
+   * Raw Ed25519 public key (32 bytes) the report binds.
+   */
+  publicKey: Uint8Array;
+
+  windowStart: number;
+  expiresAt: number;
+
+  /**
+   * Sign a per-request binding with the session key.
+   */
+  signBinding(reportData: Uint8Array): Uint8Array;
+}
+
+/**
+ * Generates the session report for a key and window; its report_data must
+ * be createSessionKeyReportData(sessionId, publicKey, windowStart,
+ * expiresAt).
+ */
+export type SessionReportGenerator = (
+  publicKey: Uint8Array,
+  windowStart: number,
+  expiresAt: number
+) => GeneratedEvidence | Promise<GeneratedEvidence>;
+
+/**
+ * Session attestations by session ID. Windows are aligned to multiples of
+ * windowMs and taken from the runner's clock, so a session's report is
+ * regenerated in the first request of each window. Concurrent requests
+ * for one session and window share one generation, and a failed
+ * generation is not cached.
+ */
+export class SessionAttestationCache {
+  private readonly windowMs: number;
+  private readonly maxSessions: number;
+  private readonly entries = new Map<
+    string,
+    { expiresAt: number; attestation: Promise<CachedSessionAttestation> }
+  >();
+
+  constructor(
+    windowMs: number = DEFAULT_ATTESTATION_WINDOW_MS,
+    maxSessions: number = MAX_CACHED_SESSIONS
+  ) {
+    this.windowMs = windowMs;
+    this.maxSessions = maxSessions;
+  }
+
+  /**
+   * Number of sessions cached, expired or not.
+   */
+  get size(): number {
+    return this.entries.size;
+  }
+
+  /**
+   * The window holding now.
+   * @param now - Current time (milliseconds since epoch)
+   */
+  window(now: number): { windowStart: number; expiresAt: number } {
+    const windowStart = now - (now % this.windowMs);
+    return { windowStart, expiresAt: windowStart + this.windowMs };
+  }
+
+  /**
+   * The session's attestation for the current window, generated if the
+   * session has none for that window.
+   * @param sessionId - Session identifier
+   * @param now - Current time (milliseconds since epoch)
+   * @param generate - Produces the session report
+   */
+  get(
+    sessionId: Uint8Array,
+    now: number,
+    generate: SessionReportGenerator
+  ): Promise<CachedSessionAttestation> {
+    const key = Buffer.from(sessionId).toString("hex");
+    const { windowStart, expiresAt } = this.window(now);
+
+    const cached = this.entries.get(key);
+    if (cached !== undefined && cached.expiresAt === expiresAt) {
+      // Most recently used goes last
+      this.entries.delete(key);
+      this.entries.set(key, cached);
+      return cached.attestation;
+    }
+
+    this.entries.delete(key);
+    this.evict(now);
+    const attestation = this.generate(windowStart, generate);
+    const entry = { expiresAt, attestation };
+    this.entries.set(key, entry);
+    attestation.catch(() => {
+      if (this.entries.get(key) === entry) {
+        this.entries.delete(key);
+      }
+    });
+    return attestation;
+  }
+
+  /**
+   * Drop every cached session.
+   */
+  clear(): void {
+    this.entries.clear();
+  }
+
+  private async generate(
+    windowStart: number,
+    generate: SessionReportGenerator
+  ): Promise<CachedSessionAttestation> {
+    const keys = generateKeyPairSync("ed25519");
+    const publicKey = rawEd25519PublicKey(keys.publicKey);
+    const { expiresAt } = this.window(windowStart);
+    const evidence = await generate(publicKey, windowStart, expiresAt);
+    return {
+      evidence,
+      publicKey,
+      windowStart,
+      expiresAt,
+      signBinding: (reportData) => new Uint8Array(sign(null, reportData, keys.privateKey)),
+    };
+  }
+
+  // Make room for one more session: windows that have ended first, then
+  // the least recently used
+  private evict(now: number): void {
+    for (const [key, entry] of this.entries) {
+      if (entry.expiresAt <= now) {
+        this.entries.delete(key);
+      }
+    }
+    for (const key of this.entries.keys()) {
+      if (this.entries.size < this.maxSessions) {
+        break;
+      }
+      this.entries.delete(key);
+    }
+  }
+}
+
+/**
+ * The 32 raw bytes of an Ed25519 public key.
+ */
+export function rawEd25519PublicKey(key: KeyObject): Uint8Array {
+  const der = key.export({ format: "der", type: "spki" });
+  return new Uint8Array(der.subarray(der.length - 32));
+}
+
+/**
+ * An Ed25519 public key from its 32 raw bytes.
+ */
+export function ed25519PublicKey(raw: Uint8Array): KeyObject {
+  // SPKI prefix of an Ed25519 key (RFC 8410)
+  const prefix = Buffer.from("302a300506032b6570032100", "hex");
+  return createPublicKey({
+    key: Buffer.concat([prefix, Buffer.from(raw)]),
+    format: "der",
+    type: "spki",
+  });
+}
@@ -1,4 +1,12 @@
 import { randomBytes, createHash } from "crypto";
+import {
+  canonicalJson,
+  domainSeparatedHash,
+  HASH_ALGORITHM_IDS,
+  HASH_DOMAINS,
+} from "../core/canonical.ts";
+import type { BindingVersion, HashAlgorithm } from "../core/canonical.ts";
+import { ConfigurationError, InvalidInputError } from "../core/errors.ts";
 import type { AxiomConfig } from "../core/config.ts";
 import type { SessionMetadata } from "../attestation/types.ts";
 
@@ -25,31 +33,237 @@ export function generateNonce(): string {
   return bytes.toString("hex");
 }
 
+/**
+ * Shortest nonce an enclave request accepts, in bytes.
+ */
+export const NONCE_MIN_BYTES = 16;
+
+/**
+ * Longest nonce an enclave request accepts, in bytes.
+ */
+export const NONCE_MAX_BYTES = 64;
+
+/**
+ * Codes of the InvalidInputError thrown for a rejected nonce.
+ */
+export const NONCE_ERROR_CODES = [
+  "NONCE_EMPTY",
+  "NONCE_ENCODING",
+  "NONCE_LENGTH",
+  "NONCE_CONSTANT",
+] as const;
+
+export type NonceErrorCode = (typeof NONCE_ERROR_CODES)[number];
+
+/**
+ * Check that a nonce can bind a session: 16 to 64 bytes, and not the same
+ * byte repeated, which an unset or zero-filled buffer would be.
+ * @param nonce - Nonce bytes
+ * @throws InvalidInputError (NONCE_EMPTY, NONCE_LENGTH, NONCE_CONSTANT)
+ */
+export function validateNonce(nonce: Uint8Array): void {
+  if (nonce.length === 0) {
+    throw new InvalidInputError("NONCE_EMPTY", "Invalid nonce: empty");
+  }
+  if (nonce.length < NONCE_MIN_BYTES || nonce.length > NONCE_MAX_BYTES) {
+    throw new InvalidInputError(
+      "NONCE_LENGTH",
+      `Invalid nonce: ${nonce.length} bytes, must be ${NONCE_MIN_BYTES} to ${NONCE_MAX_BYTES}`
+    );
+  }
+  if (nonce.every((byte) => byte === nonce[0])) {
+    throw new InvalidInputError("NONCE_CONSTANT", "Invalid nonce: every byte is the same");
+  }
+}
+
+/**
+ * Decode a hex or base64url nonce and validate it (see validateNonce).
+ * An even-length string of hex digits is read as hex; anything else must
+ * be unpadded base64url.
+ * @param nonce - Encoded nonce
+ * @returns Nonce bytes
+ * @throws InvalidInputError (NONCE_ENCODING, or a validateNonce code)
+ */
+export function decodeNonce(nonce: string): Uint8Array {
+  if (nonce === "") {
+    throw new InvalidInputError("NONCE_EMPTY", "Invalid nonce: empty");
+  }
+  let bytes: Buffer;
+  if (/^(?:[0-9a-fA-F]{2})+$/.test(nonce)) {
+    bytes = Buffer.from(nonce, "hex");
+  } else if (/^[A-Za-z0-9_-]+$/.test(nonce) && nonce.length % 4 !== 1) {
+    bytes = Buffer.from(nonce, "base64url");
+  } else {
+    throw new InvalidInputError("NONCE_ENCODING", "Invalid nonce: not hex or base64url");
+  }
+  validateNonce(bytes);
+  return new Uint8Array(bytes);
+}
+
 /**
  * Compute hash of AxiomConfig for binding.
  * @param config - The Axiom configuration
  * @returns Hex-encoded SHA-256 hash of canonical config
  */
 export function hashConfig(config: AxiomConfig): string {
-  // Create canonical representation of config
-  const canonical = JSON.stringify(
-    {
-      enclave: config.enclave,
-      platform: config.platform ?? null,
-      policyVersion: config.policyVersion,
-      securityTier: config.securityTier,
-    },
-    Object.keys({
-      enclave: null,
-      platform: null,
-      policyVersion: null,
-      securityTier: null,
-    }).sort()
-  );
+  // Create canonical representation of config. Keys are sorted at every
+  // level, so nested platform fields are covered regardless of the order
+  // they were set in.
+  const canonical = canonicalJson({
+    enclave: config.enclave,
+    platform: config.platform ?? null,
+    policyVersion: config.policyVersion,
+    securityTier: config.securityTier,
+  });
 
   return createHash("sha256").update(canonical, "utf8").digest("hex");
 }
 
+/**
+ * Compute the input commitment: SHA-256 over the item count and each raw
+ * input with its length (both 8-byte big-endian), then sessionId and
+ * nonce. The prefixes keep ["ab", "c"] and ["a", "bc"] apart. Anyone
+ * holding the raw inputs and the nonce can recompute it; without the
+ * nonce it reveals nothing about low-entropy inputs.
+ * @param rawContext - Raw input documents, UTF-8 encoded
+ * @param sessionId - Session identifier (16 bytes)
+ * @param nonce - Session nonce
+ * @returns 32-byte SHA-256 digest
+ */
+export function computeInputHash(
+  rawContext: readonly Uint8Array[],
+  sessionId: Uint8Array,
+  nonce: Uint8Array
+): Buffer {
+  const inputHash = createHash("sha256");
+  const count = Buffer.alloc(8);
+  count.writeBigUInt64BE(BigInt(rawContext.length), 0);
+  inputHash.update(count);
+  for (const document of rawContext) {
+    const length = Buffer.alloc(8);
+    length.writeBigUInt64BE(BigInt(document.length), 0);
+    inputHash.update(length);
+    inputHash.update(document);
+  }
+  inputHash.update(sessionId);
+  inputHash.update(nonce);
+  return inputHash.digest();
+}
+
+/**
+ * Compute the 32-byte report_data binding.
+ * SHA-256(sessionId || configHash || policyHash || inputHash || chainLink || outputHash || timestamp),
+ * or SHA-256(sessionId || configHash || outputHash || timestamp) under the
+ * legacy formula, which cannot be chained. inputHash is left out when the runner did not commit
+ * to its input; chainLink, SHA-512 of the previous step's output hash,
+ * only appears when the transform is chained. An output hash other than
+ * SHA-256 is preceded by its one-byte algorithm ID (HASH_ALGORITHM_IDS).
+ * The optional parts are 32, 64, and 1 or 17 bytes, so every combination
+ * has a distinct length and none can be read as another.
+ *
+ * Binding version 2 instead hashes domainSeparatedHash(HASH_DOMAINS.reportData,
+ * [sessionId, configHash, policyHash, inputHash, previousOutputHash,
+ * algorithmId, outputHash, timestamp]), each field length-prefixed and an
+ * absent one empty. Binding version 3 hashes the same fields under
+ * HASH_DOMAINS.reportDataV3 with SHA-512 and returns 64 bytes: the
+ * version byte 0x03, then the first 63 bytes of the digest. Versions 2
+ * and 3 cannot be combined with the legacy formula.
+ * @param legacy - Use the pre-policy-hash formula (migration only)
+ * @param hashAlgorithm - Algorithm that produced outputHash
+ * @param inputHash - Input commitment (see computeInputHash), if any
+ * @param previousOutputHash - Output hash of the previous step, if chained
+ * @param bindingVersion - Binding formula (default 1)
+ * @returns report_data: 32 bytes for versions 1 and 2, 64 for version 3
+ * @throws ConfigurationError if legacy is combined with binding version 2 or 3
+ */
+export function createReportData(
+  sessionId: Uint8Array,
+  configHash: Uint8Array,
+  policyHash: Uint8Array,
+  outputHash: Uint8Array,
+  timestamp: number,
+  legacy: boolean = false,
+  hashAlgorithm: HashAlgorithm = "sha256",
+  inputHash?: Uint8Array,
+  previousOutputHash?: Uint8Array,
+  bindingVersion: BindingVersion = 1
+): Buffer {
+  const timestampBytes = Buffer.alloc(8);
+  timestampBytes.writeBigUInt64BE(BigInt(timestamp), 0);
+
+  if (bindingVersion !== 1) {
+    if (legacy) {
+      throw new ConfigurationError(
+        `Binding version ${bindingVersion} cannot use the legacy report_data formula`
+      );
+    }
+    const fields = [
+      sessionId,
+      configHash,
+      policyHash,
+      inputHash ?? new Uint8Array(0),
+      previousOutputHash ?? new Uint8Array(0),
+      Uint8Array.of(HASH_ALGORITHM_IDS[hashAlgorithm]),
+      outputHash,
+      timestampBytes,
+    ];
+    if (bindingVersion === 2) {
+      return domainSeparatedHash(HASH_DOMAINS.reportData, fields);
+    }
+    const digest = domainSeparatedHash(HASH_DOMAINS.reportDataV3, fields, "sha512");
+    return Buffer.concat([Uint8Array.of(bindingVersion), digest.subarray(0, 63)]);
+  }
+
+  const reportData = createHash("sha256");
+  reportData.update(sessionId);
+  reportData.update(configHash);
+  if (!legacy) {
+    reportData.update(policyHash);
+    if (inputHash) {
+      reportData.update(inputHash);
+    }
+    if (previousOutputHash) {
+      // Fixed 64 bytes whatever algorithm produced the previous hash
+      reportData.update(createHash("sha512").update(previousOutputHash).digest());
+    }
+  }
+  if (hashAlgorithm !== "sha256") {
+    reportData.update(Uint8Array.of(HASH_ALGORITHM_IDS[hashAlgorithm]));
+  }
+  reportData.update(outputHash);
+  reportData.update(timestampBytes);
+  return reportData.digest();
+}
+
+/**
+ * report_data of a session attestation (attestationMode "session_cached"):
+ * domainSeparatedHash(HASH_DOMAINS.sessionKey, [sessionId, publicKey,
+ * windowStart, expiresAt]) with SHA-512, all 64 bytes. It binds the
+ * session key and the window it may sign in, not an output; each
+ * response signs its own binding with that key.
+ * @param sessionId - Session identifier (16 bytes)
+ * @param publicKey - Raw Ed25519 session public key (32 bytes)
+ * @param windowStart - Start of the freshness window (milliseconds since epoch)
+ * @param expiresAt - End of the window, exclusive (milliseconds since epoch)
+ * @returns 64-byte report_data
+ */
+export function createSessionKeyReportData(
+  sessionId: Uint8Array,
+  publicKey: Uint8Array,
+  windowStart: number,
+  expiresAt: number
+): Buffer {
+  const startBytes = Buffer.alloc(8);
+  startBytes.writeBigUInt64BE(BigInt(windowStart), 0);
+  const endBytes = Buffer.alloc(8);
+  endBytes.writeBigUInt64BE(BigInt(expiresAt), 0);
+  return domainSeparatedHash(
+    HASH_DOMAINS.sessionKey,
+    [sessionId, publicKey, startBytes, endBytes],
+    "sha512"
+  );
+}
+
 /**
  * Session represents a single execution of the transformation pipeline.
  * Tracks metadata and binds attestation evidence to this specific execution.
@@ -95,6 +309,11 @@ export class Session {
    */
   private outputHash?: string;
 
+  /**
+   * Hash of the masking policy applied (set during attested execution).
+   */
+  private policyHash?: string;
+
   /**
    * Whether the session has been finalized.
    */
@@ -140,14 +359,14 @@ export class Session {
 
   /**
    * Set the output hash for this session.
-   * @param hash - Hex-encoded SHA-256 hash of transformed context
+   * @param hash - Hex-encoded hash of transformed context (SHA-256 or SHA-384)
    */
   setOutputHash(hash: string): void {
     if (this.finalized) {
       throw new Error("Cannot modify finalized session");
     }
-    if (!/^[0-9a-f]{64}$/i.test(hash)) {
-      throw new Error("Invalid output hash format (expected 64 hex characters)");
+    if (!/^(?:[0-9a-f]{64}|[0-9a-f]{96})$/i.test(hash)) {
+      throw new Error("Invalid output hash format (expected 64 or 96 hex characters)");
     }
     this.outputHash = hash;
   }
@@ -160,6 +379,28 @@ export class Session {
     return this.outputHash;
   }
 
+  /**
+   * Set the hash of the masking policy applied in the enclave.
+   * @param hash - Hex-encoded SHA-256 hash of the serialized policy
+   */
+  setPolicyHash(hash: string): void {
+    if (this.finalized) {
+      throw new Error("Cannot modify finalized session");
+    }
+    if (!/^[0-9a-f]{64}$/i.test(hash)) {
+      throw new Error("Invalid policy hash format (expected 64 hex characters)");
+    }
+    this.policyHash = hash;
+  }
+
+  /**
+   * Get the policy hash (if set).
+   * @returns Policy hash or undefined
+   */
+  getPolicyHash(): string | undefined {
+    return this.policyHash;
+  }
+
   /**
    * Finalize the session (no further modifications allowed).
    * @returns Session metadata for attestation binding
@@ -221,28 +462,35 @@ export class Session {
 
   /**
    * Create binding data for attestation.
-   * Combines session_id, config_hash, and output_hash.
+   * Combines session_id, config_hash, policy_hash, and output_hash.
+   * @param legacy - Omit policy_hash (pre-policy-hash formula)
    * @returns Buffer containing binding data for attestation report
    */
-  createBindingData(): Buffer {
+  createBindingData(legacy: boolean = false): Buffer {
     if (!this.outputHash) {
       throw new Error("Output hash not set - cannot create binding data");
     }
+    if (!legacy && !this.policyHash) {
+      throw new Error("Policy hash not set - cannot create binding data");
+    }
 
-    // Concatenate: session_id (16 bytes) || config_hash (32 bytes) || output_hash (32 bytes)
+    // Concatenate: session_id (16 bytes) || config_hash (32 bytes)
+    //              || policy_hash (32 bytes, unless legacy) || output_hash (32 bytes)
     const sessionIdBytes = Buffer.from(this.sessionId, "hex");
     const configHashBytes = Buffer.from(this.configHash, "hex");
+    const policyHashBytes = legacy ? Buffer.alloc(0) : Buffer.from(this.policyHash ?? "", "hex");
     const outputHashBytes = Buffer.from(this.outputHash, "hex");
 
-    return Buffer.concat([sessionIdBytes, configHashBytes, outputHashBytes]);
+    return Buffer.concat([sessionIdBytes, configHashBytes, policyHashBytes, outputHashBytes]);
   }
 
   /**
    * Create hash of binding data for embedding in attestation report.
+   * @param legacy - Use the pre-policy-hash formula (migration only)
    * @returns SHA-256 hash of binding data (32 bytes for REPORT_DATA field)
    */
-  createReportData(): Buffer {
-    const bindingData = this.createBindingData();
+  createReportData(legacy: boolean = false): Buffer {
+    const bindingData = this.createBindingData(legacy);
     const timestamp = Buffer.alloc(8);
     timestamp.writeBigUInt64BE(BigInt(this.createdAt), 0);
 
@@ -259,15 +507,16 @@ export class Session {
    * Used during attestation verification.
    * @param reportData - The REPORT_DATA field from attestation report
    * @param outputHash - The actual output hash to verify
+   * @param legacy - Use the pre-policy-hash formula (migration only)
    * @returns True if binding is valid
    */
-  verifyBinding(reportData: Buffer, outputHash: string): boolean {
+  verifyBinding(reportData: Buffer, outputHash: string, legacy: boolean = false): boolean {
     // Temporarily set output hash for verification
     const originalOutputHash = this.outputHash;
     this.outputHash = outputHash;
 
     try {
-      const expected = this.createReportData();
+      const expected = this.createReportData(legacy);
       return reportData.equals(expected);
     } finally {
       // Restore original state
@@ -1,8 +1,121 @@
 import type { Entity, RawEntity } from "../entities/entity.ts";
 import { Role } from "../entities/roles.ts";
 import type { Relation } from "../entities/relations.ts";
+import { Distiller, ENTITY_CONFIDENCE } from "./distiller.ts";
+import type { Coordinates, IpAddress } from "./distiller.ts";
+import { lookupPlace } from "./gazetteer.ts";
+import type { MaskingPolicy } from "../core/policy.ts";
+import { DEFAULT_MASKING_POLICY, isPassthrough } from "../core/policy.ts";
 import { TransformationError } from "../core/errors.ts";
 
+/**
+ * Top-level domains that may be reported as an email or URL attribute.
+ * Anything outside this list is bucketed as "other" so rare TLDs
+ * cannot act as a fingerprint.
+ */
+export const TLD_BUCKETS = [
+  "com",
+  "org",
+  "net",
+  "edu",
+  "gov",
+  "mil",
+  "int",
+  "io",
+  "co",
+  "us",
+  "uk",
+  "eu",
+  "de",
+  "fr",
+  "es",
+  "it",
+  "nl",
+  "ca",
+  "au",
+  "in",
+  "jp",
+  "cn",
+  "br",
+  "other",
+] as const;
+
+/**
+ * File extensions reported for file paths; anything else is "other".
+ */
+export const FILE_EXTENSIONS = [
+  "pdf",
+  "doc",
+  "docx",
+  "xls",
+  "xlsx",
+  "ppt",
+  "pptx",
+  "csv",
+  "txt",
+  "md",
+  "json",
+  "xml",
+  "yaml",
+  "yml",
+  "html",
+  "png",
+  "jpg",
+  "jpeg",
+  "gif",
+  "mp3",
+  "mp4",
+  "zip",
+  "gz",
+  "tar",
+  "log",
+  "sql",
+  "db",
+  "eml",
+  "pem",
+  "key",
+  "sh",
+  "py",
+  "js",
+  "ts",
+  "exe",
+  "other",
+] as const;
+
+/**
+ * Payment card networks inferred from the IIN range.
+ */
+export const CARD_NETWORKS = [
+  "visa",
+  "mastercard",
+  "amex",
+  "discover",
+  "jcb",
+  "diners",
+  "unionpay",
+  "unknown",
+] as const;
+
+/**
+ * Verbs that type a relation when they alone separate two entities
+ * ("Alice paid Bob"), in the form written. Extended by
+ * MaskingPolicy.relationVerbs.
+ */
+export const RELATION_VERBS = [
+  "acquired",
+  "bought",
+  "called",
+  "emailed",
+  "fired",
+  "hired",
+  "invoiced",
+  "met",
+  "paid",
+  "signed",
+  "sold",
+  "sued",
+] as const;
+
 /**
  * Semantic representation before masking.
  * Contains entities with original identifiers still present.
@@ -17,6 +130,18 @@ export interface SemanticRepresentation {
    * Relations between entities.
    */
   relations: Relation[];
+
+  /**
+   * Set when relations past the policy's maxRelations were dropped
+   * (truncateRelations only).
+   */
+  relationsTruncated?: boolean;
+
+  /**
+   * Number of relations dropped because their type is not in the
+   * policy's allowedRelationTypes; absent when none were.
+   */
+  relationsFiltered?: number;
 }
 
 /**
@@ -24,17 +149,26 @@ export interface SemanticRepresentation {
  * No masking is performed at this stage.
  */
 export class Abstractor {
+  private readonly policy: MaskingPolicy;
+  private readonly relationVerbs: ReadonlySet<string>;
+
+  constructor(policy: MaskingPolicy = DEFAULT_MASKING_POLICY) {
+    this.policy = policy;
+    this.relationVerbs = new Set([...RELATION_VERBS, ...policy.relationVerbs]);
+  }
+
   /**
    * Convert raw entities into a semantic representation.
    * Assigns roles based on entity types and builds relations.
    *
    * @param rawEntities - Entities extracted by the distiller
-   * @param rawContext - Original context for relation building
+   * @param rawContext - Original context for relation building (one
+   *                     string, or one per document)
    * @returns Semantic representation with entities and relations
    */
   abstract(
     rawEntities: RawEntity[],
-    rawContext: string
+    rawContext: string | readonly string[]
   ): SemanticRepresentation {
     if (rawEntities.length === 0) {
       throw new TransformationError(
@@ -42,21 +176,46 @@ export class Abstractor {
       );
     }
 
-    const entities = this.assignRoles(rawEntities);
-    const relations = this.buildRelations(entities, rawContext);
+    const entities = this.assignRoles(rawEntities, rawContext);
+    const tokenSpans = rawEntities.map((raw) => raw.originalText.trim().split(/\s+/).length);
+    const temporalKeys = rawEntities.map((raw) => this.temporalKey(raw));
+    const { relations, truncated, filtered } = this.buildRelations(
+      entities,
+      rawContext,
+      tokenSpans,
+      temporalKeys
+    );
 
-    return { entities, relations };
+    const representation: SemanticRepresentation = { entities, relations };
+    if (truncated) {
+      representation.relationsTruncated = true;
+    }
+    if (filtered > 0) {
+      representation.relationsFiltered = filtered;
+    }
+    return representation;
   }
 
   /**
    * Assign semantic roles to raw entities based on their type.
    */
-  private assignRoles(rawEntities: RawEntity[]): Entity[] {
-    return rawEntities.map((raw, index) => ({
-      id: this.generateEntityId(raw, index),
-      role: this.determineRole(raw),
-      attributes: this.extractAttributes(raw),
-    }));
+  private assignRoles(rawEntities: RawEntity[], rawContext: string | readonly string[]): Entity[] {
+    return rawEntities.map((raw, index) => {
+      const document = typeof rawContext === "string" ? rawContext : rawContext[raw.sourceDoc ?? 0];
+      const entity: Entity = {
+        id: this.generateEntityId(raw, index),
+        role: this.determineRole(raw),
+        attributes: this.extractAttributes(raw),
+        tokenPositions: raw.tokenPositions ?? [Distiller.tokenIndex(document ?? "", raw.position)],
+      };
+      if (raw.sourceDocs) {
+        entity.sourceDocs = raw.sourceDocs;
+      }
+      if (raw.boundaryTerms) {
+        entity.boundaryTerms = raw.boundaryTerms;
+      }
+      return entity;
+    });
   }
 
   /**
@@ -73,7 +232,9 @@ export class Abstractor {
   private determineRole(raw: RawEntity): Role {
     switch (raw.entityType) {
       case "name":
-        return Role.Actor;
+        return raw.inferredRole ?? Role.Actor;
+      case "organization":
+        return Role.Organization;
       case "date":
         return Role.Temporal;
       case "currency":
@@ -82,6 +243,43 @@ export class Abstractor {
         return Role.Value;
       case "identifier":
         return Role.Participant;
+      case "ssn":
+      case "national_id":
+      case "vin":
+      case "license_plate":
+      case "npi":
+      case "mrn":
+      case "payment_card":
+      case "mac":
+      case "serial":
+      case "long_number":
+      case "uuid":
+      case "handle":
+        return Role.Identifier;
+      case "email":
+      case "phone":
+        return Role.Contact;
+      case "iban":
+        return Role.Account;
+      case "url":
+      case "file_path":
+        return Role.Resource;
+      case "ip_address":
+        return Role.NetworkEndpoint;
+      case "address":
+      case "place":
+      case "coordinates":
+      case "postal_code":
+        return Role.Location;
+      case "secret":
+      case "jwt":
+        return Role.Credential;
+      case "icd10":
+        return Role.Condition;
+      case "custom":
+        return raw.customRole ?? Role.Participant;
+      case "restricted":
+        return Role.Restricted;
       default:
         return Role.Participant;
     }
@@ -90,22 +288,160 @@ export class Abstractor {
   /**
    * Extract attributes from the raw entity.
    */
-  private extractAttributes(raw: RawEntity): Record<string, string | number> {
-    const attributes: Record<string, string | number> = {
+  private extractAttributes(raw: RawEntity): Record<string, string | number | boolean> {
+    const attributes: Record<string, string | number | boolean> = {
       type: raw.entityType,
       position: raw.position,
+      occurrences: raw.occurrences ?? 1,
+      confidence: raw.confidence ?? ENTITY_CONFIDENCE[raw.entityType],
     };
+    if (raw.sourceDoc !== undefined) {
+      attributes.sourceDoc = raw.sourceDoc;
+    }
+    if (isPassthrough(this.policy, raw.entityType)) {
+      attributes.literal = raw.originalText;
+    }
 
-    // Parse numeric values from currency and number types
-    if (raw.entityType === "currency") {
-      const numericValue = this.parseCurrencyValue(raw.originalText);
-      if (numericValue !== null) {
-        attributes.numericValue = numericValue;
+    // Currency amounts report a magnitude; the exact figure is opt-in
+    if (raw.entityType === "name") {
+      const { honorific, name } = Distiller.splitHonorific(raw.originalText);
+      this.setCount(attributes, "tokenCount", name.split(/[ \t]+/).length, [1, 2]);
+      attributes.script = Distiller.detectScript(name);
+      if (honorific !== null) {
+        attributes.honorificPresent = true;
+        if (this.policy.allowHonorifics) {
+          attributes.honorific = honorific;
+        }
+      }
+    } else if (raw.entityType === "place") {
+      attributes.region = lookupPlace(raw.originalText) ?? "unknown";
+    } else if (raw.entityType === "currency") {
+      const { code, value } = Distiller.parseCurrency(raw.originalText, this.policy.locales);
+      attributes.currency = code;
+      attributes.magnitude = this.magnitudeBucket(Math.abs(value));
+      if (value < 0) {
+        attributes.negative = true;
+      }
+      if (this.policy.allowExactValues) {
+        attributes.numericValue = value;
       }
     } else if (raw.entityType === "number") {
-      const parsed = parseFloat(raw.originalText);
-      if (!isNaN(parsed)) {
-        attributes.numericValue = parsed;
+      // Like currency, only the order of magnitude unless exact values are allowed
+      const { value, percent } = Distiller.parseNumber(raw.originalText, this.policy.locales);
+      attributes.magnitude = this.magnitudeBucket(Math.abs(value));
+      if (value < 0) {
+        attributes.negative = true;
+      }
+      if (percent) {
+        attributes.percent = true;
+      }
+      if (this.policy.allowExactValues) {
+        attributes.numericValue = value;
+      }
+    } else if (raw.entityType === "long_number") {
+      // Only the length bucket, whatever the granularity
+      const digits = Distiller.numberDigitCount(raw.originalText);
+      attributes.digitCount = digits <= 9 ? "short" : digits <= 16 ? "medium" : "long";
+    } else if (raw.entityType === "email") {
+      attributes.domainTld = this.bucketTld(raw.originalText);
+    } else if (raw.entityType === "phone") {
+      this.setCount(attributes, "digitCount", Distiller.phoneDigitCount(raw.originalText), [10, 12]);
+      attributes.hasCountryCode = /^(?:\+|00)/.test(raw.originalText);
+    } else if (raw.entityType === "payment_card") {
+      const digits = raw.originalText.replace(/\D/g, "");
+      attributes.network = this.inferCardNetwork(digits);
+      if (this.policy.allowCardLast4) {
+        attributes.last4 = digits.slice(-4);
+      }
+    } else if (raw.entityType === "date") {
+      const parts = Distiller.parseDate(raw.originalText, this.policy.locales);
+      if (parts !== null) {
+        attributes.yearBucket = `${Math.floor(parts.year / 10) * 10}s`;
+        attributes.hasTime = parts.hasTime;
+        if (this.policy.allowExactDates) {
+          attributes.year = parts.year;
+          if (parts.month !== undefined) attributes.month = parts.month;
+          if (parts.day !== undefined) attributes.day = parts.day;
+        } else if (this.policy.allowDateYear) {
+          attributes.year = parts.year;
+        }
+      }
+    } else if (raw.entityType === "url") {
+      const url = Distiller.parseUrl(raw.originalText);
+      attributes.scheme = url.scheme;
+      if (!url.isIpLiteral) {
+        attributes.domainTld = this.bucketTld(url.host);
+      }
+      this.setCount(attributes, "pathDepth", url.pathSegments.length, [1, 3]);
+    } else if (raw.entityType === "vin") {
+      const year = Distiller.vinModelYear(raw.originalText);
+      if (year !== null) {
+        attributes.yearBucket = `${Math.floor(year / 10) * 10}s`;
+      }
+    } else if (raw.entityType === "postal_code") {
+      attributes.country = Distiller.postalCountry(raw.originalText);
+      if (this.policy.allowPostalPrefix) {
+        attributes.postalPrefix = raw.originalText.slice(0, 3);
+      }
+    } else if (raw.entityType === "coordinates") {
+      const coordinates = Distiller.parseCoordinates(raw.originalText);
+      if (coordinates !== null) {
+        attributes.geoCell = this.geoCell(coordinates);
+      }
+    } else if (raw.entityType === "icd10") {
+      // Diagnosis codes are semantic content, not identifiers
+      attributes.code = raw.originalText;
+    } else if (raw.entityType === "license_plate") {
+      if (raw.plateLocale !== undefined) {
+        attributes.locale = raw.plateLocale;
+      }
+    } else if (raw.entityType === "custom") {
+      if (raw.patternName !== undefined) {
+        attributes.customPattern = raw.patternName;
+      }
+    } else if (raw.entityType === "national_id") {
+      if (raw.patternName !== undefined) {
+        attributes.idPattern = raw.patternName;
+      }
+    } else if (raw.entityType === "file_path") {
+      const path = Distiller.parsePath(raw.originalText);
+      if (path.extension !== null) {
+        attributes.extension =
+          FILE_EXTENSIONS.find((extension) => extension === path.extension) ?? "other";
+      }
+      this.setCount(attributes, "pathDepth", path.segments.length, [1, 3]);
+    } else if (raw.entityType === "secret") {
+      attributes.providerHint = Distiller.secretProvider(raw.originalText);
+    } else if (raw.entityType === "jwt") {
+      // Only the header is inspected here; claims never become attributes
+      const header = Distiller.decodeJwtHeader(raw.originalText);
+      attributes.algPresent =
+        typeof header?.alg === "string" && header.alg.toLowerCase() !== "none";
+    } else if (raw.entityType === "uuid") {
+      // The version nibble is the first character of the third group
+      attributes.version = Number(Distiller.normalizeUuid(raw.originalText).charAt(14));
+    } else if (raw.entityType === "address") {
+      const address = Distiller.parseAddress(raw.originalText);
+      attributes.hasZip = address.hasZip;
+      if (address.countryHint !== undefined) {
+        attributes.countryHint = address.countryHint;
+      }
+    } else if (raw.entityType === "mac") {
+      // Universally administered addresses start with an IEEE-assigned OUI
+      const firstOctet = parseInt(raw.originalText.slice(0, 2), 16);
+      attributes.ouiKnown = (firstOctet & 0x02) === 0;
+    } else if (raw.entityType === "ip_address") {
+      const ip = Distiller.parseIpAddress(raw.originalText);
+      if (ip !== null) {
+        attributes.family = ip.family;
+        attributes.isPrivate = this.isPrivateAddress(ip);
+        if (ip.prefixLength !== undefined) {
+          attributes.prefixLength = ip.prefixLength;
+        }
+      }
+    } else if (raw.entityType === "iban") {
+      if (this.policy.allowIbanCountry) {
+        attributes.country = raw.originalText.slice(0, 2);
       }
     }
 
@@ -113,45 +449,285 @@ export class Abstractor {
   }
 
   /**
-   * Parse numeric value from currency string.
+   * Infer the card network from the issuer identification number range.
    */
-  private parseCurrencyValue(text: string): number | null {
-    const cleaned = text.replace(/[$€£¥,\s]|USD|EUR|GBP|JPY/g, "");
-    const value = parseFloat(cleaned);
-    return isNaN(value) ? null : value;
+  private inferCardNetwork(digits: string): (typeof CARD_NETWORKS)[number] {
+    const prefix2 = Number(digits.slice(0, 2));
+    const prefix3 = Number(digits.slice(0, 3));
+    const prefix4 = Number(digits.slice(0, 4));
+
+    if (digits.startsWith("4")) return "visa";
+    if (prefix2 === 34 || prefix2 === 37) return "amex";
+    if ((prefix2 >= 51 && prefix2 <= 55) || (prefix4 >= 2221 && prefix4 <= 2720)) {
+      return "mastercard";
+    }
+    if (prefix4 === 6011 || prefix2 === 65 || (prefix3 >= 644 && prefix3 <= 649)) {
+      return "discover";
+    }
+    if (prefix4 >= 3528 && prefix4 <= 3589) return "jcb";
+    if ((prefix3 >= 300 && prefix3 <= 305) || prefix2 === 36 || prefix2 === 38) {
+      return "diners";
+    }
+    if (prefix2 === 62) return "unionpay";
+    return "unknown";
   }
 
   /**
-   * Build relations between entities based on context proximity.
-   * Entities that appear close together in the text are considered related.
+   * Check whether an address falls in a private, loopback, link-local,
+   * documentation, multicast, or otherwise reserved range.
    */
-  private buildRelations(entities: Entity[], rawContext: string): Relation[] {
-    const relations: Relation[] = [];
-    const proximityThreshold = 100; // Characters
+  private isPrivateAddress(ip: IpAddress): boolean {
+    const [a, b, c] = ip.groups;
+    if (ip.family === "ipv4") {
+      return (
+        a === 0 ||
+        a === 10 ||
+        a === 127 ||
+        a >= 224 ||
+        (a === 100 && b >= 64 && b <= 127) ||
+        (a === 169 && b === 254) ||
+        (a === 172 && b >= 16 && b <= 31) ||
+        (a === 192 && b === 0 && (c === 0 || c === 2)) ||
+        (a === 192 && b === 168) ||
+        (a === 198 && (b === 18 || b === 19)) ||
+        (a === 198 && b === 51 && c === 100) ||
+        (a === 203 && b === 0 && c === 113)
+      );
+    }
+    const leadingZeros = ip.groups.slice(0, 5).every((group) => group === 0);
+    return (
+      (leadingZeros && (ip.groups[5] === 0 || ip.groups[5] === 0xffff)) || // ::, ::1, IPv4-mapped
+      (a & 0xfe00) === 0xfc00 || // unique local
+      (a & 0xffc0) === 0xfe80 || // link-local
+      (a & 0xff00) === 0xff00 || // multicast
+      (a === 0x2001 && b === 0x0db8) // documentation
+    );
+  }
 
-    for (let i = 0; i < entities.length; i++) {
-      const entityA = entities[i];
-      const positionA = entityA.attributes.position;
-      if (typeof positionA !== "number") continue;
+  /**
+   * Reduce an email address or hostname to its bucketed top-level domain.
+   * The local part and domain name are never retained.
+   */
+  private bucketTld(text: string): string {
+    const tld = text.slice(text.lastIndexOf(".") + 1).toLowerCase();
+    const buckets: readonly string[] = TLD_BUCKETS;
+    return buckets.includes(tld) ? tld : "other";
+  }
+
+  /**
+   * Set a count attribute at the policy's granularity. v1 policies always
+   * report the exact count; v2 reports it exactly, as a bucket ("short" up
+   * to `shortMax`, "medium" up to `mediumMax`, else "long"), or not at all.
+   */
+  private setCount(
+    attributes: Record<string, string | number | boolean>,
+    key: string,
+    count: number,
+    [shortMax, mediumMax]: readonly [number, number]
+  ): void {
+    const granularity = this.policy.version === "v1" ? "exact" : this.policy.attributeGranularity;
+    if (granularity === "exact") {
+      attributes[key] = count;
+    } else if (granularity === "coarse") {
+      attributes[key] = count <= shortMax ? "short" : count <= mediumMax ? "medium" : "long";
+    }
+  }
 
-      for (let j = i + 1; j < entities.length; j++) {
-        const entityB = entities[j];
-        const positionB = entityB.attributes.position;
-        if (typeof positionB !== "number") continue;
+  /**
+   * Reduce an amount to its order of magnitude ("1e6" for 1,250,000).
+   * Amounts below 1 are bucketed as "1e0".
+   */
+  private magnitudeBucket(value: number): string {
+    // log10 is nudged so exact powers of ten land in their own bucket
+    const exponent = value >= 1 ? Math.floor(Math.log10(value) + 1e-9) : 0;
+    return `1e${exponent}`;
+  }
 
-        const distance = Math.abs(positionB - positionA);
+  /**
+   * Snap coordinates to the south-west corner of a grid cell sized by the
+   * policy's geoPrecision ("37,-123" on the 1-degree grid).
+   */
+  private geoCell({ lat, lon }: Coordinates): string {
+    const precision = this.policy.geoPrecision;
+    const size = 10 ** -precision;
+    const snap = (value: number) =>
+      (Math.floor(value / size + 1e-9) * size).toFixed(Math.max(precision, 0));
+    return `${snap(lat)},${snap(lon)}`;
+  }
 
-        if (distance <= proximityThreshold) {
-          relations.push({
-            type: this.inferRelationType(entityA, entityB, rawContext),
-            from: entityA.id,
-            to: entityB.id,
-          });
+  /**
+   * Build relations between entities based on co-occurrence: two entities
+   * are related when any of their occurrences fall within the policy's
+   * relationWindow tokens of each other (0 relates every pair). Positions
+   * are per document, so entities from different documents are only
+   * related, as "co_session", when crossDocumentRelations is set. Each
+   * pair yields one relation per verb and direction, or one untyped
+   * relation, weighted by how often it was observed.
+   */
+  private buildRelations(
+    entities: Entity[],
+    rawContext: string | readonly string[],
+    tokenSpans: readonly number[],
+    temporalKeys: ReadonlyArray<number | null>
+  ): { relations: Relation[]; truncated: boolean; filtered: number } {
+    const window = this.policy.relationWindow;
+    const { maxRelations, truncateRelations, crossDocumentRelations } = this.policy;
+    const sameDocument = (i: number, j: number): boolean =>
+      entities[i].attributes.sourceDoc === entities[j].attributes.sourceDoc;
+    let pairs: Array<[number, number]> = [];
+    // Co-occurring occurrence pairs per entity pair, keyed by i * n + j
+    const counts = new Map<number, number>();
+    let truncated = false;
+
+    // Enforce the cap as pairs are produced so a pathological input
+    // never materializes the full quadratic set
+    const overLimit = (): boolean => {
+      if (pairs.length <= maxRelations) return false;
+      if (!truncateRelations) {
+        throw new TransformationError("Relation limit exceeded");
+      }
+      truncated = true;
+      return true;
+    };
+
+    if (window === 0) {
+      // Unlimited: every pair within the same document, produced in order
+      outer: for (let i = 0; i < entities.length; i++) {
+        for (let j = i + 1; j < entities.length; j++) {
+          if (sameDocument(i, j)) {
+            pairs.push([i, j]);
+            counts.set(
+              i * entities.length + j,
+              (entities[i].tokenPositions?.length ?? 1) * (entities[j].tokenPositions?.length ?? 1)
+            );
+            if (overLimit()) break outer;
+          } else if (crossDocumentRelations) {
+            pairs.push([i, j]);
+            if (overLimit()) break outer;
+          }
+        }
+      }
+    } else {
+      // Sweep occurrences in document/token order; each one only reaches
+      // the occurrences that follow within the window
+      const occurrences = entities
+        .flatMap((entity, index) =>
+          (entity.tokenPositions ?? []).map((token) => ({
+            doc: Number(entity.attributes.sourceDoc ?? 0),
+            token,
+            index,
+          }))
+        )
+        .sort((a, b) => a.doc - b.doc || a.token - b.token || a.index - b.index);
+      for (let k = 0; k < occurrences.length; k++) {
+        const a = occurrences[k];
+        for (let l = k + 1; l < occurrences.length; l++) {
+          const b = occurrences[l];
+          if (b.doc !== a.doc || b.token - a.token > window) break;
+          if (a.index === b.index) continue;
+          const [i, j] = a.index < b.index ? [a.index, b.index] : [b.index, a.index];
+          const key = i * entities.length + j;
+          const count = counts.get(key) ?? 0;
+          counts.set(key, count + 1);
+          if (count === 0) {
+            pairs.push([i, j]);
+            // Sweep order is not output order, so keep the lowest pairs
+            if (overLimit() && pairs.length > 2 * maxRelations) {
+              pairs = byEntityOrder(pairs).slice(0, maxRelations);
+            }
+          }
         }
       }
+      if (crossDocumentRelations) {
+        for (let i = 0; i < entities.length; i++) {
+          for (let j = i + 1; j < entities.length; j++) {
+            if (!sameDocument(i, j)) {
+              pairs.push([i, j]);
+              if (overLimit() && pairs.length > 2 * maxRelations) {
+                pairs = byEntityOrder(pairs).slice(0, maxRelations);
+              }
+            }
+          }
+        }
+      }
+      pairs = byEntityOrder(pairs);
+    }
+
+    const documents = (typeof rawContext === "string" ? [rawContext] : rawContext).map((document) =>
+      Array.from(document.normalize("NFC").matchAll(/\S+/g), (match) => match[0].toLowerCase())
+    );
+    let relations: Relation[] = [];
+    for (const [i, j] of pairs.slice(0, maxRelations)) {
+      if (!sameDocument(i, j)) {
+        relations.push({ type: "co_session", from: entities[i].id, to: entities[j].id });
+        continue;
+      }
+      const tokens = documents[Number(entities[i].attributes.sourceDoc ?? 0)] ?? [];
+      const typed = [
+        ...this.verbRelations(entities, tokenSpans, i, j, tokens),
+        ...this.verbRelations(entities, tokenSpans, j, i, tokens),
+      ];
+      if (typed.length > 0) {
+        relations.push(...typed);
+      } else {
+        relations.push(
+          withWeight(
+            {
+              type:
+                temporalOrder(temporalKeys[i], temporalKeys[j]) ??
+                this.inferRelationType(entities[i], entities[j], rawContext),
+              from: entities[i].id,
+              to: entities[j].id,
+            },
+            counts.get(i * entities.length + j) ?? 1
+          )
+        );
+      }
     }
+    // Filter by type before the final cap so dropped types never crowd
+    // out allowed ones
+    const allowedTypes = this.policy.allowedRelationTypes;
+    const built = relations.length;
+    if (allowedTypes !== null) {
+      relations = relations.filter((relation) => allowedTypes.includes(relation.type));
+    }
+    const filtered = built - relations.length;
 
-    return relations;
+    // A pair typed by several verbs can push the count past the cap
+    if (relations.length > maxRelations) {
+      if (!truncateRelations) {
+        throw new TransformationError("Relation limit exceeded");
+      }
+      truncated = true;
+      relations = relations.slice(0, maxRelations);
+    }
+    return { relations, truncated, filtered };
+  }
+
+  /**
+   * Type relations by the verb between two entities: an occurrence of the
+   * subject followed by exactly one lexicon verb and then an occurrence of
+   * the object ("Alice paid Bob"). Repeats of one verb are weighted.
+   */
+  private verbRelations(
+    entities: Entity[],
+    tokenSpans: readonly number[],
+    subject: number,
+    object: number,
+    tokens: readonly string[]
+  ): Relation[] {
+    const objectPositions = new Set(entities[object].tokenPositions ?? []);
+    const verbCounts = new Map<string, number>();
+    for (const position of entities[subject].tokenPositions ?? []) {
+      const verbPosition = position + tokenSpans[subject];
+      const verb = tokens[verbPosition];
+      if (verb !== undefined && this.relationVerbs.has(verb) && objectPositions.has(verbPosition + 1)) {
+        verbCounts.set(verb, (verbCounts.get(verb) ?? 0) + 1);
+      }
+    }
+    return Array.from(verbCounts, ([type, count]) =>
+      withWeight({ type, from: entities[subject].id, to: entities[object].id }, count)
+    );
   }
 
   /**
@@ -160,7 +736,7 @@ export class Abstractor {
   private inferRelationType(
     entityA: Entity,
     entityB: Entity,
-    _rawContext: string
+    _rawContext: string | readonly string[]
   ): string {
     // Infer based on role combinations
     if (entityA.role === Role.Actor && entityB.role === Role.Value) {
@@ -175,11 +751,51 @@ export class Abstractor {
     if (entityA.role === Role.Value && entityB.role === Role.Temporal) {
       return "dated";
     }
-    if (entityA.role === Role.Temporal && entityB.role === Role.Temporal) {
-      return "precedes";
-    }
 
     return "related";
   }
+
+  /**
+   * Sort key for ordering date entities, at the policy's temporal
+   * precision: the day with allowExactDates, otherwise the decade
+   * reported as `yearBucket`. The key is compared inside the transform
+   * and never emitted.
+   *
+   * @returns Key, or null for non-dates and dates that do not parse to a
+   *          single day
+   */
+  private temporalKey(raw: RawEntity): number | null {
+    if (raw.entityType !== "date") return null;
+    const parts = Distiller.parseDate(raw.originalText, this.policy.locales);
+    // Ambiguous slash dates ("03/04/2024") leave month and day unset
+    if (parts === null || parts.month === undefined || parts.day === undefined) {
+      return null;
+    }
+    return this.policy.allowExactDates
+      ? parts.year * 10_000 + parts.month * 100 + parts.day
+      : Math.floor(parts.year / 10);
+  }
 }
 
+/**
+ * Order relation between two dates by their temporal keys, from the
+ * first entity's point of view. Equal or unknown keys give no ordering.
+ */
+function temporalOrder(a: number | null, b: number | null): "before" | "after" | null {
+  if (a === null || b === null || a === b) return null;
+  return a < b ? "before" : "after";
+}
+
+/**
+ * Sort entity index pairs into output order: by first entity, then second.
+ */
+function byEntityOrder(pairs: Array<[number, number]>): Array<[number, number]> {
+  return pairs.sort((a, b) => a[0] - b[0] || a[1] - b[1]);
+}
+
+/**
+ * Attach a weight to a relation observed more than once.
+ */
+function withWeight(relation: Relation, weight: number): Relation {
+  return weight > 1 ? { ...relation, weight } : relation;
+}
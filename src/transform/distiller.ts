@@ -1,5 +1,571 @@
 import type { RawEntity } from "../entities/entity.ts";
 import { TransformationError } from "../core/errors.ts";
+import type { CustomPattern, IdPattern, MaskingPolicy } from "../core/policy.ts";
+import {
+  DEFAULT_MASKING_POLICY,
+  PLATE_LOCALES,
+  hasLocaleRegion,
+  inputSizeLimit,
+  isRecognizerEnabled,
+  localeLanguage,
+  localeRegion,
+} from "../core/policy.ts";
+import { clearReference } from "../runtime/memory.ts";
+import { lookupPlace } from "./gazetteer.ts";
+import { isDictionaryWord } from "./wordlist.ts";
+import { Role } from "../entities/roles.ts";
+
+/**
+ * IBAN lengths by ISO 3166 country code (SWIFT IBAN registry).
+ */
+export const IBAN_LENGTHS: Readonly<Record<string, number>> = {
+  AD: 24, AE: 23, AL: 28, AT: 20, AZ: 28, BA: 20, BE: 16, BG: 22,
+  BH: 22, BR: 29, BY: 28, CH: 21, CR: 22, CY: 28, CZ: 24, DE: 22,
+  DK: 18, DO: 28, EE: 20, EG: 29, ES: 24, FI: 18, FO: 18, FR: 27,
+  GB: 22, GE: 22, GI: 23, GL: 18, GR: 27, GT: 28, HR: 21, HU: 28,
+  IE: 22, IL: 23, IQ: 23, IS: 26, IT: 27, JO: 30, KW: 30, KZ: 20,
+  LB: 28, LC: 32, LI: 21, LT: 20, LU: 20, LV: 21, MC: 27, MD: 24,
+  ME: 22, MK: 19, MR: 27, MT: 31, MU: 30, NL: 18, NO: 15, PK: 24,
+  PL: 28, PS: 29, PT: 25, QA: 29, RO: 24, RS: 22, SA: 24, SC: 31,
+  SE: 24, SI: 19, SK: 24, SM: 27, ST: 25, SV: 28, TL: 23, TN: 24,
+  TR: 26, UA: 29, VA: 22, VG: 24, XK: 20,
+};
+
+/**
+ * URL schemes recognized by the URL pattern.
+ */
+export const URL_SCHEMES = ["http", "https", "ftp", "ws", "wss"] as const;
+
+/**
+ * Components of a URL match, as written in the input.
+ */
+export interface UrlParts {
+  scheme: string;
+  userinfo?: string;
+  host: string;
+  isIpLiteral: boolean;
+  port?: string;
+  pathSegments: string[];
+  query: Array<[string, string]>;
+  fragment?: string;
+}
+
+/**
+ * Components of a file path match.
+ */
+export interface FilePath {
+  /** Path segments as written, excluding the root, drive, "." and "..". */
+  segments: string[];
+  /** Lowercased extension of the final segment, if any. */
+  extension: string | null;
+}
+
+/**
+ * Latitude and longitude in decimal degrees.
+ */
+export interface Coordinates {
+  lat: number;
+  lon: number;
+}
+
+/**
+ * IP address parsed from an address match.
+ */
+export interface IpAddress {
+  family: "ipv4" | "ipv6";
+
+  /**
+   * Octets (IPv4) or 16-bit groups (IPv6), fully expanded.
+   */
+  groups: number[];
+
+  /**
+   * CIDR prefix length, if the match carried one.
+   */
+  prefixLength?: number;
+}
+
+/**
+ * Well-known credential formats, keyed by provider hint.
+ * Checked in order; the first matching provider wins.
+ */
+const SECRET_PATTERNS: ReadonlyArray<readonly [string, RegExp]> = [
+  [
+    "private_key",
+    /-----BEGIN (?:[A-Z0-9]+ )*PRIVATE KEY-----[\s\S]*?-----END (?:[A-Z0-9]+ )*PRIVATE KEY-----/g,
+  ],
+  ["aws", /\b(?:AKIA|ASIA|AGPA|AIDA|AROA|ANPA|ANVA|AIPA)[A-Z0-9]{16}\b/g],
+  ["github", /\b(?:gh[pousr]_[A-Za-z0-9]{36,255}|github_pat_[A-Za-z0-9_]{22,255})\b/g],
+  ["slack", /\bxox[abposr]-[A-Za-z0-9-]{10,}/g],
+  ["stripe", /\b(?:sk|rk|pk)_(?:live|test)_[A-Za-z0-9]{16,}\b/g],
+  ["google", /\bAIza[0-9A-Za-z_-]{35}(?![\w-])/g],
+];
+
+/**
+ * Provider hints that may be reported for credential entities.
+ */
+export const SECRET_PROVIDERS = [
+  ...SECRET_PATTERNS.map(([provider]) => provider),
+  "generic",
+  "hex_digest",
+];
+
+/**
+ * ISO 4217 codes recognized next to currency amounts.
+ */
+export const CURRENCY_CODES = [
+  "USD",
+  "EUR",
+  "GBP",
+  "JPY",
+  "CHF",
+  "CAD",
+  "AUD",
+  "CNY",
+  "INR",
+] as const;
+
+/**
+ * ISO 4217 codes for recognized currency symbols.
+ */
+const CURRENCY_SYMBOLS: Readonly<Record<string, (typeof CURRENCY_CODES)[number]>> = {
+  $: "USD",
+  "€": "EUR",
+  "£": "GBP",
+  "¥": "JPY",
+};
+
+/**
+ * Multipliers for amount suffixes ("3.2M").
+ */
+const AMOUNT_MULTIPLIERS: Readonly<Record<string, number>> = {
+  K: 1e3,
+  M: 1e6,
+  B: 1e9,
+};
+
+/**
+ * How a locale writes decimals: "dot" is 1,234.56, "comma" is 1.234,56.
+ */
+type DecimalConvention = "dot" | "comma";
+
+/**
+ * Languages that write a decimal comma and group thousands with dots.
+ */
+const DECIMAL_COMMA_LANGUAGES = new Set([
+  "cs", "da", "de", "el", "es", "fi", "fr", "hu", "id", "it", "nb", "nl",
+  "nn", "no", "pl", "pt", "ro", "ru", "sk", "sv", "tr", "uk",
+]);
+
+/**
+ * Regions that write slash dates month-first, plus the other members of
+ * the North American Numbering Plan, which share US phone conventions.
+ */
+const MONTH_FIRST_REGIONS = new Set(["US", "PH", "FM", "MH", "PW", "AS", "GU", "MP", "PR", "VI"]);
+const NANP_REGIONS = new Set(["US", "CA", "AS", "GU", "MP", "PR", "VI", "BS", "BB", "JM", "TT", "DO"]);
+
+/**
+ * Digits with thousands separators or plain, and optional decimals,
+ * for each decimal convention.
+ */
+const GROUPED_DIGITS: Readonly<Record<DecimalConvention, string>> = {
+  dot: String.raw`(?:\d{1,3}(?:,\d{3})+|\d+)(?:\.\d+)?`,
+  comma: String.raw`(?:\d{1,3}(?:\.\d{3})+|\d+)(?:,\d+)?`,
+};
+
+/**
+ * Whole-token forms of GROUPED_DIGITS (plus a bare ".5" for dot).
+ */
+const DECIMAL_LITERALS: Readonly<Record<DecimalConvention, RegExp>> = {
+  dot: /^(?:(?:\d{1,3}(?:,\d{3})+|\d+)(?:\.\d+)?|\.\d+)$/,
+  comma: /^(?:\d{1,3}(?:\.\d{3})+|\d+)(?:,\d+)?$/,
+};
+
+/**
+ * Decimal conventions of a locale list, each once, in list order.
+ * An empty list falls back to the dot convention.
+ */
+function decimalConventions(locales: string[]): DecimalConvention[] {
+  const conventions = locales.map(
+    (locale): DecimalConvention => (DECIMAL_COMMA_LANGUAGES.has(localeLanguage(locale)) ? "comma" : "dot")
+  );
+  return conventions.length > 0 ? [...new Set(conventions)] : ["dot"];
+}
+
+/**
+ * Rewrite a digit run in the first convention it is well-formed under
+ * as a plain dot-decimal literal ("1.234,56" -> "1234.56" for comma).
+ * Runs that fit no convention are read as dot-decimal.
+ */
+function normalizeDecimal(digits: string, conventions: DecimalConvention[]): string {
+  const convention = conventions.find((candidate) => DECIMAL_LITERALS[candidate].test(digits));
+  return convention === "comma"
+    ? digits.replace(/\./g, "").replace(",", ".")
+    : digits.replace(/,/g, "");
+}
+
+/**
+ * Amount with thousands separators or plain digits, optional decimals,
+ * and an optional K/M/B multiplier. With several conventions each
+ * alternative must consume the whole digit run, so "1.234,56" is not
+ * cut short at "1.234".
+ */
+function amountPattern(conventions: DecimalConvention[]): string {
+  const alternatives = conventions.map((convention) => String.raw`${GROUPED_DIGITS[convention]}(?![.,]\d)`);
+  const digits =
+    conventions.length === 1 ? GROUPED_DIGITS[conventions[0]] : `(?:${alternatives.join("|")})`;
+  return String.raw`${digits}(?:\s?[KkMB](?!\w))?`;
+}
+
+/**
+ * Currency amount parsed from a currency match.
+ */
+export interface CurrencyAmount {
+  code: (typeof CURRENCY_CODES)[number];
+  value: number;
+}
+
+/**
+ * Numeric literal parsed from a number match.
+ */
+export interface NumericLiteral {
+  value: number;
+  percent: boolean;
+}
+
+/**
+ * USPS codes for US states, DC, and territories.
+ */
+const US_STATE_CODES = new Set([
+  "AL", "AK", "AZ", "AR", "CA", "CO", "CT", "DE", "DC", "FL", "GA", "HI",
+  "ID", "IL", "IN", "IA", "KS", "KY", "LA", "ME", "MD", "MA", "MI", "MN",
+  "MS", "MO", "MT", "NE", "NV", "NH", "NJ", "NM", "NY", "NC", "ND", "OH",
+  "OK", "OR", "PA", "RI", "SC", "SD", "TN", "TX", "UT", "VT", "VA", "WA",
+  "WV", "WI", "WY", "AS", "GU", "MP", "PR", "VI",
+]);
+
+/**
+ * Countries whose postal code formats are recognized.
+ */
+export const POSTAL_COUNTRIES = ["US", "GB", "CA"] as const;
+
+/**
+ * Structure of a street address match.
+ */
+export interface PostalAddress {
+  hasZip: boolean;
+
+  /**
+   * Country inferred from the state/ZIP tail, if any.
+   */
+  countryHint?: "US";
+}
+
+/**
+ * Unicode-aware word boundaries (`\b` only considers ASCII word characters).
+ */
+const WORD_START = String.raw`(?<![\p{L}\p{M}\p{N}_])`;
+const WORD_END = String.raw`(?![\p{L}\p{M}\p{N}_])`;
+
+/**
+ * A capitalized name word in any cased script (Latin, Cyrillic, Greek),
+ * allowing an O'/D'-style prefix and hyphenated parts ("O'Leary",
+ * "Smith-Jones").
+ */
+const NAME_WORD = String.raw`(?:\p{Lu}['’])?\p{Lu}[\p{Ll}\p{M}]+(?:-\p{Lu}[\p{Ll}\p{M}]+)*`;
+
+/**
+ * Lowercase particles that may join the words of a name.
+ */
+const NAME_PARTICLE = String.raw`(?:de|da|del|della|di|du|la|le|van|von|der|den|ter|ten|bin|ibn|al)`;
+
+/**
+ * A run of name words on one line, optionally led or joined by particles.
+ */
+const NAME_RUN = String.raw`(?:${NAME_PARTICLE}[ \t]+)*${NAME_WORD}(?:[ \t]+(?:${NAME_PARTICLE}[ \t]+)*${NAME_WORD})*`;
+
+/**
+ * License plate formats by jurisdiction. Plates are matched in uppercase
+ * only, so ordinary prose is not mistaken for a plate.
+ */
+const PLATE_PATTERNS: Readonly<Record<(typeof PLATE_LOCALES)[number], RegExp>> = {
+  // "ABC-1234", "ABC 123", California "7ABC123"
+  us: /\b(?:[A-Z]{3}[- ]?\d{3,4}|\d[A-Z]{3}\d{3})\b/g,
+  // "AB12 CDE"
+  gb: /\b[A-Z]{2}\d{2} ?[A-Z]{3}\b/g,
+  // "B-MW 1234", "M-AB 123E" (district, letters, digits, electric/historic suffix)
+  de: /(?<![\p{L}\p{N}])\p{Lu}{1,3}-[A-Z]{1,2} ?\d{1,4}[EH]?\b/gu,
+  // "AB-123-CD"
+  fr: /\b[A-Z]{2}-\d{3}-[A-Z]{2}\b/g,
+  // "MH 12 AB 1234"
+  in: /\b[A-Z]{2}[ -]?\d{1,2}[ -]?[A-Z]{1,3}[ -]?\d{4}\b/g,
+};
+
+/**
+ * VIN character values for A-Z (I, O, and Q are not used).
+ */
+const VIN_TRANSLITERATION = [
+  1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 2, 3, 4, 5, 0, 7, 0, 9, 2, 3, 4, 5, 6, 7, 8, 9,
+];
+
+/**
+ * VIN position weights; position 9 holds the check digit.
+ */
+const VIN_WEIGHTS = [8, 7, 6, 5, 4, 3, 2, 10, 0, 9, 8, 7, 6, 5, 4, 3, 2];
+
+/**
+ * VIN model-year codes in order from the start of a 30-year cycle.
+ */
+const VIN_YEAR_CODES = "ABCDEFGHJKLMNPRSTVWXY123456789";
+
+/**
+ * Verhoeff dihedral group D5 multiplication table.
+ */
+const VERHOEFF_MULTIPLICATION = [
+  [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+  [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+  [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+  [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+  [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+  [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+  [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+  [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+  [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+  [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+/**
+ * Verhoeff position-dependent permutation table.
+ */
+const VERHOEFF_PERMUTATION = [
+  [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+  [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+  [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+  [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+  [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+  [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+  [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+  [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+/**
+ * A single file path segment: may start with a dot (".ssh") but not end
+ * with one, so sentence punctuation stays outside the match.
+ */
+const PATH_SEGMENT = String.raw`\.?[\p{L}\p{N}_@%+~$-](?:[\p{L}\p{N}_.@%+~$-]*[\p{L}\p{N}_@%+~$-])?`;
+
+/**
+ * Fold text for leak comparison: strip diacritics and lowercase, so
+ * "José" and "JOSE" both compare equal to "jose".
+ *
+ * @param text - Text to fold
+ * @returns Accent- and case-folded text
+ */
+export function foldForMatching(text: string): string {
+  return text.normalize("NFD").replace(/\p{M}/gu, "").toLowerCase();
+}
+
+/**
+ * Writing systems reported for name entities.
+ */
+export const NAME_SCRIPTS = [
+  "latin",
+  "cyrillic",
+  "greek",
+  "han",
+  "hangul",
+  "arabic",
+  "other",
+] as const;
+
+/**
+ * Words after a CJK name that mark it as a person: honorific suffixes
+ * (さん, 様, 氏, 先生, 씨, ...) or English verbs of agency.
+ */
+const CJK_NAME_CUE = String.raw`(?:さん|様|氏|先生|女士|小姐|君|씨|님|\s+(?:signed|met|said|wrote|sent|called|approved|agreed|joined|reported)\b)`;
+
+/**
+ * Arabic titles that introduce a person's name (Mr, Mrs, Dr, Prof, Sheikh).
+ */
+const ARABIC_NAME_CUE = String.raw`(?:السيد|السيدة|الدكتور|الدكتورة|الأستاذ|الأستاذة|الشيخ)`;
+
+/**
+ * An Arabic word, including combining marks (harakat are Script=Inherited).
+ */
+const ARABIC_WORD = String.raw`\p{sc=Arabic}[\p{sc=Arabic}\p{M}]*`;
+
+/**
+ * Legal-form suffixes that mark an organization name.
+ * Extended per policy via MaskingPolicy.organizationSuffixes.
+ */
+export const ORGANIZATION_SUFFIXES = [
+  "Inc",
+  "Incorporated",
+  "LLC",
+  "LLP",
+  "Ltd",
+  "Limited",
+  "Corp",
+  "Corporation",
+  "Co",
+  "PLC",
+  "GmbH",
+  "AG",
+  "KG",
+  "S.A.",
+  "SA",
+  "SAS",
+  "S.p.A.",
+  "BV",
+  "NV",
+  "Pty Ltd",
+] as const;
+
+/**
+ * A word of an organization name: capitalized or all-caps, possibly
+ * containing digits, ampersands, apostrophes, or hyphens ("3M", "AT&T").
+ */
+const ORGANIZATION_WORD = String.raw`(?:[A-Z]|\d+[A-Za-z])[A-Za-z0-9&'’-]*`;
+
+/**
+ * Honorifics recognized before a name, normalized to lowercase.
+ */
+export const HONORIFICS = [
+  "dr",
+  "mr",
+  "mrs",
+  "ms",
+  "mx",
+  "prof",
+  "sir",
+  "dame",
+  "rev",
+  "hon",
+] as const;
+
+/**
+ * Month name prefixes, indexed from January.
+ */
+const MONTH_PREFIXES = [
+  "jan",
+  "feb",
+  "mar",
+  "apr",
+  "may",
+  "jun",
+  "jul",
+  "aug",
+  "sep",
+  "oct",
+  "nov",
+  "dec",
+];
+
+/**
+ * Day and month names a capitalized run may be. Weekdays are always
+ * Temporal; months only after a temporal cue, since "May" and "June"
+ * are also given names.
+ */
+const WEEKDAY_NAMES = new Set([
+  "monday",
+  "tuesday",
+  "wednesday",
+  "thursday",
+  "friday",
+  "saturday",
+  "sunday",
+]);
+const MONTH_NAMES = new Set([
+  "january",
+  "february",
+  "march",
+  "april",
+  "may",
+  "june",
+  "july",
+  "august",
+  "september",
+  "october",
+  "november",
+  "december",
+]);
+
+/**
+ * Cue phrases that end directly before a capitalized run, checked in
+ * order so longer cues ("works at", "priced at") win over the bare
+ * preposition ("at"). The first matching cue decides the role.
+ */
+const ROLE_CUES: ReadonlyArray<readonly [Role, RegExp]> = [
+  [
+    Role.Organization,
+    /\b(?:works?|worked|working) (?:at|for)\s+$|\b(?:employed (?:at|by)|hired by|joined|subsidiary of|acquired by)\s+$/i,
+  ],
+  [
+    Role.Value,
+    /\b(?:paid for|invoiced? for|invoice|billed for|fee for|price of|cost of|priced at)\s+$/i,
+  ],
+  [Role.Temporal, /\b(?:on|by|until|since|before|after|during|next|last|every|this|in)\s+$/i],
+  [Role.Location, /\b(?:in|at|near|inside|outside|across|throughout)\s+$/i],
+];
+
+/**
+ * Fixed confidence score per recognizer, in hundredths. Checksum- or
+ * structure-validated matches score highest; heuristic capitalized runs
+ * lowest. Names are further adjusted in Distiller.confidence:
+ *
+ * | Name match                              | Score |
+ * |-----------------------------------------|-------|
+ * | With an honorific ("Dr. Okafor")        | 0.9   |
+ * | Two or more words ("Alice Smith")       | 0.7   |
+ * | One word mid-sentence ("Alice")         | 0.5   |
+ * | One word at a sentence start ("Alice")  | 0.3   |
+ */
+export const ENTITY_CONFIDENCE: Readonly<Record<RawEntity["entityType"], number>> = {
+  restricted: 1,
+  payment_card: 0.99,
+  iban: 0.99,
+  vin: 0.99,
+  npi: 0.99,
+  uuid: 0.99,
+  jwt: 0.99,
+  email: 0.98,
+  url: 0.98,
+  currency: 0.95,
+  ip_address: 0.95,
+  mac: 0.95,
+  secret: 0.95,
+  national_id: 0.9,
+  ssn: 0.9,
+  custom: 0.9,
+  coordinates: 0.9,
+  file_path: 0.9,
+  date: 0.9,
+  number: 0.9,
+  mrn: 0.85,
+  icd10: 0.85,
+  phone: 0.85,
+  address: 0.85,
+  organization: 0.85,
+  postal_code: 0.8,
+  handle: 0.8,
+  place: 0.8,
+  license_plate: 0.75,
+  serial: 0.6,
+  long_number: 0.6,
+  identifier: 0.6,
+  name: 0.5,
+};
+
+/**
+ * Calendar components parsed from a date match.
+ * Month and day are absent when the format leaves them ambiguous.
+ */
+export interface DateParts {
+  year: number;
+  month?: number;
+  day?: number;
+  hasTime: boolean;
+}
 
 /**
  * Deterministic semantic distiller.
@@ -9,73 +575,766 @@ import { TransformationError } from "../core/errors.ts";
 export class Distiller {
   /**
    * Pattern for capitalized words (potential names).
-   * Matches words starting with uppercase, at least 2 characters.
+   * Matches runs of words starting with uppercase, at least 2 characters,
+   * on one line, optionally joined by lowercase name particles
+   * ("Ludwig van Beethoven", "Maria de la Cruz").
+   */
+  private static readonly NAME_PATTERN = new RegExp(
+    String.raw`${WORD_START}${NAME_WORD}(?:[ \t]+(?:${NAME_PARTICLE}[ \t]+)*${NAME_WORD})*${WORD_END}`,
+    "gu"
+  );
+
+  /**
+   * Pattern for names in uncased CJK scripts: a 2-4 character Han or
+   * Hangul run directly followed by a person cue ("田中太郎さん",
+   * "王伟 signed"). Only the run (the "value" group) becomes the entity.
+   */
+  private static readonly CJK_NAME_PATTERN = new RegExp(
+    String.raw`(?<![\p{sc=Han}\p{sc=Hangul}])(?<value>\p{sc=Han}{2,4}|\p{sc=Hangul}{2,4})(?=${CJK_NAME_CUE})`,
+    "dgu"
+  );
+
+  /**
+   * Pattern for Arabic names introduced by a title ("السيد أحمد").
+   * Takes the first word plus a second word unless it starts with the
+   * definite article, which usually marks a following noun.
    */
-  private static readonly NAME_PATTERN = /\b[A-Z][a-z]+(?:\s+[A-Z][a-z]+)*\b/g;
+  private static readonly ARABIC_NAME_PATTERN = new RegExp(
+    String.raw`${ARABIC_NAME_CUE}\s+(?<value>${ARABIC_WORD}(?:\s+(?!ال)${ARABIC_WORD})?)${WORD_END}`,
+    "dgu"
+  );
+
+  /**
+   * Pattern for an honorific followed by a name ("Dr. Chen",
+   * "Prof. van der Berg"). The honorific is part of the match so it
+   * does not become an entity of its own.
+   */
+  private static readonly HONORIFIC_NAME_PATTERN = new RegExp(
+    String.raw`\b(?:Dr|Mr|Mrs|Ms|Mx|Prof|Sir|Dame|Rev|Hon)\.?[ \t]+${NAME_RUN}${WORD_END}`,
+    "gu"
+  );
 
   /**
    * Pattern for dates in common formats.
-   * Matches: YYYY-MM-DD, MM/DD/YYYY, DD/MM/YYYY, Month DD YYYY, etc.
+   * Matches: ISO 8601 dates with optional time and offset, MM/DD/YYYY,
+   * DD/MM/YYYY, "March 15, 2024", "15 Mar 2024", etc.
+   * Slash forms require a year, so fractions like "3/4" are not dates.
    */
   private static readonly DATE_PATTERN =
-    /\b(?:\d{4}-\d{2}-\d{2}|\d{1,2}\/\d{1,2}\/\d{2,4}|(?:January|February|March|April|May|June|July|August|September|October|November|December)\s+\d{1,2},?\s+\d{4})\b/gi;
+    /\b(?:\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?)?|\d{1,2}\/\d{1,2}\/(?:\d{4}|\d{2})|(?:Jan(?:uary)?|Feb(?:ruary)?|Mar(?:ch)?|Apr(?:il)?|May|June?|July?|Aug(?:ust)?|Sep(?:t(?:ember)?)?|Oct(?:ober)?|Nov(?:ember)?|Dec(?:ember)?)\.?\s+\d{1,2}(?:st|nd|rd|th)?,?\s+\d{4}|\d{1,2}(?:st|nd|rd|th)?\s+(?:Jan(?:uary)?|Feb(?:ruary)?|Mar(?:ch)?|Apr(?:il)?|May|June?|July?|Aug(?:ust)?|Sep(?:t(?:ember)?)?|Oct(?:ober)?|Nov(?:ember)?|Dec(?:ember)?)\.?,?\s+\d{4})(?![\w:])/gi;
+
+  /**
+   * Pattern for URLs with a recognized scheme.
+   * Trailing sentence punctuation is not part of the match.
+   */
+  private static readonly URL_PATTERN = new RegExp(
+    String.raw`\b(?:${URL_SCHEMES.join("|")}):\/\/[^\s<>"']*[^\s<>"'.,;:!?)]`,
+    "gi"
+  );
+
+  /**
+   * Pattern for RFC 4122 UUIDs (versions 1-5), bare or braced,
+   * in either case.
+   */
+  private static readonly UUID_PATTERN =
+    /\{[0-9a-f]{8}-[0-9a-f]{4}-[1-5][0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}\}|\b[0-9a-f]{8}-[0-9a-f]{4}-[1-5][0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}\b/gi;
+
+  /**
+   * Pattern for JWTs: base64url header and payload (both JSON objects,
+   * so they start with "eyJ") and an optional signature, joined by dots.
+   */
+  private static readonly JWT_PATTERN =
+    /\beyJ[A-Za-z0-9_-]+\.eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*/g;
+
+  /**
+   * Pattern for long base64/base64url/hex tokens. Candidates must look
+   * random (see isHighEntropyToken) to be treated as generic secrets.
+   */
+  private static readonly GENERIC_SECRET_PATTERN =
+    /(?<![\w+/-])[A-Za-z0-9+/_-]{32,}={0,2}(?![\w+/=-])/g;
+
+  /**
+   * Minimum Shannon entropy (bits per character) for a generic token
+   * to be treated as a secret.
+   */
+  private static readonly MIN_SECRET_ENTROPY = 3.5;
+
+  /**
+   * Pattern for email addresses.
+   * Handles plus-addressing, subdomains, and mixed case.
+   */
+  private static readonly EMAIL_PATTERN =
+    /[a-zA-Z0-9._%+-]+@(?:[a-zA-Z0-9-]+\.)+[a-zA-Z]{2,}\b/g;
+
+  /**
+   * Pattern for a quoted path, which may contain spaces
+   * ("'/home/jdoe/My Documents/cv.pdf'"). Only the path inside the quotes
+   * is extracted.
+   */
+  private static readonly QUOTED_PATH_PATTERN = new RegExp(
+    String.raw`(?<quote>["'])(?<value>(?:[A-Za-z]:[\\/]|\\\\|~/|\.{0,2}/)[^"'\r\n]+)\k<quote>`,
+    "dgu"
+  );
+
+  /**
+   * Pattern for unquoted POSIX paths: absolute paths with at least two
+   * segments ("/home/jdoe/notes.txt"), or paths relative to "~", "." or
+   * "..". The lookbehind rejects fractions, dates, and CIDR suffixes.
+   */
+  private static readonly POSIX_PATH_PATTERN = new RegExp(
+    String.raw`(?<![\p{L}\p{N}_.~/:\\-])(?:(?:~|\.{1,2})(?:/${PATH_SEGMENT})+|(?:/${PATH_SEGMENT}){2,})/?(?![\p{L}\p{N}_/])`,
+    "gu"
+  );
+
+  /**
+   * Pattern for unquoted Windows paths, drive-rooted
+   * ("C:\\Users\\JDoe\\payroll.xlsx") or UNC ("\\\\server\\share\\file").
+   */
+  private static readonly WINDOWS_PATH_PATTERN = new RegExp(
+    String.raw`(?<![\p{L}\p{N}_])(?:[A-Za-z]:|\\\\${PATH_SEGMENT})(?:[\\/]${PATH_SEGMENT})+[\\/]?(?![\p{L}\p{N}_\\/])`,
+    "gu"
+  );
+
+  /**
+   * Pattern for `@`-prefixed handles ("@jdoe_1987"). The lookarounds keep
+   * email addresses and scoped package names ("@scope/pkg") from matching.
+   */
+  private static readonly HANDLE_PATTERN =
+    /(?<![\w.@/])@[A-Za-z0-9_](?:[A-Za-z0-9_.-]{0,38}[A-Za-z0-9_])?(?![\w@/])/g;
+
+  /**
+   * Pattern for a bare username after a cue word ("user jdoe",
+   * "login: jdoe_1987"). Only lowercase tokens qualify, so names and
+   * sentence-initial words are left to the name recognizers.
+   */
+  private static readonly USERNAME_PATTERN =
+    /\b(?:[Uu]ser(?:name)?|[Ll]ogin|[Aa]ccount|[Hh]andle)[ \t]*[:=]?[ \t]*(?<value>[a-z][a-z0-9_.-]{0,30}[a-z0-9_])(?![\w@])/dg;
+
+  /**
+   * Words that commonly follow a username cue in prose ("the account
+   * was closed") and are never taken as a username.
+   */
+  private static readonly USERNAME_STOPWORDS = new Set([
+    "a", "an", "and", "are", "as", "at", "be", "by", "can", "could", "did",
+    "for", "from", "had", "has", "have", "in", "is", "it", "may", "must",
+    "not", "of", "on", "or", "should", "that", "the", "this", "to", "was",
+    "were", "which", "who", "will", "with", "would",
+    "access", "account", "accounts", "attempt", "attempts", "balance",
+    "credentials", "data", "details", "error", "failed", "holder", "id",
+    "info", "login", "logins", "manager", "name", "names", "number", "owner",
+    "page", "password", "profile", "settings", "status", "type", "user",
+    "username", "users",
+  ]);
+
+  /**
+   * Pattern for vehicle identification numbers: 17 characters excluding
+   * I, O, and Q (ISO 3779). Candidates must pass the check digit.
+   */
+  private static readonly VIN_PATTERN = /\b[A-HJ-NPR-Z0-9]{17}\b/gi;
+
+  /**
+   * Pattern for MAC addresses: six hex octets separated consistently
+   * by colons or dashes.
+   */
+  private static readonly MAC_PATTERN =
+    /(?<![\w:-])[0-9A-Fa-f]{2}([:-])(?:[0-9A-Fa-f]{2}\1){4}[0-9A-Fa-f]{2}(?![\w:-])/g;
+
+  /**
+   * Pattern for serial-like identifiers: uppercase alphanumeric tokens of
+   * at least 8 characters, optionally dash-separated. Candidates must mix
+   * letters and digits. Only used when the policy enables serial detection.
+   */
+  private static readonly SERIAL_PATTERN = /\b[A-Z0-9]+(?:-[A-Z0-9]+)*\b/g;
+
+  /**
+   * Pattern for dotted-quad IPv4 addresses with optional CIDR prefix.
+   * Longer dotted sequences (version strings) are not matched.
+   */
+  private static readonly IPV4_PATTERN =
+    /(?<![\w.])(?:25[0-5]|2[0-4]\d|1?\d?\d)(?:\.(?:25[0-5]|2[0-4]\d|1?\d?\d)){3}(?:\/(?:3[0-2]|[12]?\d))?(?!\.?\w)/g;
+
+  /**
+   * Pattern for IPv6 candidates (full or "::"-compressed) with optional
+   * CIDR prefix. Candidates are validated by parsing, which rejects
+   * times and MAC addresses.
+   */
+  private static readonly IPV6_PATTERN =
+    /(?<![\w:.])(?:[0-9a-f]{0,4}:){2,7}[0-9a-f]{0,4}(?:\/\d{1,3})?(?![\w:])/gi;
 
   /**
-   * Pattern for currency amounts.
-   * Matches: $1,234.56, €100, £50.00, etc.
+   * Pattern for street addresses: house number, capitalized street name,
+   * street-suffix keyword, then optional unit, city, state, and ZIP.
+   * e.g. "123 Main Street, Springfield, IL 62704".
    */
-  private static readonly CURRENCY_PATTERN =
-    /[$€£¥]\s*\d{1,3}(?:,\d{3})*(?:\.\d{2})?\b|\b\d{1,3}(?:,\d{3})*(?:\.\d{2})?\s*(?:USD|EUR|GBP|JPY)\b/g;
+  private static readonly ADDRESS_PATTERN =
+    /\b\d{1,6}[A-Z]?\s+(?:[A-Z][a-z]*\.?\s+){1,4}(?:Street|St|Avenue|Ave|Road|Rd|Boulevard|Blvd|Lane|Ln|Drive|Dr|Court|Ct|Place|Pl|Terrace|Ter|Parkway|Pkwy|Circle|Cir|Highway|Hwy|Way)\b\.?(?:,?\s+(?:Apt|Suite|Ste|Unit|#)\.?\s*[A-Za-z0-9-]+)?(?:,\s*[A-Z][a-z]+(?:\s+[A-Z][a-z]+)*(?:,?\s+[A-Z]{2}\b(?:\s+\d{5}(?:-\d{4})?\b)?)?)?/g;
 
   /**
-   * Pattern for standalone numbers.
-   * Matches integers and decimals, excluding those already matched as currency/dates.
+   * Pattern for US ZIP+4 codes, which are distinctive on their own.
    */
-  private static readonly NUMBER_PATTERN = /\b\d+(?:\.\d+)?\b/g;
+  private static readonly ZIP4_PATTERN = /(?<![\d-])\d{5}-\d{4}(?![\d-])/g;
 
   /**
-   * Pattern for identifiers (SSN, phone, email-like patterns).
+   * Pattern for a 5-digit ZIP after a state code or a ZIP cue ("CA 94105",
+   * "zip: 94105"). Bare 5-digit numbers stay numbers.
    */
-  private static readonly IDENTIFIER_PATTERN =
-    /\b(?:\d{3}-\d{2}-\d{4}|\d{3}[-.\s]?\d{3}[-.\s]?\d{4}|[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,})\b/g;
+  private static readonly ZIP_PATTERN = new RegExp(
+    String.raw`(?:\b(?:${[...US_STATE_CODES].join("|")})[ \t]+|\b(?:zip|ZIP|Zip)(?:[ \t]*code)?[ \t]*:?[ \t]*)(?<value>\d{5})(?![\d-])`,
+    "dg"
+  );
+
+  /**
+   * Pattern for UK postcodes ("SW1A 1AA", "M1 1AE").
+   */
+  private static readonly UK_POSTCODE_PATTERN =
+    /\b[A-Z]{1,2}\d[A-Z\d]? ?\d[ABD-HJLNP-UW-Z]{2}\b/g;
+
+  /**
+   * Pattern for Canadian postal codes ("K1A 0B1").
+   */
+  private static readonly CA_POSTCODE_PATTERN =
+    /\b[ABCEGHJ-NPRSTVXY]\d[ABCEGHJ-NPRSTV-Z] ?\d[ABCEGHJ-NPRSTV-Z]\d\b/g;
+
+  /**
+   * Pattern for decimal coordinate pairs ("37.7749, -122.4194",
+   * "37.7749° N, 122.4194° W"). Both parts need three or more decimal
+   * places so lists of prices and scores are not matched.
+   */
+  private static readonly DECIMAL_COORDINATES_PATTERN =
+    /(?<![\w.+-])[-+]?\d{1,2}\.\d{3,}°?\s*[NS]?\s*,\s*[-+]?\d{1,3}\.\d{3,}°?\s*[EW]?(?![\w.])/g;
+
+  /**
+   * Pattern for degree-minute-second pairs ("37°46′30″N 122°25′10″W"),
+   * accepting ASCII quotes for minutes and seconds.
+   */
+  private static readonly DMS_COORDINATES_PATTERN =
+    /\b\d{1,2}°\s*\d{1,2}['′]\s*(?:\d{1,2}(?:\.\d+)?["″]\s*)?[NS][\s,]+\d{1,3}°\s*\d{1,2}['′]\s*(?:\d{1,2}(?:\.\d+)?["″]\s*)?[EW]\b/g;
+
+  /**
+   * Pattern for IBANs: country code, check digits, then alphanumeric
+   * groups of four with optional single spaces. Candidates are validated
+   * against the country length table and the mod-97 checksum.
+   */
+  private static readonly IBAN_PATTERN =
+    /\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b/g;
+
+  /**
+   * Pattern for payment card numbers: 13-19 digits, optionally separated
+   * by single spaces or dashes. Candidates must pass a Luhn check.
+   */
+  private static readonly CARD_PATTERN = /(?<![\d-])\d(?:[ -]?\d){12,18}(?![\d-])/g;
+
+  /**
+   * Pattern for phone numbers.
+   * Matches E.164 (+CC), 00-prefixed international, parenthesized area codes,
+   * and digit groups separated by spaces, dashes, or dots.
+   * Candidates are validated by digit count before being accepted.
+   */
+  private static readonly PHONE_PATTERN =
+    /(?<![\w+])(?:(?:\+|00)\d{1,3}[\s.-]?)?(?:\(\d{1,4}\)[\s.-]?)?\d{2,4}(?:[\s.-]?\d{2,4}){1,4}(?!\w)/g;
+
+  /**
+   * Pattern for US Social Security Numbers in ddd-dd-dddd form.
+   */
+  private static readonly SSN_PATTERN = /\b\d{3}-\d{2}-\d{4}\b/g;
+
+  /**
+   * Pattern for National Provider Identifiers: 10 digits starting with 1
+   * or 2. Candidates must pass the NPI check digit.
+   */
+  private static readonly NPI_PATTERN = /\b[12]\d{9}\b/g;
+
+  /**
+   * Pattern for a medical record number introduced by a cue ("MRN:",
+   * "medical record #"). Only the value becomes the entity.
+   */
+  private static readonly MRN_PATTERN =
+    /\b(?:MRN|medical\s+record\s+(?:number|no\.?|#))[\s#:.-]*(?<value>[A-Z0-9](?:[A-Z0-9-]{2,18}[A-Z0-9]))\b/dgi;
+
+  /**
+   * Pattern for dotted ICD-10 codes ("E11.9", "S72.001A"). Undotted
+   * categories ("E11") are too ambiguous to match on their own.
+   */
+  private static readonly ICD10_PATTERN = /\b[A-Z]\d[0-9A-Z]\.[0-9A-Z]{1,4}\b/g;
+
+  /**
+   * Pattern for 9 contiguous digits introduced by an SSN context word.
+   * Only the digits (the "value" group) become the entity.
+   */
+  private static readonly SSN_CONTEXT_PATTERN =
+    /\b(?:SSN|social\s+security(?:\s+(?:number|no\.?|#))?)[\s#:.-]*(?<value>\d{9})\b/dgi;
+
+  private readonly policy: MaskingPolicy;
+
+  /**
+   * Whether the last distill call dropped entities past the policy's
+   * maxEntities.
+   */
+  entitiesTruncated = false;
+
+  /**
+   * Pattern for organization names: a run of capitalized words (optionally
+   * joined by "&" or "of") followed by a legal suffix such as "Inc." or
+   * "GmbH". Built per instance because the suffix list is policy-driven.
+   */
+  private readonly organizationPattern: RegExp;
+
+  /**
+   * Pattern for currency amounts, optionally signed.
+   * Matches: $1,250,000.00, €100, £2.5K, EUR 3.2M, 400 USD, $-1,234.56,
+   * and "EUR 1.234,56" when a decimal-comma locale is enabled. Built per
+   * instance because the number conventions are policy-driven.
+   */
+  private readonly currencyPattern: RegExp;
+
+  /**
+   * Pattern for standalone numeric literals, excluding those already
+   * matched as currency/dates: optional sign, thousands separators,
+   * decimal point (including a bare ".5"), exponent, and percent suffix
+   * ("-42", "1,234.5", "3.5%", "1e9", "6.02E+23", and "1.234,5" under a
+   * decimal-comma locale). At least one digit is required, so
+   * punctuation-only tokens ("...") never match.
+   */
+  private readonly numberPattern: RegExp;
+
+  /**
+   * Pattern for the legal suffix at the end of an organization match.
+   */
+  private readonly organizationSuffixPattern: RegExp;
+
+  /**
+   * Custom identifier patterns from the policy, compiled once per instance.
+   */
+  private readonly idPatterns: Array<IdPattern & { regex: RegExp }>;
+
+  /**
+   * Custom redaction patterns from the policy, compiled once per instance.
+   */
+  private readonly customPatterns: Array<CustomPattern & { regex: RegExp }>;
+
+  /**
+   * Policy deny terms as one case-insensitive alternation, longest first,
+   * with internal whitespace matching any run of whitespace. Null when the
+   * policy has no deny terms.
+   */
+  private readonly denyTermPattern: RegExp | null;
+
+  constructor(policy: MaskingPolicy = DEFAULT_MASKING_POLICY) {
+    this.policy = policy;
+    this.idPatterns = policy.idPatterns.map((idPattern) => ({
+      ...idPattern,
+      regex: new RegExp(idPattern.pattern, "dg"),
+    }));
+    const terms = [...new Set(policy.denyTerms.map((term) => term.trim()))]
+      .sort((a, b) => b.length - a.length)
+      .map((term) =>
+        term
+          .split(/\s+/)
+          .map((word) => word.replace(/[.*+?^${}()|[\]\\]/g, "\\$&"))
+          .join(String.raw`\s+`)
+      );
+    this.denyTermPattern =
+      terms.length > 0
+        ? new RegExp(`${WORD_START}(?:${terms.join("|")})${WORD_END}`, "giu")
+        : null;
+    this.customPatterns = policy.customPatterns.map((customPattern) => ({
+      ...customPattern,
+      regex: new RegExp(customPattern.pattern, "dg"),
+    }));
+
+    // Longest suffix first so "Pty Ltd" wins over "Ltd"; a trailing period is optional
+    const suffixes = [...ORGANIZATION_SUFFIXES, ...policy.organizationSuffixes]
+      .map((suffix) => suffix.replace(/\.$/, ""))
+      .sort((a, b) => b.length - a.length)
+      .map((suffix) => suffix.replace(/[.*+?^${}()|[\]\\]/g, "\\$&"))
+      .join("|");
+    const suffix = String.raw`,?[ \t]+(?:${suffixes})(?![\w&'’-])\.?`;
+    this.organizationPattern = new RegExp(
+      String.raw`\b${ORGANIZATION_WORD}(?:[ \t]+(?:(?:&|and|of)[ \t]+)?${ORGANIZATION_WORD})*${suffix}`,
+      "g"
+    );
+    this.organizationSuffixPattern = new RegExp(`${suffix}$`);
+
+    const conventions = decimalConventions(policy.locales);
+    const amount = amountPattern(conventions);
+    this.currencyPattern = new RegExp(
+      [
+        String.raw`(?<![\w.])[-−]?[$€£¥]\s?[-−]?${amount}`,
+        String.raw`\b(?:${CURRENCY_CODES.join("|")})\s?[-−]?${amount}`,
+        String.raw`(?:(?<![\w.])[-−])?\b${amount}\s?(?:${CURRENCY_CODES.join("|")})\b`,
+      ].join("|"),
+      "g"
+    );
+    const digits = conventions.map((convention) => GROUPED_DIGITS[convention]).join("|");
+    this.numberPattern = new RegExp(
+      String.raw`(?<![\p{L}\p{N}_.,])(?:[-+−](?=\.?\d))?(?:${digits}|\.\d+)(?:[eE][-+]?\d+)?%?(?![\p{L}\p{N}_]|[.,]\d)`,
+      "gu"
+    );
+  }
 
   /**
    * Distill raw input text into extracted entities.
+   * Each document of an array is extracted on its own, so no match can
+   * span two documents; entities then carry the document index in
+   * `sourceDoc` and positions relative to their document.
+   *
    * @param input - Raw text input (string or array of strings)
    * @returns Array of raw entities with positions and types
+   * @throws TransformationError if the input is empty or larger than the
+   *         policy's input size limit, or under the strict preset when it
+   *         holds more than maxEntities entities
    */
-  distill(input: string | string[]): RawEntity[] {
-    const text = Array.isArray(input) ? input.join("\n") : input;
-
-    if (text.length === 0) {
+  distill(input: string | readonly string[]): RawEntity[] {
+    this.entitiesTruncated = false;
+    const documents = typeof input === "string" ? [input] : input;
+    if (documents.every((document) => document.length === 0)) {
       throw new TransformationError("Cannot distill empty input");
     }
+    Distiller.checkInputSize(
+      documents.reduce((total, document) => total + Buffer.byteLength(document, "utf8"), 0),
+      this.policy
+    );
+
+    const entities = documents.flatMap((document, index) => {
+      const found = this.extractDocument(document);
+      if (documents.length > 1) {
+        for (const entity of found) {
+          entity.sourceDoc = index;
+        }
+      }
+      return found;
+    });
+    return this.mergeRepeatedEntities(entities);
+  }
+
+  /**
+   * Reject input whose UTF-8 size exceeds the policy's limit (clamped to
+   * the absolute ceiling).
+   *
+   * @param size - Input size in bytes
+   * @param policy - Masking policy supplying maxInputSize
+   * @throws TransformationError naming the size and the limit
+   */
+  static checkInputSize(size: number, policy: MaskingPolicy): void {
+    const limit = inputSizeLimit(policy);
+    if (size > limit) {
+      throw new TransformationError(`Input too large: ${size} bytes (limit: ${limit} bytes)`);
+    }
+  }
 
+  /**
+   * Extract entities from one document, sorted by position.
+   */
+  private extractDocument(document: string): RawEntity[] {
+    // NFC so precomposed and combining-mark spellings ("é" vs "e\u0301")
+    // match the same patterns
+    const text = document.normalize("NFC");
     const entities: RawEntity[] = [];
     const processedPositions = new Set<string>();
 
+    // Deny terms, then custom patterns, run first so they win any overlap
+    if (this.denyTermPattern !== null) {
+      this.extractPattern(text, this.denyTermPattern, "restricted", entities, processedPositions);
+    }
+    for (const { name, regex, role } of this.customPatterns) {
+      const start = entities.length;
+      this.extractPattern(text, regex, "custom", entities, processedPositions);
+      for (const entity of entities.slice(start)) {
+        entity.patternName = name;
+        entity.customRole = role;
+      }
+    }
+
     // Extract in order of specificity to avoid overlaps
     this.extractPattern(
       text,
-      Distiller.IDENTIFIER_PATTERN,
-      "identifier",
+      Distiller.URL_PATTERN,
+      "url",
       entities,
       processedPositions
     );
+    this.extractPattern(
+      text,
+      Distiller.QUOTED_PATH_PATTERN,
+      "file_path",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.WINDOWS_PATH_PATTERN,
+      "file_path",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.POSIX_PATH_PATTERN,
+      "file_path",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.UUID_PATTERN,
+      "uuid",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.JWT_PATTERN,
+      "jwt",
+      entities,
+      processedPositions,
+      (candidate) => Distiller.decodeJwtHeader(candidate) !== null
+    );
+    for (const [, pattern] of SECRET_PATTERNS) {
+      this.extractPattern(text, pattern, "secret", entities, processedPositions);
+    }
+    this.extractPattern(
+      text,
+      Distiller.GENERIC_SECRET_PATTERN,
+      "secret",
+      entities,
+      processedPositions,
+      (candidate) => this.isHighEntropyToken(candidate)
+    );
+    this.extractPattern(
+      text,
+      Distiller.EMAIL_PATTERN,
+      "email",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.HANDLE_PATTERN,
+      "handle",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.MAC_PATTERN,
+      "mac",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.IPV4_PATTERN,
+      "ip_address",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.IPV6_PATTERN,
+      "ip_address",
+      entities,
+      processedPositions,
+      (candidate) => Distiller.parseIpAddress(candidate) !== null
+    );
+    this.extractPattern(
+      text,
+      Distiller.DMS_COORDINATES_PATTERN,
+      "coordinates",
+      entities,
+      processedPositions,
+      (candidate) => Distiller.parseCoordinates(candidate) !== null
+    );
+    this.extractPattern(
+      text,
+      Distiller.DECIMAL_COORDINATES_PATTERN,
+      "coordinates",
+      entities,
+      processedPositions,
+      (candidate) => Distiller.parseCoordinates(candidate) !== null
+    );
+    this.extractPattern(
+      text,
+      Distiller.IBAN_PATTERN,
+      "iban",
+      entities,
+      processedPositions,
+      (candidate) => Distiller.isValidIban(candidate)
+    );
+    this.extractPattern(
+      text,
+      Distiller.CARD_PATTERN,
+      "payment_card",
+      entities,
+      processedPositions,
+      (candidate) => Distiller.passesLuhn(candidate.replace(/\D/g, ""))
+    );
+    if (hasLocaleRegion(this.policy.nationalIdLocales, "US")) {
+      this.extractPattern(
+        text,
+        Distiller.SSN_PATTERN,
+        "ssn",
+        entities,
+        processedPositions,
+        (candidate) => Distiller.isValidSsn(candidate)
+      );
+      this.extractPattern(
+        text,
+        Distiller.SSN_CONTEXT_PATTERN,
+        "ssn",
+        entities,
+        processedPositions,
+        (candidate) => Distiller.isValidSsn(candidate)
+      );
+    }
+    this.extractPattern(
+      text,
+      Distiller.VIN_PATTERN,
+      "vin",
+      entities,
+      processedPositions,
+      (candidate) => Distiller.passesVinCheckDigit(candidate)
+    );
+    if (this.policy.healthcare) {
+      this.extractPattern(
+        text,
+        Distiller.MRN_PATTERN,
+        "mrn",
+        entities,
+        processedPositions
+      );
+      this.extractPattern(
+        text,
+        Distiller.NPI_PATTERN,
+        "npi",
+        entities,
+        processedPositions,
+        // The NPI check digit is Luhn over the number prefixed with 80840
+        (candidate) => Distiller.passesLuhn(`80840${candidate}`)
+      );
+      this.extractPattern(
+        text,
+        Distiller.ICD10_PATTERN,
+        "icd10",
+        entities,
+        processedPositions
+      );
+    }
+    // Canonical locale order, so the policy's list order cannot change output
+    for (const locale of PLATE_LOCALES) {
+      if (!this.policy.plateLocales.includes(locale)) continue;
+      const start = entities.length;
+      this.extractPattern(
+        text,
+        PLATE_PATTERNS[locale],
+        "license_plate",
+        entities,
+        processedPositions
+      );
+      for (const entity of entities.slice(start)) {
+        entity.plateLocale = locale;
+      }
+    }
+    for (const { name, regex, checksum } of this.idPatterns) {
+      const start = entities.length;
+      this.extractPattern(
+        text,
+        regex,
+        "national_id",
+        entities,
+        processedPositions,
+        (candidate) =>
+          checksum === undefined || Distiller.passesChecksum(checksum, candidate.replace(/\D/g, ""))
+      );
+      for (const entity of entities.slice(start)) {
+        entity.patternName = name;
+      }
+    }
+    this.extractPattern(
+      text,
+      Distiller.ADDRESS_PATTERN,
+      "address",
+      entities,
+      processedPositions
+    );
+    for (const pattern of [
+      Distiller.ZIP4_PATTERN,
+      Distiller.ZIP_PATTERN,
+      Distiller.UK_POSTCODE_PATTERN,
+      Distiller.CA_POSTCODE_PATTERN,
+    ]) {
+      this.extractPattern(text, pattern, "postal_code", entities, processedPositions);
+    }
     this.extractPattern(
       text,
       Distiller.DATE_PATTERN,
       "date",
       entities,
-      processedPositions
+      processedPositions,
+      (candidate) => Distiller.parseDate(candidate) !== null
     );
     this.extractPattern(
       text,
-      Distiller.CURRENCY_PATTERN,
+      this.currencyPattern,
       "currency",
       entities,
       processedPositions
     );
+    this.extractPattern(
+      text,
+      Distiller.PHONE_PATTERN,
+      "phone",
+      entities,
+      processedPositions,
+      (candidate) => this.isPlausiblePhone(candidate)
+    );
+    if (this.policy.detectSerials) {
+      this.extractPattern(
+        text,
+        Distiller.SERIAL_PATTERN,
+        "serial",
+        entities,
+        processedPositions,
+        (candidate) => Distiller.isSerialLike(candidate)
+      );
+    }
+    if (this.policy.detectUsernames) {
+      this.extractPattern(
+        text,
+        Distiller.USERNAME_PATTERN,
+        "handle",
+        entities,
+        processedPositions,
+        (candidate) => !Distiller.USERNAME_STOPWORDS.has(candidate.toLowerCase())
+      );
+    }
+    this.extractPattern(
+      text,
+      this.organizationPattern,
+      "organization",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.HONORIFIC_NAME_PATTERN,
+      "name",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.CJK_NAME_PATTERN,
+      "name",
+      entities,
+      processedPositions
+    );
+    this.extractPattern(
+      text,
+      Distiller.ARABIC_NAME_PATTERN,
+      "name",
+      entities,
+      processedPositions
+    );
     this.extractPattern(
       text,
       Distiller.NAME_PATTERN,
@@ -83,18 +1342,240 @@ export class Distiller {
       entities,
       processedPositions
     );
+    if (this.policy.allowCommonWords) {
+      this.dropSentenceInitialWords(text, entities);
+    }
+    // Long digit runs claim their text before the number recognizer does
+    const minDigits = this.policy.maskNumbersOverDigits;
+    if (minDigits !== null) {
+      this.extractPattern(
+        text,
+        this.numberPattern,
+        "long_number",
+        entities,
+        processedPositions,
+        (candidate) => Distiller.numberDigitCount(candidate) >= minDigits
+      );
+    }
     this.extractPattern(
       text,
-      Distiller.NUMBER_PATTERN,
+      this.numberPattern,
       "number",
       entities,
       processedPositions
     );
 
-    // Sort by position for deterministic output
-    entities.sort((a, b) => a.position - b.position);
+    // Capitalized runs that are known places are locations, not people
+    if (this.policy.detectPlaces && isRecognizerEnabled(this.policy, "place")) {
+      for (const entity of entities) {
+        if (entity.entityType === "name" && lookupPlace(entity.originalText) !== null) {
+          entity.entityType = "place";
+        }
+      }
+    }
+    this.inferNameRoles(text, entities);
+    for (const entity of entities) {
+      entity.confidence = Distiller.confidence(text, entity);
+    }
+
+    // Sort by position for deterministic output
+    entities.sort((a, b) => a.position - b.position);
+
+    // Token index of each match, for relation windows
+    const tokenEnds = Array.from(text.matchAll(/\S+/g), (match) => match.index + match[0].length);
+    let token = 0;
+    for (const entity of entities) {
+      while (token < tokenEnds.length && tokenEnds[token] <= entity.position) {
+        token++;
+      }
+      entity.tokenPositions = [token];
+    }
+    return entities;
+  }
+
+  /**
+   * Whitespace-token index of the token containing a position: the number
+   * of tokens that end before it.
+   *
+   * @param text - Document text
+   * @param position - Character offset of a match
+   */
+  static tokenIndex(text: string, position: number): number {
+    return (text.slice(0, position).match(/\S+(?=\s)/g) ?? []).length;
+  }
+
+  /**
+   * Fixed confidence for a match: the recognizer's ENTITY_CONFIDENCE
+   * score, with names graded by honorific, length, and position.
+   */
+  private static confidence(text: string, entity: RawEntity): number {
+    if (entity.entityType !== "name") {
+      return ENTITY_CONFIDENCE[entity.entityType];
+    }
+    if (Distiller.splitHonorific(entity.originalText).honorific !== null) {
+      return 0.9;
+    }
+    if (/\s/.test(entity.originalText.trim())) {
+      return 0.7;
+    }
+    return Distiller.isSentenceStart(text, entity.position) ? 0.3 : ENTITY_CONFIDENCE.name;
+  }
+
+  /**
+   * Infer roles for the remaining capitalized runs from the cue words
+   * directly before them. Each decision depends only on the entity's own
+   * text and its left context, so the result is independent of order.
+   * Runs with an honorific are always people; anything without a cue
+   * keeps the default Actor role.
+   */
+  private inferNameRoles(text: string, entities: RawEntity[]): void {
+    for (const entity of entities) {
+      if (
+        entity.entityType !== "name" ||
+        Distiller.splitHonorific(entity.originalText).honorific !== null
+      ) {
+        continue;
+      }
+      const before = text.slice(Math.max(0, entity.position - 24), entity.position);
+      const word = entity.originalText.toLowerCase();
+      let role: Role | undefined;
+      if (WEEKDAY_NAMES.has(word)) {
+        role = Role.Temporal;
+      } else {
+        for (const [cueRole, cue] of ROLE_CUES) {
+          if (!cue.test(before)) continue;
+          if (cueRole === Role.Temporal && !MONTH_NAMES.has(word)) continue;
+          if (cueRole === Role.Location && !this.policy.detectPlaces) continue;
+          role = cueRole;
+          break;
+        }
+      }
+      if (role !== undefined) {
+        entity.inferredRole = role;
+      }
+    }
+  }
 
-    return entities;
+  /**
+   * Trim a leading dictionary word ("The", "Contract") from capitalized
+   * runs that start a sentence, dropping runs left empty. Positions stay
+   * claimed so the trimmed word is not picked up by later patterns.
+   */
+  private dropSentenceInitialWords(text: string, entities: RawEntity[]): void {
+    for (let i = entities.length - 1; i >= 0; i--) {
+      const entity = entities[i];
+      if (entity.entityType !== "name" || !Distiller.isSentenceStart(text, entity.position)) {
+        continue;
+      }
+      const [first] = entity.originalText.split(/[ \t]/, 1);
+      if (!isDictionaryWord(first)) {
+        continue;
+      }
+
+      // Skip the word and any name particles that followed it
+      const rest = /^[ \t]+(?:\p{Ll}+[ \t]+)*/u.exec(entity.originalText.slice(first.length));
+      if (rest === null) {
+        entities.splice(i, 1);
+        continue;
+      }
+      const offset = first.length + rest[0].length;
+      entity.originalText = entity.originalText.slice(offset);
+      entity.position += offset;
+      const boundaryTerms = this.deriveBoundaryTerms("name", entity.originalText);
+      if (boundaryTerms.length > 0) {
+        entity.boundaryTerms = boundaryTerms;
+      } else {
+        delete entity.boundaryTerms;
+      }
+    }
+  }
+
+  /**
+   * Check whether a position starts a sentence: the start of the text or
+   * a line, or after sentence-final punctuation, allowing for quotes and
+   * brackets on either side of the break.
+   */
+  private static isSentenceStart(text: string, position: number): boolean {
+    const start = Math.max(0, position - 16);
+    const before = text.slice(start, position);
+    if (start === 0 && /^\s*["'‘“(\[]*$/u.test(before)) {
+      return true;
+    }
+    return /(?:[.!?]["'’”)\]]*\s+|\n\s*)["'‘“(\[]*$/u.test(before);
+  }
+
+  /**
+   * Collapse repeated surface forms into the first occurrence, counting
+   * occurrences and keeping every spelling seen for boundary checks.
+   * Forms are compared case-insensitively with whitespace collapsed
+   * (UUIDs also ignore braces), so "Alice" named ten times, in any of the
+   * documents, is one entity and therefore one synthetic ID. New forms
+   * past the policy's maxEntities are dropped, or fail the transform under
+   * the strict preset. The surface-form map is wiped before returning.
+   */
+  private mergeRepeatedEntities(entities: RawEntity[]): RawEntity[] {
+    const { maxEntities, preset } = this.policy;
+    const firstByKey = new Map<string, RawEntity>();
+    const merged = entities.filter((entity) => {
+      const key = [
+        entity.patternName ?? "",
+        Distiller.normalizeSurfaceForm(entity.entityType, entity.originalText),
+      ].join("\u0000");
+      const first = firstByKey.get(key);
+      if (first === undefined) {
+        // Input order decides which entities survive the cap, so the
+        // output and its hash are reproducible
+        if (firstByKey.size >= maxEntities) {
+          if (preset === "strict") {
+            throw new TransformationError("Entity limit exceeded");
+          }
+          this.entitiesTruncated = true;
+          return false;
+        }
+        firstByKey.set(key, entity);
+        return true;
+      }
+
+      first.occurrences = (first.occurrences ?? 1) + (entity.occurrences ?? 1);
+      first.confidence = Math.max(first.confidence ?? 0, entity.confidence ?? 0);
+      if (first.sourceDoc === entity.sourceDoc) {
+        first.tokenPositions = [...(first.tokenPositions ?? []), ...(entity.tokenPositions ?? [])];
+      } else if (entity.sourceDoc !== undefined) {
+        const documents = first.sourceDocs ?? [first.sourceDoc ?? 0];
+        if (!documents.includes(entity.sourceDoc)) {
+          first.sourceDocs = [...documents, entity.sourceDoc];
+        }
+      }
+      const terms = new Set([
+        ...(first.boundaryTerms ?? []),
+        ...(entity.boundaryTerms ?? []),
+        entity.originalText,
+      ]);
+      terms.delete(first.originalText);
+      if (terms.size > 0) {
+        first.boundaryTerms = [...terms];
+      }
+      return false;
+    });
+    firstByKey.clear();
+    return merged;
+  }
+
+  /**
+   * Normalized surface form used to recognize repeats of one value and
+   * to key session-stable synthetic IDs.
+   *
+   * @param entityType - Entity type of the match
+   * @param originalText - Text as matched
+   * @returns Case-folded, whitespace-collapsed form
+   */
+  static normalizeSurfaceForm(entityType: string, originalText: string): string {
+    if (entityType === "uuid") {
+      return Distiller.normalizeUuid(originalText);
+    }
+    // "Acme Corp." and "Acme Corp" are the same organization
+    const text = entityType === "organization" ? originalText.replace(/[.,]/g, "") : originalText;
+    return text.replace(/\s+/g, " ").toLowerCase();
   }
 
   /**
@@ -106,15 +1587,27 @@ export class Distiller {
     pattern: RegExp,
     entityType: RawEntity["entityType"],
     entities: RawEntity[],
-    processedPositions: Set<string>
+    processedPositions: Set<string>,
+    validate?: (candidate: string) => boolean
   ): void {
+    // Recognizers the policy disables do not run
+    if (!isRecognizerEnabled(this.policy, entityType)) {
+      return;
+    }
+
     // Reset regex state for global patterns
     pattern.lastIndex = 0;
 
     let match: RegExpExecArray | null;
     while ((match = pattern.exec(text)) !== null) {
-      const position = match.index;
-      const endPosition = position + match[0].length;
+      // Patterns with a "value" group only extract that group
+      const value = match.groups?.value ?? match[0];
+      if (validate && !validate(value)) {
+        continue;
+      }
+
+      const position = match.indices?.groups?.value?.[0] ?? match.index;
+      const endPosition = position + value.length;
 
       // Check if this range overlaps with any processed position
       let overlaps = false;
@@ -126,11 +1619,16 @@ export class Distiller {
       }
 
       if (!overlaps) {
-        entities.push({
-          originalText: match[0],
+        const entity: RawEntity = {
+          originalText: value,
           entityType,
           position,
-        });
+        };
+        const boundaryTerms = this.deriveBoundaryTerms(entityType, value);
+        if (boundaryTerms.length > 0) {
+          entity.boundaryTerms = boundaryTerms;
+        }
+        entities.push(entity);
 
         // Mark these positions as processed
         for (let i = position; i < endPosition; i++) {
@@ -139,5 +1637,780 @@ export class Distiller {
       }
     }
   }
+
+  /**
+   * Derive component forms of a match that must be boundary-checked
+   * in addition to the full match.
+   */
+  private deriveBoundaryTerms(
+    entityType: RawEntity["entityType"],
+    text: string
+  ): string[] {
+    if (entityType === "email") {
+      const at = text.lastIndexOf("@");
+      return [text.slice(0, at), text.slice(at + 1)];
+    }
+    if (entityType === "phone") {
+      // Cover the contiguous digit sequence regardless of separators
+      const digits = text.replace(/\D/g, "");
+      const national = Distiller.nationalPhoneDigits(text);
+      return national === digits ? [digits] : [digits, national];
+    }
+    if (entityType === "long_number") {
+      // "1,234,567,890" and "1234567890" are the same raw identifier
+      const digits = text.replace(/\D/g, "");
+      return digits !== text ? [digits] : [];
+    }
+    if (entityType === "payment_card") {
+      // Contiguous and separated forms are the same raw identifier
+      const digits = text.replace(/\D/g, "");
+      const groups = digits.match(/.{1,4}/g) ?? [];
+      return [digits, groups.join(" "), groups.join("-")].filter(
+        (form) => form !== text
+      );
+    }
+    if (entityType === "url") {
+      const url = Distiller.parseUrl(text);
+      const terms = [url.host];
+      if (url.host.startsWith("[")) {
+        terms.push(url.host.slice(1, -1));
+      } else if (!url.isIpLiteral) {
+        // Every label but the TLD (e.g. "internal", "acme-corp")
+        terms.push(...url.host.split(".").slice(0, -1));
+      }
+      if (url.port !== undefined) terms.push(`${url.host}:${url.port}`);
+      if (url.userinfo !== undefined) {
+        terms.push(url.userinfo, ...url.userinfo.split(":"));
+      }
+      terms.push(...url.pathSegments);
+      // Query values often carry usernames or tokens: cover raw and decoded forms.
+      // Parameter names alone are not identifying and are only covered as pairs.
+      for (const [name, value] of url.query) {
+        if (value.length === 0) {
+          terms.push(name);
+          continue;
+        }
+        terms.push(value, Distiller.decodeUrlComponent(value), `${name}=${value}`);
+      }
+      if (url.fragment !== undefined) terms.push(url.fragment);
+      return [...new Set(terms)].filter((term) => term.length > 0 && term !== text);
+    }
+    if (entityType === "jwt") {
+      // Each segment, plus every string claim value decoded from the payload
+      const segments = text.split(".").filter((segment) => segment.length > 0);
+      return [...new Set([...segments, ...Distiller.jwtClaimValues(text)])];
+    }
+    if (entityType === "secret") {
+      // Cover the body of prefixed tokens and each line of key blocks
+      const body = /^(?:gh[pousr]_|github_pat_|xox[abposr]-|(?:sk|rk|pk)_(?:live|test)_)(.+)$/.exec(text);
+      const lines = text.split(/\r?\n/).filter((line) => !line.startsWith("-----"));
+      const terms = body ? [body[1]] : lines.length > 1 ? lines : [];
+      return terms.map((term) => term.trim()).filter((term) => term.length > 0);
+    }
+    if (entityType === "uuid") {
+      // Cover bare and braced spellings in either case
+      const uuid = Distiller.normalizeUuid(text);
+      const forms = [uuid, uuid.toUpperCase()];
+      const terms = [...forms, ...forms.map((form) => `{${form}}`)];
+      return terms.filter((term) => term !== text);
+    }
+    if (entityType === "address") {
+      // Street line, city, and every component token (street name, ZIP, ...)
+      const parts = text.split(",").map((part) => part.trim());
+      const tokens = text.match(/[A-Za-z0-9#-]+/g) ?? [];
+      return [...new Set([...parts, ...tokens])].filter(
+        (term) => term.length > 0 && term !== text
+      );
+    }
+    if (entityType === "mac") {
+      // Cover colon, dash, and unseparated spellings in either case
+      const octets = text.split(/[:-]/);
+      const forms = [octets.join(":"), octets.join("-"), octets.join("")];
+      const terms = forms.flatMap((form) => [form.toUpperCase(), form.toLowerCase()]);
+      return [...new Set(terms)].filter((term) => term !== text);
+    }
+    if (entityType === "ip_address") {
+      const ip = Distiller.parseIpAddress(text);
+      if (ip === null) return [];
+      const terms = [text.split("/")[0]];
+      if (ip.family === "ipv6") {
+        // Cover compressed and expanded spellings of the same address
+        const hextets = ip.groups.map((group) => group.toString(16));
+        terms.push(
+          hextets.map((hextet) => hextet.padStart(4, "0")).join(":"),
+          hextets.join(":"),
+          Distiller.compressIpv6(hextets)
+        );
+      }
+      return [...new Set(terms)].filter((term) => term !== text);
+    }
+    if (entityType === "iban") {
+      // Cover both the compact and the printed (grouped) forms
+      const compact = text.replace(/ /g, "");
+      const grouped = (compact.match(/.{1,4}/g) ?? []).join(" ");
+      return [compact, grouped].filter((form) => form !== text);
+    }
+    if (entityType === "file_path") {
+      // Cover every segment, the file name without its extension, and each
+      // word of a segment containing spaces
+      const { segments, extension } = Distiller.parsePath(text);
+      const terms = [...segments];
+      const last = segments[segments.length - 1];
+      if (extension !== null && last !== undefined) {
+        terms.push(last.slice(0, last.length - extension.length - 1));
+      }
+      for (const segment of segments) {
+        if (/\s/.test(segment)) terms.push(...segment.split(/\s+/));
+      }
+      return [...new Set(terms)].filter((term) => term.length > 0 && term !== text);
+    }
+    if (entityType === "handle") {
+      // Cover the handle with and without its "@"
+      return text.startsWith("@") ? [text.slice(1)] : [`@${text}`];
+    }
+    if (entityType === "organization") {
+      // Cover the name without its legal suffix, and each of its words
+      const name = text.replace(this.organizationSuffixPattern, "");
+      const words = name.split(/[ \t]+/).filter((word) => /^[A-Z0-9]/.test(word));
+      return [...new Set([name, ...words])].filter((term) => term !== text);
+    }
+    if (entityType === "name" || entityType === "place") {
+      // Cover each capitalized word of a multi-word or titled name on its
+      // own, and each part of a hyphenated compound ("Álvarez-Núñez")
+      const { honorific, name } = Distiller.splitHonorific(text);
+      const words = name.split(/[ \t]+/).filter((word) => !/^\p{Ll}/u.test(word));
+      const parts = words.flatMap((word) => (word.includes("-") ? word.split("-") : []));
+      return [...(words.length > 1 || honorific !== null ? words : []), ...parts];
+    }
+    if (entityType === "date") {
+      // Cover the normalized calendar date when the match is written differently
+      const parts = Distiller.parseDate(text, this.policy.locales);
+      if (parts?.month === undefined || parts.day === undefined) return [];
+      const iso = [
+        String(parts.year).padStart(4, "0"),
+        String(parts.month).padStart(2, "0"),
+        String(parts.day).padStart(2, "0"),
+      ].join("-");
+      return iso === text ? [] : [iso];
+    }
+    if (entityType === "restricted") {
+      // Any case variant of the phrase or of its words must not be echoed
+      const configured = this.policy.denyTerms.filter(
+        (term) => foldForMatching(term.trim().replace(/\s+/g, " ")) === foldForMatching(text.replace(/\s+/g, " "))
+      );
+      const forms = [text, ...configured].flatMap((form) => [form, ...form.split(/\s+/)]);
+      const variants = forms.flatMap((form) => [form, form.toLowerCase(), form.toUpperCase()]);
+      return [...new Set(variants)].filter((term) => term !== text);
+    }
+    if (entityType === "postal_code") {
+      // Cover the code with and without its space or ZIP+4 suffix, in either case
+      const compact = text.replace(/[ -]/g, "");
+      const forms = [compact];
+      if (/^\d/.test(text)) {
+        forms.push(text.slice(0, 5));
+      } else {
+        const spaced = `${compact.slice(0, -3)} ${compact.slice(-3)}`;
+        forms.push(spaced, ...[compact, spaced].map((form) => form.toLowerCase()));
+      }
+      return [...new Set(forms)].filter((form) => form !== text);
+    }
+    if (entityType === "coordinates") {
+      // Cover each component as written, with and without its sign
+      const parts = text.split(/[\s,]+/).filter((part) => /\d/.test(part));
+      const numbers = text.match(/\d+(?:\.\d+)?/g) ?? [];
+      const decimals = numbers.filter((n) => n.includes("."));
+      return [...new Set([...parts, ...decimals])].filter((term) => term !== text);
+    }
+    if (entityType === "mrn") {
+      // Cover the other letter case and the value without hyphens
+      const forms = [text.toUpperCase(), text.toLowerCase(), text.replace(/-/g, "")];
+      return [...new Set(forms)].filter((form) => form !== text);
+    }
+    if (entityType === "license_plate") {
+      // Cover the plate without separators
+      const compact = text.replace(/[\s-]/g, "");
+      return compact === text ? [] : [compact];
+    }
+    if (entityType === "vin") {
+      // Cover the other letter case and the world manufacturer identifier
+      const upper = text.toUpperCase();
+      const forms = [upper, text.toLowerCase(), upper.slice(0, 3), upper.slice(0, 3).toLowerCase()];
+      return [...new Set(forms)].filter((form) => form !== text);
+    }
+    if (entityType === "national_id") {
+      // Cover the contiguous form when the match has separators
+      const compact = text.replace(/[\s./-]/g, "");
+      return compact === text ? [] : [compact];
+    }
+    if (entityType === "ssn") {
+      // Cover both the dashed and contiguous forms
+      const digits = text.replace(/\D/g, "");
+      const dashed = `${digits.slice(0, 3)}-${digits.slice(3, 5)}-${digits.slice(5)}`;
+      return text === digits ? [dashed] : [digits];
+    }
+    return [];
+  }
+
+  /**
+   * Luhn (mod 10) checksum over a digit string.
+   */
+  static passesLuhn(digits: string): boolean {
+    let sum = 0;
+    let double = false;
+    for (let i = digits.length - 1; i >= 0; i--) {
+      let digit = digits.charCodeAt(i) - 48;
+      if (double) {
+        digit *= 2;
+        if (digit > 9) digit -= 9;
+      }
+      sum += digit;
+      double = !double;
+    }
+    return digits.length > 0 && sum % 10 === 0;
+  }
+
+  /**
+   * VIN check digit (position 9): transliterated characters weighted by
+   * position, sum mod 11, with 10 written as "X".
+   */
+  static passesVinCheckDigit(vin: string): boolean {
+    const upper = vin.toUpperCase();
+    if (!/^[A-HJ-NPR-Z0-9]{17}$/.test(upper)) return false;
+    let sum = 0;
+    for (let i = 0; i < 17; i++) {
+      const ch = upper[i];
+      const value = /\d/.test(ch) ? Number(ch) : VIN_TRANSLITERATION[ch.charCodeAt(0) - 65];
+      sum += value * VIN_WEIGHTS[i];
+    }
+    const check = sum % 11;
+    return upper[8] === (check === 10 ? "X" : String(check));
+  }
+
+  /**
+   * Model year encoded at VIN position 10. The 30-year cycle is resolved
+   * by position 7: a letter there marks the 2010+ cycle.
+   */
+  static vinModelYear(vin: string): number | null {
+    const upper = vin.toUpperCase();
+    const index = VIN_YEAR_CODES.indexOf(upper[9]);
+    if (index < 0) return null;
+    return (/[A-Z]/.test(upper[6]) ? 2010 : 1980) + index;
+  }
+
+  /**
+   * Check digit validation for custom identifier patterns.
+   */
+  static passesChecksum(kind: NonNullable<IdPattern["checksum"]>, digits: string): boolean {
+    return kind === "mod11" ? Distiller.passesMod11(digits) : Distiller.passesVerhoeff(digits);
+  }
+
+  /**
+   * Mod 11 check on the final digit: weights 2, 3, 4, ... from the right
+   * over the preceding digits, remainder below 2 mapping to 0 (CPF style).
+   */
+  static passesMod11(digits: string): boolean {
+    if (digits.length < 2) return false;
+    let sum = 0;
+    for (let i = digits.length - 2, weight = 2; i >= 0; i--, weight++) {
+      sum += (digits.charCodeAt(i) - 48) * weight;
+    }
+    const remainder = sum % 11;
+    return digits.charCodeAt(digits.length - 1) - 48 === (remainder < 2 ? 0 : 11 - remainder);
+  }
+
+  /**
+   * Verhoeff checksum over a digit string (Aadhaar).
+   */
+  static passesVerhoeff(digits: string): boolean {
+    let check = 0;
+    for (let i = 0; i < digits.length; i++) {
+      const digit = digits.charCodeAt(digits.length - 1 - i) - 48;
+      check = VERHOEFF_MULTIPLICATION[check][VERHOEFF_PERMUTATION[i % 8][digit]];
+    }
+    return digits.length > 0 && check === 0;
+  }
+
+  /**
+   * Identify the country of a postal code match by its shape.
+   */
+  static postalCountry(code: string): (typeof POSTAL_COUNTRIES)[number] {
+    if (/^\d/.test(code)) return "US";
+    return /^[A-Z]\d[A-Z] ?\d[A-Z]\d$/.test(code) ? "CA" : "GB";
+  }
+
+  /**
+   * Parse a decimal or DMS coordinate pair. Hemisphere letters set the
+   * sign; returns null when either part is out of range.
+   */
+  static parseCoordinates(text: string): Coordinates | null {
+    const halves = text.split(/(?<=[NS])[\s,]+|\s*,\s*/);
+    if (halves.length !== 2) return null;
+    const [lat, lon] = halves.map((half) => {
+      const numbers = (half.match(/\d+(?:\.\d+)?/g) ?? []).map(Number);
+      const degrees = (numbers[0] ?? 0) + (numbers[1] ?? 0) / 60 + (numbers[2] ?? 0) / 3600;
+      return /^-|[SW]\s*$/.test(half.trim()) ? -degrees : degrees;
+    });
+    if (Math.abs(lat) > 90 || Math.abs(lon) > 180) return null;
+    return { lat, lon };
+  }
+
+  /**
+   * Split a file path match into its segments and extension.
+   */
+  static parsePath(text: string): FilePath {
+    const segments = text
+      .split(/[\\/]+/)
+      .filter((segment) => !["", "~", ".", ".."].includes(segment) && !/^[A-Za-z]:$/.test(segment));
+    const last = /[\\/]$/.test(text) ? undefined : segments[segments.length - 1];
+    const extension = last !== undefined ? /[^.]\.([A-Za-z0-9]{1,8})$/.exec(last)?.[1] : undefined;
+    return { segments, extension: extension?.toLowerCase() ?? null };
+  }
+
+  /**
+   * Split a URL match into its components without normalizing them,
+   * so each component can be checked against the output as written.
+   */
+  static parseUrl(text: string): UrlParts {
+    const match =
+      /^([a-z]+):\/\/(?:([^@/?#]*)@)?(\[[^\]]*\]|[^:/?#]*)(?::(\d*))?([^?#]*)(?:\?([^#]*))?(?:#(.*))?$/i.exec(
+        text
+      );
+    if (!match) {
+      throw new TransformationError("URL match could not be parsed");
+    }
+    const [, scheme, userinfo, host, port, path, query, fragment] = match;
+    return {
+      scheme: scheme.toLowerCase(),
+      userinfo,
+      host,
+      isIpLiteral: host.startsWith("[") || /^\d{1,3}(?:\.\d{1,3}){3}$/.test(host),
+      port,
+      pathSegments: path.split("/").filter((segment) => segment.length > 0),
+      query: (query ?? "")
+        .split("&")
+        .filter((pair) => pair.length > 0)
+        .map((pair): [string, string] => {
+          const eq = pair.indexOf("=");
+          return eq === -1 ? [pair, ""] : [pair.slice(0, eq), pair.slice(eq + 1)];
+        }),
+      fragment,
+    };
+  }
+
+  /**
+   * Decode the JOSE header of a JWT.
+   *
+   * @returns Header object, or null if the token is not a well-formed JWT
+   */
+  static decodeJwtHeader(token: string): Record<string, unknown> | null {
+    const [header, payload] = token.split(".");
+    const claims = Distiller.decodeJwtSegment(payload);
+    if (claims === null) return null;
+    clearReference(claims);
+    return Distiller.decodeJwtSegment(header);
+  }
+
+  /**
+   * Collect every string value in a JWT payload (sub, email, nested claims).
+   * The decoded bytes and claim object are cleared before returning; see
+   * runtime/memory.ts for the limits of clearing in JavaScript.
+   */
+  private static jwtClaimValues(token: string): string[] {
+    const claims = Distiller.decodeJwtSegment(token.split(".")[1]);
+    if (claims === null) return [];
+
+    const values: string[] = [];
+    const collect = (value: unknown): void => {
+      if (typeof value === "string") {
+        if (value.length > 0) values.push(value);
+      } else if (typeof value === "object" && value !== null) {
+        Object.values(value).forEach(collect);
+      }
+    };
+    collect(claims);
+    clearReference(claims);
+    return values;
+  }
+
+  /**
+   * Decode a base64url JWT segment into a JSON object.
+   * The intermediate byte buffer is zeroed after decoding.
+   */
+  private static decodeJwtSegment(segment: string): Record<string, unknown> | null {
+    const bytes = Buffer.from(segment, "base64url");
+    try {
+      const parsed: unknown = JSON.parse(new TextDecoder().decode(bytes));
+      return typeof parsed === "object" && parsed !== null && !Array.isArray(parsed)
+        ? (parsed as Record<string, unknown>)
+        : null;
+    } catch {
+      return null;
+    } finally {
+      bytes.fill(0);
+    }
+  }
+
+  /**
+   * Check whether a generic token looks like a random secret.
+   * Hex digests are secrets unless the policy allows them through.
+   */
+  private isHighEntropyToken(candidate: string): boolean {
+    if (/^[0-9a-f]+$/i.test(candidate)) {
+      return !this.policy.allowHexDigests;
+    }
+    return (
+      /[A-Za-z]/.test(candidate) &&
+      /\d/.test(candidate) &&
+      Distiller.shannonEntropy(candidate) >= Distiller.MIN_SECRET_ENTROPY
+    );
+  }
+
+  /**
+   * Shannon entropy of a string in bits per character.
+   */
+  static shannonEntropy(text: string): number {
+    const counts = new Map<string, number>();
+    for (const char of text) {
+      counts.set(char, (counts.get(char) ?? 0) + 1);
+    }
+    let entropy = 0;
+    for (const count of counts.values()) {
+      const p = count / text.length;
+      entropy -= p * Math.log2(p);
+    }
+    return entropy;
+  }
+
+  /**
+   * Identify the provider of a credential match.
+   */
+  static secretProvider(text: string): string {
+    for (const [provider, pattern] of SECRET_PATTERNS) {
+      pattern.lastIndex = 0;
+      if (pattern.exec(text)?.[0] === text) {
+        return provider;
+      }
+    }
+    return /^[0-9a-f]+$/i.test(text) ? "hex_digest" : "generic";
+  }
+
+  /**
+   * Identify the writing system of a name from its first letter.
+   */
+  static detectScript(text: string): (typeof NAME_SCRIPTS)[number] {
+    const letter = /\p{L}/u.exec(text)?.[0] ?? "";
+    if (/\p{sc=Latin}/u.test(letter)) return "latin";
+    if (/\p{sc=Cyrillic}/u.test(letter)) return "cyrillic";
+    if (/\p{sc=Greek}/u.test(letter)) return "greek";
+    if (/\p{sc=Han}/u.test(letter)) return "han";
+    if (/\p{sc=Hangul}/u.test(letter)) return "hangul";
+    if (/\p{sc=Arabic}/u.test(letter)) return "arabic";
+    return "other";
+  }
+
+  /**
+   * Separate a leading honorific from a name match.
+   *
+   * @returns Normalized honorific (or null) and the remaining name
+   */
+  static splitHonorific(text: string): {
+    honorific: (typeof HONORIFICS)[number] | null;
+    name: string;
+  } {
+    const match = /^([A-Z][a-z]+)\.?[ \t]+(.+)$/.exec(text);
+    const honorific = HONORIFICS.find((candidate) => candidate === match?.[1].toLowerCase());
+    return match && honorific !== undefined
+      ? { honorific, name: match[2] }
+      : { honorific: null, name: text };
+  }
+
+  /**
+   * Lowercase a UUID and strip any surrounding braces.
+   */
+  static normalizeUuid(text: string): string {
+    return text.replace(/[{}]/g, "").toLowerCase();
+  }
+
+  /**
+   * Parse the structure of a street address match.
+   * A two-letter tail is only treated as a US state if it is a USPS code.
+   */
+  static parseAddress(text: string): PostalAddress {
+    const tail = /\s([A-Z]{2})(?:\s+(\d{5}(?:-\d{4})?))?$/.exec(text);
+    if (!tail || !US_STATE_CODES.has(tail[1])) {
+      return { hasZip: false };
+    }
+    return { hasZip: tail[2] !== undefined, countryHint: "US" };
+  }
+
+  /**
+   * Check that a serial candidate is long enough and mixes letters
+   * and digits (at least two of each).
+   */
+  private static isSerialLike(candidate: string): boolean {
+    const characters = candidate.replace(/-/g, "");
+    const digits = characters.replace(/\D/g, "").length;
+    const letters = characters.length - digits;
+    return characters.length >= 8 && digits >= 2 && letters >= 2;
+  }
+
+  /**
+   * Parse an IPv4 or IPv6 address with optional CIDR prefix.
+   *
+   * @returns Parsed address, or null if the candidate is not a valid address
+   */
+  static parseIpAddress(text: string): IpAddress | null {
+    const [address, prefix] = text.split("/");
+    const prefixLength = prefix !== undefined ? Number(prefix) : undefined;
+
+    if (!address.includes(":")) {
+      const groups = address.split(".").map(Number);
+      const valid = groups.length === 4 && groups.every((octet) => octet <= 255);
+      if (!valid || (prefixLength !== undefined && prefixLength > 32)) return null;
+      return { family: "ipv4", groups, prefixLength };
+    }
+
+    const halves = address.split("::");
+    if (halves.length > 2) return null;
+    const head = halves[0] === "" ? [] : halves[0].split(":");
+    const tail = halves.length === 2 && halves[1] !== "" ? halves[1].split(":") : [];
+    const missing = 8 - head.length - tail.length;
+    if (halves.length === 2 ? missing < 1 : missing !== 0) return null;
+
+    const hextets = [...head, ...Array.from({ length: missing }, () => "0"), ...tail];
+    if (!hextets.every((hextet) => /^[0-9a-f]{1,4}$/i.test(hextet))) return null;
+    if (prefixLength !== undefined && prefixLength > 128) return null;
+    return {
+      family: "ipv6",
+      groups: hextets.map((hextet) => parseInt(hextet, 16)),
+      prefixLength,
+    };
+  }
+
+  /**
+   * Compress the longest run of zero groups (RFC 5952).
+   */
+  private static compressIpv6(hextets: string[]): string {
+    let bestStart = -1;
+    let bestLength = 1;
+    for (let i = 0; i < hextets.length; i++) {
+      let length = 0;
+      while (hextets[i + length] === "0") length++;
+      if (length > bestLength) {
+        bestStart = i;
+        bestLength = length;
+      }
+    }
+    if (bestStart === -1) return hextets.join(":");
+    const head = hextets.slice(0, bestStart).join(":");
+    const tail = hextets.slice(bestStart + bestLength).join(":");
+    return `${head}::${tail}`;
+  }
+
+  /**
+   * Percent-decode a URL component, leaving malformed escapes as written.
+   */
+  private static decodeUrlComponent(value: string): string {
+    try {
+      return decodeURIComponent(value.replace(/\+/g, " "));
+    } catch {
+      return value;
+    }
+  }
+
+  /**
+   * Parse the currency code and amount of a currency match,
+   * applying any K/M/B multiplier.
+   */
+  static parseCurrency(
+    text: string,
+    locales: string[] = DEFAULT_MASKING_POLICY.locales
+  ): CurrencyAmount {
+    const sign = /[-−]/.test(text) ? -1 : 1;
+    const symbol = CURRENCY_SYMBOLS[text.replace(/^[-−]/, "").charAt(0)];
+    const code =
+      symbol ??
+      CURRENCY_CODES.find((candidate) => text.includes(candidate)) ??
+      "USD";
+    const amount = /(\d[\d,.]*\d|\d)(?:\s?([KkMB]))?/.exec(text);
+    const base = amount ? Number(normalizeDecimal(amount[1], decimalConventions(locales))) : 0;
+    const multiplier = amount?.[2] ? AMOUNT_MULTIPLIERS[amount[2].toUpperCase()] : 1;
+    return { code, value: sign * base * multiplier };
+  }
+
+  /**
+   * Parse a numeric literal match: sign, thousands separators, decimal
+   * point, exponent, and percent suffix. Separators are read under the
+   * first locale the digits are well-formed for, so "1.234" is 1.234
+   * under ["en-US", "de-DE"] and 1234 under ["de-DE", "en-US"].
+   */
+  static parseNumber(
+    text: string,
+    locales: string[] = DEFAULT_MASKING_POLICY.locales
+  ): NumericLiteral {
+    const percent = text.endsWith("%");
+    const match = /^([-+−]?)([\d.,]*)((?:[eE][-+]?\d+)?)%?$/.exec(text);
+    if (!match) return { value: NaN, percent };
+    const [, sign, digits, exponent] = match;
+    const mantissa = normalizeDecimal(digits, decimalConventions(locales));
+    return { value: Number(`${sign.replace("−", "-")}${mantissa}${exponent}`), percent };
+  }
+
+  /**
+   * Parse the calendar components of a date match.
+   * Two-digit years pivot at 69 (00-68 -> 2000s, 69-99 -> 1900s).
+   * Slash dates where both leading fields could be the month are read
+   * DD/MM when the first locale writes dates day-first ("en-GB",
+   * "de-DE"); otherwise, including the default "en-US", month and day
+   * are left unset.
+   *
+   * @returns Date components, or null if the match is not a valid date
+   */
+  static parseDate(
+    text: string,
+    locales: string[] = DEFAULT_MASKING_POLICY.locales
+  ): DateParts | null {
+    let year: number;
+    let month: number | undefined;
+    let day: number | undefined;
+    let hasTime = false;
+
+    const iso = /^(\d{4})-(\d{2})-(\d{2})(.*)$/.exec(text);
+    const slash = /^(\d{1,2})\/(\d{1,2})\/(\d{2,4})$/.exec(text);
+    if (iso) {
+      year = Number(iso[1]);
+      month = Number(iso[2]);
+      day = Number(iso[3]);
+      hasTime = iso[4].length > 0;
+    } else if (slash) {
+      const first = Number(slash[1]);
+      const second = Number(slash[2]);
+      year = Number(slash[3]);
+      if (slash[3].length === 2) {
+        year += year < 69 ? 2000 : 1900;
+      }
+      if (first === 0 || second === 0 || (first > 12 && second > 12)) {
+        return null;
+      }
+      if (first > 12) {
+        day = first;
+        month = second;
+      } else if (second > 12) {
+        month = first;
+        day = second;
+      } else if (first === second) {
+        month = day = first;
+      } else if (locales.length > 0 && Distiller.writesDayFirst(locales[0])) {
+        day = first;
+        month = second;
+      }
+      // Otherwise MM/DD and DD/MM are both possible
+    } else {
+      const words = text.toLowerCase().match(/[a-z]+|\d+/g) ?? [];
+      // Ordinal suffixes ("15th") are two letters; month names are longer
+      const monthWord = words.find((word) => /^[a-z]{3,}$/.test(word));
+      const numbers = words.filter((word) => /^\d+$/.test(word));
+      if (!monthWord || numbers.length !== 2) return null;
+      month = MONTH_PREFIXES.indexOf(monthWord.slice(0, 3)) + 1;
+      day = Number(numbers[0]);
+      year = Number(numbers[1]);
+    }
+
+    if (month !== undefined && (month < 1 || month > 12)) return null;
+    if (day !== undefined && (day < 1 || day > 31)) return null;
+    return { year, month, day, hasTime };
+  }
+
+  /**
+   * Whether a locale writes slash dates day-first. Language-only tags
+   * and month-first regions do not.
+   */
+  private static writesDayFirst(locale: string): boolean {
+    const region = localeRegion(locale);
+    return region !== null && !MONTH_FIRST_REGIONS.has(region);
+  }
+
+  /**
+   * Check an IBAN candidate against the country length table and
+   * the ISO 7064 mod-97 checksum.
+   */
+  private static isValidIban(candidate: string): boolean {
+    const compact = candidate.replace(/ /g, "");
+    if (IBAN_LENGTHS[compact.slice(0, 2)] !== compact.length) return false;
+
+    // Move the first four characters to the end, letters become 10-35
+    const rearranged = compact.slice(4) + compact.slice(0, 4);
+    let remainder = 0;
+    for (const char of rearranged) {
+      const value = parseInt(char, 36);
+      remainder = (value > 9 ? remainder * 100 : remainder * 10) + value;
+      remainder %= 97;
+    }
+    return remainder === 1;
+  }
+
+  /**
+   * Check SSN structural rules: area not 000, 666, or 9xx;
+   * group not 00; serial not 0000.
+   */
+  private static isValidSsn(candidate: string): boolean {
+    const digits = candidate.replace(/\D/g, "");
+    if (digits.length !== 9) return false;
+    const area = digits.slice(0, 3);
+    const group = digits.slice(3, 5);
+    const serial = digits.slice(5);
+    return (
+      area !== "000" &&
+      area !== "666" &&
+      !area.startsWith("9") &&
+      group !== "00" &&
+      serial !== "0000"
+    );
+  }
+
+  /**
+   * Check that a phone candidate has a plausible number of digits
+   * (10-15 excluding any 00 international prefix, per E.164). When a
+   * locale region outside the North American Numbering Plan is enabled,
+   * 9-digit national numbers with a "0" trunk prefix ("06 1234567")
+   * are plausible too.
+   */
+  private isPlausiblePhone(candidate: string): boolean {
+    const digitCount = Distiller.phoneDigitCount(candidate);
+    if (digitCount >= 10 && digitCount <= 15) return true;
+    return (
+      digitCount === 9 &&
+      /^0[1-9]/.test(candidate) &&
+      this.policy.locales.some((locale) => {
+        const region = localeRegion(locale);
+        return region !== null && !NANP_REGIONS.has(region);
+      })
+    );
+  }
+
+  /**
+   * Count the digits of a number match, integer and fractional parts,
+   * excluding any exponent.
+   */
+  static numberDigitCount(text: string): number {
+    return text.replace(/[eE][-+]?\d+$/, "").replace(/\D/g, "").length;
+  }
+
+  /**
+   * Count the significant digits of a phone number.
+   * A leading "00" international dialing prefix is not counted.
+   */
+  static phoneDigitCount(text: string): number {
+    const digits = text.replace(/\D/g, "");
+    return text.startsWith("00") ? digits.length - 2 : digits.length;
+  }
+
+  /**
+   * Digits of a phone number without its country code, if one is present.
+   */
+  private static nationalPhoneDigits(text: string): string {
+    const match = /^(?:\+|00)(\d{1,3})[\s.-]?(.*)$/.exec(text);
+    return match ? match[2].replace(/\D/g, "") : text.replace(/\D/g, "");
+  }
 }
 
@@ -0,0 +1,123 @@
+/**
+ * Embedded gazetteer for place-name recognition.
+ * Deliberately small: country names, major cities, and geographic
+ * suffix words. Only a coarse region bucket is ever reported.
+ */
+
+/**
+ * Coarse regions that may be reported for a place.
+ */
+export const PLACE_REGIONS = [
+  "africa",
+  "asia",
+  "europe",
+  "middle_east",
+  "north_america",
+  "south_america",
+  "oceania",
+  "unknown",
+] as const;
+
+/**
+ * Type representing a coarse region bucket.
+ */
+export type PlaceRegion = (typeof PLACE_REGIONS)[number];
+
+/**
+ * Known place names by region.
+ */
+const PLACES_BY_REGION: Readonly<Record<Exclude<PlaceRegion, "unknown">, readonly string[]>> = {
+  africa: [
+    "Africa", "Algeria", "Egypt", "Ethiopia", "Ghana", "Kenya", "Morocco",
+    "Nigeria", "South Africa", "Tanzania", "Cairo", "Cape Town",
+    "Johannesburg", "Lagos", "Nairobi", "Casablanca", "Accra",
+  ],
+  asia: [
+    "Asia", "Bangladesh", "China", "India", "Indonesia", "Japan", "Korea",
+    "South Korea", "Malaysia", "Pakistan", "Philippines", "Singapore",
+    "Taiwan", "Thailand", "Vietnam", "Bangkok", "Beijing", "Delhi",
+    "New Delhi", "Hong Kong", "Jakarta", "Karachi", "Kyoto", "Manila",
+    "Mumbai", "Osaka", "Seoul", "Shanghai", "Shenzhen", "Tokyo",
+  ],
+  europe: [
+    "Europe", "Austria", "Belgium", "Denmark", "England", "Finland",
+    "France", "Germany", "Greece", "Ireland", "Italy", "Netherlands",
+    "Norway", "Poland", "Portugal", "Russia", "Scotland", "Spain",
+    "Sweden", "Switzerland", "Ukraine", "United Kingdom", "Amsterdam",
+    "Athens", "Barcelona", "Berlin", "Brussels", "Dublin", "Frankfurt",
+    "Geneva", "Lisbon", "London", "Madrid", "Milan", "Moscow", "Munich",
+    "Oslo", "Paris", "Prague", "Rome", "Stockholm", "Vienna", "Warsaw",
+    "Zurich",
+  ],
+  middle_east: [
+    "Iran", "Iraq", "Israel", "Jordan", "Lebanon", "Qatar", "Saudi Arabia",
+    "Turkey", "United Arab Emirates", "Abu Dhabi", "Doha", "Dubai",
+    "Istanbul", "Jerusalem", "Riyadh", "Tel Aviv",
+  ],
+  north_america: [
+    "America", "North America", "United States", "Canada", "Mexico",
+    "Atlanta", "Boston", "Chicago", "Dallas", "Denver", "Houston",
+    "Los Angeles", "Mexico City", "Miami", "Montreal", "New York",
+    "New York City", "Philadelphia", "San Francisco", "Seattle",
+    "Toronto", "Vancouver", "Washington",
+  ],
+  south_america: [
+    "South America", "Argentina", "Brazil", "Chile", "Colombia", "Peru",
+    "Venezuela", "Bogota", "Buenos Aires", "Lima", "Rio de Janeiro",
+    "Santiago", "Sao Paulo",
+  ],
+  oceania: [
+    "Australia", "New Zealand", "Auckland", "Brisbane", "Melbourne",
+    "Perth", "Sydney", "Wellington",
+  ],
+};
+
+/**
+ * Trailing words that mark a geographic feature or administrative area
+ * ("Nile River", "Orange County").
+ */
+const GEOGRAPHIC_SUFFIXES = new Set([
+  "Bay",
+  "Canyon",
+  "County",
+  "Desert",
+  "District",
+  "Island",
+  "Islands",
+  "Lake",
+  "Mountain",
+  "Mountains",
+  "Ocean",
+  "Peninsula",
+  "Prefecture",
+  "Province",
+  "Region",
+  "River",
+  "Sea",
+  "Valley",
+]);
+
+const REGION_BY_PLACE = new Map<string, PlaceRegion>(
+  Object.entries(PLACES_BY_REGION).flatMap(([region, places]) =>
+    places.map((place): [string, PlaceRegion] => [place, region as PlaceRegion])
+  )
+);
+
+/**
+ * Look up a capitalized run in the gazetteer.
+ *
+ * @param name - Candidate place name as written
+ * @returns Region bucket, "unknown" for geographic-suffix matches,
+ *          or null if the run is not a known place
+ */
+export function lookupPlace(name: string): PlaceRegion | null {
+  const region = REGION_BY_PLACE.get(name);
+  if (region !== undefined) {
+    return region;
+  }
+  const words = name.split(/[ \t]+/);
+  if (words.length > 1 && GEOGRAPHIC_SUFFIXES.has(words[words.length - 1])) {
+    return "unknown";
+  }
+  return null;
+}
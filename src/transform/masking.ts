@@ -1,7 +1,191 @@
+import { createHmac } from "crypto";
 import type { Entity } from "../entities/entity.ts";
+import { Role } from "../entities/roles.ts";
 import type { Relation } from "../entities/relations.ts";
 import type { SemanticRepresentation } from "./abstraction.ts";
-import { BoundaryViolationError } from "../core/errors.ts";
+import { CARD_NETWORKS, FILE_EXTENSIONS, RELATION_VERBS, TLD_BUCKETS } from "./abstraction.ts";
+import {
+  CURRENCY_CODES,
+  Distiller,
+  HONORIFICS,
+  IBAN_LENGTHS,
+  NAME_SCRIPTS,
+  POSTAL_COUNTRIES,
+  SECRET_PROVIDERS,
+  URL_SCHEMES,
+  foldForMatching,
+} from "./distiller.ts";
+import { PLACE_REGIONS } from "./gazetteer.ts";
+import type { ContextConstraints } from "../core/config.ts";
+import type { MaskingPolicy, PlaceholderStyle } from "../core/policy.ts";
+import { DEFAULT_MASKING_POLICY, PLATE_LOCALES, idSaltBytes, isPassthrough } from "../core/policy.ts";
+import { BoundaryViolationError, TransformationError } from "../core/errors.ts";
+
+/**
+ * Attribute values that are validated by shape rather than by allow-list.
+ * These are only emitted when the masking policy explicitly permits them
+ * or, for `idPattern` and `customPattern`, come from the policy itself.
+ */
+export const SHAPED_ATTRIBUTE_VALUES: Readonly<Record<string, RegExp>> = {
+  last4: /^\d{4}$/,
+  yearBucket: /^\d{1,3}0s$/,
+  magnitude: /^1e\d{1,3}$/,
+  idPattern: /^[a-z][a-z0-9_]{0,31}$/,
+  customPattern: /^[a-z][a-z0-9_]{0,31}$/,
+  code: /^[A-Z]\d[0-9A-Z]\.[0-9A-Z]{1,4}$/,
+  geoCell: /^-?\d{1,3}(?:\.\d)?,-?\d{1,3}(?:\.\d)?$/,
+  postalPrefix: /^[A-Z0-9]{3}$/,
+  tokenCount: /^(?:short|medium|long)$/,
+  digitCount: /^(?:short|medium|long)$/,
+  pathDepth: /^(?:short|medium|long)$/,
+  lengthBucket: /^(?:short|medium|long)$/,
+};
+
+/**
+ * Attributes the k-anonymity pass leaves alone: entity type, locators,
+ * counts, and scores the rest of the pipeline relies on, plus policy
+ * passthrough literals.
+ */
+export const K_ANONYMITY_EXEMPT_ATTRIBUTES: ReadonlySet<string> = new Set([
+  "type",
+  "position",
+  "sourceDoc",
+  "occurrences",
+  "confidence",
+  "literal",
+]);
+
+/**
+ * Sentinel that replaces an attribute value shared by fewer than
+ * kThreshold entities.
+ */
+export const SUPPRESSED_ATTRIBUTE_VALUE = "suppressed";
+
+/**
+ * Entity types whose matched text is semantic content rather than an
+ * identifier (ICD-10 codes). Their text is not treated as a raw identifier
+ * by the leak check; every other form of raw data still is.
+ */
+export const NON_IDENTIFYING_TYPES: ReadonlySet<string> = new Set(["icd10"]);
+
+/**
+ * Hex length of keyed synthetic IDs ("ENTITY_a1b2c3d4").
+ */
+export const KEYED_ID_LENGTH = 8;
+
+/**
+ * Keyed synthetic IDs in serialized output. Their hex suffixes are
+ * blanked before leak scans so a digest that happens to spell a raw
+ * value ("cafe", "123") is not reported as a leak.
+ */
+export const KEYED_ID_PATTERN = /\bENTITY_[0-9a-f]{8}\b/g;
+
+/**
+ * Derive session-stable synthetic ID suffixes from normalized surface
+ * forms as a truncated HMAC-SHA256 keyed by a secret salt.
+ *
+ * Collisions at the truncation length are resolved deterministically:
+ * forms are assigned in sorted order, and a form whose suffix is taken
+ * is rehashed with an attempt counter ("form\u0000" + n) until free.
+ * The assignment therefore depends only on the salt and the set of
+ * forms, not on the order they were mentioned.
+ *
+ * @param forms - Normalized surface forms
+ * @param salt - Secret salt; never leaves the transform
+ * @param length - Hex characters kept from the digest
+ * @returns Map from form to hex suffix, unique across the forms
+ */
+export function keyedSyntheticIds(
+  forms: readonly string[],
+  salt: Uint8Array,
+  length: number = KEYED_ID_LENGTH
+): Map<string, string> {
+  const ids = new Map<string, string>();
+  const used = new Set<string>();
+  for (const form of [...new Set(forms)].sort()) {
+    for (let attempt = 0; ; attempt++) {
+      const message = attempt === 0 ? form : `${form}\u0000${attempt}`;
+      const suffix = createHmac("sha256", salt).update(message).digest("hex").slice(0, length);
+      if (!used.has(suffix)) {
+        used.add(suffix);
+        ids.set(form, suffix);
+        break;
+      }
+    }
+  }
+  return ids;
+}
+
+/**
+ * Check the referential integrity of a transformed graph: entity IDs are
+ * unique, every relation endpoint is an entity, and no relation points
+ * at its own source unless the policy allows self-relations.
+ *
+ * @param context - Masked entities and relations
+ * @param policy - Masking policy (for allowSelfRelations)
+ * @throws TransformationError naming the offending synthetic ID
+ */
+export function validateTransformedContext(
+  context: Pick<MaskedRepresentation, "entities" | "relations">,
+  policy: Pick<MaskingPolicy, "allowSelfRelations"> = DEFAULT_MASKING_POLICY
+): void {
+  // Only synthetic IDs may appear in the message
+  const label = (id: string) =>
+    /^(?:ENTITY_(?:\d{4}|[0-9a-f]{8})|DOC_\d{4})$/.test(id) ? id : "(non-synthetic ID)";
+
+  const ids = new Set<string>();
+  for (const entity of context.entities) {
+    if (ids.has(entity.syntheticId)) {
+      throw new TransformationError(`Duplicate entity ID: ${label(entity.syntheticId)}`);
+    }
+    ids.add(entity.syntheticId);
+  }
+
+  for (const relation of context.relations) {
+    for (const id of [relation.from, relation.to]) {
+      if (!ids.has(id)) {
+        throw new TransformationError(`Relation references missing entity: ${label(id)}`);
+      }
+    }
+    if (relation.from === relation.to && !policy.allowSelfRelations) {
+      throw new TransformationError(`Self-relation on entity: ${label(relation.from)}`);
+    }
+  }
+}
+
+/**
+ * Copy of a masked representation without the `literal` attributes that
+ * the policy's passthrough controls permit, for leak scanning: those
+ * values are emitted verbatim by design.
+ *
+ * @param masked - Masked representation
+ * @param policy - Masking policy (for entityControls)
+ */
+export function withoutLiterals(
+  masked: MaskedRepresentation,
+  policy: Pick<MaskingPolicy, "entityControls">
+): MaskedRepresentation {
+  return {
+    ...masked,
+    entities: masked.entities.map((entity) => {
+      if (
+        entity.attributes.literal === undefined ||
+        !isPassthrough(policy, String(entity.attributes.type))
+      ) {
+        return entity;
+      }
+      const { literal: _literal, ...attributes } = entity.attributes;
+      return { ...entity, attributes };
+    }),
+  };
+}
+
+/**
+ * Synthetic ID of a document node ("DOC_0000").
+ */
+function documentId(index: number): string {
+  return `DOC_${String(index).padStart(4, "0")}`;
+}
 
 /**
  * Masked entity with synthetic ID.
@@ -9,7 +193,8 @@ import { BoundaryViolationError } from "../core/errors.ts";
  */
 export interface MaskedEntity {
   /**
-   * Synthetic stable ID (e.g., "ENTITY_0001").
+   * Synthetic stable ID: sequential ("ENTITY_0001") or, with an ID
+   * salt, keyed by surface form ("ENTITY_a1b2c3d4").
    */
   syntheticId: string;
 
@@ -21,7 +206,7 @@ export interface MaskedEntity {
   /**
    * Attributes with identifiers removed.
    */
-  attributes: Record<string, string | number>;
+  attributes: Record<string, string | number | boolean>;
 }
 
 /**
@@ -42,6 +227,11 @@ export interface MaskedRelation {
    * Synthetic ID of target entity.
    */
   to: string;
+
+  /**
+   * Number of observations collapsed into this relation; absent means one.
+   */
+  weight?: number;
 }
 
 /**
@@ -58,6 +248,64 @@ export interface MaskedRepresentation {
    * Relations using synthetic IDs.
    */
   relations: MaskedRelation[];
+
+  /**
+   * Aggregate invariants over the masked entities and relations.
+   */
+  constraints?: ContextConstraints;
+}
+
+/**
+ * Label used by bracketed and custom placeholders: PERSON for names, the
+ * upper-cased entity type otherwise ("EMAIL", "LONG_NUMBER").
+ */
+export function placeholderLabel(entity: MaskedEntity): string {
+  const type = String(entity.attributes.type ?? entity.role);
+  return type === "name" ? "PERSON" : type.toUpperCase();
+}
+
+/**
+ * Renders masked entities as text placeholders in the policy's
+ * placeholderStyle. Per-label indexes are assigned in first-render
+ * order and kept per synthetic ID, so one renderer should cover one
+ * output.
+ */
+export class PlaceholderRenderer {
+  private readonly style: PlaceholderStyle;
+  private readonly indexes = new Map<string, number>();
+  private readonly labelCounts = new Map<string, number>();
+
+  constructor(policy: Pick<MaskingPolicy, "placeholderStyle"> = DEFAULT_MASKING_POLICY) {
+    this.style = policy.placeholderStyle;
+  }
+
+  /**
+   * Placeholder text for an entity.
+   */
+  render(entity: MaskedEntity): string {
+    const label = placeholderLabel(entity);
+    let n = this.indexes.get(entity.syntheticId);
+    if (n === undefined) {
+      n = (this.labelCounts.get(label) ?? 0) + 1;
+      this.labelCounts.set(label, n);
+      this.indexes.set(entity.syntheticId, n);
+    }
+
+    if (typeof this.style === "object") {
+      return this.style.custom.replace(/\{(id|label|n)\}/g, (_field, name: string) =>
+        name === "id" ? entity.syntheticId : name === "label" ? label : String(n)
+      );
+    }
+    switch (this.style) {
+      case "bracketed":
+        return `[${label}_${n}]`;
+      case "angle_id":
+        return `<<${entity.syntheticId}>>`;
+      case "blocks":
+        // Fixed width, so the placeholder says nothing about the value's length
+        return "\u2588".repeat(5);
+    }
+  }
 }
 
 /**
@@ -66,6 +314,8 @@ export interface MaskedRepresentation {
  * Fails fast if any raw identifier remains after masking.
  */
 export class Masker {
+  private readonly policy: MaskingPolicy;
+
   /**
    * Mapping from original entity IDs to synthetic IDs.
    */
@@ -76,51 +326,210 @@ export class Masker {
    */
   private syntheticIdCounter = 0;
 
+  /**
+   * Number of attribute values the last mask call suppressed under the
+   * policy's kThreshold.
+   */
+  attributesSuppressed = 0;
+
+  constructor(policy: MaskingPolicy = DEFAULT_MASKING_POLICY) {
+    this.policy = policy;
+  }
+
   /**
    * Mask the semantic representation.
    * Removes all raw identifiers and replaces with synthetic IDs.
    *
    * @param representation - Semantic representation with raw identifiers
    * @param rawInputs - Original raw input strings for validation
+   * @param idSalt - Optional secret salt; when set, synthetic IDs are keyed
+   *                 by surface form so they stay stable across calls.
+   *                 Defaults to the policy's idSalt.
    * @returns Masked representation safe for boundary crossing
    * @throws BoundaryViolationError if any raw identifier remains
+   * @throws TransformationError if a relation is dangling or a self-loop,
+   *         or an entity ID is repeated
    */
   mask(
     representation: SemanticRepresentation,
-    rawInputs: string[]
+    rawInputs: string[],
+    idSalt: Uint8Array | undefined = idSaltBytes(this.policy)
   ): MaskedRepresentation {
     // Reset state for each mask operation
     this.idMapping.clear();
     this.syntheticIdCounter = 0;
+    this.attributesSuppressed = 0;
+
+    try {
+      // Extract all raw identifiers from entities, including those about
+      // to be suppressed, so the leak check still covers them
+      const rawIdentifiers = this.extractRawIdentifiers(representation.entities);
+
+      // Low-confidence entities are dropped along with their relations
+      const entities = representation.entities.filter((entity) => this.meetsConfidence(entity));
+      const keptIds = new Set(entities.map((entity) => entity.id));
+      const relations = representation.relations.filter(
+        (relation) => keptIds.has(relation.from) && keptIds.has(relation.to)
+      );
+
+      // Build ID mapping; repeated surface forms were already merged into
+      // one entity by the distiller, so each form maps to exactly one ID
+      if (idSalt !== undefined) {
+        this.buildKeyedMapping(entities, idSalt);
+      } else {
+        for (const entity of entities) {
+          const syntheticId = this.generateSyntheticId();
+          this.idMapping.set(entity.id, syntheticId);
+        }
+      }
+
+      // Mask entities, then generalize attribute values too rare to share
+      const maskedEntities = entities.map((entity) => this.maskEntity(entity, rawIdentifiers));
+      this.attributesSuppressed = this.suppressRareAttributes(maskedEntities);
+
+      // Mask relations, merging any that coincide once IDs are replaced
+      const firstOccurrence = new Map(
+        entities.map((entity) => [
+          entity.id,
+          [Number(entity.attributes.sourceDoc ?? 0), Number(entity.attributes.position ?? 0)],
+        ])
+      );
+      const maskedRelations = this.mergeRelations(
+        relations.map((relation) => this.maskRelation(relation, firstOccurrence))
+      );
+
+      // Document nodes follow the extracted entities, so their IDs are unchanged
+      if (this.policy.emitDocumentNodes) {
+        maskedEntities.push(...this.documentNodes(rawInputs));
+        maskedRelations.push(...this.membershipRelations(entities));
+      }
+
+      const result: MaskedRepresentation = {
+        entities: maskedEntities,
+        relations: maskedRelations,
+        constraints: this.deriveConstraints(maskedEntities, maskedRelations),
+      };
 
-    // Extract all raw identifiers from entities
-    const rawIdentifiers = this.extractRawIdentifiers(representation.entities);
+      // Verify no raw data leaked; passthrough literals are exempt
+      this.verifyNoRawDataLeakage(withoutLiterals(result, this.policy), rawInputs, rawIdentifiers);
 
-    // Build ID mapping
-    for (const entity of representation.entities) {
-      const syntheticId = this.generateSyntheticId();
-      this.idMapping.set(entity.id, syntheticId);
+      // Reject dangling references, self-loops, and duplicate IDs
+      validateTransformedContext(result, this.policy);
+
+      return result;
+    } finally {
+      // Entity IDs embed raw text; the mapping must not outlive the transform
+      this.idMapping.clear();
     }
+  }
 
-    // Mask entities
-    const maskedEntities = representation.entities.map((entity) =>
-      this.maskEntity(entity, rawIdentifiers)
-    );
+  /**
+   * Placeholder renderer in this masker's policy style, for surfaces that
+   * substitute masked entities into text.
+   */
+  createPlaceholderRenderer(): PlaceholderRenderer {
+    return new PlaceholderRenderer(this.policy);
+  }
 
-    // Mask relations
-    const maskedRelations = representation.relations.map((relation) =>
-      this.maskRelation(relation)
+  /**
+   * One synthetic "Document" entity per input, carrying only its index
+   * and a length bucket (under 1,000 characters short, under 10,000
+   * medium, else long).
+   */
+  private documentNodes(rawInputs: string[]): MaskedEntity[] {
+    return rawInputs.map((document, index) => ({
+      syntheticId: documentId(index),
+      role: Role.Document,
+      attributes: {
+        index,
+        lengthBucket: document.length < 1_000 ? "short" : document.length < 10_000 ? "medium" : "long",
+      },
+    }));
+  }
+
+  /**
+   * A "mentioned_in" relation from each entity to every document it occurs
+   * in, unless the policy's allowedRelationTypes excludes the type.
+   */
+  private membershipRelations(entities: Entity[]): MaskedRelation[] {
+    const allowed = this.policy.allowedRelationTypes;
+    if (allowed !== null && !allowed.includes("mentioned_in")) {
+      return [];
+    }
+    return entities.flatMap((entity) =>
+      (entity.sourceDocs ?? [Number(entity.attributes.sourceDoc ?? 0)]).map((document) => ({
+        type: "mentioned_in",
+        from: this.idMapping.get(entity.id)!,
+        to: documentId(document),
+      }))
     );
+  }
 
-    const result: MaskedRepresentation = {
-      entities: maskedEntities,
-      relations: maskedRelations,
-    };
+  /**
+   * Replace each descriptive attribute value held by fewer than the
+   * policy's kThreshold entities with SUPPRESSED_ATTRIBUTE_VALUE. Counts
+   * are over the whole entity list, so the result does not depend on
+   * entity order.
+   *
+   * @returns Number of values suppressed
+   */
+  private suppressRareAttributes(entities: MaskedEntity[]): number {
+    const k = this.policy.kThreshold;
+    if (k === null) {
+      return 0;
+    }
+    const keyOf = (key: string, value: string | number | boolean) =>
+      `${key}\u0000${typeof value}\u0000${String(value)}`;
+    const counts = new Map<string, number>();
+    for (const entity of entities) {
+      for (const [key, value] of Object.entries(entity.attributes)) {
+        if (!K_ANONYMITY_EXEMPT_ATTRIBUTES.has(key)) {
+          counts.set(keyOf(key, value), (counts.get(keyOf(key, value)) ?? 0) + 1);
+        }
+      }
+    }
+    let suppressed = 0;
+    for (const entity of entities) {
+      for (const [key, value] of Object.entries(entity.attributes)) {
+        if (!K_ANONYMITY_EXEMPT_ATTRIBUTES.has(key) && (counts.get(keyOf(key, value)) ?? 0) < k) {
+          entity.attributes[key] = SUPPRESSED_ATTRIBUTE_VALUE;
+          suppressed++;
+        }
+      }
+    }
+    return suppressed;
+  }
+
+  /**
+   * Derive aggregate invariants: entities per role (keys sorted), the
+   * highest relation degree, and the policy version.
+   */
+  private deriveConstraints(
+    entities: MaskedEntity[],
+    relations: MaskedRelation[]
+  ): ContextConstraints {
+    const counts = new Map<string, number>();
+    for (const entity of entities) {
+      counts.set(entity.role, (counts.get(entity.role) ?? 0) + 1);
+    }
+    const roleCounts: Record<string, number> = {};
+    for (const role of [...counts.keys()].sort()) {
+      roleCounts[role] = counts.get(role)!;
+    }
 
-    // Verify no raw data leaked
-    this.verifyNoRawDataLeakage(result, rawInputs, rawIdentifiers);
+    const degrees = new Map<string, number>();
+    for (const relation of relations) {
+      degrees.set(relation.from, (degrees.get(relation.from) ?? 0) + 1);
+      if (relation.to !== relation.from) {
+        degrees.set(relation.to, (degrees.get(relation.to) ?? 0) + 1);
+      }
+    }
 
-    return result;
+    return {
+      roleCounts,
+      maxRelationDegree: Math.max(0, ...degrees.values()),
+      policyVersion: this.policy.version,
+    };
   }
 
   /**
@@ -132,6 +541,48 @@ export class Masker {
     return id;
   }
 
+  /**
+   * Check an entity's confidence against the policy threshold.
+   */
+  meetsConfidence(entity: Entity): boolean {
+    const confidence = entity.attributes.confidence;
+    return typeof confidence !== "number" || confidence >= this.policy.minConfidence;
+  }
+
+  /**
+   * Map each entity to a keyed synthetic ID derived from its normalized
+   * surface form. Entities sharing a form (distinct policy patterns) are
+   * told apart by their order of appearance.
+   */
+  private buildKeyedMapping(entities: Entity[], idSalt: Uint8Array): void {
+    const seen = new Map<string, number>();
+    const forms = entities.map((entity) => {
+      const type = String(entity.attributes.type);
+      const form = Distiller.normalizeSurfaceForm(type, this.originalText(entity) ?? entity.id);
+      const count = seen.get(form) ?? 0;
+      seen.set(form, count + 1);
+      return count === 0 ? form : `${form}\u0000#${count}`;
+    });
+    const suffixes = keyedSyntheticIds(forms, idSalt);
+    entities.forEach((entity, index) => {
+      this.idMapping.set(entity.id, `ENTITY_${suffixes.get(forms[index])}`);
+    });
+  }
+
+  /**
+   * Recover the matched text from a pre-masking entity ID
+   * ("type_index_text"); the type itself may contain underscores.
+   */
+  private originalText(entity: Entity): string | null {
+    const type = String(entity.attributes.type);
+    if (!entity.id.startsWith(`${type}_`)) {
+      return null;
+    }
+    const rest = entity.id.slice(type.length + 1);
+    const originalText = rest.slice(rest.indexOf("_") + 1);
+    return originalText.length > 0 ? originalText : null;
+  }
+
   /**
    * Extract all raw identifiers from entities.
    */
@@ -139,23 +590,31 @@ export class Masker {
     const identifiers = new Set<string>();
 
     for (const entity of entities) {
-      // The entity ID contains the original text
-      const parts = entity.id.split("_");
-      if (parts.length >= 3) {
-        // Original text is everything after type_index_
-        const originalText = parts.slice(2).join("_");
-        if (originalText.length > 0) {
-          identifiers.add(originalText);
+      // Passthrough matches are emitted verbatim by design
+      if (isPassthrough(this.policy, String(entity.attributes.type))) {
+        continue;
+      }
+
+      // The entity ID contains the original text after "type_index_"
+      const originalText = this.originalText(entity);
+      if (originalText !== null && !NON_IDENTIFYING_TYPES.has(String(entity.attributes.type))) {
+        identifiers.add(originalText);
+      }
+
+      // Component forms recorded by the distiller (e.g. email local part)
+      for (const term of entity.boundaryTerms ?? []) {
+        if (term.length > 0) {
+          identifiers.add(term);
         }
       }
 
       // Check attributes for string values that might be identifiers
       // Exclude known safe values
-      for (const value of Object.values(entity.attributes)) {
+      for (const [key, value] of Object.entries(entity.attributes)) {
         if (
           typeof value === "string" &&
           value.length > 2 &&
-          !this.isSafeAttributeValue(value)
+          !this.isSafeAttributeValue(value, key)
         ) {
           identifiers.add(value);
         }
@@ -180,19 +639,26 @@ export class Masker {
     }
 
     // Mask attributes - remove any that contain raw identifiers
-    const maskedAttributes: Record<string, string | number> = {};
+    const maskedAttributes: Record<string, string | number | boolean> = {};
     for (const [key, value] of Object.entries(entity.attributes)) {
-      if (typeof value === "number") {
-        // Numeric values are safe to pass through
+      if (typeof value === "number" || typeof value === "boolean") {
+        // Numeric and boolean values are safe to pass through
         maskedAttributes[key] = value;
       } else if (typeof value === "string") {
+        // Verbatim text the policy's passthrough control asks for
+        if (key === "literal") {
+          if (isPassthrough(this.policy, String(entity.attributes.type))) {
+            maskedAttributes[key] = value;
+          }
+          continue;
+        }
         // Check if the string value is a raw identifier
         if (rawIdentifiers.has(value)) {
           // Skip this attribute - it contains raw data
           continue;
         }
         // Check if it's a safe enumerated value
-        if (this.isSafeAttributeValue(value)) {
+        if (this.isSafeAttributeValue(value, key)) {
           maskedAttributes[key] = value;
         }
         // Otherwise, skip the attribute
@@ -209,7 +675,12 @@ export class Masker {
   /**
    * Check if an attribute value is safe to include.
    */
-  private isSafeAttributeValue(value: string): boolean {
+  private isSafeAttributeValue(value: string, key?: string): boolean {
+    const shape = key !== undefined ? SHAPED_ATTRIBUTE_VALUES[key] : undefined;
+    if (shape && shape.test(value)) {
+      return true;
+    }
+
     // Allow-list of safe attribute values
     const safeValues = new Set([
       "name",
@@ -217,14 +688,63 @@ export class Masker {
       "date",
       "currency",
       "identifier",
+      "email",
+      "phone",
+      "ssn",
+      "national_id",
+      "vin",
+      "license_plate",
+      "npi",
+      "mrn",
+      "icd10",
+      "coordinates",
+      "postal_code",
+      "custom",
+      "restricted",
+      "payment_card",
+      "iban",
+      "url",
+      "ip_address",
+      "ipv4",
+      "ipv6",
+      "mac",
+      "serial",
+      "long_number",
+      "handle",
+      "file_path",
+      "address",
+      "uuid",
+      "secret",
+      "jwt",
+      "organization",
+      "place",
+      "US",
+      ...TLD_BUCKETS,
+      ...CARD_NETWORKS,
+      ...FILE_EXTENSIONS,
+      ...Object.keys(IBAN_LENGTHS),
+      ...CURRENCY_CODES,
+      ...URL_SCHEMES,
+      ...SECRET_PROVIDERS,
+      ...HONORIFICS,
+      ...PLACE_REGIONS,
+      ...NAME_SCRIPTS,
+      ...PLATE_LOCALES,
+      ...POSTAL_COUNTRIES,
     ]);
     return safeValues.has(value);
   }
 
   /**
    * Mask a relation by replacing entity IDs with synthetic IDs.
+   *
+   * @param firstOccurrence - Document index and offset where each entity
+   *                          first occurs, keyed by pre-masking ID
    */
-  private maskRelation(relation: Relation): MaskedRelation {
+  private maskRelation(
+    relation: Relation,
+    firstOccurrence: ReadonlyMap<string, number[]>
+  ): MaskedRelation {
     const fromSynthetic = this.idMapping.get(relation.from);
     const toSynthetic = this.idMapping.get(relation.to);
 
@@ -234,11 +754,39 @@ export class Masker {
       );
     }
 
-    return {
-      type: relation.type,
-      from: fromSynthetic,
-      to: toSynthetic,
-    };
+    // Untyped edges follow reading order: from the entity that occurs
+    // first, ties broken by synthetic ID, regardless of extraction order
+    const [fromDoc, fromOffset] = firstOccurrence.get(relation.from) ?? [0, 0];
+    const [toDoc, toOffset] = firstOccurrence.get(relation.to) ?? [0, 0];
+    const reversed =
+      (toDoc - fromDoc || toOffset - fromOffset || (toSynthetic < fromSynthetic ? -1 : 1)) < 0;
+    const [from, to] =
+      (relation.type === "related" || relation.type === "co_session") && reversed
+        ? [toSynthetic, fromSynthetic]
+        : [fromSynthetic, toSynthetic];
+    const masked: MaskedRelation = { type: relation.type, from, to };
+    if (relation.weight !== undefined) {
+      masked.weight = relation.weight;
+    }
+    return masked;
+  }
+
+  /**
+   * Deduplicate relations on (from, to, type), summing their weights.
+   * Keeps the first occurrence's position.
+   */
+  private mergeRelations(relations: MaskedRelation[]): MaskedRelation[] {
+    const byKey = new Map<string, MaskedRelation>();
+    for (const relation of relations) {
+      const key = [relation.from, relation.to, relation.type].join("\u0000");
+      const existing = byKey.get(key);
+      if (existing === undefined) {
+        byKey.set(key, relation);
+      } else {
+        existing.weight = (existing.weight ?? 1) + (relation.weight ?? 1);
+      }
+    }
+    return [...byKey.values()];
   }
 
   /**
@@ -250,11 +798,17 @@ export class Masker {
     rawInputs: string[],
     rawIdentifiers: Set<string>
   ): void {
-    const serialized = JSON.stringify(result);
+    const serialized = JSON.stringify(result).replace(KEYED_ID_PATTERN, "ENTITY_");
+    const foldedValues = foldForMatching(
+      this.collectStringValues(result).replace(KEYED_ID_PATTERN, "ENTITY_")
+    );
 
     // Check for any raw identifier in the output
     for (const identifier of rawIdentifiers) {
-      if (identifier.length >= 3 && serialized.includes(identifier)) {
+      if (
+        identifier.length >= 3 &&
+        (serialized.includes(identifier) || this.foldedLeak(identifier, foldedValues))
+      ) {
         throw new BoundaryViolationError(
           `Raw identifier leaked through masking: detected in output`
         );
@@ -264,13 +818,16 @@ export class Masker {
     // Check for any significant substring from raw inputs
     for (const input of rawInputs) {
       // Check for substrings of significant length (3+ chars)
-      const words = input.match(/\b\w{3,}\b/g) || [];
+      const words = input.normalize("NFC").match(/[\p{L}\p{M}\p{N}_]{3,}/gu) || [];
       for (const word of words) {
         // Skip common words and safe values
         if (this.isCommonWord(word)) continue;
 
         // Check if this word appears as a capitalized name or identifier
-        if (/^[A-Z][a-z]+/.test(word) && serialized.includes(word)) {
+        if (
+          /^\p{Lu}\p{Ll}/u.test(word) &&
+          (serialized.includes(word) || this.foldedLeak(word, foldedValues))
+        ) {
           throw new BoundaryViolationError(
             `Raw text leaked through masking: potential name detected`
           );
@@ -279,6 +836,39 @@ export class Masker {
     }
   }
 
+  /**
+   * Check whether an accented raw term appears in the output in folded
+   * form ("José" as "jose"). ASCII terms are matched exactly by the
+   * caller, since the output vocabulary is itself lowercase ASCII.
+   */
+  private foldedLeak(term: string, foldedValues: string): boolean {
+    const folded = foldForMatching(term);
+    return (
+      folded !== term.toLowerCase() &&
+      folded.length >= 3 &&
+      !this.isCommonWord(folded) &&
+      !this.isSafeAttributeValue(folded) &&
+      foldedValues.includes(folded)
+    );
+  }
+
+  /**
+   * Join every string value in the masked output (keys excluded).
+   */
+  private collectStringValues(result: MaskedRepresentation): string {
+    const values: string[] = [];
+    for (const entity of result.entities) {
+      values.push(entity.syntheticId, entity.role);
+      for (const value of Object.values(entity.attributes)) {
+        if (typeof value === "string") values.push(value);
+      }
+    }
+    for (const relation of result.relations) {
+      values.push(relation.type, relation.from, relation.to);
+    }
+    return values.join("\n");
+  }
+
   /**
    * Check if a word is common enough to not be considered identifying.
    */
@@ -327,11 +917,26 @@ export class Masker {
       "references",
       "dated",
       "precedes",
+      "before",
+      "after",
       "name",
       "number",
       "date",
       "currency",
       "identifier",
+      "email",
+      "contact",
+      "url",
+      "resource",
+      "scheme",
+      "path",
+      "http",
+      "https",
+      "account",
+      "location",
+      "credential",
+      "secret",
+      "organization",
       "position",
       "numericValue",
       "syntheticId",
@@ -340,7 +945,13 @@ export class Masker {
       "relations",
       "ENTITY",
     ]);
-    return commonWords.has(word.toLowerCase()) || commonWords.has(word);
+    const verbs: readonly string[] = RELATION_VERBS;
+    return (
+      commonWords.has(word.toLowerCase()) ||
+      commonWords.has(word) ||
+      verbs.includes(word.toLowerCase()) ||
+      this.policy.relationVerbs.includes(word.toLowerCase())
+    );
   }
 }
 
@@ -0,0 +1,127 @@
+/**
+ * Embedded common-English wordlist.
+ * Used when MaskingPolicy.allowCommonWords is set to keep capitalized
+ * sentence-initial words ("The", "Contract") from becoming entities.
+ * Words that double as common given names ("will", "mark", "grace")
+ * are deliberately omitted so they are still treated as identifying.
+ */
+
+/**
+ * Lowercase words, space-delimited to keep the module compact.
+ */
+const WORDS = `
+a able about above absence absolute abstract accept acceptance access according account accounts accuracy
+achieve acknowledge acquire across act action actions active activity actual actually add added addition
+additional address adequate adjust adjustment administration admit adopt advance advantage advice affect
+after afternoon again against age agency agenda agent ago agree agreed agreement ahead aid aim air all
+allocate allow allowed almost alone along already also alternative although always amend amendment among
+amount analysis analyst and annual another answer any anyone anything anyway apart apparent appeal appear
+appendix applicable application apply appoint appointment appreciate approach appropriate approval approve
+approved approximately are area areas argue argument arise around arrange arrangement arrival arrive
+article as aside ask asked asset assets assess assessment assign assignment assist assistance assistant
+associate assume assurance at attach attached attachment attempt attend attendance attention attorney
+audit author authority authorize automatic available average avoid await award aware away back
+background bad balance bank base based basic basis be became because become been before began begin
+beginning behalf behind being believe below beneficiary benefit benefits best better between beyond bid
+big billing binding board body book both bottom box branch breach break brief briefly bring broad
+brought budget build building business busy but buy buyer by calculate calendar call called came can
+cancel cancellation cannot capacity capital card care careful carry case cases cash cause caution
+certain certainly certificate chain chair chairman chance change changes chapter charge charges chart
+check checked chief choice choose chosen circumstances cite claim claims class clause clear clearly
+client clients close closed closing code collect collection come comes coming comment comments commercial
+commission commit commitment committee common communicate communication company compare comparison
+compensation complete completed completely completion compliance comply component concern concerned
+concerning conclude conclusion condition conditions conduct conference confidential confidentiality
+confirm confirmation conflict consent consequence consider consideration considered consist consistent
+constitute construction consult consultant consumer contact contain contains content context continue
+continued contract contractor contracts contrary contribute contribution control convenience copy
+corporate correct cost costs could council counsel count counter country course court cover coverage
+create created credit criteria critical current currently customer customers cycle daily damage damages
+data date dated day days deadline deal dear debt decide decision declare decline deduct default defect
+defend define defined definition degree delay deliver delivery demand department depend deposit describe
+described description design detail detailed details determine determined develop development did
+differ difference different difficult direct direction directly director disclose disclosure discount
+discuss discussed discussion dispute distribute distribution division do document documents does doing
+done down draft due during duty each early earlier easily east easy effect effective efficient effort
+either electronic element else email employ employee employees employer employment enable enclosed end
+ending enforce engage enough ensure enter entire entirely entitled entity equal equipment error escrow
+especially establish estimate evaluate evaluation even evening event events eventually ever every
+everyone everything evidence exact exactly example exceed except exception exchange exclude exclusive
+execute executed execution executive exercise exist existing expect expected expense expenses
+experience expert expiration expire explain express extend extension extent external extra face
+facility fact factor factors fail failure fair fall false far fee fees few field figure file filed
+filing final finally finance financial find finding findings fine finish firm first fiscal fix fixed
+floor follow following follows for force forecast form formal format former forth forward found
+foundation free frequency frequent from full fully function fund funding funds further future gain
+general generally get give given giving go goal goals going good goods govern governing government
+great greater gross group growth guarantee guidance guide had half hand handle happen hard has
+have having he head heading health hear hearing held help hence her here hereby herein hereto high
+higher highly him his history hold holder home hour hours how however human idea identify if immediate
+immediately impact implement implementation important improve improvement in include included includes
+including income increase incurred indeed independent index indicate indicated individual industry
+information initial input inquiry inside instead instruction instructions insurance intend intended
+intent interest interested interim internal into introduce invoice invoices involve involved issue
+issued issues it item items its itself job join joint judgment just keep key kind know knowledge known
+labor lack large last late later latest law laws lead leader leave left legal lender less let letter
+level liability liable license like likely limit limited limitation line list little loan local long
+look loss losses low made mail main maintain maintenance major majority make makes making manage
+management manager mandatory manner manual many market marketing master material materials matter
+matters maximum me mean means measure measures meet meeting meetings member members memo mention
+merely method might minimum minor minute minutes missing mode model modify moment money month monthly
+months more moreover morning most mostly move much must mutual my name names narrow nature near
+nearly necessary need needed needs negotiate neither net never nevertheless new next no none nor normal
+north not note noted notes nothing notice notification notify now number numbers object objective
+obligation obligations observe obtain obvious occur of off offer office officer official often on
+once one ongoing only open operate operation operations opinion opportunity option optional options or
+order orders ordinary organize original other others otherwise our out outcome outline output outside
+outstanding over overall overdue owe own owner ownership page paid paper paragraph part partial
+participate particular particularly parties partner party pass past patient pay payable payment
+payments pending people per percent perform performance period permit person personal persons phase
+phone place plan planned planning plans please plus point points policy portion position possible
+post potential power practice prefer preliminary premium prepare prepared presence present president
+previous previously price prices pricing primary principal prior priority private probably problem
+procedure proceed process product production products professional program progress project projects
+promptly proper property proposal propose proposed protect protection provide provided provider
+provides provision provisions public purchase purpose purposes pursuant put qualify quality quantity
+quarter quarterly question questions quick quickly quite quote raise range rate rates rather reach
+read ready real reason reasonable receipt receive received recent recently recommend recommendation
+record records recover reduce reduction refer reference referred refund regard regarding regards
+region register regular regulation regulations reimburse reject related relating relation relationship
+release relevant remain remaining remedy reminder remove renew renewal rent repair replace reply report
+reported reports represent representative request requested requests require required requirement
+requirements research reserve resolution resolve resource resources respect respective respond
+response responsibility responsible rest result results retain retention return revenue review reviewed
+revise revised right rights risk role rule rules run safe safety said salary sale sales same sample
+satisfy save schedule scheduled scope second section sector secure security see seek seem seen select
+sell seller send senior sent separate series serve service services session set settle settlement
+several shall share shares she short should show shown sign signature signed significant similar
+simple since single site situation size small so sold sole solely solution some someone something
+soon sort source south special specific specified staff stage standard standards start started state
+stated statement status step steps still stock stop storage strategy street strong structure subject
+submit submitted subsequent substantial successful such sufficient suggest suitable summary supplier
+supply support sure survey suspend system systems table take taken target task tasks tax team
+technical term terminate termination terms test than thank thanks that the their them then there
+thereafter therefore these they thing things third this those though through throughout thus time
+timeline times title to today together too top total toward track trade training transaction
+transfer transition travel treat trial true trust try turn type under understand understanding unit
+unless until up update updated upon urgent us use used user using usual usually valid value various
+vendor verify version very via view visit void volume wage waive waiver want warranty was way we week
+weekly weeks well went were west what whatever when whereas whether which while who whole whom whose
+why wide with within without work workers working would write written year yearly years yes yet you
+your
+`;
+
+/**
+ * Common-word set, built once at module load.
+ */
+const COMMON_ENGLISH_WORDS: ReadonlySet<string> = new Set(WORDS.split(/\s+/).filter(Boolean));
+
+/**
+ * Check whether a word is in the embedded common-English wordlist.
+ *
+ * @param word - Word as written; compared case-insensitively
+ * @returns True for dictionary words
+ */
+export function isDictionaryWord(word: string): boolean {
+  return COMMON_ENGLISH_WORDS.has(word.toLowerCase());
+}
@@ -1,7 +1,159 @@
 import { describe, it } from "node:test";
 import assert from "node:assert";
 import { Axiom } from "../src/core/axiom.ts";
-import { AttestationVerifier } from "../src/attestation/verifier.ts";
+import { AttestationVerifier, verifyAttestationReport } from "../src/attestation/verifier.ts";
+import { EnclaveBridge, hashPolicy, validateRequest } from "../src/runtime/enclave-bridge.ts";
+import { resolveMaskingPolicy } from "../src/core/policy.ts";
+import { canonicalize, hash } from "../src/core/canonical.ts";
+import { generateTestVectors } from "../src/core/vectors.ts";
+import { HASH_ENCODINGS, encodeDigest, hashOutput } from "../src/core/serialization.ts";
+import type { HashEncoding } from "../src/core/serialization.ts";
+import {
+  AttestationError,
+  ConfigurationError,
+  InvalidInputError,
+  MeasurementMismatchError,
+  SecurityInvariantError,
+  TokenError,
+} from "../src/core/errors.ts";
+import { computeInputHash, createReportData, decodeNonce } from "../src/runtime/session.ts";
+import {
+  SIMULATOR_MEASUREMENT_ENV,
+  SimulatorBackend,
+  TdxSimulatorBackend,
+  bindingReportData,
+  simulatorMeasurement,
+} from "../src/runtime/attestation-backend.ts";
+import type { AttestationBackend, BindingInput } from "../src/runtime/attestation-backend.ts";
+import {
+  CERT_TABLE_GUIDS,
+  createCertTable,
+  crlUrl,
+  fetchVcek,
+  parseCertTable,
+  vcekUrl,
+} from "../src/attestation/certs.ts";
+import type { VcekOptions } from "../src/attestation/certs.ts";
+import {
+  createEvidenceEnvelope,
+  evidenceFromJson,
+  evidenceToJson,
+} from "../src/attestation/evidence.ts";
+import {
+  coseExpectedBinding,
+  createCoseEvidence,
+  decodeCoseEvidence,
+  decodeCbor,
+  readSign1,
+} from "../src/attestation/cose.ts";
+import {
+  AMD_OEMID,
+  EAT_CLAIM_KEYS,
+  createEatToken,
+  decodeEatToken,
+  getEatToken,
+  verifyEatToken,
+} from "../src/attestation/eat.ts";
+import {
+  mintAttestationToken,
+  verificationTime,
+  verifyAttestationToken,
+} from "../src/attestation/token.ts";
+import {
+  SIGNATURE_ALGO_SIMULATOR_ED25519,
+  SIGNED_REPORT_BYTES,
+  SIMULATOR_REPORT_V3_VERSION,
+  SIMULATOR_REPORT_VERSION,
+  emptyReport,
+  parseReport,
+  reportToBytes,
+  reportToJson,
+  signatureToEd25519,
+} from "../src/attestation/report.ts";
+import {
+  TDX_SIGNED_QUOTE_BYTES,
+  isTdxQuote,
+  parseTdxQuote,
+  tdxQuoteToBytes,
+} from "../src/attestation/tdx.ts";
+import {
+  createHash,
+  createPrivateKey,
+  generateKeyPairSync,
+  verify as cryptoVerify,
+} from "crypto";
+import type { KeyObject } from "crypto";
+import { readFileSync } from "fs";
+import { mkdtemp, readFile, readdir, rm, writeFile } from "fs/promises";
+import { execFileSync } from "child_process";
+import { createServer } from "http";
+import type { AddressInfo } from "net";
+import { tmpdir } from "os";
+import { join } from "path";
+import { fileURLToPath } from "url";
+import type {
+  EnclaveRequest,
+  EnclaveResponse,
+  ReportVerificationResult,
+} from "../src/attestation/types.ts";
+import { signMeasurementRegistry } from "../src/attestation/registry.ts";
+import {
+  SDK_VERSION,
+  VERIFICATION_BUNDLE_VERSION,
+  verificationBundleFromJson,
+  verifyVerificationBundle,
+} from "../src/attestation/bundle.ts";
+import type { VerificationBundle } from "../src/attestation/bundle.ts";
+import { SessionAttestationCache } from "../src/runtime/session-attestation.ts";
+import { SIMULATOR_KEY_LABEL } from "../src/attestation/types.ts";
+import type { AxiomConfig, ReasonResult } from "../src/core/config.ts";
+
+// 32 distinct bytes from seed, so the nonce passes validateNonce
+function testNonce(seed: number): Uint8Array {
+  return Uint8Array.from({ length: 32 }, (_, index) => (seed + index) & 0xff);
+}
+
+// What a native stub's report must bind: its output (and input, when it
+// sends input_hash) to request
+function stubBinding(
+  request: EnclaveRequest,
+  outputHash: Uint8Array,
+  inputHash?: Uint8Array
+): BindingInput {
+  return {
+    sessionId: request.sessionId,
+    nonce: request.nonce,
+    configHash: Buffer.from(request.configHash, "hex"),
+    policyHash: Buffer.from(hashPolicy(request.policy), "hex"),
+    outputHash,
+    timestamp: request.timestamp,
+    legacyReportData: request.legacyReportData === true,
+    hashAlgorithm: request.hashAlgorithm ?? "sha256",
+    ...(inputHash !== undefined ? { inputHash } : {}),
+    bindingVersion: request.bindingVersion ?? 1,
+  };
+}
+
+// Stand in for the native module of a bridge constructed with preferNative
+function withNativeModule(bridge: EnclaveBridge, stub: object): void {
+  (bridge as unknown as { runner: { nativeModule: unknown } }).runner.nativeModule = stub;
+}
+
+const STUB_REPORT_KEY = generateKeyPairSync("ed25519").privateKey;
+
+// A simulator report for a native stub's attestation_report (see stubBinding)
+function stubReport(
+  request: EnclaveRequest,
+  outputHash: Uint8Array,
+  inputHash?: Uint8Array
+): number[] {
+  const backend = new SimulatorBackend({
+    reportVersion: 2,
+    deterministic: true,
+    signingKey: STUB_REPORT_KEY,
+  });
+  return Array.from(backend.generateEvidence(stubBinding(request, outputHash, inputHash)).report);
+}
 
 describe("Attested end-to-end (simulator)", () => {
   it("should produce verifiable attestation evidence", async () => {
@@ -36,5 +188,3405 @@ describe("Attested end-to-end (simulator)", () => {
     assert.strictEqual(verdict.valid, true, "Attestation should verify in simulator mode");
     assert.strictEqual(verdict.claims.sessionBinding, true);
   });
-});
 
+  it("should count unique entities and total identifier occurrences", async () => {
+    const request = () => ({
+      rawContext: [new TextEncoder().encode("Alice paid Bob. Alice thanked Bob. Alice left.")],
+      policy: resolveMaskingPolicy(),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+    });
+    const bridge = new EnclaveBridge(false);
+    const first = await bridge.execute(request());
+    const second = await bridge.execute(request());
+
+    assert.strictEqual(first.redactionStats.entityCount, 2);
+    assert.strictEqual(first.redactionStats.identifiersReplaced, 5);
+    assert.strictEqual(first.redactionStats.pseudonymCount, 2);
+    assert.strictEqual(first.redactionStats.suppressedLowConfidence, 0);
+    assert.deepStrictEqual(first.outputHash, second.outputHash);
+  });
+
+  it("should count entities suppressed below the confidence threshold", async () => {
+    const response = await new EnclaveBridge(false).execute({
+      rawContext: [new TextEncoder().encode("Alice met Dr. Okafor.")],
+      policy: resolveMaskingPolicy({ minConfidence: 0.5 }),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+    });
+
+    assert.strictEqual(response.redactionStats.entityCount, 1);
+    assert.strictEqual(response.redactionStats.suppressedLowConfidence, 1);
+  });
+
+  it("should report relation truncation in the redaction stats", async () => {
+    const request = (truncateRelations: boolean) => ({
+      rawContext: [new TextEncoder().encode("Alice met Bob and Carol on Monday.")],
+      policy: resolveMaskingPolicy({ maxRelations: 2, truncateRelations }),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+    });
+    const bridge = new EnclaveBridge(false);
+
+    const response = await bridge.execute(request(true));
+    assert.strictEqual(response.redactionStats.relationCount, 2);
+    assert.strictEqual(response.redactionStats.relationsTruncated, true);
+    await assert.rejects(() => bridge.execute(request(false)), /Relation limit exceeded/);
+  });
+
+  it("should count suppressed attribute values", async () => {
+    const response = await new EnclaveBridge(false).execute({
+      rawContext: [new TextEncoder().encode("Alice met Bob and Mary Jane Smith.")],
+      policy: resolveMaskingPolicy({ kThreshold: 2 }),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+    });
+    const context = JSON.parse(new TextDecoder().decode(response.transformedContext));
+
+    assert.strictEqual(response.redactionStats.attributesSuppressed, 1);
+    assert.strictEqual(context.entities[2].attributes.tokenCount, "suppressed");
+  });
+
+  it("should report entity truncation in the redaction stats", async () => {
+    const request = (preset: "balanced" | "strict") => ({
+      rawContext: [new TextEncoder().encode("Alice met Bob and Carol.")],
+      policy: resolveMaskingPolicy({ preset, maxEntities: 2 }),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+    });
+    const bridge = new EnclaveBridge(false);
+
+    const response = await bridge.execute(request("balanced"));
+    assert.strictEqual(response.redactionStats.entityCount, 2);
+    assert.strictEqual(response.redactionStats.entitiesTruncated, true);
+    await assert.rejects(() => bridge.execute(request("strict")), /Entity limit exceeded/);
+  });
+
+  it("should count document nodes apart from extracted entities", async () => {
+    const request = (emitDocumentNodes: boolean) => ({
+      rawContext: ["Alice paid Bob.", "Carol met Alice."].map((text) => new TextEncoder().encode(text)),
+      policy: resolveMaskingPolicy({ emitDocumentNodes }),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+    });
+    const bridge = new EnclaveBridge(false);
+
+    const plain = await bridge.execute(request(false));
+    const withDocuments = await bridge.execute(request(true));
+    assert.strictEqual(plain.redactionStats.documentCount, 0);
+    assert.strictEqual(withDocuments.redactionStats.documentCount, 2);
+    assert.strictEqual(withDocuments.redactionStats.entityCount, plain.redactionStats.entityCount);
+    assert.strictEqual(
+      withDocuments.redactionStats.identifiersReplaced,
+      plain.redactionStats.identifiersReplaced
+    );
+    assert.notDeepStrictEqual(withDocuments.outputHash, plain.outputHash);
+  });
+
+  it("should report relations dropped by the type allowlist", async () => {
+    const request = (allowedRelationTypes: string[] | null) => ({
+      rawContext: [new TextEncoder().encode("Alice paid Bob and Carol.")],
+      policy: resolveMaskingPolicy({ allowedRelationTypes }),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+    });
+    const bridge = new EnclaveBridge(false);
+
+    const all = await bridge.execute(request(null));
+    const paidOnly = await bridge.execute(request(["paid"]));
+    assert.strictEqual(all.redactionStats.relationsFiltered, 0);
+    assert.strictEqual(paidOnly.redactionStats.relationCount, 1);
+    assert.strictEqual(
+      paidOnly.redactionStats.relationsFiltered,
+      all.redactionStats.relationCount - 1
+    );
+    assert.notDeepStrictEqual(paidOnly.outputHash, all.outputHash);
+  });
+
+  it("should bind the policy hash into report_data", async () => {
+    const request = (maxRelations: number, legacyReportData = false) => ({
+      rawContext: [new TextEncoder().encode("Alice paid Bob.")],
+      policy: resolveMaskingPolicy({ maxRelations }),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+      legacyReportData,
+    });
+    const reportData = (response: { attestationReport: Uint8Array }) =>
+      Buffer.from(parseReport(response.attestationReport).reportData).toString("hex");
+    const bridge = new EnclaveBridge(false);
+
+    const base = await bridge.execute(request(50000));
+    const repeat = await bridge.execute(request(50000));
+    const altered = await bridge.execute(request(40000));
+    assert.deepStrictEqual(base.outputHash, altered.outputHash);
+    assert.strictEqual(reportData(base), reportData(repeat));
+    assert.notStrictEqual(reportData(base), reportData(altered));
+    assert.strictEqual(
+      Buffer.from(base.policyHash).toString("hex"),
+      hashPolicy(resolveMaskingPolicy({ maxRelations: 50000 }))
+    );
+
+    // The legacy formula ignores the policy
+    const legacyBase = await bridge.execute(request(50000, true));
+    const legacyAltered = await bridge.execute(request(40000, true));
+    assert.strictEqual(reportData(legacyBase), reportData(legacyAltered));
+    assert.notStrictEqual(reportData(legacyBase), reportData(base));
+  });
+
+  it("should keep the policy salt out of the attested response", async () => {
+    const idSalt = "attested-policy-salt-0c9e";
+    const result = await new Axiom({
+      securityTier: "attested",
+      enclave: "auto",
+      policyVersion: "v1",
+      maskingPolicy: { idSalt },
+    }).reason({ context: "Alice met Bob.", task: "track people" });
+    for (const entity of result.transformedContext.entities) {
+      assert.match(entity.syntheticId, /^ENTITY_[0-9a-f]{8}$/);
+    }
+    assert.ok(!JSON.stringify(result).includes(idSalt));
+    assert.ok(!Buffer.from(JSON.stringify(result)).toString("hex").includes(Buffer.from(idSalt).toString("hex")));
+  });
+
+  it("should verify the policy binding end to end", async () => {
+    const config = {
+      securityTier: "attested" as const,
+      enclave: "auto" as const,
+      policyVersion: "v1" as const,
+      maskingPolicy: { allowHonorifics: false },
+    };
+    const result = await new Axiom(config).reason({
+      context: "Alice paid Bob $100 on Monday.",
+      task: "summarize payment",
+    });
+    const evidence = result.attestationEvidence!;
+    const verifier = new AttestationVerifier();
+    const verify = (expectedPolicyHash: string, legacyReportData = false) =>
+      verifier.verify(evidence, result.transformedContext, {
+        expectedMeasurement: result.verificationHint?.expectedMeasurement,
+        expectedPolicyHash,
+        legacyReportData,
+        mode: "permissive",
+      });
+
+    const expected = hashPolicy(resolveMaskingPolicy({ allowHonorifics: false, version: "v1" }));
+    assert.strictEqual(evidence.policyHash, expected);
+    assert.strictEqual((await verify(expected)).valid, true);
+
+    const swapped = await verify(hashPolicy(resolveMaskingPolicy({ preset: "permissive" })));
+    assert.strictEqual(swapped.claims.policyBinding, false);
+
+    const legacy = await verify(expected, true);
+    assert.strictEqual(legacy.claims.sessionBinding, false);
+  });
+
+  it("should tag and verify a SHA-384 output hash end to end", async () => {
+    const result = await new Axiom({
+      securityTier: "attested",
+      enclave: "auto",
+      policyVersion: "v1",
+      hashAlgorithm: "sha384",
+    }).reason({ context: "Alice paid Bob $100 on Monday.", task: "summarize payment" });
+    const evidence = result.attestationEvidence!;
+    const verifier = new AttestationVerifier();
+    const options = {
+      expectedMeasurement: result.verificationHint?.expectedMeasurement,
+      mode: "permissive" as const,
+    };
+
+    assert.strictEqual(evidence.hashAlgorithm, "sha384");
+    assert.match(evidence.outputHash, /^[0-9a-f]{96}$/);
+    assert.strictEqual(
+      evidence.outputHash,
+      hashOutput(result.transformedContext, "json", "sha384", "jcs", 2)
+    );
+    assert.strictEqual((await verifier.verify(evidence, result.transformedContext, options)).valid, true);
+
+    // Relabeling the digest as another algorithm breaks the binding
+    const relabeled = await verifier.verify(
+      { ...evidence, hashAlgorithm: undefined, outputHash: evidence.outputHash.slice(0, 64) },
+      result.transformedContext,
+      options
+    );
+    assert.strictEqual(relabeled.claims.sessionBinding, false);
+  });
+
+  it("should reproduce the enclave output hash from the canonical context", async () => {
+    const bridge = new EnclaveBridge(false);
+    const hashAlgorithms = ["sha256", "sha384"] as const;
+    for (const hashAlgorithm of hashAlgorithms) {
+      const response = await bridge.execute({
+        rawContext: [new TextEncoder().encode("Alice paid Bob $100 on Monday.")],
+        taskHint: "summarize payment",
+        policy: resolveMaskingPolicy({}),
+        sessionId: new Uint8Array(16),
+        configHash: "00".repeat(32),
+        nonce: testNonce(0),
+        timestamp: 1700000000000,
+        hashAlgorithm,
+      });
+      const json = new TextDecoder().decode(response.transformedContext);
+      const canonical = bridge.getCanonicalContext(json);
+
+      assert.strictEqual(canonical, json);
+      assert.strictEqual(
+        bridge.computeContextHash(canonical, hashAlgorithm),
+        Buffer.from(response.outputHash).toString("hex")
+      );
+      assert.strictEqual(
+        bridge.computeContextHash(JSON.stringify({ ...JSON.parse(json), schemaVersion: 1 }), hashAlgorithm),
+        Buffer.from(response.outputHash).toString("hex")
+      );
+    }
+  });
+
+  it("should reject an unknown context schema version", () => {
+    const bridge = new EnclaveBridge(false);
+    const json = JSON.stringify({ entities: [], relations: [], task: "t", schemaVersion: 2 });
+
+    assert.throws(() => bridge.getCanonicalContext(json), ConfigurationError);
+    assert.throws(() => bridge.computeContextHash(json), /Unsupported transformed context schemaVersion: 2/);
+    assert.throws(() => bridge.getCanonicalContext("{"), ConfigurationError);
+  });
+
+  it("should expose the runner's hash test vectors for parity checks", () => {
+    const simulated = new EnclaveBridge(false).getHashTestVectors();
+    assert.deepStrictEqual(simulated, generateTestVectors());
+
+    const bridge = new EnclaveBridge(true);
+    withNativeModule(bridge, {
+      transform: () => "{}",
+    });
+    assert.throws(() => bridge.getHashTestVectors(), /does not export get_hash_test_vectors/);
+
+    withNativeModule(bridge, {
+      transform: () => "{}",
+      get_hash_test_vectors: () => JSON.stringify(simulated),
+    });
+    for (const vector of bridge.getHashTestVectors()) {
+      assert.strictEqual(canonicalize(JSON.parse(vector.context)), vector.canonical, vector.name);
+      assert.strictEqual(hash(JSON.parse(vector.context)), vector.sha256, vector.name);
+    }
+  });
+
+  it("should exclude metadata from the hash scope but not constraints", async () => {
+    const result = await new Axiom({
+      securityTier: "attested",
+      enclave: "auto",
+      policyVersion: "v1",
+    }).reason({ context: "Alice paid Bob $100 on Monday.", task: "summarize payment" });
+    const evidence = result.attestationEvidence!;
+    const context = result.transformedContext;
+    const verifier = new AttestationVerifier();
+
+    assert.strictEqual(evidence.hashScope, "context-v1");
+    assert.ok(context.constraints);
+    assert.strictEqual(verifier.verifyOutputBinding(evidence, context).valid, true);
+
+    const withMetadata = { ...context, metadata: { runnerVersion: "9.9.9", host: "node-7" } };
+    assert.strictEqual(hashOutput(withMetadata, "json", "sha256", "jcs", 2), evidence.outputHash);
+    assert.strictEqual(verifier.verifyOutputBinding(evidence, withMetadata).valid, true);
+
+    const withConstraints = {
+      ...context,
+      constraints: { ...context.constraints, maxRelationDegree: context.constraints.maxRelationDegree + 1 },
+    };
+    assert.notStrictEqual(
+      hashOutput(withConstraints, "json", "sha256", "jcs", 2),
+      evidence.outputHash
+    );
+    assert.strictEqual(verifier.verifyOutputBinding(evidence, withConstraints).valid, false);
+
+    const unknownScope = verifier.verifyOutputBinding(
+      { ...evidence, hashScope: "context-v2" as unknown as "context-v1" },
+      context
+    );
+    assert.strictEqual(unknownScope.valid, false);
+    assert.match(unknownScope.error!, /Unsupported hash scope: context-v2/);
+  });
+
+  it("should pass native metadata through and check the hash scope", async () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("x")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+    };
+    const results: Array<EnclaveResponse | Error> = [];
+    for (const hashScope of [undefined, "context-v1", "everything"]) {
+      const bridge = new EnclaveBridge(true);
+      withNativeModule(bridge, {
+        check_availability: () => true,
+        transform: () =>
+          JSON.stringify({
+            transformed_context: {
+              entities: [],
+              relations: [],
+              metadata: { runner_version: "0.4.0", nested: { dropped: true } },
+            },
+            output_hash: "00".repeat(32),
+            ...(hashScope !== undefined ? { hash_scope: hashScope } : {}),
+            attestation_report: stubReport(request, new Uint8Array(32)),
+            redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+            measurement: "native_stub",
+          }),
+      });
+      try {
+        results.push(await bridge.execute(request));
+      } catch (error) {
+        results.push(error as Error);
+      }
+    }
+
+    for (const response of results.slice(0, 2) as EnclaveResponse[]) {
+      assert.strictEqual(response.hashScope, "context-v1");
+      assert.deepStrictEqual(response.metadata, { runner_version: "0.4.0" });
+      assert.doesNotMatch(new TextDecoder().decode(response.transformedContext), /metadata/);
+    }
+    assert.ok(results[2] instanceof SecurityInvariantError);
+    assert.match((results[2] as Error).message, /Unsupported enclave hash scope: everything/);
+  });
+
+  it("should bind with the domain-separated formula unless configured otherwise", async () => {
+    const reason = (config: Partial<AxiomConfig>) =>
+      new Axiom({
+        securityTier: "attested",
+        enclave: "auto",
+        policyVersion: "v1",
+        ...config,
+      } as unknown as AxiomConfig).reason({ context: "Alice paid Bob $100.", task: "summarize" });
+    const verifier = new AttestationVerifier();
+    const check = async (result: ReasonResult) =>
+      (
+        await verifier.verify(result.attestationEvidence!, result.transformedContext, {
+          expectedMeasurement: result.verificationHint?.expectedMeasurement,
+          mode: "permissive",
+          acceptShortReportData: result.attestationEvidence?.bindingVersion !== 3,
+        })
+      ).claims.sessionBinding;
+
+    const current = await reason({});
+    assert.strictEqual(current.attestationEvidence!.bindingVersion, 3);
+    assert.strictEqual(
+      current.attestationEvidence!.outputHash,
+      hashOutput(current.transformedContext, "json", "sha256", "jcs", 3)
+    );
+    assert.strictEqual(await check(current), true);
+
+    const short = await reason({ bindingVersion: 2 });
+    assert.strictEqual(short.attestationEvidence!.bindingVersion, 2);
+    assert.strictEqual(await check(short), true);
+
+    const previous = await reason({ bindingVersion: 1 });
+    assert.strictEqual(previous.attestationEvidence!.bindingVersion, 1);
+    assert.strictEqual(previous.attestationEvidence!.outputHash, hash(previous.transformedContext));
+    assert.strictEqual(await check(previous), true);
+
+    const legacy = await reason({ legacyReportData: true });
+    assert.strictEqual(legacy.attestationEvidence!.bindingVersion, 1);
+
+    assert.throws(() => reason({ bindingVersion: 2, legacyReportData: true }), ConfigurationError);
+    assert.throws(() => reason({ bindingVersion: 3, legacyReportData: true }), ConfigurationError);
+    assert.throws(() => reason({ bindingVersion: 4 as unknown as 3 }), /Invalid bindingVersion: 4/);
+  });
+
+  it("should reject a native runner that ignores the requested binding version", async () => {
+    let sent: { binding_version?: number } = {};
+    const bridge = new EnclaveBridge(true);
+    withNativeModule(bridge, {
+      check_availability: () => true,
+      transform: (requestJson: string) => {
+        sent = JSON.parse(requestJson);
+        return JSON.stringify({
+          transformed_context: { entities: [], relations: [] },
+          output_hash: "00".repeat(32),
+          attestation_report: [],
+          redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+          measurement: "native_stub",
+        });
+      },
+    });
+
+    await assert.rejects(
+      bridge.execute({
+        rawContext: [new TextEncoder().encode("x")],
+        policy: resolveMaskingPolicy({}),
+        sessionId: new Uint8Array(16),
+        configHash: "00".repeat(32),
+        nonce: testNonce(0),
+        timestamp: 1700000000000,
+        bindingVersion: 2,
+      }),
+      /Enclave binding version mismatch: requested 2, enclave used 1/
+    );
+    assert.strictEqual(sent.binding_version, 2);
+  });
+
+  it("should reject a native report that does not bind the request", async () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("x")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16),
+      configHash: "00".repeat(32),
+      nonce: testNonce(0),
+      timestamp: 1700000000000,
+    };
+    let report = stubReport(request, new Uint8Array(32));
+    const bridge = new EnclaveBridge(true);
+    withNativeModule(bridge, {
+      check_availability: () => true,
+      transform: () =>
+        JSON.stringify({
+          transformed_context: { entities: [], relations: [] },
+          output_hash: "00".repeat(32),
+          attestation_report: report,
+          redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+          measurement: "native_stub",
+        }),
+    });
+    await bridge.execute(request);
+
+    // A report for another request, another output, or an input the
+    // response does not commit to
+    const mismatched = [
+      stubReport({ ...request, sessionId: new Uint8Array(16).fill(1) }, new Uint8Array(32)),
+      stubReport(request, new Uint8Array(32).fill(1)),
+      stubReport(request, new Uint8Array(32), new Uint8Array(32)),
+    ];
+    for (report of mismatched) {
+      await assert.rejects(
+        bridge.execute(request),
+        (error: unknown) =>
+          error instanceof SecurityInvariantError &&
+          /Enclave evidence does not carry the request's report_data/.test(error.message)
+      );
+    }
+  });
+
+  it("should decode runner digests identically in every hash encoding", async () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(3),
+      configHash: "11".repeat(32),
+      nonce: testNonce(4),
+      timestamp: 1700000000000,
+    };
+    const outputHash = createHash("sha256").update("output").digest();
+    const inputHash = computeInputHash(request.rawContext, request.sessionId, request.nonce);
+    const policyHash = Buffer.from(hashPolicy(request.policy), "hex");
+    const stubFor = (bridge: EnclaveBridge, echo?: string) => {
+      withNativeModule(bridge, {
+        check_availability: () => true,
+        transform: (requestJson: string) => {
+          const encoding = JSON.parse(requestJson).hash_encoding as HashEncoding;
+          return JSON.stringify({
+            transformed_context: { entities: [], relations: [] },
+            output_hash: encodeDigest(outputHash, encoding),
+            input_hash: encodeDigest(inputHash, encoding),
+            policy_hash: encodeDigest(policyHash, encoding),
+            hash_encoding: echo ?? encoding,
+            attestation_report: stubReport(request, outputHash, inputHash),
+            redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+            measurement: "native_stub",
+          });
+        },
+      });
+      return bridge;
+    };
+
+    const reportData = new Set<string>();
+    for (const hashEncoding of HASH_ENCODINGS) {
+      const response = await stubFor(new EnclaveBridge(true)).execute({ ...request, hashEncoding });
+
+      assert.ok(Buffer.from(response.outputHash).equals(outputHash), hashEncoding);
+      assert.ok(Buffer.from(response.inputHash!).equals(inputHash), hashEncoding);
+      assert.ok(Buffer.from(response.policyHash).equals(policyHash), hashEncoding);
+      reportData.add(
+        createReportData(
+          request.sessionId,
+          Buffer.from(request.configHash, "hex"),
+          response.policyHash,
+          response.outputHash,
+          request.timestamp,
+          false,
+          response.hashAlgorithm,
+          response.inputHash
+        ).toString("hex")
+      );
+    }
+    assert.strictEqual(reportData.size, 1);
+
+    await assert.rejects(
+      stubFor(new EnclaveBridge(true), "hex").execute({ ...request, hashEncoding: "base64url" }),
+      /Enclave hash encoding mismatch: requested base64url, enclave used hex/
+    );
+    await assert.rejects(
+      stubFor(new EnclaveBridge(true)).execute({
+        ...request,
+        hashEncoding: "base58" as unknown as HashEncoding,
+      }),
+      ConfigurationError
+    );
+  });
+
+  it("should record and verify a CBOR output hash end to end", async () => {
+    const result = await new Axiom({
+      securityTier: "attested",
+      enclave: "auto",
+      policyVersion: "v1",
+      outputEncoding: "cbor",
+    }).reason({ context: "Alice paid Bob $100 on Monday.", task: "summarize payment" });
+    const evidence = result.attestationEvidence!;
+    const verifier = new AttestationVerifier();
+    const options = {
+      expectedMeasurement: result.verificationHint?.expectedMeasurement,
+      mode: "permissive" as const,
+    };
+
+    assert.strictEqual(evidence.outputEncoding, "cbor");
+    assert.strictEqual(evidence.hashAlgorithm, "sha256");
+    assert.strictEqual(
+      evidence.outputHash,
+      hashOutput(result.transformedContext, "cbor", "sha256", "jcs", 2)
+    );
+    assert.notStrictEqual(
+      evidence.outputHash,
+      hashOutput(result.transformedContext, "json", "sha256", "jcs", 2)
+    );
+    assert.strictEqual((await verifier.verify(evidence, result.transformedContext, options)).valid, true);
+
+    const relabeled = await verifier.verify(
+      { ...evidence, outputEncoding: "json" },
+      result.transformedContext,
+      options
+    );
+    assert.strictEqual(relabeled.claims.sessionBinding, false);
+  });
+
+  it("should commit to the raw input without revealing it", async () => {
+    const context = ["Alice paid Bob $100.", "Bob thanked Alice."];
+    const result = await new Axiom({
+      securityTier: "attested",
+      enclave: "auto",
+      policyVersion: "v1",
+    }).reason({ context, task: "summarize payment" });
+    const evidence = result.attestationEvidence!;
+    const verifier = new AttestationVerifier();
+    const options = {
+      expectedMeasurement: result.verificationHint?.expectedMeasurement,
+      mode: "permissive" as const,
+    };
+
+    assert.match(evidence.inputHash ?? "", /^[0-9a-f]{64}$/);
+    assert.match(result.inputNonce ?? "", /^[0-9a-f]{64}$/);
+    assert.ok(!JSON.stringify(evidence).includes("Alice"));
+    assert.strictEqual((await verifier.verify(evidence, result.transformedContext, options)).valid, true);
+
+    assert.strictEqual(verifier.verifyInputCommitment(evidence, context, result.inputNonce!), true);
+    assert.strictEqual(
+      verifier.verifyInputCommitment(evidence, ["Alice paid Bob $100.Bob thanked Alice."], result.inputNonce!),
+      false
+    );
+    assert.strictEqual(
+      verifier.verifyInputCommitment(evidence, context, "00".repeat(32)),
+      false
+    );
+
+    // Dropping the commitment from the evidence breaks the binding
+    const stripped = await verifier.verify(
+      { ...evidence, inputHash: undefined },
+      result.transformedContext,
+      options
+    );
+    assert.strictEqual(stripped.claims.sessionBinding, false);
+  });
+
+  it("should round-trip input_hash through the native JSON interface", async () => {
+    const responses: Array<(request: Record<string, unknown>) => string | undefined> = [
+      (request) =>
+        computeInputHash(
+          (request.raw_context as string[]).map((d) => new TextEncoder().encode(d)),
+          Buffer.from(request.session_id as string, "hex"),
+          Buffer.from(request.nonce as string, "hex")
+        ).toString("hex"),
+      () => "ff".repeat(32),
+      () => undefined,
+    ];
+    const results: Array<string | undefined | Error> = [];
+    const request = {
+      rawContext: [new TextEncoder().encode("ab"), new TextEncoder().encode("c")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+    };
+
+    for (const inputHashFor of responses) {
+      const bridge = new EnclaveBridge(true);
+      withNativeModule(bridge, {
+        check_availability: () => true,
+        transform: (requestJson: string) => {
+          const inputHash = inputHashFor(JSON.parse(requestJson));
+          const bound = inputHash !== undefined ? Buffer.from(inputHash, "hex") : undefined;
+          return JSON.stringify({
+            transformed_context: { entities: [], relations: [] },
+            output_hash: "00".repeat(32),
+            input_hash: inputHash,
+            attestation_report: stubReport(request, new Uint8Array(32), bound),
+            redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+            measurement: "native_stub",
+          });
+        },
+      });
+      try {
+        const response = await bridge.execute(request);
+        results.push(
+          response.inputHash ? Buffer.from(response.inputHash).toString("hex") : undefined
+        );
+      } catch (error) {
+        results.push(error as Error);
+      }
+    }
+
+    const expected = computeInputHash(
+      [new TextEncoder().encode("ab"), new TextEncoder().encode("c")],
+      new Uint8Array(16).fill(1),
+      testNonce(2)
+    ).toString("hex");
+    assert.strictEqual(results[0], expected);
+    assert.ok(results[1] instanceof SecurityInvariantError);
+    assert.match((results[1] as Error).message, /Enclave input hash mismatch/);
+    assert.strictEqual(results[2], undefined);
+  });
+
+  describe("Typed attestation report", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+      bindingVersion: 2 as const,
+    };
+
+    it("should build simulator reports that round-trip", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute(request);
+      const report = bridge.parseReport(response.attestationReport);
+
+      assert.strictEqual(report.version, SIMULATOR_REPORT_VERSION);
+      assert.deepStrictEqual(reportToBytes(report), response.attestationReport);
+      assert.deepStrictEqual(
+        Buffer.from(report.reportData.subarray(0, 32)),
+        createReportData(
+          request.sessionId,
+          Buffer.from(request.configHash, "hex"),
+          response.policyHash,
+          response.outputHash,
+          request.timestamp,
+          false,
+          "sha256",
+          response.inputHash,
+          undefined,
+          2
+        )
+      );
+      assert.ok(report.reportData.subarray(32).every((byte) => byte === 0));
+    });
+
+    it("should read the native runner's parse_report JSON", async () => {
+      const simulated = await new EnclaveBridge(false).execute(request);
+      const bridge = new EnclaveBridge(true);
+      withNativeModule(bridge, {
+        parse_report: (report: Buffer) => JSON.stringify(reportToJson(parseReport(report))),
+      });
+      assert.deepStrictEqual(
+        bridge.parseReport(simulated.attestationReport),
+        parseReport(simulated.attestationReport)
+      );
+
+      withNativeModule(bridge, {});
+      assert.throws(() => bridge.parseReport(simulated.attestationReport), ConfigurationError);
+    });
+
+    it("should verify a simulator response through the bridge", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute(request);
+      const expected = {
+        sessionId: Buffer.from(request.sessionId).toString("hex"),
+        configHash: request.configHash,
+        policyHash: Buffer.from(response.policyHash).toString("hex"),
+        outputHash: Buffer.from(response.outputHash).toString("hex"),
+        timestamp: request.timestamp,
+        inputHash: Buffer.from(response.inputHash!).toString("hex"),
+        bindingVersion: 2 as const,
+        acceptShortReportData: true,
+        allowSimulator: true,
+      };
+
+      const result = bridge.verifyAttestationReport(response.attestationReport, expected);
+      assert.strictEqual(result.valid, true);
+      assert.strictEqual(result.simulator, true);
+
+      const replayed = bridge.verifyAttestationReport(response.attestationReport, {
+        ...expected,
+        timestamp: request.timestamp + 1,
+      });
+      assert.strictEqual(replayed.valid, false);
+      assert.deepStrictEqual(
+        replayed.checks.filter((check) => !check.passed).map((check) => check.name),
+        ["report_data"]
+      );
+    });
+
+    it("should pass the expected binding to the native verifier", async () => {
+      const simulated = await new EnclaveBridge(false).execute(request);
+      const calls: Array<{ report: string; expected: Record<string, unknown> }> = [];
+      let checks: unknown[] = [{ name: "structure", passed: true }];
+      const bridge = new EnclaveBridge(true);
+      withNativeModule(bridge, {
+        verify_attestation_report: (report: string, expectedJson: string) => {
+          calls.push({ report, expected: JSON.parse(expectedJson) });
+          // A runner claiming validity over a failed check is not believed
+          return JSON.stringify({ valid: true, simulator: true, checks });
+        },
+      });
+      const expected = {
+        sessionId: "11".repeat(16),
+        configHash: "22".repeat(32),
+        outputHash: "44".repeat(32),
+        timestamp: 1700000000000,
+        certificates: { vcek: new Uint8Array(Buffer.from("vcek")) },
+      };
+
+      assert.strictEqual(bridge.verifyAttestationReport(simulated.attestationReport, expected).valid, true);
+      assert.strictEqual(calls[0].report, Buffer.from(simulated.attestationReport).toString("base64"));
+      assert.strictEqual(calls[0].expected.session_id, "11".repeat(16));
+      assert.strictEqual(calls[0].expected.policy_hash, null);
+      assert.strictEqual(calls[0].expected.binding_version, 1);
+      assert.strictEqual(calls[0].expected.accept_short_report_data, false);
+      assert.strictEqual(calls[0].expected.allow_simulator, false);
+      assert.strictEqual(calls[0].expected.minimum_tcb, null);
+      assert.match(
+        (calls[0].expected.certificates as { vcek: string }).vcek,
+        /^-----BEGIN CERTIFICATE-----\n/
+      );
+
+      checks = [{ name: "structure", passed: true }, { name: "signature", passed: false, reason: "bad" }];
+      const failed = bridge.verifyAttestationReport(simulated.attestationReport, expected);
+      assert.strictEqual(failed.valid, false);
+      assert.deepStrictEqual(failed.checks[1], { name: "signature", passed: false, reason: "bad" });
+
+      checks = [{ name: "vibes", passed: true }];
+      assert.throws(
+        () => bridge.verifyAttestationReport(simulated.attestationReport, expected),
+        /Unknown report check from enclave: vibes/
+      );
+
+      checks = [{ name: "tcb", passed: true }];
+      bridge.verifyAttestationReport(simulated.attestationReport, {
+        ...expected,
+        minimumTcb: { snp: 8 },
+      });
+      assert.deepStrictEqual(calls[3].expected.minimum_tcb, {
+        boot_loader: null,
+        tee: null,
+        snp: 8,
+        microcode: null,
+      });
+    });
+  });
+
+  describe("Report versions", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+    };
+
+    it("should build version 2 simulator reports by default", async () => {
+      const response = await new EnclaveBridge(false).execute(request);
+      const report = parseReport(response.attestationReport);
+      assert.strictEqual(report.version, SIMULATOR_REPORT_VERSION);
+      assert.strictEqual(report.cpuidFamId, undefined);
+    });
+
+    it("should build version 3 simulator reports on request", async () => {
+      const bridge = new EnclaveBridge(false, {}, { reportVersion: 3 });
+      const response = await bridge.execute(request);
+      const report = parseReport(response.attestationReport);
+
+      assert.strictEqual(report.version, SIMULATOR_REPORT_V3_VERSION);
+      assert.deepStrictEqual(
+        [report.cpuidFamId, report.cpuidModId, report.cpuidStep],
+        [0x19, 0x01, 0x01]
+      );
+      const result = bridge.verifyAttestationReport(response.attestationReport, {
+        sessionId: Buffer.from(request.sessionId).toString("hex"),
+        configHash: request.configHash,
+        policyHash: Buffer.from(response.policyHash).toString("hex"),
+        outputHash: Buffer.from(response.outputHash).toString("hex"),
+        timestamp: request.timestamp,
+        inputHash: Buffer.from(response.inputHash!).toString("hex"),
+        bindingVersion: 3 as const,
+        allowSimulator: true,
+      });
+      assert.strictEqual(result.valid, true);
+      assert.strictEqual(result.simulator, true);
+
+      bridge.useSimulator();
+      const again = await bridge.execute(request);
+      assert.strictEqual(parseReport(again.attestationReport).version, SIMULATOR_REPORT_V3_VERSION);
+    });
+
+    it("should reject an unsupported simulator report version", () => {
+      assert.throws(
+        () => new EnclaveBridge(false, {}, { reportVersion: 4 as 2 }),
+        /Invalid simulator reportVersion: 4\. Must be one of 2, 3\./
+      );
+    });
+  });
+
+  describe("Simulator signing", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+    };
+
+    function expectedFor(response: EnclaveResponse) {
+      return {
+        sessionId: Buffer.from(request.sessionId).toString("hex"),
+        configHash: request.configHash,
+        policyHash: Buffer.from(response.policyHash).toString("hex"),
+        outputHash: Buffer.from(response.outputHash).toString("hex"),
+        timestamp: request.timestamp,
+        inputHash: Buffer.from(response.inputHash!).toString("hex"),
+        bindingVersion: 3 as const,
+        allowSimulator: true,
+      };
+    }
+
+    it("should sign simulator reports with a labeled non-production key", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute(request);
+      const key = bridge.getSimulatorVerificationKey();
+
+      assert.strictEqual(key.algorithm, "ed25519");
+      assert.strictEqual(key.production, false);
+      assert.strictEqual(key.label, SIMULATOR_KEY_LABEL);
+      assert.match(key.publicKey, /^-----BEGIN PUBLIC KEY-----\n/);
+
+      const report = parseReport(response.attestationReport);
+      const signature = signatureToEd25519(report.signature);
+      assert.strictEqual(report.signatureAlgo, SIGNATURE_ALGO_SIMULATOR_ED25519);
+      assert.ok(signature !== undefined);
+      assert.ok(
+        cryptoVerify(
+          null,
+          response.attestationReport.subarray(0, SIGNED_REPORT_BYTES),
+          key.publicKey,
+          signature
+        )
+      );
+    });
+
+    it("should pass a valid signature and fail a flipped byte", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute(request);
+      const expected = expectedFor(response);
+      const simulatorKey = bridge.getSimulatorVerificationKey().publicKey;
+
+      // The SDK verifier needs the key; the simulator runner defaults to its own
+      assert.strictEqual(
+        verifyAttestationReport(response.attestationReport, { ...expected, simulatorKey }).valid,
+        true
+      );
+      assert.strictEqual(bridge.verifyAttestationReport(response.attestationReport, expected).valid, true);
+
+      const flipped = Uint8Array.from(response.attestationReport);
+      flipped[0x1a0] ^= 1; // chip_id
+      const result = bridge.verifyAttestationReport(flipped, expected);
+      assert.strictEqual(result.valid, false);
+      assert.deepStrictEqual(result.checks.filter((check) => !check.passed), [
+        {
+          name: "signature",
+          passed: false,
+          reason: "Report signature does not verify against the simulator key",
+        },
+      ]);
+
+      const otherBridge = new EnclaveBridge(false);
+      assert.strictEqual(
+        otherBridge.verifyAttestationReport(response.attestationReport, expected).valid,
+        false
+      );
+    });
+
+    it("should load the signing key from a file or AXIOM_SIMULATOR_KEY", async () => {
+      const dir = await mkdtemp(join(tmpdir(), "axiom-simkey-"));
+      const path = join(dir, "simulator.pem");
+      const keys = generateKeyPairSync("ed25519");
+      await writeFile(path, keys.privateKey.export({ format: "pem", type: "pkcs8" }));
+      const publicKey = keys.publicKey.export({ format: "pem", type: "spki" }).toString();
+      const previous = process.env.AXIOM_SIMULATOR_KEY;
+      try {
+        const fromOption = new EnclaveBridge(false, {}, { signingKeyPath: path });
+        assert.strictEqual(fromOption.getSimulatorVerificationKey().publicKey, publicKey);
+
+        process.env.AXIOM_SIMULATOR_KEY = path;
+        const fromEnv = new EnclaveBridge(false);
+        assert.strictEqual(fromEnv.getSimulatorVerificationKey().publicKey, publicKey);
+
+        const ecPath = join(dir, "ec.pem");
+        const ecKey = generateKeyPairSync("ec", { namedCurve: "P-256" }).privateKey;
+        await writeFile(ecPath, ecKey.export({ format: "pem", type: "pkcs8" }));
+        assert.throws(
+          () => new EnclaveBridge(false, {}, { signingKeyPath: ecPath }),
+          /is not an Ed25519 key/
+        );
+        assert.throws(
+          () => new EnclaveBridge(false, {}, { signingKeyPath: join(dir, "missing.pem") }),
+          ConfigurationError
+        );
+      } finally {
+        if (previous === undefined) {
+          delete process.env.AXIOM_SIMULATOR_KEY;
+        } else {
+          process.env.AXIOM_SIMULATOR_KEY = previous;
+        }
+        await rm(dir, { recursive: true, force: true });
+      }
+    });
+
+    it("should read the native runner's simulator key and pass it to the verifier", () => {
+      const keys = generateKeyPairSync("ed25519");
+      const publicKey = keys.publicKey.export({ format: "pem", type: "spki" }).toString();
+      let production = false;
+      let sentKey: unknown;
+      const bridge = new EnclaveBridge(true);
+      withNativeModule(bridge, {
+        get_simulator_verification_key: () =>
+          JSON.stringify({
+            algorithm: "ed25519",
+            public_key: publicKey,
+            production,
+            label: "runner key",
+          }),
+        verify_attestation_report: (_report: string, expectedJson: string) => {
+          sentKey = JSON.parse(expectedJson).simulator_key;
+          const checks = [{ name: "structure", passed: true }];
+          return JSON.stringify({ valid: true, simulator: true, checks });
+        },
+      });
+
+      assert.deepStrictEqual(bridge.getSimulatorVerificationKey(), {
+        algorithm: "ed25519",
+        publicKey,
+        production: false,
+        label: "runner key",
+      });
+      bridge.verifyAttestationReport(new Uint8Array(0), {
+        sessionId: "11".repeat(16),
+        configHash: "22".repeat(32),
+        outputHash: "44".repeat(32),
+        timestamp: 1700000000000,
+        simulatorKey: keys.publicKey.export({ format: "der", type: "spki" }),
+      });
+      assert.strictEqual(sentKey, publicKey);
+
+      production = true;
+      assert.throws(() => bridge.getSimulatorVerificationKey(), SecurityInvariantError);
+
+      withNativeModule(bridge, {});
+      assert.throws(() => bridge.getSimulatorVerificationKey(), ConfigurationError);
+    });
+  });
+
+  describe("Simulator report modes", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+    };
+
+    it("should give byte-identical reports for identical requests by default", async () => {
+      const bridge = new EnclaveBridge(false);
+      const first = await bridge.execute(request);
+      const second = await bridge.execute(request);
+
+      assert.deepStrictEqual(first.attestationReport, second.attestationReport);
+      assert.deepStrictEqual(first.metadata, { simulator_report_mode: "deterministic" });
+
+      const report = parseReport(first.attestationReport);
+      assert.ok(report.reportId.some((byte) => byte !== 0));
+      const otherNonce = await bridge.execute({ ...request, nonce: testNonce(3) });
+      const other = parseReport(otherNonce.attestationReport);
+      assert.notDeepStrictEqual(other.chipId, report.chipId);
+      assert.notDeepStrictEqual(other.reportId, report.reportId);
+    });
+
+    it("should vary reports for identical requests in realistic mode", async () => {
+      const bridge = new EnclaveBridge(false);
+      bridge.initializeWithConfig({ reportMode: "realistic" });
+      const before = Date.now();
+      const first = await bridge.execute(request);
+      const second = await bridge.execute(request);
+
+      assert.notDeepStrictEqual(first.attestationReport, second.attestationReport);
+      assert.notDeepStrictEqual(
+        parseReport(first.attestationReport).chipId,
+        parseReport(second.attestationReport).chipId
+      );
+      assert.strictEqual(first.metadata?.simulator_report_mode, "realistic");
+      assert.ok((first.metadata?.simulator_generated_at as number) >= before);
+
+      const result = bridge.verifyAttestationReport(first.attestationReport, {
+        sessionId: Buffer.from(request.sessionId).toString("hex"),
+        configHash: request.configHash,
+        policyHash: Buffer.from(first.policyHash).toString("hex"),
+        outputHash: Buffer.from(first.outputHash).toString("hex"),
+        timestamp: request.timestamp,
+        inputHash: Buffer.from(first.inputHash!).toString("hex"),
+        bindingVersion: 3,
+        allowSimulator: true,
+      });
+      assert.strictEqual(result.valid, true);
+
+      // The mode survives a switch back to the simulator
+      bridge.useSimulator();
+      assert.strictEqual((await bridge.execute(request)).metadata?.simulator_report_mode, "realistic");
+    });
+
+    it("should reject an unknown report mode", () => {
+      const bridge = new EnclaveBridge(false);
+      assert.throws(
+        () => bridge.initializeWithConfig({ reportMode: "random" as "realistic" }),
+        /Invalid simulator reportMode: random\. Must be one of deterministic, realistic\./
+      );
+      assert.throws(
+        () => new EnclaveBridge(false, {}, { reportMode: "random" as "realistic" }),
+        ConfigurationError
+      );
+    });
+
+    it("should pass the configuration to the native runner", () => {
+      const calls: unknown[] = [];
+      const bridge = new EnclaveBridge(true);
+      withNativeModule(bridge, {
+        initialize_with_config: (configJson: string) => {
+          calls.push(JSON.parse(configJson));
+          return "{}";
+        },
+      });
+      bridge.initializeWithConfig({ reportMode: "realistic" });
+      assert.deepStrictEqual(calls, [
+        {
+          report_mode: "realistic",
+          report_version: null,
+          signing_key_path: null,
+          token_key_path: null,
+          backend: null,
+          measurement_registry: null,
+          measurement_registry_key_path: null,
+          attestation_window_ms: null,
+        },
+      ]);
+
+      withNativeModule(bridge, {});
+      assert.throws(() => bridge.initializeWithConfig({ reportMode: "realistic" }), ConfigurationError);
+    });
+  });
+
+  describe("Platform evidence", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+    };
+
+    it("should report fixed simulator platform fields with a hashed chip_id", async () => {
+      const response = await new EnclaveBridge(false).execute(request);
+      const report = parseReport(response.attestationReport);
+      const tcb = { bootLoader: 3, tee: 0, snp: 8, microcode: 115 };
+
+      assert.deepStrictEqual(response.platform, {
+        reportedTcb: tcb,
+        committedTcb: tcb,
+        platformInfo: 1n,
+        chipIdHash: createHash("sha256").update(report.chipId).digest("hex"),
+      });
+      assert.ok(report.chipId.some((byte) => byte !== 0));
+      assert.deepStrictEqual(report.currentTcb, tcb);
+      assert.deepStrictEqual(report.launchTcb, tcb);
+    });
+
+    it("should include the raw chip_id only when requested", async () => {
+      const response = await new EnclaveBridge(false).execute({ ...request, rawChipId: true });
+      const report = parseReport(response.attestationReport);
+      assert.strictEqual(response.platform?.chipId, Buffer.from(report.chipId).toString("hex"));
+    });
+
+    it("should reject a native report that does not parse", async () => {
+      const bridge = new EnclaveBridge(true);
+      withNativeModule(bridge, {
+        check_availability: () => true,
+        transform: () =>
+          JSON.stringify({
+            transformed_context: { entities: [], relations: [] },
+            output_hash: "00".repeat(32),
+            attestation_report: [1, 2, 3],
+            redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+            measurement: "native_stub",
+            binding_version: 3,
+          }),
+      });
+      await assert.rejects(
+        bridge.execute(request),
+        (error: unknown) =>
+          error instanceof AttestationError &&
+          /Enclave returned unparseable evidence/.test(error.message)
+      );
+    });
+
+    it("should enforce a minimum TCB on simulator reports", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute(request);
+      const expected = {
+        sessionId: Buffer.from(request.sessionId).toString("hex"),
+        configHash: request.configHash,
+        policyHash: Buffer.from(response.policyHash).toString("hex"),
+        outputHash: Buffer.from(response.outputHash).toString("hex"),
+        timestamp: request.timestamp,
+        inputHash: Buffer.from(response.inputHash!).toString("hex"),
+        bindingVersion: 3 as const,
+        allowSimulator: true,
+      };
+
+      const atFloor = bridge.verifyAttestationReport(response.attestationReport, {
+        ...expected,
+        minimumTcb: response.platform!.reportedTcb,
+      });
+      assert.strictEqual(atFloor.valid, true);
+
+      const belowFloor = bridge.verifyAttestationReport(response.attestationReport, {
+        ...expected,
+        minimumTcb: { snp: 9 },
+      });
+      assert.strictEqual(belowFloor.valid, false);
+      assert.deepStrictEqual(
+        belowFloor.checks.filter((check) => !check.passed),
+        [{ name: "tcb", passed: false, reason: "Reported TCB below minimum: snp 8 < 9" }]
+      );
+    });
+  });
+
+  describe("Evidence envelope", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+    };
+    const expectedFor = (response: EnclaveResponse) => ({
+      sessionId: Buffer.from(request.sessionId).toString("hex"),
+      configHash: request.configHash,
+      policyHash: Buffer.from(response.policyHash).toString("hex"),
+      outputHash: Buffer.from(response.outputHash).toString("hex"),
+      timestamp: request.timestamp,
+      inputHash: Buffer.from(response.inputHash!).toString("hex"),
+      allowSimulator: true,
+    });
+
+    it("should describe the report and round-trip through JSON", async () => {
+      const response = await new EnclaveBridge(false).execute(request);
+      const evidence = response.evidence!;
+
+      assert.strictEqual(evidence.platform, "simulator");
+      assert.strictEqual(evidence.format, "snp-report-v2");
+      assert.strictEqual(evidence.bindingFormula, "v3");
+      assert.deepStrictEqual(
+        Buffer.from(evidence.certificates!.vcek!, "base64"),
+        Buffer.from(response.certificates!.vcek!)
+      );
+      assert.deepStrictEqual(
+        Buffer.from(evidence.reportB64, "base64"),
+        Buffer.from(response.attestationReport)
+      );
+
+      const json = evidenceToJson(evidence);
+      assert.deepStrictEqual(Object.keys(JSON.parse(json)), [
+        "platform",
+        "format",
+        "reportB64",
+        "certificates",
+        "bindingFormula",
+      ]);
+      assert.deepStrictEqual(evidenceFromJson(json), evidence);
+
+      const v3 = await new EnclaveBridge(false, {}, { reportVersion: 3 }).execute(request);
+      assert.strictEqual(v3.evidence!.format, "snp-report-v3");
+    });
+
+    it("should reject envelopes that contradict their report", async () => {
+      const { evidence } = await new EnclaveBridge(false).execute(request);
+      const json = (changes: Record<string, unknown>) => JSON.stringify({ ...evidence, ...changes });
+
+      assert.throws(() => evidenceFromJson(json({ platform: "sev-snp" })), /does not match a simulator/);
+      assert.throws(() => evidenceFromJson(json({ format: "snp-report-v3" })), /version 2 report/);
+      assert.throws(() => evidenceFromJson(json({ format: "tdx-quote" })), /Unknown evidence format/);
+      assert.throws(() => evidenceFromJson(json({ bindingFormula: "v9" })), /Unknown binding formula/);
+      assert.throws(() => evidenceFromJson(json({ reportB64: "AAAA" })), AttestationError);
+      assert.throws(() => evidenceFromJson("[]"), /must be a JSON object/);
+    });
+
+    it("should verify through the envelope with its binding version", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute(request);
+      const expected = expectedFor(response);
+      const simulatorKey = bridge.getSimulatorVerificationKey().publicKey;
+
+      // expected names no binding version; the envelope supplies v3
+      assert.strictEqual(bridge.verifyAttestationReport(response.evidence!, expected).valid, true);
+      assert.strictEqual(
+        verifyAttestationReport(response.evidence!, { ...expected, simulatorKey }).valid,
+        true
+      );
+      assert.strictEqual(
+        bridge.verifyAttestationReport(response.attestationReport, expected).valid,
+        false
+      );
+
+      const mismatched = bridge.verifyAttestationReport(response.evidence!, {
+        ...expected,
+        bindingVersion: 2,
+      });
+      assert.deepStrictEqual(mismatched.checks, [
+        {
+          name: "structure",
+          passed: false,
+          reason: "Evidence binding formula v3 does not match bindingVersion 2",
+        },
+      ]);
+      const relabeled = verifyAttestationReport(
+        { ...response.evidence!, platform: "sev-snp" },
+        expected
+      );
+      assert.strictEqual(relabeled.checks[0].passed, false);
+    });
+
+    it("should check an envelope from the native runner against the legacy field", async () => {
+      const simulated = await new EnclaveBridge(false).execute(request);
+      const bridge = new EnclaveBridge(true);
+      let evidence: unknown = simulated.evidence;
+      withNativeModule(bridge, {
+        check_availability: () => true,
+        transform: () =>
+          JSON.stringify({
+            transformed_context: { entities: [], relations: [] },
+            output_hash: Buffer.from(simulated.outputHash).toString("hex"),
+            input_hash: Buffer.from(simulated.inputHash!).toString("hex"),
+            attestation_report: Array.from(simulated.attestationReport),
+            evidence,
+            redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+            measurement: "native_stub",
+            binding_version: 3,
+          }),
+      });
+
+      assert.deepStrictEqual((await bridge.execute(request)).evidence, simulated.evidence);
+
+      const other = await new EnclaveBridge(false).execute({ ...request, nonce: testNonce(9) });
+      evidence = other.evidence;
+      await assert.rejects(bridge.execute(request), /evidence report differs from attestation_report/);
+      evidence = { ...simulated.evidence, bindingFormula: "v2" };
+      await assert.rejects(bridge.execute(request), /binding formula v2 differs/);
+      evidence = { ...simulated.evidence, format: "snp-report-v3" };
+      await assert.rejects(bridge.execute(request), /Invalid enclave evidence/);
+
+      // A runner that sends no envelope gets one built from its report
+      evidence = undefined;
+      assert.deepStrictEqual(
+        (await bridge.execute(request)).evidence,
+        createEvidenceEnvelope(simulated.attestationReport, 3)
+      );
+    });
+  });
+
+  describe("COSE evidence", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+      evidenceFormat: "cose" as const,
+    };
+
+    it("should round-trip into a second verification of the report", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute(request);
+      assert.strictEqual(response.coseEvidence![0], 0xd2); // tag 18
+
+      const result = bridge.verifyCoseEvidence(response.coseEvidence!);
+      assert.strictEqual(result.valid, true, result.reason);
+      assert.strictEqual(result.signed, true);
+      const payload = result.payload!;
+      assert.strictEqual(payload.platform, "simulator");
+      assert.deepStrictEqual(Buffer.from(payload.report), Buffer.from(response.attestationReport));
+      assert.strictEqual(payload.outputHash, Buffer.from(response.outputHash).toString("hex"));
+      assert.strictEqual(payload.bindingVersion, 3);
+
+      // The payload alone is enough to verify the report it carries
+      const second = bridge.verifyAttestationReport(payload.report, coseExpectedBinding(payload));
+      assert.strictEqual(second.valid, true, JSON.stringify(second.checks));
+      const altered = { ...payload, timestamp: payload.timestamp + 1 };
+      assert.strictEqual(
+        bridge.verifyAttestationReport(payload.report, coseExpectedBinding(altered)).valid,
+        false
+      );
+
+      const raw = await bridge.execute({ ...request, evidenceFormat: "raw" });
+      assert.strictEqual(raw.coseEvidence, undefined);
+    });
+
+    it("should reject a tampered payload or the wrong key", async () => {
+      const bridge = new EnclaveBridge(false);
+      const { coseEvidence, outputHash } = await bridge.execute(request);
+      const tampered = Buffer.from(coseEvidence!);
+      const at = tampered.indexOf(Buffer.from(outputHash));
+      assert.ok(at > 0);
+      tampered[at] ^= 0x01;
+
+      const result = bridge.verifyCoseEvidence(tampered);
+      assert.strictEqual(result.valid, false);
+      assert.strictEqual(result.payload, undefined);
+      assert.match(result.reason!, /signature does not verify/);
+
+      const otherKey = new EnclaveBridge(false).getSimulatorVerificationKey().publicKey;
+      assert.strictEqual(bridge.verifyCoseEvidence(coseEvidence!, otherKey).valid, false);
+      assert.match(
+        bridge.verifyCoseEvidence(coseEvidence!.subarray(0, 40)).reason!,
+        /truncated/
+      );
+    });
+
+    it("should require simulator evidence to be signed", async () => {
+      const bridge = new EnclaveBridge(false);
+      const { coseEvidence } = await bridge.execute(request);
+      const unsigned = createCoseEvidence(decodeCoseEvidence(coseEvidence!).payload);
+
+      assert.match(bridge.verifyCoseEvidence(unsigned).reason!, /must be signed/);
+      assert.throws(() => decodeCoseEvidence(unsigned), AttestationError);
+      assert.throws(
+        () => validateRequest({ ...request, evidenceFormat: "jws" as "cose" }),
+        /Invalid evidenceFormat: jws/
+      );
+    });
+
+    it("should check COSE evidence from the native runner", async () => {
+      const simulated = await new EnclaveBridge(false).execute(request);
+      const bridge = new EnclaveBridge(true);
+      let coseEvidence: number[] | undefined = Array.from(simulated.coseEvidence!);
+      let sent: Record<string, unknown> = {};
+      const stub: Record<string, unknown> = {
+        check_availability: () => true,
+        transform: (requestJson: string) => {
+          sent = JSON.parse(requestJson);
+          return JSON.stringify({
+            transformed_context: { entities: [], relations: [] },
+            output_hash: Buffer.from(simulated.outputHash).toString("hex"),
+            input_hash: Buffer.from(simulated.inputHash!).toString("hex"),
+            attestation_report: Array.from(simulated.attestationReport),
+            cose_evidence: coseEvidence,
+            redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+            measurement: "native_stub",
+            binding_version: 3,
+          });
+        },
+      };
+      withNativeModule(bridge, stub);
+
+      const response = await bridge.execute(request);
+      assert.strictEqual(sent.evidence_format, "cose");
+      assert.deepStrictEqual(response.coseEvidence, simulated.coseEvidence);
+      assert.throws(
+        () => bridge.verifyCoseEvidence(response.coseEvidence!),
+        /does not export verify_cose_evidence/
+      );
+
+      let keyPem: string | null = null;
+      stub.verify_cose_evidence = (_cose: string, key: string | null) => {
+        keyPem = key;
+        return JSON.stringify({ valid: true, signed: true });
+      };
+      const simulatorKey = new EnclaveBridge(false).getSimulatorVerificationKey().publicKey;
+      const result = bridge.verifyCoseEvidence(response.coseEvidence!, simulatorKey);
+      assert.strictEqual(keyPem, simulatorKey);
+      assert.deepStrictEqual(result.payload, decodeCoseEvidence(simulated.coseEvidence!).payload);
+      // The SDK decodes the payload itself and does not take the runner's word
+      assert.throws(
+        () => bridge.verifyCoseEvidence(new Uint8Array([0xd2, 0x80])),
+        SecurityInvariantError
+      );
+
+      const other = await new EnclaveBridge(false).execute({ ...request, nonce: testNonce(9) });
+      coseEvidence = Array.from(other.coseEvidence!);
+      await assert.rejects(bridge.execute(request), /COSE evidence report differs/);
+      coseEvidence = undefined;
+      await assert.rejects(bridge.execute(request), /COSE evidence requested/);
+    });
+  });
+
+  describe("EAT tokens", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+      evidenceFormat: "eat" as const,
+    };
+
+    it("should carry the EAT claims for the report", async () => {
+      const bridge = new EnclaveBridge(false, {}, { reportVersion: 3 });
+      const response = await bridge.execute(request);
+      const token = getEatToken(response);
+      const simulatorKey = bridge.getSimulatorVerificationKey().publicKey;
+
+      const claimSet = decodeCbor(readSign1(token).body) as Map<number, unknown>;
+      for (const key of Object.values(EAT_CLAIM_KEYS)) {
+        assert.ok(claimSet.has(key), `claim ${key} missing`);
+      }
+
+      const result = verifyEatToken(token, simulatorKey);
+      assert.strictEqual(result.valid, true, result.reason);
+      assert.strictEqual(result.signed, true);
+      const claims = result.claims!;
+      assert.deepStrictEqual(Buffer.from(claims.nonce), Buffer.from(request.nonce));
+      assert.strictEqual(claims.issuedAt, 1700000000);
+      assert.strictEqual(claims.oemid, AMD_OEMID);
+      assert.deepStrictEqual(Array.from(claims.hwmodel!), [0x19, 0x01, 0x01]);
+      assert.strictEqual(claims.swname, "axiom-enclave-runner");
+      assert.deepStrictEqual(
+        Buffer.from(claims.measurement),
+        Buffer.from(parseReport(response.attestationReport).measurement)
+      );
+      assert.strictEqual(claims.outputHash, Buffer.from(response.outputHash).toString("hex"));
+      assert.deepStrictEqual(Buffer.from(claims.report), Buffer.from(response.attestationReport));
+
+      // Version 2 reports carry no CPUID, so no hwmodel
+      const v2 = await new EnclaveBridge(false).execute(request);
+      assert.strictEqual(decodeEatToken(getEatToken(v2)).claims.hwmodel, undefined);
+      const raw = await bridge.execute({ ...request, evidenceFormat: "raw" });
+      assert.throws(() => getEatToken(raw), ConfigurationError);
+    });
+
+    it("should change the token when output_hash changes", async () => {
+      const bridge = new EnclaveBridge(false);
+      const token = getEatToken(await bridge.execute(request));
+      const { claims } = decodeEatToken(token);
+      const outputHash = "ff".repeat(32);
+
+      const unsigned = createEatToken(claims);
+      assert.notDeepStrictEqual(createEatToken({ ...claims, outputHash }), unsigned);
+      assert.deepStrictEqual(createEatToken({ ...claims }), unsigned);
+
+      const other = getEatToken(
+        await bridge.execute({ ...request, rawContext: [new TextEncoder().encode("Carol")] })
+      );
+      assert.notDeepStrictEqual(other, token);
+
+      // A simulator token must be signed, and tampering breaks the signature
+      assert.match(verifyEatToken(unsigned).reason!, /must be signed/);
+      const tampered = Buffer.from(token);
+      tampered[tampered.indexOf(Buffer.from(claims.outputHash, "hex"))] ^= 0x01;
+      const simulatorKey = bridge.getSimulatorVerificationKey().publicKey;
+      assert.match(verifyEatToken(tampered, simulatorKey).reason!, /signature does not verify/);
+    });
+
+    it("should check a native token against the response", async () => {
+      const simulated = await new EnclaveBridge(false).execute(request);
+      const bridge = new EnclaveBridge(true);
+      let eatToken: number[] | undefined = Array.from(simulated.eatToken!);
+      withNativeModule(bridge, {
+        check_availability: () => true,
+        transform: () =>
+          JSON.stringify({
+            transformed_context: { entities: [], relations: [] },
+            output_hash: Buffer.from(simulated.outputHash).toString("hex"),
+            input_hash: Buffer.from(simulated.inputHash!).toString("hex"),
+            attestation_report: Array.from(simulated.attestationReport),
+            eat_token: eatToken,
+            redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+            measurement: "native_stub",
+            binding_version: 3,
+          }),
+      });
+
+      assert.deepStrictEqual((await bridge.execute(request)).eatToken, simulated.eatToken);
+      // A token for this report, but with another request's nonce
+      const { claims } = decodeEatToken(simulated.eatToken!);
+      eatToken = Array.from(createEatToken({ ...claims, nonce: testNonce(9) }, STUB_REPORT_KEY));
+      await assert.rejects(bridge.execute(request), /eat_nonce differs from the request nonce/);
+      eatToken = Array.from(new Uint8Array([0xd9, 0x02, 0x59, 0xa0]));
+      await assert.rejects(bridge.execute(request), /Invalid enclave EAT token/);
+      eatToken = undefined;
+      await assert.rejects(bridge.execute(request), /EAT token requested/);
+    });
+  });
+
+  describe("Attestation tokens", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+    };
+    const withTokenKey = async (
+      run: (path: string, publicKey: string, dir: string) => Promise<void>
+    ) => {
+      const dir = await mkdtemp(join(tmpdir(), "axiom-tokenkey-"));
+      const path = join(dir, "token.pem");
+      const keys = generateKeyPairSync("ec", { namedCurve: "P-256" });
+      await writeFile(path, keys.privateKey.export({ format: "pem", type: "pkcs8" }));
+      try {
+        await run(path, keys.publicKey.export({ format: "pem", type: "spki" }).toString(), dir);
+      } finally {
+        await rm(dir, { recursive: true, force: true });
+      }
+    };
+
+    it("should mint an ES256 JWT that verifies against the configured key", async () => {
+      await withTokenKey(async (path, publicKey) => {
+        const bridge = new EnclaveBridge(false);
+        bridge.initializeWithConfig({ tokenKeyPath: path });
+        const before = Math.floor(Date.now() / 1000);
+        const { response, token, claims } = await bridge.attestAndTokenize(request);
+
+        // Any JWT library does the same: ES256 over header.payload
+        const [header, payload, signature] = token.split(".");
+        assert.deepStrictEqual(JSON.parse(Buffer.from(header, "base64url").toString()), {
+          alg: "ES256",
+          typ: "JWT",
+        });
+        assert.ok(
+          cryptoVerify(
+            "sha256",
+            Buffer.from(`${header}.${payload}`),
+            { key: publicKey, dsaEncoding: "ieee-p1363" },
+            Buffer.from(signature, "base64url")
+          )
+        );
+        assert.deepStrictEqual(verifyAttestationToken(token, publicKey), claims);
+
+        assert.strictEqual(claims.iss, "axiom-enclave-runner");
+        assert.strictEqual(claims.session_id, "01".repeat(16));
+        assert.strictEqual(claims.nonce, Buffer.from(request.nonce).toString("hex"));
+        assert.strictEqual(claims.output_hash, Buffer.from(response.outputHash).toString("hex"));
+        assert.strictEqual(claims.measurement, response.measurement);
+        assert.strictEqual(claims.platform, "simulator");
+        assert.ok(claims.iat >= before && claims.iat <= Math.floor(Date.now() / 1000));
+
+        const forged = `${header}.${Buffer.from(
+          JSON.stringify({ ...claims, output_hash: "00".repeat(32) })
+        ).toString("base64url")}.${signature}`;
+        assert.throws(() => verifyAttestationToken(forged, publicKey), /does not verify/);
+      });
+    });
+
+    it("should fail with typed errors for key and clock problems", async () => {
+      await withTokenKey(async (path, _publicKey, dir) => {
+        const code = (expected: string) => (error: unknown) =>
+          error instanceof TokenError && error.code === expected;
+
+        await assert.rejects(
+          new EnclaveBridge(false).attestAndTokenize(request),
+          code("TOKEN_KEY_MISSING")
+        );
+        const missing = join(dir, "none.pem");
+        assert.throws(
+          () => new EnclaveBridge(false).initializeWithConfig({ tokenKeyPath: missing }),
+          code("TOKEN_KEY_INVALID")
+        );
+        const edPath = join(dir, "ed25519.pem");
+        const edKey = generateKeyPairSync("ed25519").privateKey;
+        await writeFile(edPath, edKey.export({ format: "pem", type: "pkcs8" }));
+        assert.throws(
+          () => new EnclaveBridge(false, {}, { tokenKeyPath: edPath }),
+          code("TOKEN_KEY_INVALID")
+        );
+
+        const bridge = new EnclaveBridge(false, {}, { tokenKeyPath: path });
+        await assert.rejects(
+          bridge.attestAndTokenize({ ...request, timestamp: Date.now() + 3600000 }),
+          code("TOKEN_CLOCK")
+        );
+        assert.throws(() => verificationTime(request.timestamp, 0), code("TOKEN_CLOCK"));
+        assert.throws(() => verificationTime(request.timestamp, NaN), code("TOKEN_CLOCK"));
+      });
+    });
+
+    it("should check a native token against the response", async () => {
+      await withTokenKey(async (path) => {
+        const simulated = await new EnclaveBridge(false).execute(request);
+        const bridge = new EnclaveBridge(true);
+        const tokenKey = createPrivateKey(await readFile(path, "utf8"));
+        const hex = (bytes: Uint8Array) => Buffer.from(bytes).toString("hex");
+        let claims = {
+          iss: "axiom-enclave-runner",
+          iat: 1700000001,
+          session_id: hex(request.sessionId),
+          nonce: hex(request.nonce),
+          output_hash: hex(simulated.outputHash),
+          measurement: "native_stub",
+          platform: "simulator" as const,
+        };
+        let config: Record<string, unknown> = {};
+        const stub: Record<string, unknown> = {
+          check_availability: () => true,
+          initialize_with_config: (json: string) => {
+            config = JSON.parse(json);
+            return "ok";
+          },
+          transform: () => "{}",
+        };
+        withNativeModule(bridge, stub);
+
+        bridge.initializeWithConfig({ tokenKeyPath: path });
+        assert.strictEqual(config.token_key_path, path);
+        await assert.rejects(
+          bridge.attestAndTokenize(request),
+          /does not export attest_and_tokenize/
+        );
+
+        stub.attest_and_tokenize = async () =>
+          JSON.stringify({
+            response: {
+              transformed_context: { entities: [], relations: [] },
+              output_hash: hex(simulated.outputHash),
+              input_hash: hex(simulated.inputHash!),
+              attestation_report: Array.from(simulated.attestationReport),
+              redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+              measurement: "native_stub",
+              binding_version: 3,
+            },
+            token: mintAttestationToken(claims, tokenKey),
+          });
+        const result = await bridge.attestAndTokenize(request);
+        assert.deepStrictEqual(result.claims, claims);
+        assert.strictEqual(result.response.measurement, "native_stub");
+
+        claims = { ...claims, nonce: "00".repeat(32), output_hash: "11".repeat(32) };
+        await assert.rejects(
+          bridge.attestAndTokenize(request),
+          /token differs from the response: nonce, output_hash/
+        );
+      });
+    });
+  });
+
+  describe("TDX backend", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(3),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+    };
+    const INTEL_QE_VENDOR_ID = Uint8Array.from(
+      Buffer.from("939a7233f79c4ca9940a0db3957f0607", "hex")
+    );
+    const nativeResponse = (simulated: EnclaveResponse) =>
+      JSON.stringify({
+        transformed_context: { entities: [], relations: [] },
+        output_hash: Buffer.from(simulated.outputHash).toString("hex"),
+        input_hash: Buffer.from(simulated.inputHash!).toString("hex"),
+        attestation_report: Array.from(simulated.attestationReport),
+        redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+        measurement: "native_stub",
+        binding_version: 3,
+      });
+
+    it("should emit a TDX-shaped simulator quote with the SEV-SNP report_data", async () => {
+      const bridge = new EnclaveBridge(false, {}, { backend: "tdx" });
+      assert.strictEqual(bridge.getPlatform(), "tdx-simulator");
+      const response = await bridge.execute(request);
+      const snp = await new EnclaveBridge(false).execute(request);
+
+      const bytes = response.attestationReport;
+      assert.ok(isTdxQuote(bytes));
+      assert.ok(!isTdxQuote(snp.attestationReport));
+      const quote = parseTdxQuote(bytes);
+      assert.strictEqual(quote.version, 4);
+      assert.strictEqual(quote.teeType, 0x81);
+      assert.deepStrictEqual(tdxQuoteToBytes(quote), bytes);
+      assert.deepStrictEqual(quote.reportData, parseReport(snp.attestationReport).reportData);
+      assert.ok(
+        cryptoVerify(
+          null,
+          bytes.subarray(0, TDX_SIGNED_QUOTE_BYTES),
+          bridge.getSimulatorVerificationKey().publicKey,
+          quote.signatureData
+        )
+      );
+
+      const evidence = response.evidence;
+      assert.ok(evidence);
+      assert.strictEqual(evidence.platform, "simulator");
+      assert.strictEqual(evidence.format, "tdx-quote-v4");
+      assert.strictEqual(evidence.certificates, undefined);
+      assert.deepStrictEqual(evidenceFromJson(evidenceToJson(evidence)), evidence);
+      assert.strictEqual(response.platform, undefined);
+    });
+
+    it("should label hardware quotes tdx and reject contradicting envelopes", async () => {
+      const simulated = await new EnclaveBridge(false, {}, { backend: "tdx" }).execute(request);
+      const quote = parseTdxQuote(simulated.attestationReport);
+      const hardware = tdxQuoteToBytes({ ...quote, qeVendorId: INTEL_QE_VENDOR_ID });
+      const envelope = createEvidenceEnvelope(hardware, 3);
+      assert.strictEqual(envelope.platform, "tdx");
+      assert.strictEqual(envelope.format, "tdx-quote-v4");
+
+      const json = (overrides: Record<string, unknown>) =>
+        JSON.stringify({ ...envelope, ...overrides });
+      for (const platform of ["sev-snp", "simulator"]) {
+        assert.throws(() => evidenceFromJson(json({ platform })), /does not match a TDX quote/);
+      }
+      assert.throws(
+        () => evidenceFromJson(json({ format: "snp-report-v2" })),
+        /snp-report-v2 does not match a TDX quote/
+      );
+      assert.throws(
+        () => parseTdxQuote(hardware.subarray(0, hardware.length - 1)),
+        /signature data length/
+      );
+
+      const result = verifyAttestationReport(envelope, {
+        sessionId: "01".repeat(16),
+        configHash: request.configHash,
+        outputHash: Buffer.from(simulated.outputHash).toString("hex"),
+        timestamp: request.timestamp,
+      });
+      assert.strictEqual(result.valid, false);
+      assert.strictEqual(result.checks[0].name, "structure");
+      assert.match(String(result.checks[0].reason), /TDX quote verification is not supported/);
+    });
+
+    it("should fail closed in the simulator where a SEV-SNP report is required", async () => {
+      const bridge = new EnclaveBridge(false, {}, { backend: "tdx" });
+      await assert.rejects(
+        bridge.execute({ ...request, requireVcek: true }),
+        /not a SEV-SNP hardware report/
+      );
+      for (const evidenceFormat of ["cose", "eat"] as const) {
+        await assert.rejects(
+          bridge.execute({ ...request, evidenceFormat }),
+          (error: unknown) =>
+            error instanceof ConfigurationError && /tdx backend/.test(error.message)
+        );
+      }
+      assert.throws(
+        () => new EnclaveBridge(false, {}, { backend: "sgx" as "tdx" }),
+        /Invalid backend: sgx/
+      );
+
+      bridge.initializeWithConfig({ backend: "sev-snp" });
+      assert.strictEqual(bridge.getPlatform(), "sev-snp-simulator");
+      assert.ok(!isTdxQuote((await bridge.execute(request)).attestationReport));
+    });
+
+    it("should refuse an unavailable native TDX backend", async () => {
+      const bridge = new EnclaveBridge(true);
+      let transformed = false;
+      withNativeModule(bridge, {
+        initialize: () => "tdx",
+        check_availability: () => false,
+        transform: () => {
+          transformed = true;
+          return "{}";
+        },
+      });
+      assert.strictEqual(bridge.getPlatform(), "tdx");
+      assert.strictEqual(await bridge.isAvailable(), false);
+      await assert.rejects(
+        bridge.execute(request),
+        (error: unknown) =>
+          error instanceof ConfigurationError && /not available in native mode/.test(error.message)
+      );
+      assert.strictEqual(transformed, false);
+
+      // A backend the SDK does not know is unavailable even if the runner says otherwise
+      const unknown = new EnclaveBridge(true);
+      withNativeModule(unknown, {
+        initialize: () => "sgx",
+        check_availability: () => true,
+        transform: () => "{}",
+      });
+      assert.strictEqual(await unknown.isAvailable(), false);
+      assert.throws(() => unknown.getPlatform(), SecurityInvariantError);
+      await assert.rejects(unknown.execute(request), /not available in native mode/);
+    });
+
+    it("should reject native evidence from a different backend", async () => {
+      const tdx = await new EnclaveBridge(false, {}, { backend: "tdx" }).execute(request);
+      const snp = await new EnclaveBridge(false).execute(request);
+
+      const tdxBridge = new EnclaveBridge(true);
+      let config: Record<string, unknown> = {};
+      withNativeModule(tdxBridge, {
+        initialize: () => "tdx",
+        initialize_with_config: (json: string) => {
+          config = JSON.parse(json);
+          return "ok";
+        },
+        check_availability: () => true,
+        transform: () => nativeResponse(snp),
+      });
+      await assert.rejects(
+        tdxBridge.execute(request),
+        (error: unknown) =>
+          error instanceof SecurityInvariantError &&
+          /backend is tdx, but it returned no TDX quote/.test(error.message)
+      );
+      tdxBridge.initializeWithConfig({ backend: "tdx" });
+      assert.strictEqual(config.backend, "tdx");
+
+      const snpBridge = new EnclaveBridge(true);
+      withNativeModule(snpBridge, {
+        check_availability: () => true,
+        transform: () => nativeResponse(tdx),
+      });
+      assert.strictEqual(snpBridge.getPlatform(), "sev-snp");
+      await assert.rejects(
+        snpBridge.execute(request),
+        /backend is sev-snp, but it returned a TDX quote/
+      );
+
+      withNativeModule(tdxBridge, {
+        initialize: () => "tdx",
+        check_availability: () => true,
+        transform: () => nativeResponse(tdx),
+      });
+      const response = await tdxBridge.execute(request);
+      assert.strictEqual(response.evidence?.format, "tdx-quote-v4");
+      assert.strictEqual(response.measurement, "native_stub");
+    });
+  });
+
+  describe("Attestation backends", () => {
+    const SNAPSHOTS = JSON.parse(
+      readFileSync(new URL("./vectors/simulator-evidence.json", import.meta.url), "utf8")
+    ) as {
+      measurement: string;
+      cases: Array<{
+        name: string;
+        simulator: { reportVersion?: 2 | 3; backend?: "sev-snp" | "tdx" };
+        request: {
+          bindingVersion: 1 | 2 | 3;
+          legacyReportData?: boolean;
+          evidenceFormat?: "cose" | "eat";
+        };
+        output_hash: string;
+        report_sha256: string;
+        cose_sha256?: string;
+        eat_sha256?: string;
+      }>;
+    };
+    const signingKeyPath = fileURLToPath(new URL("./vectors/simulator-key.pem", import.meta.url));
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100 on Monday.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(7),
+      timestamp: 1700000000000,
+    };
+    const sha256 = (bytes: Uint8Array) => createHash("sha256").update(bytes).digest("hex");
+    const withBackend = (backend: AttestationBackend) =>
+      new EnclaveBridge(false, {}, { attestationBackend: backend });
+    const mockBackend = (overrides: Partial<AttestationBackend>): AttestationBackend => {
+      const simulator = new SimulatorBackend({
+        reportVersion: 2,
+        deterministic: true,
+        signingKey: generateKeyPairSync("ed25519").privateKey,
+      });
+      return {
+        isAvailable: () => true,
+        measurement: () => "mock_measurement",
+        generateEvidence: (binding: BindingInput) => simulator.generateEvidence(binding),
+        platformId: () => "sev-snp-simulator",
+        ...overrides,
+      };
+    };
+
+    it("should reproduce the simulator evidence snapshots byte for byte", async () => {
+      assert.ok(SNAPSHOTS.cases.length > 0);
+      const previous = process.env[SIMULATOR_MEASUREMENT_ENV];
+      process.env[SIMULATOR_MEASUREMENT_ENV] = SNAPSHOTS.measurement;
+      try {
+        for (const snapshot of SNAPSHOTS.cases) {
+          const bridge = new EnclaveBridge(false, {}, { ...snapshot.simulator, signingKeyPath });
+          const response = await bridge.execute({ ...request, ...snapshot.request });
+          assert.strictEqual(
+            Buffer.from(response.outputHash).toString("hex"),
+            snapshot.output_hash,
+            snapshot.name
+          );
+          assert.strictEqual(
+            sha256(response.attestationReport),
+            snapshot.report_sha256,
+            snapshot.name
+          );
+          assert.strictEqual(
+            response.coseEvidence && sha256(response.coseEvidence),
+            snapshot.cose_sha256,
+            snapshot.name
+          );
+          assert.strictEqual(
+            response.eatToken && sha256(response.eatToken),
+            snapshot.eat_sha256,
+            snapshot.name
+          );
+        }
+      } finally {
+        if (previous === undefined) {
+          delete process.env[SIMULATOR_MEASUREMENT_ENV];
+        } else {
+          process.env[SIMULATOR_MEASUREMENT_ENV] = previous;
+        }
+      }
+    });
+
+    it("should select the backend when the runner is initialized", async () => {
+      const bridge = new EnclaveBridge(false);
+      assert.strictEqual(bridge.getPlatform(), "sev-snp-simulator");
+      assert.ok(!isTdxQuote((await bridge.execute(request)).attestationReport));
+      bridge.initializeWithConfig({ backend: "tdx" });
+      assert.strictEqual(bridge.getPlatform(), "tdx-simulator");
+      assert.ok(isTdxQuote((await bridge.execute(request)).attestationReport));
+
+      // An injected backend wins over the one backend selects
+      const injected = withBackend(mockBackend({ platformId: () => "sev-snp" }));
+      injected.initializeWithConfig({ backend: "tdx", reportVersion: 3 });
+      assert.strictEqual(injected.getPlatform(), "sev-snp");
+    });
+
+    it("should take availability, measurement, and platform from the backend", async () => {
+      const bridge = withBackend(mockBackend({ platformId: () => "sev-snp" }));
+      assert.strictEqual(bridge.getPlatform(), "sev-snp");
+      const response = await bridge.execute(request);
+      assert.strictEqual(response.measurement, "mock_measurement");
+
+      let generated = false;
+      const unavailable = withBackend(
+        mockBackend({
+          isAvailable: () => false,
+          generateEvidence: () => {
+            generated = true;
+            throw new Error("unreachable");
+          },
+        })
+      );
+      assert.strictEqual(await unavailable.isAvailable(), false);
+      await assert.rejects(
+        unavailable.execute(request),
+        (error: unknown) =>
+          error instanceof ConfigurationError &&
+          /not available in simulator mode/.test(error.message)
+      );
+      assert.strictEqual(generated, false);
+    });
+
+    it("should reject backend evidence that does not carry the binding", async () => {
+      const simulator = new SimulatorBackend({
+        reportVersion: 3,
+        deterministic: true,
+        signingKey: generateKeyPairSync("ed25519").privateKey,
+      });
+      const stale = withBackend(
+        mockBackend({
+          generateEvidence: (binding) =>
+            simulator.generateEvidence({ ...binding, timestamp: binding.timestamp - 1 }),
+        })
+      );
+      await assert.rejects(
+        stale.execute(request),
+        (error: unknown) =>
+          error instanceof SecurityInvariantError &&
+          /does not carry the request's report_data/.test(error.message)
+      );
+
+      const garbage = withBackend(
+        mockBackend({ generateEvidence: () => ({ report: new Uint8Array(100) }) })
+      );
+      await assert.rejects(garbage.execute(request), (error: unknown) =>
+        error instanceof AttestationError && /unparseable evidence/.test(error.message)
+      );
+
+      const failing = withBackend(
+        mockBackend({
+          generateEvidence: () => {
+            throw new AttestationError("quote generation failed");
+          },
+        })
+      );
+      await assert.rejects(failing.execute(request), /quote generation failed/);
+    });
+
+    it("should compute the same report_data for every backend", () => {
+      const binding: BindingInput = {
+        sessionId: request.sessionId,
+        nonce: request.nonce,
+        configHash: new Uint8Array(32),
+        policyHash: new Uint8Array(32).fill(2),
+        outputHash: new Uint8Array(32).fill(3),
+        timestamp: request.timestamp,
+        legacyReportData: false,
+        hashAlgorithm: "sha256",
+        bindingVersion: 2,
+      };
+      const options = {
+        reportVersion: 2 as const,
+        deterministic: true,
+        signingKey: generateKeyPairSync("ed25519").privateKey,
+      };
+      const expected = Buffer.alloc(64);
+      expected.set(bindingReportData(binding));
+      const snp = new SimulatorBackend(options).generateEvidence(binding).report;
+      const tdx = new TdxSimulatorBackend(options).generateEvidence(binding).report;
+      assert.deepStrictEqual(Buffer.from(parseReport(snp).reportData), expected);
+      assert.deepStrictEqual(Buffer.from(parseTdxQuote(tdx).reportData), expected);
+    });
+
+    it("should measure the executable and embed the measurement", async () => {
+      const measurement = simulatorMeasurement();
+      assert.match(measurement, /^[0-9a-f]{96}$/);
+      const bytes = Buffer.from(measurement, "hex");
+      assert.strictEqual(bytes.length, 48);
+      assert.strictEqual(
+        measurement,
+        createHash("sha384").update(readFileSync(process.execPath)).digest("hex")
+      );
+
+      const response = await new EnclaveBridge(false).execute(request);
+      assert.strictEqual(response.measurement, measurement);
+      const report = Buffer.from(response.attestationReport);
+      assert.deepStrictEqual(report.subarray(0x090, 0x090 + 48), bytes);
+      assert.deepStrictEqual(Buffer.from(parseReport(report).measurement), bytes);
+
+      const tdx = await new EnclaveBridge(false, {}, { backend: "tdx" }).execute(request);
+      assert.deepStrictEqual(Buffer.from(parseTdxQuote(tdx.attestationReport).mrTd), bytes);
+    });
+
+    it("should take the simulator measurement from the environment when set", async () => {
+      const previous = process.env[SIMULATOR_MEASUREMENT_ENV];
+      try {
+        const pinned = "5a".repeat(48);
+        process.env[SIMULATOR_MEASUREMENT_ENV] = pinned.toUpperCase();
+        assert.strictEqual(simulatorMeasurement(), pinned);
+        const response = await new EnclaveBridge(false).execute(request);
+        assert.strictEqual(response.measurement, pinned);
+        assert.strictEqual(
+          Buffer.from(parseReport(response.attestationReport).measurement).toString("hex"),
+          pinned
+        );
+
+        for (const invalid of ["5a".repeat(47), "zz".repeat(48)]) {
+          process.env[SIMULATOR_MEASUREMENT_ENV] = invalid;
+          assert.throws(() => simulatorMeasurement(), ConfigurationError, invalid);
+        }
+      } finally {
+        if (previous === undefined) {
+          delete process.env[SIMULATOR_MEASUREMENT_ENV];
+        } else {
+          process.env[SIMULATOR_MEASUREMENT_ENV] = previous;
+        }
+      }
+    });
+  });
+
+  describe("Expected measurement", () => {
+    const HARDWARE_MEASUREMENT = "ab".repeat(48);
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100 on Monday.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(8),
+      timestamp: 1700000000000,
+    };
+
+    it("should run when the pinned measurement matches, in either case", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute({
+        ...request,
+        expectedMeasurement: simulatorMeasurement().toUpperCase(),
+      });
+      assert.strictEqual(response.measurement, simulatorMeasurement());
+    });
+
+    it("should fail with both measurements before reading rawContext", async () => {
+      let read = false;
+      const watched = {
+        ...request,
+        expectedMeasurement: HARDWARE_MEASUREMENT.toUpperCase(),
+        get rawContext() {
+          read = true;
+          return request.rawContext;
+        },
+      };
+      await assert.rejects(
+        new EnclaveBridge(false).execute(watched),
+        (error: unknown) =>
+          error instanceof MeasurementMismatchError &&
+          error.expected === HARDWARE_MEASUREMENT &&
+          error.actual === simulatorMeasurement()
+      );
+      assert.strictEqual(read, false);
+    });
+
+    it("should reject a malformed pinned measurement", () => {
+      for (const expectedMeasurement of [
+        "",
+        "ab".repeat(47),
+        "ab".repeat(49),
+        "zz".repeat(48),
+        "simulator_measurement_00",
+      ]) {
+        assert.throws(
+          () => validateRequest({ ...request, expectedMeasurement }),
+          (error: unknown) =>
+            error instanceof ConfigurationError &&
+            /Invalid expectedMeasurement/.test(error.message),
+          JSON.stringify(expectedMeasurement)
+        );
+      }
+    });
+
+    it("should check get_measurement before a native transform", async () => {
+      let sent: { expected_measurement?: string | null } | undefined;
+      const bridge = new EnclaveBridge(true);
+      const stub = {
+        check_availability: () => true,
+        transform: (json: string) => {
+          sent = JSON.parse(json);
+          throw new Error("transform reached");
+        },
+      };
+      withNativeModule(bridge, stub);
+      const pinned = { ...request, expectedMeasurement: HARDWARE_MEASUREMENT };
+      await assert.rejects(bridge.execute(pinned), /does not export get_measurement/);
+
+      withNativeModule(bridge, { ...stub, get_measurement: () => "cd".repeat(48) });
+      await assert.rejects(bridge.execute(pinned), MeasurementMismatchError);
+      assert.strictEqual(sent, undefined);
+
+      withNativeModule(bridge, {
+        ...stub,
+        get_measurement: () => HARDWARE_MEASUREMENT.toUpperCase(),
+      });
+      await assert.rejects(bridge.execute(pinned), /transform reached/);
+      assert.strictEqual(sent?.expected_measurement, HARDWARE_MEASUREMENT);
+    });
+  });
+
+  describe("Measurement registry", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100 on Monday.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(9),
+      timestamp: 1700000000000,
+    };
+    const PREVIOUS_RELEASE = "0f".repeat(48);
+
+    // Write a registry and its public key; returns their paths
+    async function writeRegistry(
+      dir: string,
+      registry: object,
+      publicKey: KeyObject
+    ): Promise<{ measurementRegistry: string; measurementRegistryKeyPath: string }> {
+      const measurementRegistry = join(dir, "registry.json");
+      const measurementRegistryKeyPath = join(dir, "registry-key.pem");
+      await writeFile(measurementRegistry, JSON.stringify(registry));
+      await writeFile(
+        measurementRegistryKeyPath,
+        publicKey.export({ format: "pem", type: "spki" })
+      );
+      return { measurementRegistry, measurementRegistryKeyPath };
+    }
+
+    it("should attest when the runner's measurement is in the registry", async () => {
+      const dir = await mkdtemp(join(tmpdir(), "axiom-registry-"));
+      try {
+        const keys = generateKeyPairSync("ed25519");
+        const registry = signMeasurementRegistry(
+          [simulatorMeasurement().toUpperCase(), PREVIOUS_RELEASE],
+          keys.privateKey
+        );
+        const bridge = new EnclaveBridge(false);
+        bridge.initializeWithConfig(await writeRegistry(dir, registry, keys.publicKey));
+        assert.strictEqual(await bridge.isAvailable(), true);
+        const response = await bridge.execute(request);
+        assert.strictEqual(response.measurement, simulatorMeasurement());
+      } finally {
+        await rm(dir, { recursive: true, force: true });
+      }
+    });
+
+    it("should refuse to attest when the measurement is not in the registry", async () => {
+      const dir = await mkdtemp(join(tmpdir(), "axiom-registry-"));
+      try {
+        const keys = generateKeyPairSync("ed25519");
+        const registry = signMeasurementRegistry([PREVIOUS_RELEASE], keys.privateKey);
+        const paths = await writeRegistry(dir, registry, keys.publicKey);
+        const bridge = new EnclaveBridge(false, {}, paths);
+        assert.strictEqual(await bridge.isAvailable(), false);
+        await assert.rejects(bridge.execute(request), ConfigurationError);
+
+        // The runner checks again when it attests, so a measurement that
+        // changes after the availability check is still refused
+        const backend = new SimulatorBackend({ reportVersion: 2, deterministic: true });
+        let measured = 0;
+        const attestationBackend: AttestationBackend = {
+          isAvailable: () => true,
+          measurement: () => {
+            measured += 1;
+            return measured === 1 ? PREVIOUS_RELEASE : backend.measurement();
+          },
+          platformId: () => backend.platformId(),
+          generateEvidence: (binding: BindingInput) => backend.generateEvidence(binding),
+        };
+        const changing = new EnclaveBridge(false, {}, { ...paths, attestationBackend });
+        await assert.rejects(changing.execute(request), /is not in the measurement registry/);
+      } finally {
+        await rm(dir, { recursive: true, force: true });
+      }
+    });
+
+    it("should reject an unsigned or tampered registry", async () => {
+      const dir = await mkdtemp(join(tmpdir(), "axiom-registry-"));
+      try {
+        const keys = generateKeyPairSync("ed25519");
+        const signed = signMeasurementRegistry([PREVIOUS_RELEASE], keys.privateKey);
+        const bridge = new EnclaveBridge(false);
+
+        const unsigned = { version: signed.version, measurements: signed.measurements };
+        const unsignedPaths = await writeRegistry(dir, unsigned, keys.publicKey);
+        assert.throws(
+          () => bridge.initializeWithConfig(unsignedPaths),
+          (error: unknown) =>
+            error instanceof ConfigurationError && /is not signed/.test(error.message)
+        );
+
+        const tampered = {
+          ...signed,
+          measurements: [...signed.measurements, simulatorMeasurement()],
+        };
+        const tamperedPaths = await writeRegistry(dir, tampered, keys.publicKey);
+        assert.throws(
+          () => bridge.initializeWithConfig(tamperedPaths),
+          /does not verify under the registry key/
+        );
+
+        const otherKey = generateKeyPairSync("ed25519").publicKey;
+        const otherPaths = await writeRegistry(dir, signed, otherKey);
+        assert.throws(() => bridge.initializeWithConfig(otherPaths), /does not verify/);
+        assert.strictEqual(await bridge.isAvailable(), true);
+
+        // A rejected registry leaves the signing key and registry key as they were
+        const verificationKey = bridge.getSimulatorVerificationKey().publicKey;
+        const signingKeyPath = fileURLToPath(
+          new URL("./vectors/simulator-key.pem", import.meta.url)
+        );
+        assert.throws(
+          () => bridge.initializeWithConfig({ ...tamperedPaths, signingKeyPath }),
+          /does not verify under the registry key/
+        );
+        assert.strictEqual(bridge.getSimulatorVerificationKey().publicKey, verificationKey);
+        const { measurementRegistry } = tamperedPaths;
+        assert.throws(
+          () => bridge.initializeWithConfig({ measurementRegistry }),
+          /measurementRegistry needs measurementRegistryKeyPath/
+        );
+      } finally {
+        await rm(dir, { recursive: true, force: true });
+      }
+    });
+
+    it("should accept any measurement without a registry", async () => {
+      const bridge = new EnclaveBridge(false);
+      assert.strictEqual(await bridge.isAvailable(), true);
+      await bridge.execute(request);
+      assert.throws(
+        () => bridge.initializeWithConfig({ measurementRegistry: "registry.json" }),
+        /measurementRegistry needs measurementRegistryKeyPath/
+      );
+    });
+
+    it("should pass the registry paths to the native runner", () => {
+      const calls: Array<Record<string, unknown>> = [];
+      const bridge = new EnclaveBridge(true);
+      withNativeModule(bridge, {
+        initialize_with_config: (configJson: string) => {
+          calls.push(JSON.parse(configJson));
+          return "{}";
+        },
+      });
+      bridge.initializeWithConfig({
+        measurementRegistry: "/etc/axiom/registry.json",
+        measurementRegistryKeyPath: "/etc/axiom/registry-key.pem",
+      });
+      assert.strictEqual(calls[0].measurement_registry, "/etc/axiom/registry.json");
+      assert.strictEqual(calls[0].measurement_registry_key_path, "/etc/axiom/registry-key.pem");
+    });
+  });
+
+  describe("Session-cached attestation", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100 on Monday.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(4),
+      configHash: "00".repeat(32),
+      nonce: testNonce(10),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+      attestationMode: "session_cached" as const,
+    };
+    const hex = (bytes: Uint8Array) => Buffer.from(bytes).toString("hex");
+    const expectedFor = (response: EnclaveResponse, timestamp: number) => ({
+      sessionId: hex(request.sessionId),
+      configHash: request.configHash,
+      policyHash: hex(response.policyHash),
+      outputHash: hex(response.outputHash),
+      timestamp,
+      inputHash: hex(response.inputHash!),
+      bindingVersion: 3 as const,
+      allowSimulator: true,
+      sessionAttestation: response.sessionAttestation,
+      verifiedAt: timestamp,
+    });
+
+    // A simulator bridge whose backend counts report generations, on a
+    // clock the test sets. The backend signs with the bridge's key, so the
+    // bridge verifies its reports.
+    function countingBridge(attestationWindowMs = 1000) {
+      const signingKeyPath = fileURLToPath(new URL("./vectors/simulator-key.pem", import.meta.url));
+      const backend = new SimulatorBackend({
+        reportVersion: 2,
+        deterministic: true,
+        signingKey: createPrivateKey(readFileSync(signingKeyPath)),
+      });
+      const counter = { reports: 0 };
+      const attestationBackend: AttestationBackend = {
+        isAvailable: () => backend.isAvailable(),
+        measurement: () => backend.measurement(),
+        platformId: () => backend.platformId(),
+        generateEvidence: (binding: BindingInput) => {
+          counter.reports += 1;
+          return backend.generateEvidence(binding);
+        },
+      };
+      const clock = { now: request.timestamp };
+      const bridge = new EnclaveBridge(
+        false,
+        {},
+        { signingKeyPath, attestationWindowMs, attestationBackend, clock: () => clock.now }
+      );
+      return { bridge, counter, clock };
+    }
+
+    it("should generate one report for many transforms in a window", async () => {
+      const { bridge, counter, clock } = countingBridge();
+      const responses: EnclaveResponse[] = [];
+      for (let index = 0; index < 10; index += 1) {
+        const timestamp = request.timestamp + index * 50;
+        clock.now = timestamp;
+        responses.push(await bridge.execute({ ...request, timestamp, nonce: testNonce(index) }));
+      }
+      assert.strictEqual(counter.reports, 1);
+
+      for (const [index, response] of responses.entries()) {
+        assert.deepStrictEqual(response.attestationReport, responses[0].attestationReport);
+        assert.strictEqual(response.sessionAttestation?.windowStart, 1700000000000);
+        assert.strictEqual(response.sessionAttestation?.expiresAt, 1700000001000);
+        const result = bridge.verifyAttestationReport(
+          response.attestationReport,
+          expectedFor(response, request.timestamp + index * 50)
+        );
+        assert.strictEqual(result.valid, true, JSON.stringify(result.checks));
+        assert.ok(result.checks.some((check) => check.name === "session_signature"));
+      }
+    });
+
+    it("should share one generation between concurrent transforms", async () => {
+      const { bridge, counter } = countingBridge();
+      const responses = await Promise.all(
+        Array.from({ length: 8 }, (_, index) =>
+          bridge.execute({ ...request, nonce: testNonce(20 + index) })
+        )
+      );
+      assert.strictEqual(counter.reports, 1);
+      const keys = new Set(
+        responses.map((response) => hex(response.sessionAttestation!.publicKey))
+      );
+      assert.strictEqual(keys.size, 1);
+
+      await bridge.execute({ ...request, sessionId: new Uint8Array(16).fill(5) });
+      assert.strictEqual(counter.reports, 2);
+    });
+
+    it("should regenerate the report after the window expires", async () => {
+      const { bridge, counter, clock } = countingBridge();
+      const first = await bridge.execute(request);
+      clock.now = request.timestamp + 999;
+      await bridge.execute({ ...request, timestamp: clock.now });
+      assert.strictEqual(counter.reports, 1);
+
+      // The window moves with the clock, not with the request
+      clock.now = request.timestamp + 1000;
+      await assert.rejects(bridge.execute(request), /outside the session window/);
+      const later = { ...request, timestamp: clock.now };
+      const renewed = await bridge.execute(later);
+      assert.strictEqual(counter.reports, 2);
+      assert.strictEqual(renewed.sessionAttestation?.windowStart, 1700000001000);
+      assert.notDeepStrictEqual(
+        renewed.sessionAttestation?.publicKey,
+        first.sessionAttestation?.publicKey
+      );
+      const result = bridge.verifyAttestationReport(
+        renewed.attestationReport,
+        expectedFor(renewed, later.timestamp)
+      );
+      assert.strictEqual(result.valid, true);
+
+      // The old window's report does not verify with the new key
+      const mixed = bridge.verifyAttestationReport(
+        first.attestationReport,
+        expectedFor(renewed, later.timestamp)
+      );
+      assert.deepStrictEqual(
+        mixed.checks.filter((check) => !check.passed).map((check) => check.name),
+        ["report_data"]
+      );
+    });
+
+    it("should fail verification for a binding the session key did not sign", async () => {
+      const { bridge } = countingBridge();
+      const response = await bridge.execute(request);
+      const expected = expectedFor(response, request.timestamp);
+
+      const replayed = bridge.verifyAttestationReport(response.attestationReport, {
+        ...expected,
+        timestamp: request.timestamp + 1,
+      });
+      assert.deepStrictEqual(
+        replayed.checks.filter((check) => !check.passed).map((check) => check.name),
+        ["session_signature"]
+      );
+
+      const unbound = bridge.verifyAttestationReport(response.attestationReport, {
+        ...expected,
+        sessionAttestation: undefined,
+      });
+      assert.deepStrictEqual(
+        unbound.checks.filter((check) => !check.passed).map((check) => check.name),
+        ["report_data"]
+      );
+
+      const perRequestResponse = await bridge.execute({
+        ...request,
+        attestationMode: "per_request",
+      });
+      assert.strictEqual(perRequestResponse.sessionAttestation, undefined);
+    });
+
+    it("should verify session-cached evidence from Axiom", async () => {
+      const config = {
+        securityTier: "attested",
+        enclave: "auto",
+        policyVersion: "v1",
+        attestationMode: "session_cached",
+      } as const;
+      const result = await new Axiom(config).reason({
+        context: "Alice paid Bob $100 on Monday.",
+        task: "summarize payment",
+      });
+      const evidence = result.attestationEvidence!;
+      const session = evidence.sessionAttestation!;
+      assert.ok(evidence.timestamp >= session.windowStart);
+      assert.ok(evidence.timestamp < session.expiresAt);
+
+      const verifier = new AttestationVerifier();
+      const verdict = await verifier.verify(evidence, result.transformedContext, {
+        expectedMeasurement: result.verificationHint?.expectedMeasurement,
+        mode: "permissive",
+      });
+      assert.strictEqual(verdict.claims.sessionBinding, true, verdict.errors.join("; "));
+
+      const forged = verifier.verifyOutputBinding(
+        { ...evidence, sessionAttestation: { ...session, signature: new Uint8Array(64) } },
+        result.transformedContext
+      );
+      assert.match(forged.error ?? "", /does not cover the expected binding/);
+      const perRequest = verifier.verifyOutputBinding(
+        { ...evidence, sessionAttestation: undefined },
+        result.transformedContext
+      );
+      assert.strictEqual(perRequest.valid, false);
+
+      assert.throws(
+        () => new Axiom({ ...config, attestationMode: "cached" as "per_request" }),
+        /Invalid attestationMode: cached/
+      );
+    });
+
+    it("should reject an unknown mode, packaged evidence, and a bad window", () => {
+      assert.throws(
+        () => validateRequest({ ...request, attestationMode: "cached" as "per_request" }),
+        /Invalid attestationMode: cached/
+      );
+      assert.throws(
+        () => validateRequest({ ...request, evidenceFormat: "cose" }),
+        /evidenceFormat cose cannot be combined with attestationMode session_cached/
+      );
+      for (const attestationWindowMs of [0, -1, 1.5]) {
+        assert.throws(
+          () => new EnclaveBridge(false, {}, { attestationWindowMs }),
+          /Invalid attestationWindowMs/
+        );
+      }
+    });
+
+    it("should fail the session signature outside the session window", async () => {
+      const { bridge, clock } = countingBridge();
+      clock.now = request.timestamp + 400;
+      const response = await bridge.execute({ ...request, timestamp: clock.now });
+      const inside = bridge.verifyAttestationReport(
+        response.attestationReport,
+        expectedFor(response, request.timestamp + 400)
+      );
+      assert.strictEqual(inside.valid, true);
+
+      // Before the window and at its (exclusive) end
+      const session = response.sessionAttestation!;
+      for (const timestamp of [session.windowStart - 1, session.expiresAt]) {
+        const result = bridge.verifyAttestationReport(
+          response.attestationReport,
+          expectedFor(response, timestamp)
+        );
+        const failed = result.checks.filter((check) => !check.passed);
+        assert.deepStrictEqual(failed.map((check) => check.name), ["session_signature"]);
+        assert.match(failed[0].reason!, /outside the session window/);
+      }
+
+      // A stretched window no longer matches the report
+      const stretched = bridge.verifyAttestationReport(response.attestationReport, {
+        ...expectedFor(response, request.timestamp + 400),
+        sessionAttestation: { ...session, expiresAt: session.expiresAt + 60000 },
+      });
+      assert.deepStrictEqual(
+        stretched.checks.filter((check) => !check.passed).map((check) => check.name),
+        ["report_data"]
+      );
+    });
+
+    it("should fail the session signature once the window has ended", async () => {
+      const { bridge } = countingBridge();
+      const response = await bridge.execute(request);
+      const expired = bridge.verifyAttestationReport(response.attestationReport, {
+        ...expectedFor(response, request.timestamp),
+        verifiedAt: response.sessionAttestation!.expiresAt,
+      });
+      const failed = expired.checks.filter((check) => !check.passed);
+      assert.deepStrictEqual(failed.map((check) => check.name), ["session_signature"]);
+      assert.match(failed[0].reason!, /Session attestation expired/);
+
+      // Without verifiedAt the window is checked against now
+      const late = bridge.verifyAttestationReport(response.attestationReport, {
+        ...expectedFor(response, request.timestamp),
+        verifiedAt: undefined,
+      });
+      assert.strictEqual(late.valid, false);
+    });
+
+    it("should evict expired sessions first, then the least recently used", async () => {
+      const cache = new SessionAttestationCache(1000, 2);
+      let now = 0;
+      let generated = 0;
+      const generate = () => {
+        generated += 1;
+        return { report: new Uint8Array(0) };
+      };
+      const session = (byte: number) => new Uint8Array(16).fill(byte);
+
+      await cache.get(session(1), now, generate);
+      await cache.get(session(2), now, generate);
+      await cache.get(session(1), now, generate);
+      await cache.get(session(3), now, generate);
+      assert.strictEqual(cache.size, 2);
+      await cache.get(session(1), now, generate);
+      assert.strictEqual(generated, 3);
+      await cache.get(session(2), now, generate);
+      assert.strictEqual(generated, 4);
+
+      now = 1000;
+      await cache.get(session(3), now, generate);
+      assert.strictEqual(cache.size, 1);
+
+      const failing = cache.get(session(4), now, () => {
+        throw new Error("report generation failed");
+      });
+      await assert.rejects(failing, /report generation failed/);
+      await cache.get(session(4), now, generate);
+      assert.strictEqual(generated, 6);
+
+      // Only windows that have ended are evicted, whichever window the
+      // incoming request is in
+      const wide = new SessionAttestationCache(1000, 10);
+      await wide.get(session(1), 1500, generate);
+      await wide.get(session(2), 500, generate);
+      assert.strictEqual(wide.size, 2);
+      await wide.get(session(3), 1200, generate);
+      assert.strictEqual(wide.size, 2);
+      await wide.get(session(1), 1200, generate);
+      assert.strictEqual(generated, 9);
+    });
+  });
+
+  describe("Verification bundles", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100 on Monday.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(6),
+      configHash: "11".repeat(32),
+      nonce: testNonce(40),
+      timestamp: 1700000000000,
+      bindingVersion: 3 as const,
+    };
+    const hex = (bytes: Uint8Array) => Buffer.from(bytes).toString("hex");
+
+    // Verify a bundle in a fresh Node process that sees only the bundle
+    // JSON, as an auditor's machine would
+    function verifyElsewhere(
+      bundle: string,
+      outputHash: string,
+      simulatorKey: string
+    ): ReportVerificationResult {
+      const script = `
+        import { verifyVerificationBundle } from ${JSON.stringify(
+          new URL("../src/attestation/bundle.ts", import.meta.url).href
+        )};
+        let input = "";
+        for await (const chunk of process.stdin) input += chunk;
+        const { bundle, outputHash, simulatorKey } = JSON.parse(input);
+        const options = { allowSimulator: true, simulatorKey };
+        const result = verifyVerificationBundle(bundle, outputHash, options);
+        process.stdout.write(JSON.stringify(result));
+      `;
+      const execArgv = process.execArgv.filter((arg) => !arg.startsWith("--test"));
+      const output = execFileSync(
+        process.execPath,
+        [...execArgv, "--input-type=module", "--eval", script],
+        { input: JSON.stringify({ bundle, outputHash, simulatorKey }), encoding: "utf8" }
+      );
+      return JSON.parse(output) as ReportVerificationResult;
+    }
+
+    it("should verify an exported bundle in a separate process", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute(request);
+      const json = bridge.exportVerificationBundle(response, request);
+
+      const bundle = verificationBundleFromJson(json);
+      assert.strictEqual(bundle.bundleVersion, VERIFICATION_BUNDLE_VERSION);
+      assert.strictEqual(bundle.sdkVersion, SDK_VERSION);
+      assert.strictEqual(bundle.measurement, response.measurement);
+      assert.strictEqual(bundle.evidence.bindingFormula, "v3");
+      assert.strictEqual(bundle.binding.sessionId, hex(request.sessionId));
+      assert.strictEqual(bundle.binding.outputHash, hex(response.outputHash));
+      assert.strictEqual(bundle.binding.outputEncoding, response.outputEncoding);
+      assert.strictEqual(bundle.simulatorKey, bridge.getSimulatorVerificationKey().publicKey);
+      // Hashes only: neither the input nor the output is bundled
+      assert.ok(!json.includes("Alice"));
+      assert.ok(!json.includes(Buffer.from(response.transformedContext).toString("base64")));
+
+      const simulatorKey = bridge.getSimulatorVerificationKey().publicKey;
+      const result = verifyElsewhere(json, hex(response.outputHash).toUpperCase(), simulatorKey);
+      assert.strictEqual(result.valid, true, JSON.stringify(result.checks));
+      assert.strictEqual(result.simulator, true);
+
+      const wrong = verifyElsewhere(json, "ab".repeat(32), simulatorKey);
+      assert.strictEqual(wrong.valid, false);
+      assert.deepStrictEqual(
+        wrong.checks.filter((check) => !check.passed).map((check) => check.name),
+        ["report_data"]
+      );
+    });
+
+    it("should fail tampered, malformed, and untrusted bundles", async () => {
+      const bridge = new EnclaveBridge(false);
+      const response = await bridge.execute(request);
+      const json = bridge.exportVerificationBundle(response, request);
+      const outputHash = hex(response.outputHash);
+      const failed = (result: ReportVerificationResult) =>
+        result.checks.filter((check) => !check.passed).map((check) => check.name);
+
+      const tampered = JSON.parse(json) as VerificationBundle;
+      tampered.binding.timestamp += 1;
+      const result = bridge.verifyBundle(JSON.stringify(tampered), outputHash, {
+        allowSimulator: true,
+      });
+      assert.deepStrictEqual(failed(result), ["report_data"]);
+
+      const simulatorKey = bridge.getSimulatorVerificationKey().publicKey;
+      const pinned = verifyVerificationBundle(json, outputHash, {
+        allowSimulator: true,
+        simulatorKey,
+        measurement: "ab".repeat(48),
+      });
+      assert.deepStrictEqual(failed(pinned), ["measurement"]);
+
+      // Simulator evidence is refused unless the auditor opts in
+      assert.ok(failed(verifyVerificationBundle(json, outputHash)).includes("platform"));
+
+      const foreignKey = generateKeyPairSync("ed25519")
+        .publicKey.export({ format: "pem", type: "spki" })
+        .toString();
+      const unsigned = verifyVerificationBundle(json, outputHash, {
+        allowSimulator: true,
+        simulatorKey: foreignKey,
+      });
+      assert.deepStrictEqual(failed(unsigned), ["signature"]);
+
+      // The key a bundle carries is never trusted, even though it signed the report
+      const embedded = verifyVerificationBundle(json, outputHash, { allowSimulator: true });
+      assert.deepStrictEqual(failed(embedded), ["signature"]);
+      assert.strictEqual(
+        embedded.checks.find((check) => check.name === "signature")?.reason,
+        "No simulator verification key given"
+      );
+
+      for (const [bundle, reason] of [
+        ["not json", /not valid JSON/],
+        [JSON.stringify({ ...JSON.parse(json), bundleVersion: 2 }), /version 2/],
+        [JSON.stringify({ ...JSON.parse(json), binding: {} }), /binding is incomplete/],
+      ] as const) {
+        const malformed = verifyVerificationBundle(bundle, outputHash, { allowSimulator: true });
+        assert.strictEqual(malformed.valid, false);
+        assert.strictEqual(malformed.checks[0].name, "structure");
+        assert.match(malformed.checks[0].reason ?? "", reason);
+      }
+    });
+
+    it("should bundle a session-cached attestation", async () => {
+      const bridge = new EnclaveBridge(false);
+      const sessionRequest = {
+        ...request,
+        timestamp: Date.now(),
+        attestationMode: "session_cached" as const,
+      };
+      const response = await bridge.execute(sessionRequest);
+      const json = bridge.exportVerificationBundle(response, sessionRequest);
+      const bundle = verificationBundleFromJson(json);
+      assert.strictEqual(
+        bundle.sessionAttestation?.publicKey,
+        hex(response.sessionAttestation!.publicKey)
+      );
+
+      const simulatorKey = bridge.getSimulatorVerificationKey().publicKey;
+      const result = verifyElsewhere(json, hex(response.outputHash), simulatorKey);
+      assert.strictEqual(result.valid, true, JSON.stringify(result.checks));
+      assert.ok(result.checks.some((check) => check.name === "session_signature"));
+    });
+
+    it("should verify bundles with the native runner's verify_bundle", () => {
+      const bridge = new EnclaveBridge(true);
+      withNativeModule(bridge, {
+        transform: () => "{}",
+      });
+      assert.throws(
+        () => bridge.verifyBundle("{}", "00".repeat(32)),
+        /does not export verify_bundle/
+      );
+
+      let sent: unknown[] = [];
+      withNativeModule(bridge, {
+        transform: () => "{}",
+        verify_bundle: (...args: unknown[]) => {
+          sent = args;
+          return JSON.stringify({
+            valid: true,
+            simulator: true,
+            checks: [{ name: "report_data", passed: true }],
+          });
+        },
+      });
+      const result = bridge.verifyBundle("{}", "00".repeat(32), { allowSimulator: true });
+      assert.strictEqual(result.valid, true);
+      assert.deepStrictEqual(sent, [
+        "{}",
+        "00".repeat(32),
+        JSON.stringify({
+          trusted_ark: null,
+          measurement: null,
+          allow_simulator: true,
+          simulator_key: null,
+        }),
+      ]);
+    });
+  });
+
+  describe("Nonce validation", () => {
+    const request = {
+      rawContext: [new TextEncoder().encode("Alice paid Bob $100.")],
+      policy: resolveMaskingPolicy({}),
+      sessionId: new Uint8Array(16).fill(1),
+      configHash: "00".repeat(32),
+      nonce: testNonce(2),
+      timestamp: 1700000000000,
+    };
+    const rejection = async (nonce: Uint8Array) => {
+      try {
+        await new EnclaveBridge(false).execute({ ...request, nonce });
+      } catch (error) {
+        assert.ok(error instanceof InvalidInputError);
+        return error.code;
+      }
+      return undefined;
+    };
+
+    it("should reject empty, short, long, and constant nonces", async () => {
+      assert.strictEqual(await rejection(new Uint8Array(0)), "NONCE_EMPTY");
+      assert.strictEqual(await rejection(testNonce(1).subarray(0, 15)), "NONCE_LENGTH");
+      assert.strictEqual(await rejection(new Uint8Array(65).map((_, i) => i)), "NONCE_LENGTH");
+      assert.strictEqual(await rejection(new Uint8Array(32)), "NONCE_CONSTANT");
+      assert.strictEqual(await rejection(new Uint8Array(32).fill(7)), "NONCE_CONSTANT");
+    });
+
+    it("should accept nonces of 16 to 64 varied bytes", async () => {
+      assert.strictEqual(await rejection(testNonce(1).subarray(0, 16)), undefined);
+      assert.strictEqual(await rejection(testNonce(1)), undefined);
+      assert.strictEqual(await rejection(new Uint8Array(64).map((_, i) => i)), undefined);
+    });
+
+    it("should decode hex and base64url nonces and reject anything else", () => {
+      const bytes = testNonce(5);
+      assert.deepStrictEqual(decodeNonce(Buffer.from(bytes).toString("hex")), bytes);
+      assert.deepStrictEqual(decodeNonce(Buffer.from(bytes).toString("base64url")), bytes);
+
+      const code = (nonce: string) => {
+        try {
+          decodeNonce(nonce);
+        } catch (error) {
+          return (error as InvalidInputError).code;
+        }
+        return undefined;
+      };
+      assert.strictEqual(code(""), "NONCE_EMPTY");
+      assert.strictEqual(code("not a nonce!"), "NONCE_ENCODING");
+      assert.strictEqual(code(`${"ab".repeat(16)}=`), "NONCE_ENCODING");
+      assert.strictEqual(code("0102030405"), "NONCE_LENGTH");
+      assert.strictEqual(code("00".repeat(32)), "NONCE_CONSTANT");
+    });
+
+    it("should generate nonces from the runner", () => {
+      const bridge = new EnclaveBridge(false);
+      const first = bridge.generateNonce();
+      assert.match(first, /^[0-9a-f]{64}$/);
+      assert.notStrictEqual(bridge.generateNonce(), first);
+      assert.strictEqual(decodeNonce(first).length, 32);
+
+      const native = new EnclaveBridge(true);
+      let next = "ab".repeat(16) + "cd".repeat(16);
+      withNativeModule(native, {
+        generate_nonce: () => next,
+      });
+      assert.strictEqual(native.generateNonce(), next);
+      next = "00".repeat(32);
+      assert.throws(() => native.generateNonce(), /Native nonce rejected: Invalid nonce/);
+      next = "ab".repeat(16);
+      assert.throws(() => native.generateNonce(), SecurityInvariantError);
+
+      withNativeModule(native, {});
+      assert.throws(() => native.generateNonce(), /does not export generate_nonce/);
+    });
+  });
+
+  describe("Hash chaining", () => {
+    const config = {
+      securityTier: "attested" as const,
+      enclave: "auto" as const,
+      policyVersion: "v1" as const,
+    };
+
+    async function buildChain() {
+      const axiom = new Axiom(config);
+      const steps = ["Alice paid Bob $100.", "Bob paid Carol $50.", "Carol paid Dan $20."];
+      const links = [];
+      let previousOutputHash: string | undefined;
+      for (const context of steps) {
+        const result = await axiom.reason({
+          context,
+          task: "track payments",
+          ...(previousOutputHash !== undefined ? { previousOutputHash } : {}),
+        });
+        const evidence = result.attestationEvidence!;
+        links.push({ evidence, transformedContext: result.transformedContext });
+        previousOutputHash = evidence.outputHash;
+      }
+      return links;
+    }
+
+    it("should verify a three-link chain", async () => {
+      const links = await buildChain();
+      const verifier = new AttestationVerifier();
+
+      assert.strictEqual(links[0].evidence.chainedFrom, undefined);
+      assert.strictEqual(links[1].evidence.chainedFrom, links[0].evidence.outputHash);
+      assert.strictEqual(links[2].evidence.chainedFrom, links[1].evidence.outputHash);
+      assert.strictEqual(verifier.verifyChain(links), true);
+      for (const { evidence, transformedContext } of links) {
+        const verdict = await verifier.verify(evidence, transformedContext, {
+          expectedMeasurement: evidence.measurement,
+          mode: "permissive",
+        });
+        assert.strictEqual(verdict.valid, true);
+      }
+    });
+
+    it("should detect a tampered middle link", async () => {
+      const verifier = new AttestationVerifier();
+      const links = await buildChain();
+
+      const tamperedContext = links.slice();
+      tamperedContext[1] = {
+        ...links[1],
+        transformedContext: { ...links[1].transformedContext, task: "tampered" },
+      };
+      assert.strictEqual(verifier.verifyChain(tamperedContext), false);
+
+      const relabeled = links.slice();
+      relabeled[1] = {
+        ...links[1],
+        evidence: { ...links[1].evidence, outputHash: links[0].evidence.outputHash },
+      };
+      assert.strictEqual(verifier.verifyChain(relabeled), false);
+
+      const unlinked = links.slice();
+      unlinked[1] = {
+        ...links[1],
+        evidence: { ...links[1].evidence, chainedFrom: links[2].evidence.outputHash },
+      };
+      assert.strictEqual(verifier.verifyChain(unlinked), false);
+
+      assert.strictEqual(verifier.verifyChain([links[0], links[2]]), false);
+      assert.strictEqual(verifier.verifyChain([]), false);
+    });
+
+    it("should reject a malformed previous output hash", async () => {
+      const axiom = new Axiom(config);
+      for (const previousOutputHash of ["zz".repeat(32), "ab".repeat(20), ""]) {
+        await assert.rejects(
+          axiom.reason({ context: "Alice paid Bob.", task: "track", previousOutputHash }),
+          (error: Error) =>
+            error instanceof ConfigurationError && /Invalid previousOutputHash/.test(error.message)
+        );
+      }
+
+      await assert.rejects(
+        new Axiom({ securityTier: "standard", enclave: "none", policyVersion: "v1" }).reason({
+          context: "Alice paid Bob.",
+          task: "track",
+          previousOutputHash: "ab".repeat(32),
+        }),
+        /requires securityTier "attested"/
+      );
+
+      await assert.rejects(
+        new EnclaveBridge(false).execute({
+          rawContext: [new TextEncoder().encode("Alice paid Bob.")],
+          policy: resolveMaskingPolicy({}),
+          sessionId: new Uint8Array(16),
+          configHash: "00".repeat(32),
+          nonce: testNonce(0),
+          timestamp: 1700000000000,
+          previousOutputHash: "ab".repeat(31),
+        }),
+        ConfigurationError
+      );
+    });
+  });
+});
+
+describe("VCEK certificates", () => {
+  const VCEK_PEM = `-----BEGIN CERTIFICATE-----
+MIIB5TCCAWqgAwIBAgIUW/h8dOoT0akNH3OgoIFQ4nOB/D8wCgYIKoZIzj0EAwIw
+KDERMA8GA1UEAwwIU0VWLVZDRUsxEzARBgNVBAoMCkF4aW9tIFRlc3QwIBcNMjYx
+MDE1MTExNTM3WhgPMjEyNjA5MjExMTE1MzdaMCgxETAPBgNVBAMMCFNFVi1WQ0VL
+MRMwEQYDVQQKDApBeGlvbSBUZXN0MHYwEAYHKoZIzj0CAQYFK4EEACIDYgAEkKpX
+UJdHb+hzp52z+0VmenuyZev1zoGOOEJtZqUHr0IWzxBHQAJ0FaXMNHkVOcAC3VCw
+c95kxeDtlrvCNnSfTWep9/7YeY9FMPdsOg8IwUMLKUlPsZuGr6s6O051jo17o1Mw
+UTAdBgNVHQ4EFgQUaVq++towe4dXNBjpu0r0eIHfd5gwHwYDVR0jBBgwFoAUaVq+
++towe4dXNBjpu0r0eIHfd5gwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNp
+ADBmAjEA5uegVrpksftL9AaAXmiw5+TdMA5ZTjjbaaR9+WS2dmyNvx5xl9JJCKFt
+tB3Yj1AzAjEAm7x0giOpw4UAe2Ge/CdS0mzO58y7qNQHmPfn4Hs/9bs1mvViz9HI
+YuNJeGJ2VEGo
+-----END CERTIFICATE-----
+`;
+  const VCEK_DER = Buffer.from(
+    VCEK_PEM.replace(/-----[A-Z ]+-----/g, "").replace(/\s+/g, ""),
+    "base64"
+  );
+
+  // Bound to request for stubBridge's response (see stubBinding)
+  function hardwareReport(snp: number = 8): Uint8Array {
+    const report = emptyReport();
+    report.reportedTcb = { bootLoader: 3, tee: 0, snp, microcode: 115 };
+    report.chipId.fill(0xab);
+    report.reportData.set(bindingReportData(stubBinding(request, new Uint8Array(32))));
+    return reportToBytes(report);
+  }
+
+  // Serves each request with the next status in the list (the last one
+  // repeats) and counts requests.
+  async function startKds(statuses: number[]) {
+    const paths: string[] = [];
+    const server = createServer((req, res) => {
+      const status = statuses[Math.min(paths.length, statuses.length - 1)];
+      paths.push(req.url ?? "");
+      res.writeHead(status, { "content-type": "application/octet-stream" });
+      res.end(status === 200 ? VCEK_DER : "");
+    });
+    await new Promise<void>((resolve) => server.listen(0, "127.0.0.1", () => resolve()));
+    const { port } = server.address() as AddressInfo;
+    const url = `http://127.0.0.1:${port}`;
+    return { url, paths, close: () => new Promise((resolve) => server.close(resolve)) };
+  }
+
+  async function closedPortUrl(): Promise<string> {
+    const kds = await startKds([200]);
+    await kds.close();
+    return kds.url;
+  }
+
+  async function withCacheDir(run: (dir: string) => Promise<void>) {
+    const dir = await mkdtemp(join(tmpdir(), "axiom-vcek-"));
+    try {
+      await run(dir);
+    } finally {
+      await rm(dir, { recursive: true, force: true });
+    }
+  }
+
+  function stubBridge(
+    report: Uint8Array,
+    vcekOptions: VcekOptions,
+    certTable?: Uint8Array,
+    vcekPem?: string
+  ): EnclaveBridge {
+    const bridge = new EnclaveBridge(true, vcekOptions);
+    withNativeModule(bridge, {
+      check_availability: () => true,
+      transform: () =>
+        JSON.stringify({
+          transformed_context: { entities: [], relations: [] },
+          output_hash: "00".repeat(32),
+          attestation_report: Array.from(report),
+          ...(certTable ? { cert_table: Array.from(certTable) } : {}),
+          ...(vcekPem ? { vcek_pem: vcekPem } : {}),
+          redaction_stats: { entity_count: 0, relation_count: 0, identifiers_replaced: 0 },
+          measurement: "native_stub",
+        }),
+    });
+    return bridge;
+  }
+
+  const request = {
+    rawContext: [new TextEncoder().encode("Alice paid Bob.")],
+    policy: resolveMaskingPolicy({}),
+    sessionId: new Uint8Array(16).fill(1),
+    configHash: "00".repeat(32),
+    nonce: testNonce(2),
+    timestamp: 1700000000000,
+  };
+
+  it("should build the KDS URL from chip_id and reported TCB", () => {
+    assert.strictEqual(
+      vcekUrl(hardwareReport(), { kdsUrl: "https://kds.example/", product: "Genoa" }),
+      `https://kds.example/vcek/v1/Genoa/${"ab".repeat(64)}?blSPL=03&teeSPL=00&snpSPL=08&ucodeSPL=115`
+    );
+  });
+
+  it("should build the CRL URL from the same KDS base", () => {
+    for (const kdsUrl of ["https://kds.example", "https://kds.example/", "https://kds.example//"]) {
+      assert.strictEqual(
+        crlUrl({ kdsUrl, product: "Genoa" }),
+        "https://kds.example/vcek/v1/Genoa/crl"
+      );
+    }
+  });
+
+  it("should fetch on a cache miss and serve the cache afterwards", async () => {
+    await withCacheDir(async (cacheDir) => {
+      const kds = await startKds([200]);
+      try {
+        const options = { kdsUrl: kds.url, cacheDir };
+        assert.strictEqual(await fetchVcek(hardwareReport(), options), VCEK_PEM);
+        assert.strictEqual(await fetchVcek(hardwareReport(), options), VCEK_PEM);
+        assert.strictEqual(kds.paths.length, 1);
+        assert.deepStrictEqual(await readdir(cacheDir), [
+          `Milan-${"ab".repeat(64)}-0300000000000873.pem`,
+        ]);
+      } finally {
+        await kds.close();
+      }
+    });
+  });
+
+  it("should key the cache by TCB", async () => {
+    await withCacheDir(async (cacheDir) => {
+      const kds = await startKds([200]);
+      try {
+        const options = { kdsUrl: kds.url, cacheDir };
+        await fetchVcek(hardwareReport(), options);
+        await fetchVcek(hardwareReport(9), options);
+        assert.strictEqual(kds.paths.length, 2);
+        assert.match(kds.paths[1], /snpSPL=09/);
+      } finally {
+        await kds.close();
+      }
+    });
+  });
+
+  it("should serve a cache hit without the network", async () => {
+    await withCacheDir(async (cacheDir) => {
+      await writeFile(
+        join(cacheDir, `Milan-${"ab".repeat(64)}-0300000000000873.pem`),
+        VCEK_PEM
+      );
+      const pem = await fetchVcek(hardwareReport(), {
+        kdsUrl: await closedPortUrl(),
+        cacheDir,
+        retries: 0,
+      });
+      assert.strictEqual(pem, VCEK_PEM);
+    });
+  });
+
+  it("should refetch over a corrupted cache entry", async () => {
+    await withCacheDir(async (cacheDir) => {
+      await writeFile(
+        join(cacheDir, `Milan-${"ab".repeat(64)}-0300000000000873.pem`),
+        "-----BEGIN CERTIFICATE-----\ntruncated"
+      );
+      const kds = await startKds([200]);
+      try {
+        assert.strictEqual(
+          await fetchVcek(hardwareReport(), { kdsUrl: kds.url, cacheDir }),
+          VCEK_PEM
+        );
+        assert.strictEqual(kds.paths.length, 1);
+      } finally {
+        await kds.close();
+      }
+    });
+  });
+
+  it("should retry server errors but not a 404", async () => {
+    await withCacheDir(async (cacheDir) => {
+      const flaky = await startKds([503, 429, 200]);
+      const missing = await startKds([404]);
+      try {
+        const options = { cacheDir, retryDelayMs: 1 };
+        assert.strictEqual(
+          await fetchVcek(hardwareReport(), { ...options, kdsUrl: flaky.url }),
+          VCEK_PEM
+        );
+        assert.strictEqual(flaky.paths.length, 3);
+
+        await rm(cacheDir, { recursive: true, force: true });
+        await assert.rejects(
+          fetchVcek(hardwareReport(), { ...options, kdsUrl: missing.url }),
+          /HTTP 404/
+        );
+        assert.strictEqual(missing.paths.length, 1);
+      } finally {
+        await flaky.close();
+        await missing.close();
+      }
+    });
+  });
+
+  it("should attach the VCEK to a hardware report response", async () => {
+    await withCacheDir(async (cacheDir) => {
+      const kds = await startKds([200]);
+      try {
+        const bridge = stubBridge(hardwareReport(), { kdsUrl: kds.url, cacheDir });
+        const response = await bridge.execute({ ...request, requireVcek: true });
+        assert.strictEqual(response.vcekPem, VCEK_PEM);
+        assert.strictEqual(response.vcekUnavailable, undefined);
+        assert.strictEqual(response.warnings, undefined);
+      } finally {
+        await kds.close();
+      }
+    });
+  });
+
+  it("should return the report with a warning when offline", async () => {
+    await withCacheDir(async (cacheDir) => {
+      const options = { kdsUrl: await closedPortUrl(), cacheDir, retries: 1, retryDelayMs: 1 };
+      const response = await stubBridge(hardwareReport(), options).execute(request);
+      assert.strictEqual(response.attestationReport.length, 1184);
+      assert.strictEqual(response.vcekPem, undefined);
+      assert.strictEqual(response.vcekUnavailable, true);
+      assert.strictEqual(response.warnings?.length, 1);
+      assert.match(response.warnings![0], /^VCEK certificate unavailable: /);
+
+      await assert.rejects(
+        stubBridge(hardwareReport(), options).execute({ ...request, requireVcek: true }),
+        (error: Error) =>
+          error instanceof SecurityInvariantError &&
+          /VCEK certificate required but unavailable/.test(error.message)
+      );
+    });
+  });
+
+  it("should skip simulator reports unless the VCEK is required", async () => {
+    const bridge = new EnclaveBridge(false, { kdsUrl: await closedPortUrl(), retries: 0 });
+    const response = await bridge.execute(request);
+    assert.strictEqual(response.vcekPem, undefined);
+    assert.strictEqual(response.vcekUnavailable, undefined);
+    await assert.rejects(
+      bridge.execute({ ...request, requireVcek: true }),
+      /not a SEV-SNP hardware report/
+    );
+  });
+
+  it("should keep a VCEK supplied by the runner", async () => {
+    const vcekOptions = { kdsUrl: await closedPortUrl(), retries: 0 };
+    const bridge = stubBridge(hardwareReport(), vcekOptions, undefined, VCEK_PEM);
+    const response = await bridge.execute({ ...request, requireVcek: true });
+    assert.strictEqual(response.vcekPem, VCEK_PEM);
+  });
+
+  describe("Extended report certificate table", () => {
+    const ask = new TextEncoder().encode("ask certificate");
+    const ark = new TextEncoder().encode("ark certificate");
+
+    function entry(guid: string, offset: number, length: number): Uint8Array {
+      const bytes = new Uint8Array(24);
+      bytes.set(Buffer.from(guid.replace(/-/g, ""), "hex"));
+      new DataView(bytes.buffer).setUint32(16, offset, true);
+      new DataView(bytes.buffer).setUint32(20, length, true);
+      return bytes;
+    }
+
+    it("should round-trip a VCEK, ASK, ARK table", () => {
+      const chain = parseCertTable(createCertTable({ vcek: VCEK_DER, ask, ark }));
+      assert.deepStrictEqual(chain, { vcek: new Uint8Array(VCEK_DER), ask, ark });
+    });
+
+    it("should skip unknown GUIDs, repeats, and out-of-bounds entries", () => {
+      const vlek = "a8074bc2-a25a-483e-aae6-39c045a0b8a1";
+      const dataAt = 6 * 24;
+      const table = Buffer.concat([
+        entry(vlek, dataAt, 3),
+        entry(CERT_TABLE_GUIDS.ask, dataAt, 3),
+        entry(CERT_TABLE_GUIDS.ask, dataAt + 3, 3),
+        entry(CERT_TABLE_GUIDS.ark, dataAt + 3, 1000),
+        entry(CERT_TABLE_GUIDS.vcek, dataAt + 3, 0),
+        new Uint8Array(24),
+        Buffer.from("askark"),
+      ]);
+      assert.deepStrictEqual(parseCertTable(table), {
+        ask: new Uint8Array(Buffer.from("ask")),
+      });
+    });
+
+    it("should keep complete entries of a truncated table", () => {
+      const table = createCertTable({ ask, ark });
+      // Cut inside the terminator: both entries point past the end
+      assert.deepStrictEqual(parseCertTable(table.subarray(0, 60)), {});
+      // Cut inside the ARK data
+      assert.deepStrictEqual(parseCertTable(table.subarray(0, 72 + ask.length)), { ask });
+      assert.deepStrictEqual(parseCertTable(new Uint8Array(10)), {});
+    });
+
+    it("should fabricate a placeholder chain in the simulator", async () => {
+      const response = await new EnclaveBridge(false).execute(request);
+      assert.deepStrictEqual(Object.keys(response.certificates ?? {}), ["vcek", "ask", "ark"]);
+      assert.match(Buffer.from(response.certificates!.vcek!).toString(), /SIMULATOR VCEK/);
+      assert.strictEqual(response.vcekPem, undefined);
+    });
+
+    it("should use the host-supplied VCEK instead of KDS", async () => {
+      const table = createCertTable({ vcek: VCEK_DER, ask, ark });
+      const bridge = stubBridge(hardwareReport(), { kdsUrl: await closedPortUrl(), retries: 0 }, table);
+      const response = await bridge.execute({ ...request, requireVcek: true });
+      assert.strictEqual(response.vcekPem, VCEK_PEM);
+      assert.deepStrictEqual(response.certificates?.ask, ask);
+      assert.deepStrictEqual(response.certificates?.ark, ark);
+    });
+
+    it("should omit certificates when the runner fell back to a plain report", async () => {
+      const options = { kdsUrl: await closedPortUrl(), retries: 0 };
+      const plain = await stubBridge(hardwareReport(), options).execute(request);
+      const empty = await stubBridge(hardwareReport(), options, new Uint8Array(24)).execute(request);
+      assert.strictEqual(plain.certificates, undefined);
+      assert.strictEqual(empty.certificates, undefined);
+      assert.strictEqual(empty.vcekUnavailable, true);
+    });
+  });
+});
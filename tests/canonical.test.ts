@@ -1,7 +1,46 @@
 import { describe, it } from "node:test";
 import assert from "node:assert";
-import { canonicalize, hash, verifyEquivalence, createDigest } from "../src/core/canonical.ts";
-import type { TransformedContext } from "../src/core/config.ts";
+import { createHash } from "crypto";
+import { readFileSync } from "fs";
+import {
+  canonicalize,
+  canonicalJson,
+  hash,
+  isHashAlgorithmAvailable,
+  verifyEquivalence,
+  createDigest,
+  getCanonicalContext,
+  HASH_DOMAINS,
+  canonicalByteLength,
+  domainSeparatedHash,
+  writeCanonical,
+  writeCanonicalJson,
+} from "../src/core/canonical.ts";
+import type { AxiomConfig, TransformedContext } from "../src/core/config.ts";
+import { Axiom } from "../src/core/axiom.ts";
+import {
+  HASH_ENCODINGS,
+  decodeDigest,
+  encodeDigest,
+  hashOutput,
+  toCanonicalCbor,
+} from "../src/core/serialization.ts";
+import { generateTestVectors } from "../src/core/vectors.ts";
+import { ConfigurationError, TransformationError } from "../src/core/errors.ts";
+
+interface CanonicalVector {
+  name: string;
+  kind: "value" | "transformed_context";
+  input: string;
+  canonical: string;
+  sha256: string;
+}
+
+const VECTORS = (
+  JSON.parse(
+    readFileSync(new URL("./vectors/canonicalization.json", import.meta.url), "utf8")
+  ) as { vectors: CanonicalVector[] }
+).vectors;
 
 describe("Canonical Serialization", () => {
   describe("Deterministic hashing", () => {
@@ -127,6 +166,26 @@ describe("Canonical Serialization", () => {
       assert.strictEqual(parsed.relations[1].from, "ENTITY_0001");
       assert.strictEqual(parsed.relations[1].to, "ENTITY_0003");
     });
+
+    it("should keep the reading-order direction of related edges", async () => {
+      const axiom = new Axiom({
+        securityTier: "standard",
+        enclave: "none",
+        policyVersion: "v1",
+        idSalt: new Uint8Array(32).fill(5),
+      });
+      const canonicalOf = async (context: string) => {
+        const result = await axiom.reason({ context, task: "test" });
+        return { result, parsed: JSON.parse(canonicalize(result.transformedContext)) };
+      };
+
+      const forward = await canonicalOf("Alice sent the report to Bob.");
+      const backward = await canonicalOf("Bob sent the report to Alice.");
+      const [alice, bob] = forward.result.transformedContext.entities.map((e) => e.syntheticId);
+      assert.deepStrictEqual(forward.parsed.relations, [{ from: alice, to: bob, type: "related" }]);
+      assert.deepStrictEqual(backward.parsed.relations, [{ from: bob, to: alice, type: "related" }]);
+      assert.notStrictEqual(hash(forward.result.transformedContext), hash(backward.result.transformedContext));
+    });
   });
 
   describe("Number normalization", () => {
@@ -176,6 +235,33 @@ describe("Canonical Serialization", () => {
 
       assert.strictEqual(hash1, hash2);
     });
+
+    it("should keep large integers exact", () => {
+      const amounts = [Number.MAX_SAFE_INTEGER, Number.MAX_SAFE_INTEGER - 1, 1234567890123];
+      const hashes = amounts.map((amount) => {
+        const context: TransformedContext = {
+          entities: [{ syntheticId: "ENTITY_0001", role: "Value", attributes: { amount } }],
+          relations: [],
+          task: "test",
+        };
+        assert.match(canonicalize(context), new RegExp(`"amount":${amount}[,}]`));
+        return hash(context);
+      });
+
+      assert.strictEqual(new Set(hashes).size, amounts.length);
+    });
+
+    it("should canonicalize magnitudes that overflow when rounded", () => {
+      const context: TransformedContext = {
+        entities: [
+          { syntheticId: "ENTITY_0001", role: "Value", attributes: { amount: Number.MAX_VALUE } },
+        ],
+        relations: [],
+        task: "test",
+      };
+
+      assert.match(canonicalize(context), /"amount":1\.7976931348623157e\+308/);
+    });
   });
 
   describe("Canonical JSON format", () => {
@@ -323,5 +409,520 @@ describe("Canonical Serialization", () => {
       assert.match(h, /^[0-9a-f]{64}$/, "Should be lowercase hex");
     });
   });
-});
 
+  describe("Key insertion order", () => {
+    it("should hash attributes inserted in any order identically", () => {
+      const entries: Array<[string, string | number | boolean]> = [
+        ["type", "name"],
+        ["position", 12],
+        ["confidence", 0.87],
+        ["occurrences", 3],
+        ["sourceDoc", "doc_0"],
+      ];
+      const hashes = new Set<string>();
+
+      for (let i = 0; i < 100; i++) {
+        const shuffled = entries.slice();
+        for (let j = shuffled.length - 1; j > 0; j--) {
+          const k = Math.floor(Math.random() * (j + 1));
+          [shuffled[j], shuffled[k]] = [shuffled[k], shuffled[j]];
+        }
+        const attributes: Record<string, string | number | boolean> = {};
+        for (const [key, value] of shuffled) {
+          attributes[key] = value;
+        }
+        hashes.add(
+          hash({
+            entities: [{ syntheticId: "ENTITY_0000", role: "Actor", attributes }],
+            relations: [],
+            task: "test",
+            constraints: {
+              maxRelationDegree: 2,
+              policyVersion: "v1",
+              roleCounts: Object.fromEntries(shuffled.map(([key]) => [key, key.length])),
+            },
+          })
+        );
+      }
+
+      assert.strictEqual(hashes.size, 1);
+    });
+  });
+
+  describe("Hash algorithms", () => {
+    const context: TransformedContext = {
+      entities: [
+        { syntheticId: "ENTITY_0000", role: "Actor", attributes: { type: "name", confidence: 0.9 } },
+      ],
+      relations: [],
+      task: "analyze",
+    };
+
+    it("should pin the SHA-256 digest as the default", () => {
+      const expected = "af992781bf51808911b3ad551006a388e9a7444a757b9f5d309c985a6143762a";
+      assert.strictEqual(hash(context), expected);
+      assert.strictEqual(hash(context, "jcs", "sha256"), expected);
+    });
+
+    it("should pin the SHA-384 digest", () => {
+      assert.strictEqual(
+        hash(context, "jcs", "sha384"),
+        "3da86dc59ecdc98c34bda0d418be0ec375ca38deae0fb0d1161bcd381266713a555f281586bc0458f117d195f2d95963"
+      );
+    });
+
+    it("should reject BLAKE3 when the runtime lacks it", () => {
+      if (isHashAlgorithmAvailable("blake3")) {
+        assert.match(hash(context, "jcs", "blake3"), /^[0-9a-f]{64}$/);
+        return;
+      }
+      assert.throws(() => hash(context, "jcs", "blake3"), ConfigurationError);
+      assert.throws(
+        () =>
+          new Axiom({
+            securityTier: "standard",
+            enclave: "none",
+            policyVersion: "v1",
+            hashAlgorithm: "blake3",
+          }),
+        /not available in this runtime/
+      );
+    });
+
+    it("should reject an unknown hash algorithm in the config", () => {
+      assert.throws(
+        () =>
+          new Axiom({
+            securityTier: "standard",
+            enclave: "none",
+            policyVersion: "v1",
+            hashAlgorithm: "md5",
+          } as unknown as AxiomConfig),
+        /Invalid hashAlgorithm: md5/
+      );
+    });
+  });
+
+  describe("Canonical CBOR", () => {
+    const context: TransformedContext = {
+      entities: [
+        { syntheticId: "E1", role: "Actor", attributes: { type: "name", confidence: 0.9, n: -300 } },
+      ],
+      relations: [{ from: "E1", to: "E1", type: "self", weight: 1e21 }],
+      task: "t",
+    };
+
+    it("should encode deterministically with bytewise-sorted keys and no floats", () => {
+      const expected = [
+        "a4", // map(4), keys shortest first
+        "647461736b", "6174", // "task": "t"
+        "656d6f64656c", "f6", // "model": null
+        "68656e746974696573", "81", "a3", // "entities": [ map(3)
+        "64726f6c65", "654163746f72", // "role": "Actor"
+        "6a61747472696275746573", "a3", // "attributes": map(3)
+        "616e", "39012b", // "n": -300
+        "6474797065", "646e616d65", // "type": "name"
+        "6a636f6e666964656e6365", "c4822009", // "confidence": 4([-1, 9])
+        "6b73796e7468657469634964", "624531", // "syntheticId": "E1"
+        "6972656c6174696f6e73", "81", "a4", // "relations": [ map(4)
+        "62746f", "624531", // "to": "E1"
+        "6466726f6d", "624531", // "from": "E1"
+        "6474797065", "6473656c66", // "type": "self"
+        "66776569676874", "c4821501", // "weight": 4([21, 1])
+      ].join("");
+
+      assert.strictEqual(Buffer.from(toCanonicalCbor(context)).toString("hex"), expected);
+    });
+
+    it("should encode decimal fractions from the shortest decimal form", () => {
+      const encode = (value: number) =>
+        Buffer.from(
+          toCanonicalCbor({
+            entities: [],
+            relations: [{ from: "a", to: "b", type: "c", weight: value }],
+            task: "t",
+          })
+        )
+          .toString("hex")
+          .split("66776569676874")[1];
+
+      assert.strictEqual(encode(0.5), "c4822005"); // 4([-1, 5])
+      assert.strictEqual(encode(-1.5e-7), "c482272e"); // 4([-8, -15])
+      assert.strictEqual(encode(2 ** 53), "c482001b0020000000000000"); // 4([0, 2^53])
+      assert.strictEqual(encode(-0), "00");
+      assert.strictEqual(encode(1000), "1903e8");
+    });
+
+    it("should reject non-finite numbers and lone surrogates", () => {
+      assert.throws(
+        () =>
+          toCanonicalCbor({
+            entities: [],
+            relations: [{ from: "a", to: "b", type: "c", weight: NaN }],
+            task: "t",
+          }),
+        TransformationError
+      );
+      assert.throws(
+        () => toCanonicalCbor({ entities: [], relations: [], task: "\ud800" }),
+        TransformationError
+      );
+    });
+
+    it("should give JSON and CBOR different but individually stable hashes", () => {
+      const reordered: TransformedContext = {
+        ...context,
+        entities: [
+          { syntheticId: "E1", role: "Actor", attributes: { n: -300, confidence: 0.9, type: "name" } },
+        ],
+      };
+
+      const json = hashOutput(context, "json");
+      const cbor = hashOutput(context, "cbor");
+      assert.notStrictEqual(json, cbor);
+      assert.strictEqual(json, hash(context));
+      assert.strictEqual(hashOutput(reordered, "json"), json);
+      assert.strictEqual(hashOutput(reordered, "cbor"), cbor);
+      assert.match(hashOutput(context, "cbor", "sha384"), /^[0-9a-f]{96}$/);
+    });
+
+    it("should reject an unknown output encoding in the config", () => {
+      assert.throws(
+        () =>
+          new Axiom({
+            securityTier: "standard",
+            enclave: "none",
+            policyVersion: "v1",
+            outputEncoding: "xml",
+          } as unknown as AxiomConfig),
+        /Invalid outputEncoding: xml/
+      );
+    });
+  });
+
+  describe("RFC 8785 (JCS)", () => {
+    for (const vector of VECTORS) {
+      it(`should match vector ${vector.name}`, () => {
+        const input = JSON.parse(vector.input);
+        const canonical =
+          vector.kind === "value" ? canonicalJson(input) : canonicalize(input as TransformedContext);
+
+        assert.strictEqual(canonical, vector.canonical);
+        assert.strictEqual(
+          createHash("sha256").update(canonical, "utf8").digest("hex"),
+          vector.sha256
+        );
+        if (vector.kind === "transformed_context") {
+          assert.strictEqual(hash(input as TransformedContext), vector.sha256);
+        }
+      });
+    }
+
+    it("should sort keys by UTF-16 code units, not locale", () => {
+      assert.strictEqual(canonicalJson({ b: 1, B: 2, a: 3 }), '{"B":2,"a":3,"b":1}');
+    });
+
+    it("should order entities by code units in jcs mode and by locale in legacy mode", () => {
+      const context: TransformedContext = {
+        entities: [
+          { syntheticId: "ENTITY_a", role: "Actor", attributes: { type: "name" } },
+          { syntheticId: "ENTITY_B", role: "Actor", attributes: { type: "name" } },
+        ],
+        relations: [],
+        task: "test",
+      };
+
+      const jcsIds = JSON.parse(canonicalize(context)).entities.map(
+        (e: { syntheticId: string }) => e.syntheticId
+      );
+      const legacyIds = JSON.parse(canonicalize(context, "legacy")).entities.map(
+        (e: { syntheticId: string }) => e.syntheticId
+      );
+
+      assert.deepStrictEqual(jcsIds, ["ENTITY_B", "ENTITY_a"]);
+      assert.deepStrictEqual(legacyIds, ["ENTITY_a", "ENTITY_B"]);
+    });
+
+    it("should match legacy output for ASCII contexts with consistent ordering", () => {
+      const context: TransformedContext = {
+        entities: [
+          { syntheticId: "ENTITY_0000", role: "Actor", attributes: { type: "name", confidence: 0.9 } },
+        ],
+        relations: [{ from: "ENTITY_0000", to: "ENTITY_0001", type: "owns" }],
+        task: "analyze",
+      };
+
+      assert.strictEqual(canonicalize(context), canonicalize(context, "legacy"));
+      assert.strictEqual(hash(context), hash(context, "legacy"));
+    });
+
+    it("should reject non-finite numbers in jcs mode", () => {
+      const context: TransformedContext = {
+        entities: [
+          { syntheticId: "ENTITY_0000", role: "Value", attributes: { type: "number", amount: Infinity } },
+        ],
+        relations: [],
+        task: "test",
+      };
+
+      assert.throws(() => canonicalize(context), TransformationError);
+      assert.match(canonicalize(context, "legacy"), /"amount":null/);
+    });
+
+    it("should reject lone surrogates", () => {
+      assert.throws(() => canonicalJson({ text: "\ud800" }), TransformationError);
+    });
+  });
+
+  describe("Generated test vectors", () => {
+    const vectors = generateTestVectors();
+
+    it("should publish at least 20 uniquely named vectors", () => {
+      assert.ok(vectors.length >= 20);
+      assert.strictEqual(new Set(vectors.map((v) => v.name)).size, vectors.length);
+    });
+
+    it("should be stable across calls", () => {
+      assert.deepStrictEqual(generateTestVectors(), vectors);
+    });
+
+    for (const vector of vectors) {
+      it(`should reproduce vector ${vector.name} from its JSON context`, () => {
+        const context = JSON.parse(vector.context) as TransformedContext;
+
+        assert.strictEqual(canonicalize(context), vector.canonical);
+        assert.strictEqual(hash(context), vector.sha256);
+        assert.strictEqual(canonicalize(JSON.parse(vector.canonical)), vector.canonical);
+      });
+    }
+  });
+
+  describe("Hash scope", () => {
+    const base: TransformedContext = {
+      entities: [{ syntheticId: "ENTITY_0000", role: "Actor", attributes: { type: "name" } }],
+      relations: [],
+      task: "test",
+      constraints: { roleCounts: { Actor: 1, Value: 0 }, maxRelationDegree: 0, policyVersion: "v1" },
+    };
+
+    it("should leave metadata out of the canonical form and every hash", () => {
+      const withMetadata: TransformedContext = { ...base, metadata: { runnerVersion: "1.2.3" } };
+      const changed: TransformedContext = { ...base, metadata: { runnerVersion: "2.0.0", warm: true } };
+
+      assert.doesNotMatch(canonicalize(withMetadata), /metadata/);
+      for (const context of [withMetadata, changed]) {
+        assert.strictEqual(canonicalize(context), canonicalize(base));
+        assert.strictEqual(hash(context), hash(base));
+        assert.strictEqual(hashOutput(context, "cbor"), hashOutput(base, "cbor"));
+      }
+    });
+
+    it("should change the hash when constraints change", () => {
+      const variants: TransformedContext[] = [
+        { ...base, constraints: { ...base.constraints!, maxRelationDegree: 1 } },
+        { ...base, constraints: { ...base.constraints!, policyVersion: "v2" } },
+        { ...base, constraints: { ...base.constraints!, roleCounts: { Actor: 2, Value: 0 } } },
+        { ...base, constraints: { ...base.constraints!, roleCounts: { Actor: 1 } } },
+        { ...base, constraints: undefined },
+      ];
+
+      for (const variant of variants) {
+        assert.notStrictEqual(hash(variant), hash(base));
+        assert.notStrictEqual(hashOutput(variant, "cbor"), hashOutput(base, "cbor"));
+      }
+    });
+
+    it("should hash constraints independently of key order", () => {
+      const reordered: TransformedContext = {
+        ...base,
+        constraints: { policyVersion: "v1", roleCounts: { Value: 0, Actor: 1 }, maxRelationDegree: 0 },
+      };
+
+      assert.strictEqual(canonicalize(reordered), canonicalize(base));
+      assert.strictEqual(hash(reordered), hash(base));
+    });
+
+    it("should drop metadata in getCanonicalContext", () => {
+      const json = JSON.stringify({ ...base, schemaVersion: 1, metadata: { runnerVersion: "1.2.3" } });
+
+      assert.strictEqual(getCanonicalContext(json), canonicalize(base));
+    });
+  });
+
+  describe("Digest encodings", () => {
+    const abc = createHash("sha256").update("abc").digest();
+
+    it("should render a known digest in each encoding", () => {
+      assert.strictEqual(
+        encodeDigest(abc, "hex"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+      );
+      assert.strictEqual(encodeDigest(abc, "base64url"), "ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0");
+      assert.strictEqual(encodeDigest(abc, "multibase"), "uungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0");
+      assert.strictEqual(encodeDigest(abc), encodeDigest(abc, "hex"));
+    });
+
+    it("should decode every encoding to the same bytes", () => {
+      for (const digest of [abc, createHash("sha384").update("abc").digest(), Buffer.alloc(0)]) {
+        for (const encoding of HASH_ENCODINGS) {
+          assert.ok(decodeDigest(encodeDigest(digest, encoding), encoding).equals(digest), encoding);
+        }
+      }
+    });
+
+    it("should accept correct padding, uppercase hex, and base16 multibase", () => {
+      const sha384 = createHash("sha384").update("abc").digest();
+
+      assert.ok(decodeDigest(`${abc.toString("base64url")}=`, "base64url").equals(abc));
+      assert.ok(decodeDigest(abc.toString("hex").toUpperCase(), "hex").equals(abc));
+      assert.ok(decodeDigest(`f${abc.toString("hex")}`, "multibase").equals(abc));
+      assert.ok(decodeDigest(`U${sha384.toString("base64url")}`, "multibase").equals(sha384));
+    });
+
+    it("should reject malformed renderings", () => {
+      const base64url = abc.toString("base64url");
+      const invalid: Array<[string, (typeof HASH_ENCODINGS)[number]]> = [
+        [`${base64url}==`, "base64url"],
+        [abc.toString("base64"), "base64url"],
+        [`${base64url.slice(0, -1)}!`, "base64url"],
+        [base64url.slice(0, -1), "base64url"],
+        [abc.toString("hex").slice(1), "hex"],
+        [`${abc.toString("hex").slice(2)}zz`, "hex"],
+        [`z${base64url}`, "multibase"],
+        ["", "multibase"],
+      ];
+
+      for (const [text, encoding] of invalid) {
+        assert.throws(() => decodeDigest(text, encoding), ConfigurationError, `${encoding}: ${text}`);
+      }
+    });
+  });
+
+  describe("Streaming hash", () => {
+    // Seeded so a failure reproduces
+    function random(seed: number): () => number {
+      return () => {
+        seed = (seed + 0x6d2b79f5) | 0;
+        let t = Math.imul(seed ^ (seed >>> 15), seed | 1);
+        t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+        return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+      };
+    }
+
+    const ALPHABET = ["a", "Z", "0", " ", '"', "\\", "\n", "\u0001", "é", "中", "😀", "\u2028", "/"];
+
+    function randomContext(next: () => number, maxEntities: number): TransformedContext {
+      function pick<T>(items: readonly T[]): T {
+        return items[Math.floor(next() * items.length)];
+      }
+      const text = (max: number) =>
+        Array.from({ length: Math.floor(next() * max) }, () => pick(ALPHABET)).join("");
+      const scalar = (): string | number | boolean =>
+        pick([
+          () => text(12),
+          () => Math.floor(next() * 2 ** 53) * (next() < 0.5 ? -1 : 1),
+          () => (next() - 0.5) * 10 ** Math.floor(next() * 40 - 20),
+          () => next() < 0.5,
+        ])();
+      const ids = Array.from({ length: Math.floor(next() * maxEntities) }, () => `ENTITY_${text(6)}`);
+
+      return {
+        entities: ids.map((syntheticId) => ({
+          syntheticId,
+          role: pick(["Actor", "Value", "Temporal", text(8)]),
+          attributes: Object.fromEntries(
+            Array.from({ length: Math.floor(next() * 6) }, () => [text(8), scalar()])
+          ),
+        })),
+        relations: Array.from({ length: Math.floor(next() * maxEntities) }, () => ({
+          from: pick(ids.length > 0 ? ids : ["ENTITY_0000"]),
+          to: pick(ids.length > 0 ? ids : ["ENTITY_0000"]),
+          type: text(8),
+          ...(next() < 0.5 ? { weight: Math.floor(next() * 10) } : {}),
+        })),
+        task: text(40),
+        ...(next() < 0.5 ? { model: text(10) } : {}),
+        ...(next() < 0.5
+          ? {
+              constraints: {
+                roleCounts: Object.fromEntries(ids.map((id, i) => [text(5) + i, i])),
+                maxRelationDegree: Math.floor(next() * 10),
+                policyVersion: pick(["v1", "v2"]),
+              },
+            }
+          : {}),
+      };
+    }
+
+    it("should stream the same bytes as canonicalize over randomized contexts", () => {
+      const next = random(0x5eed);
+      for (let run = 0; run < 200; run++) {
+        const context = randomContext(next, 12);
+        const canonical = canonicalize(context);
+        let streamed = "";
+        writeCanonical(context, (chunk) => (streamed += chunk));
+
+        assert.strictEqual(streamed, canonical, `run ${run}`);
+        assert.strictEqual(canonicalByteLength(context), Buffer.byteLength(canonical, "utf8"));
+        assert.strictEqual(hash(context), createHash("sha256").update(canonical, "utf8").digest("hex"));
+        assert.strictEqual(
+          hash(context, "jcs", "sha384"),
+          createHash("sha384").update(canonical, "utf8").digest("hex")
+        );
+        assert.strictEqual(
+          hashOutput(context, "json", "sha256", "jcs", 2),
+          domainSeparatedHash(HASH_DOMAINS.context, [Buffer.from(canonical, "utf8")]).toString("hex")
+        );
+      }
+    });
+
+    it("should stream the same characters as canonicalJson for arbitrary values", () => {
+      const next = random(42);
+      const value = (depth: number): unknown => {
+        const roll = next();
+        if (depth > 3 || roll < 0.4) {
+          return [null, true, false, next() * 1e6, "ü\u0000😀", -0, 1e21][Math.floor(next() * 7)];
+        }
+        if (roll < 0.7) {
+          return Array.from({ length: Math.floor(next() * 4) }, () => value(depth + 1));
+        }
+        return Object.fromEntries(
+          Array.from({ length: Math.floor(next() * 4) }, (_, i) => [
+            `k${Math.floor(next() * 9)}${i}`,
+            value(depth + 1),
+          ])
+        );
+      };
+      for (let run = 0; run < 200; run++) {
+        const input = value(0);
+        let streamed = "";
+        writeCanonicalJson(input, (chunk) => (streamed += chunk));
+
+        assert.strictEqual(streamed, canonicalJson(input));
+      }
+    });
+
+    it("should hash contexts larger than the digest buffer", () => {
+      const context = randomContext(random(7), 4000);
+      const canonical = canonicalize(context);
+
+      assert.ok(canonical.length > 4 * 64 * 1024);
+      assert.strictEqual(hash(context), createHash("sha256").update(canonical, "utf8").digest("hex"));
+      assert.strictEqual(
+        hash(context, "legacy"),
+        createHash("sha256").update(canonicalize(context, "legacy"), "utf8").digest("hex")
+      );
+    });
+
+    it("should reject invalid values on the streaming path too", () => {
+      const context: TransformedContext = {
+        entities: [{ syntheticId: "ENTITY_0000", role: "Actor", attributes: { note: "\ud800" } }],
+        relations: [],
+        task: "test",
+      };
+
+      assert.throws(() => hash(context), TransformationError);
+      assert.throws(() => canonicalByteLength(context), TransformationError);
+    });
+  });
+});